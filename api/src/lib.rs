@@ -1,3 +1,8 @@
+#[cfg(feature = "lua")]
+pub mod lua;
+
+pub mod lua_serializer;
+
 pub mod persistence;
 
 pub mod runtime;
@@ -0,0 +1,42 @@
+use crate::persistence::CommonPresetMetaData;
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+
+/// Parses the YAML frontmatter of a Lua preset (consecutive `--- ` lines at the very top).
+pub fn parse_lua_frontmatter<T: for<'a> Deserialize<'a>>(lua_code: &str) -> anyhow::Result<T> {
+    const PREFIX: &str = "--- ";
+    let frontmatter_lines: Vec<&str> = lua_code
+        .lines()
+        .map_while(|line| {
+            if let Some(yaml_line) = line.strip_prefix(PREFIX) {
+                // Valid non-empty YAML frontmatter line
+                Some(yaml_line)
+            } else if line.starts_with("---") {
+                // Valid empty YAML frontmatter line
+                Some("")
+            } else {
+                // End of frontmatter
+                None
+            }
+        })
+        .collect();
+    let frontmatter = frontmatter_lines.join("\n");
+    if frontmatter.is_empty() {
+        bail!("Lua presets need at least a \"{PREFIX} name: ...\" line at the very top!");
+    }
+    let value: T = serde_yaml::from_str(&frontmatter).map_err(|e| {
+        anyhow!("Error while parsing Lua preset frontmatter:\n\n{e}\n\nFrontmatter was:\n===\n{frontmatter}\n===\n")
+    })?;
+    Ok(value)
+}
+
+/// Formats the given meta data as Lua preset frontmatter.
+pub fn format_lua_frontmatter(meta_data: &CommonPresetMetaData) -> anyhow::Result<String> {
+    let yaml = serde_yaml::to_string(meta_data)?;
+    let frontmatter = yaml
+        .lines()
+        .filter(|line| *line != "---")
+        .map(|line| format!("--- {line}\n"))
+        .collect();
+    Ok(frontmatter)
+}
@@ -0,0 +1,15 @@
+//! Sandboxed execution of Lua code as used by ReaLearn presets.
+//!
+//! Shared between ReaLearn and tools which build Lua presets outside of REAPER.
+
+mod safe_lua;
+pub use safe_lua::*;
+
+mod module_container;
+pub use module_container::*;
+
+mod preset_runtime;
+pub use preset_runtime::*;
+
+mod frontmatter;
+pub use frontmatter::*;
@@ -1,8 +1,10 @@
-use crate::domain::{compile_and_execute, create_fresh_environment};
+use crate::lua::{
+    compile_and_execute, create_fresh_environment, PresetBuildEnvironment, PresetBuildRecording,
+    PresetRuntime,
+};
 use anyhow::{bail, Context};
 use auto_impl::auto_impl;
 use camino::Utf8Path;
-use include_dir::Dir;
 use mlua::{Function, Lua, Value};
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -13,6 +15,7 @@ use std::rc::Rc;
 /// Allows executing Lua code as a module that may require other modules.
 pub struct LuaModuleContainer<F> {
     finder: Result<F, &'static str>,
+    runtime: PresetRuntime,
     // modules: NonCryptoHashMap<String, Value<'a>>,
 }
 
@@ -41,7 +44,23 @@ where
     /// If you pass `None`, executing Lua code will still work but any usage of `require` will yield a readable error
     /// message. This way, we can inform users in scenarios where `require` intentionally is not allowed.
     pub fn new(finder: Result<F, &'static str>) -> Self {
-        Self { finder }
+        Self {
+            finder,
+            runtime: Default::default(),
+        }
+    }
+
+    /// Makes the given environment available to Lua code via the `preset_runtime` module.
+    ///
+    /// Without it, the environment-related functions of `preset_runtime` yield an error.
+    pub fn with_build_environment(mut self, environment: Rc<dyn PresetBuildEnvironment>) -> Self {
+        self.runtime.environment = Some(environment);
+        self
+    }
+
+    /// Returns everything that the executed Lua code has read so far (modules and environment).
+    pub fn recording(&self) -> PresetBuildRecording {
+        self.runtime.recording.borrow().clone()
     }
 
     pub fn execute_as_module<'lua>(
@@ -58,6 +77,7 @@ where
             code,
             self.finder.clone(),
             SharedAccumulator::default(),
+            self.runtime.clone(),
         )
     }
 }
@@ -92,6 +112,7 @@ fn find_and_execute_module<'lua>(
     lua: &'lua Lua,
     finder: impl LuaModuleFinder + Clone + 'static,
     accumulator: SharedAccumulator,
+    runtime: PresetRuntime,
     required_path: &str,
 ) -> anyhow::Result<Value<'lua>> {
     // Validate
@@ -108,15 +129,7 @@ fn find_and_execute_module<'lua>(
     }
     // Substitute preset runtime stub
     if lua_module_path_without_ext(path.as_str()) == LUA_PRESET_RUNTIME_NAME {
-        let table = lua.create_table()?;
-        let finder = finder.clone();
-        let include_str = lua.create_function(move |_, path: String| {
-            let content = finder
-                .find_source_by_path(&path)
-                .map(|content| content.to_string());
-            Ok(content)
-        })?;
-        table.set("include_str", include_str)?;
+        let table = runtime.create_lua_table(lua, finder.clone())?;
         return Ok(Value::Table(table));
     }
     // Find module and get its source
@@ -128,6 +141,10 @@ fn find_and_execute_module<'lua>(
         let source = finder
             .find_source_by_path(path.as_str())
             .with_context(|| format!("Couldn't find Lua module [{path}].{}", root_info()))?;
+        runtime
+            .recording
+            .borrow_mut()
+            .record_module_source(path.as_str(), Some(source.as_ref()));
         (path.to_string(), source)
     } else {
         // No extension given. Try ".luau" and ".lua".
@@ -136,8 +153,12 @@ fn find_and_execute_module<'lua>(
             .find_map(|ext| {
                 let path_with_extension = format!("{path}.{ext}");
                 tracing::debug!(msg = "Finding module by path...", %path_with_extension);
-                let source = finder.find_source_by_path(&path_with_extension)?;
-                Some((path_with_extension, source))
+                let source = finder.find_source_by_path(&path_with_extension);
+                runtime
+                    .recording
+                    .borrow_mut()
+                    .record_module_source(&path_with_extension, source.as_deref());
+                Some((path_with_extension, source?))
             })
             .with_context(|| {
                 format!(
@@ -153,6 +174,7 @@ fn find_and_execute_module<'lua>(
         source.as_ref(),
         Ok(finder),
         accumulator,
+        runtime,
     )
 }
 
@@ -169,9 +191,10 @@ fn execute_as_module<'lua>(
     code: &str,
     finder: Result<impl LuaModuleFinder + Clone + 'static, &'static str>,
     accumulator: SharedAccumulator,
+    runtime: PresetRuntime,
 ) -> anyhow::Result<Value<'lua>> {
     let env = create_fresh_environment(lua, true)?;
-    let require = create_require_function(lua, finder, accumulator.clone())?;
+    let require = create_require_function(lua, finder, accumulator.clone(), runtime)?;
     env.set("require", require)?;
     let pop_later = if let Some(p) = normalized_path {
         accumulator.borrow_mut().push_module(p)?;
@@ -208,40 +231,23 @@ fn create_require_function<'lua>(
     lua: &'lua Lua,
     finder: Result<impl LuaModuleFinder + Clone + 'static, &'static str>,
     accumulator: SharedAccumulator,
+    runtime: PresetRuntime,
 ) -> anyhow::Result<Function<'lua>> {
     let require = lua.create_function_mut(move |lua, required_path: String| {
         let finder = finder.clone().map_err(mlua::Error::runtime)?;
-        let value =
-            find_and_execute_module(lua, finder.clone(), accumulator.clone(), &required_path)
-                .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+        let value = find_and_execute_module(
+            lua,
+            finder.clone(),
+            accumulator.clone(),
+            runtime.clone(),
+            &required_path,
+        )
+        .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
         Ok(value)
     })?;
     Ok(require)
 }
 
-/// Files Lua modules within a specified binary-included directory.
-#[derive(Clone)]
-pub struct IncludedDirLuaModuleFinder {
-    dir: Dir<'static>,
-}
-
-impl IncludedDirLuaModuleFinder {
-    pub fn new(dir: Dir<'static>) -> Self {
-        Self { dir }
-    }
-}
-
-impl LuaModuleFinder for IncludedDirLuaModuleFinder {
-    fn module_root_path(&self) -> String {
-        "factory:/".to_string()
-    }
-
-    fn find_source_by_path(&self, path: &str) -> Option<Cow<'static, str>> {
-        let contents = self.dir.get_file(path)?.contents_utf8()?;
-        Some(contents.into())
-    }
-}
-
 /// Files Lua modules within a specified file-system directory.
 #[derive(Clone)]
 pub struct FsDirLuaModuleFinder {
@@ -0,0 +1,448 @@
+use crate::lua::LuaModuleFinder;
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// Provides read-only information about the machine on which a Lua preset is built.
+///
+/// Lua presets can access this information via the `preset_runtime` module. Answers are plain data
+/// (no handles), so presets can't modify anything.
+pub trait PresetBuildEnvironment {
+    fn query(&self, query: &PresetBuildQuery) -> anyhow::Result<serde_json::Value>;
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PresetBuildQuery {
+    /// Tracks of the project (without master track).
+    Tracks,
+    /// FXs in the normal FX chain of the given track.
+    Fxs {
+        track_guid: String,
+    },
+    /// Parameters of the given FX.
+    FxParameters {
+        track_guid: String,
+        fx_index: u32,
+    },
+    /// The currently focused FX.
+    FocusedFx,
+    MidiInputDevices,
+    MidiOutputDevices,
+    OscDevices,
+    /// Value stored under the given key in the custom data of the given compartment.
+    CustomData {
+        compartment: PresetBuildCompartment,
+        key: String,
+    },
+}
+
+/// The compartment whose custom data is queried.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PresetBuildCompartment {
+    Controller,
+    Main,
+}
+
+/// Records everything a Lua preset has read while being built.
+///
+/// Building the same preset again leads to the same result as long as the recording is up to date,
+/// so the result can be cached.
+#[derive(Clone, Debug, Default)]
+pub struct PresetBuildRecording {
+    /// Module paths and hashes of their sources (`None` if the module couldn't be found).
+    module_sources: Vec<(String, Option<u64>)>,
+    answers: Vec<(PresetBuildQuery, serde_json::Value)>,
+}
+
+impl PresetBuildRecording {
+    /// Returns `true` if all modules and environment answers are still the same.
+    pub fn is_up_to_date(
+        &self,
+        finder: &dyn LuaModuleFinder,
+        environment: &dyn PresetBuildEnvironment,
+    ) -> bool {
+        let modules_unchanged = self
+            .module_sources
+            .iter()
+            .all(|(path, hash)| finder.find_source_by_path(path).map(|s| hash_source(&s)) == *hash);
+        modules_unchanged
+            && self
+                .answers
+                .iter()
+                .all(|(query, answer)| environment.query(query).ok().as_ref() == Some(answer))
+    }
+
+    pub(crate) fn record_module_source(&mut self, path: &str, source: Option<&str>) {
+        if self.module_sources.iter().any(|(p, _)| p == path) {
+            return;
+        }
+        self.module_sources
+            .push((path.to_string(), source.map(hash_source)));
+    }
+
+    fn record_answer(&mut self, query: PresetBuildQuery, answer: serde_json::Value) {
+        if self.answers.iter().any(|(q, _)| q == &query) {
+            return;
+        }
+        self.answers.push((query, answer));
+    }
+}
+
+/// State shared by all modules executed as part of building one preset.
+#[derive(Clone, Default)]
+pub(crate) struct PresetRuntime {
+    pub environment: Option<Rc<dyn PresetBuildEnvironment>>,
+    pub recording: Rc<RefCell<PresetBuildRecording>>,
+}
+
+impl PresetRuntime {
+    /// Creates the table which is returned when requiring the `preset_runtime` module.
+    pub fn create_lua_table<'lua>(
+        &self,
+        lua: &'lua Lua,
+        finder: impl LuaModuleFinder + Clone + 'static,
+    ) -> anyhow::Result<Table<'lua>> {
+        let table = lua.create_table()?;
+        let recording = self.recording.clone();
+        let include_str = lua.create_function(move |_, path: String| {
+            let content = finder
+                .find_source_by_path(&path)
+                .map(|content| content.to_string());
+            recording
+                .borrow_mut()
+                .record_module_source(&path, content.as_deref());
+            Ok(content)
+        })?;
+        table.set("include_str", include_str)?;
+        self.register(lua, &table, "get_tracks", |_: ()| {
+            Ok(PresetBuildQuery::Tracks)
+        })?;
+        self.register(lua, &table, "get_fxs", |track_guid: String| {
+            Ok(PresetBuildQuery::Fxs { track_guid })
+        })?;
+        self.register(
+            lua,
+            &table,
+            "get_fx_parameters",
+            |(track_guid, fx_index): (String, u32)| {
+                Ok(PresetBuildQuery::FxParameters {
+                    track_guid,
+                    fx_index,
+                })
+            },
+        )?;
+        self.register(lua, &table, "get_focused_fx", |_: ()| {
+            Ok(PresetBuildQuery::FocusedFx)
+        })?;
+        self.register(lua, &table, "get_midi_input_devices", |_: ()| {
+            Ok(PresetBuildQuery::MidiInputDevices)
+        })?;
+        self.register(lua, &table, "get_midi_output_devices", |_: ()| {
+            Ok(PresetBuildQuery::MidiOutputDevices)
+        })?;
+        self.register(lua, &table, "get_osc_devices", |_: ()| {
+            Ok(PresetBuildQuery::OscDevices)
+        })?;
+        self.register(
+            lua,
+            &table,
+            "get_custom_data",
+            |(compartment, key): (String, String)| {
+                let compartment = match compartment.as_str() {
+                    "controller" => PresetBuildCompartment::Controller,
+                    "main" => PresetBuildCompartment::Main,
+                    _ => {
+                        return Err(mlua::Error::runtime(
+                            "compartment must be \"controller\" or \"main\"",
+                        ))
+                    }
+                };
+                Ok(PresetBuildQuery::CustomData { compartment, key })
+            },
+        )?;
+        Ok(table)
+    }
+
+    /// Registers a Lua function which answers the query built from its arguments.
+    fn register<'lua, A>(
+        &self,
+        lua: &'lua Lua,
+        table: &Table<'lua>,
+        name: &str,
+        build_query: impl Fn(A) -> mlua::Result<PresetBuildQuery> + 'static,
+    ) -> anyhow::Result<()>
+    where
+        A: for<'a> mlua::FromLuaMulti<'a>,
+    {
+        let runtime = self.clone();
+        let function_name = name.to_string();
+        let function = lua.create_function(move |lua, args: A| {
+            let environment = runtime.environment.as_ref().ok_or_else(|| {
+                mlua::Error::runtime(format!(
+                    "preset_runtime.{function_name} is only available when building presets"
+                ))
+            })?;
+            let query = build_query(args)?;
+            let answer = environment
+                .query(&query)
+                .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+            // This is important, otherwise null ends up as some userdata and not nil.
+            let mut serialize_options = mlua::SerializeOptions::new();
+            serialize_options.serialize_none_to_null = false;
+            serialize_options.serialize_unit_to_null = false;
+            let value: Value = lua.to_value_with(&answer, serialize_options)?;
+            runtime.recording.borrow_mut().record_answer(query, answer);
+            Ok(value)
+        })?;
+        table.set(name, function)?;
+        Ok(())
+    }
+}
+
+fn hash_source(source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lua::{LuaModuleContainer, SafeLua};
+    use serde_json::json;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    const PRESET_CODE: &str = r#"
+        local runtime = require("preset_runtime")
+        local lib = require("lib")
+        return { track_count = #runtime.get_tracks(), lib = lib }
+    "#;
+
+    #[test]
+    fn record_answer_only_once_per_query() {
+        // Given
+        let mut recording = PresetBuildRecording::default();
+        // When
+        recording.record_answer(PresetBuildQuery::Tracks, json!(["a"]));
+        recording.record_answer(PresetBuildQuery::Tracks, json!(["b"]));
+        recording.record_answer(PresetBuildQuery::OscDevices, json!([]));
+        // Then
+        assert_eq!(
+            recording.answers,
+            vec![
+                (PresetBuildQuery::Tracks, json!(["a"])),
+                (PresetBuildQuery::OscDevices, json!([])),
+            ]
+        );
+    }
+
+    #[test]
+    fn record_module_source_only_once_per_path() {
+        // Given
+        let mut recording = PresetBuildRecording::default();
+        // When
+        recording.record_module_source("lib.luau", None);
+        recording.record_module_source("lib.luau", Some("return 1"));
+        // Then
+        assert_eq!(
+            recording.module_sources,
+            vec![("lib.luau".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn up_to_date_if_nothing_changed() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!(["Track 1", "Track 2"]));
+        // When
+        let (value, recording) = build_preset(&finder, &environment);
+        // Then
+        // Luau only knows floating-point numbers
+        assert_eq!(value["track_count"].as_f64(), Some(2.0));
+        assert_eq!(value["lib"].as_f64(), Some(1.0));
+        assert_eq!(
+            recording.answers,
+            vec![(PresetBuildQuery::Tracks, json!(["Track 1", "Track 2"]))]
+        );
+        assert!(recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn outdated_if_answer_changed() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!(["Track 1", "Track 2"]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        environment.set_tracks(json!(["Track 1"]));
+        // Then
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn outdated_if_answer_not_available_anymore() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        environment.set_tracks(serde_json::Value::Null);
+        // Then
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn outdated_if_module_source_changed() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        finder.set_source("lib.lua", Some("return 2"));
+        // Then
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn outdated_if_module_removed() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        finder.set_source("lib.lua", None);
+        // Then
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn outdated_if_previously_missing_module_appears() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        // ".luau" is tried first, so this would shadow "lib.lua" when building again
+        finder.set_source("lib.luau", Some("return 2"));
+        // Then
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn up_to_date_if_unrelated_module_changed() {
+        // Given
+        let finder = FakeModuleFinder::new([("lib.lua", "return 1"), ("other.lua", "return 1")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let (_, recording) = build_preset(&finder, &environment);
+        // When
+        finder.set_source("other.lua", Some("return 2"));
+        // Then
+        assert!(recording.is_up_to_date(&finder, &environment));
+    }
+
+    #[test]
+    fn record_included_module_sources() {
+        // Given
+        let finder = FakeModuleFinder::new([("data.txt", "foo")]);
+        let environment = FakeEnvironment::new(json!([]));
+        let code = r#"return require("preset_runtime").include_str("data.txt")"#;
+        let lua = SafeLua::new().unwrap();
+        let container = LuaModuleContainer::new(Ok(finder.clone()))
+            .with_build_environment(Rc::new(environment.clone()));
+        // When
+        let value = container
+            .execute_as_module(lua.as_ref(), None, "Test".to_string(), code)
+            .unwrap();
+        let recording = container.recording();
+        finder.set_source("data.txt", Some("bar"));
+        // Then
+        assert_eq!(lua.as_ref().from_value::<String>(value).unwrap(), "foo");
+        assert!(!recording.is_up_to_date(&finder, &environment));
+    }
+
+    /// Builds the test preset and returns its result along with the recording.
+    fn build_preset(
+        finder: &FakeModuleFinder,
+        environment: &FakeEnvironment,
+    ) -> (serde_json::Value, PresetBuildRecording) {
+        let lua = SafeLua::new().unwrap();
+        let container = LuaModuleContainer::new(Ok(finder.clone()))
+            .with_build_environment(Rc::new(environment.clone()));
+        let value = container
+            .execute_as_module(lua.as_ref(), None, "Test".to_string(), PRESET_CODE)
+            .unwrap();
+        let json = lua.as_ref().from_value(value).unwrap();
+        (json, container.recording())
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeModuleFinder {
+        sources: Rc<RefCell<HashMap<String, String>>>,
+    }
+
+    impl FakeModuleFinder {
+        fn new<const N: usize>(sources: [(&str, &str); N]) -> Self {
+            let finder = Self::default();
+            for (path, source) in sources {
+                finder.set_source(path, Some(source));
+            }
+            finder
+        }
+
+        fn set_source(&self, path: &str, source: Option<&str>) {
+            let mut sources = self.sources.borrow_mut();
+            match source {
+                None => sources.remove(path),
+                Some(s) => sources.insert(path.to_string(), s.to_string()),
+            };
+        }
+    }
+
+    impl LuaModuleFinder for FakeModuleFinder {
+        fn module_root_path(&self) -> String {
+            "fake".to_string()
+        }
+
+        fn find_source_by_path(&self, path: &str) -> Option<Cow<'static, str>> {
+            let source = self.sources.borrow().get(path)?.clone();
+            Some(source.into())
+        }
+    }
+
+    /// Only knows tracks. `null` means that tracks can't be queried.
+    #[derive(Clone)]
+    struct FakeEnvironment {
+        tracks: Rc<RefCell<serde_json::Value>>,
+    }
+
+    impl FakeEnvironment {
+        fn new(tracks: serde_json::Value) -> Self {
+            Self {
+                tracks: Rc::new(RefCell::new(tracks)),
+            }
+        }
+
+        fn set_tracks(&self, tracks: serde_json::Value) {
+            *self.tracks.borrow_mut() = tracks;
+        }
+    }
+
+    impl PresetBuildEnvironment for FakeEnvironment {
+        fn query(&self, query: &PresetBuildQuery) -> anyhow::Result<serde_json::Value> {
+            match query {
+                PresetBuildQuery::Tracks => {
+                    let tracks = self.tracks.borrow();
+                    if tracks.is_null() {
+                        anyhow::bail!("tracks not available");
+                    }
+                    Ok(tracks.clone())
+                }
+                _ => Ok(serde_json::Value::Null),
+            }
+        }
+    }
+}
@@ -70,19 +70,44 @@ impl SafeLua {
 
     /// Call before executing user code in order to prevent code from taking too long to execute.
     pub fn start_execution_time_limit_countdown(self) -> anyhow::Result<Self> {
-        const MAX_DURATION: Duration = Duration::from_millis(200);
-        let instant = Instant::now();
-        self.0.set_interrupt(move |_lua| {
-            if instant.elapsed() > MAX_DURATION {
-                Err(mlua::Error::ExternalError(Arc::new(
-                    RealearnScriptError::Timeout,
-                )))
-            } else {
-                Ok(VmState::Continue)
-            }
-        });
+        self.start_execution_time_limit_countdown_with(MAX_EXECUTION_DURATION)
+    }
+
+    /// Like [`Self::start_execution_time_limit_countdown`] but with a custom limit.
+    pub fn start_execution_time_limit_countdown_with(
+        self,
+        max_duration: Duration,
+    ) -> anyhow::Result<Self> {
+        set_execution_time_limit(&self.0, max_duration);
         Ok(self)
     }
+
+    /// Executes the given function with an execution time limit.
+    ///
+    /// In contrast to [`Self::start_execution_time_limit_countdown`], this is suited for Lua states
+    /// which are used over and over again because the limit is lifted after execution.
+    pub fn with_execution_time_limit<R>(&self, f: impl FnOnce() -> R) -> R {
+        set_execution_time_limit(&self.0, MAX_EXECUTION_DURATION);
+        let result = f();
+        self.0.remove_interrupt();
+        result
+    }
+}
+
+/// Maximum time which user code may take to execute.
+pub const MAX_EXECUTION_DURATION: Duration = Duration::from_millis(200);
+
+fn set_execution_time_limit(lua: &Lua, max_duration: Duration) {
+    let instant = Instant::now();
+    lua.set_interrupt(move |_lua| {
+        if instant.elapsed() > max_duration {
+            Err(mlua::Error::ExternalError(Arc::new(
+                RealearnScriptError::Timeout,
+            )))
+        } else {
+            Ok(VmState::Continue)
+        }
+    });
 }
 
 /// Creates a fresh environment for this Lua state.
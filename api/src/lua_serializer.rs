@@ -1,3 +1,5 @@
+//! Serializes Rust values into Lua table expressions (as used in ReaLearn Script and Lua presets).
+
 use serde::ser::Impossible;
 use serde::{ser, Serialize};
 use std::fmt::{Display, Formatter};
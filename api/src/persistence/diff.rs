@@ -0,0 +1,551 @@
+//! Semantic diff and three-way merge of compartments.
+//!
+//! Mappings and groups are matched by their ID (key), parameters by their index. That makes the
+//! result independent of the order in which items appear and of how the preset was formatted.
+//! Items without ID are matched by their position.
+
+use crate::persistence::Compartment;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// Compartment fields which contain lists of identifiable items.
+const ITEM_LIST_FIELDS: [&str; 3] = ["parameters", "groups", "mappings"];
+
+/// Differences between two compartments.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CompartmentDiff {
+    pub parameters: Vec<ItemChange>,
+    pub groups: Vec<ItemChange>,
+    pub mappings: Vec<ItemChange>,
+    /// Changes of other compartment fields, e.g. `notes` or `default_group`.
+    pub properties: Vec<PropertyChange>,
+}
+
+/// Change of a parameter, group or mapping.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ItemChange {
+    Added(ItemDescriptor),
+    Removed(ItemDescriptor),
+    Changed {
+        item: ItemDescriptor,
+        properties: Vec<PropertyChange>,
+    },
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ItemDescriptor {
+    /// ID of the item or `#<position>` if it doesn't have one.
+    pub key: String,
+    pub name: Option<String>,
+}
+
+/// Change of a single value.
+///
+/// The path is relative to the changed item, e.g. `glue.source_interval` or `target.track.index`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PropertyChange {
+    pub path: String,
+    /// `None` if the property didn't exist before.
+    pub old: Option<Value>,
+    /// `None` if the property doesn't exist anymore.
+    pub new: Option<Value>,
+}
+
+/// Result of a three-way merge.
+pub struct CompartmentMerge {
+    pub merged: Compartment,
+    /// Places where both sides changed the same value differently.
+    ///
+    /// In this case, the merged compartment contains the value of "ours".
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MergeConflict {
+    /// Path of the conflicting value, e.g. `mappings[volume].glue.source_interval`.
+    pub path: String,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// Computes the differences between an old and a new version of a compartment.
+pub fn diff_compartments(
+    old: &Compartment,
+    new: &Compartment,
+) -> Result<CompartmentDiff, serde_json::Error> {
+    let old = to_object(old)?;
+    let new = to_object(new)?;
+    let mut properties = vec![];
+    for key in union_of_keys(&old, &new) {
+        if ITEM_LIST_FIELDS.contains(&key) {
+            continue;
+        }
+        diff_values(old.get(key), new.get(key), key.to_string(), &mut properties);
+    }
+    let diff = CompartmentDiff {
+        parameters: diff_items(&old, &new, "parameters"),
+        groups: diff_items(&old, &new, "groups"),
+        mappings: diff_items(&old, &new, "mappings"),
+        properties,
+    };
+    Ok(diff)
+}
+
+/// Merges the changes made in "ours" and "theirs" since their common ancestor "base".
+///
+/// Changes made on only one side are taken over. If both sides changed the same value in a
+/// different way, "ours" wins and the conflict is reported. The same applies if one side modified
+/// an item which the other side removed.
+pub fn merge_compartments(
+    base: &Compartment,
+    ours: &Compartment,
+    theirs: &Compartment,
+) -> Result<CompartmentMerge, serde_json::Error> {
+    let base = to_object(base)?;
+    let ours = to_object(ours)?;
+    let theirs = to_object(theirs)?;
+    let mut merger = Merger { conflicts: vec![] };
+    let mut merged = Map::new();
+    for key in union_of_keys(&ours, &theirs) {
+        let value = if ITEM_LIST_FIELDS.contains(&key) {
+            merger.merge_items(&base, &ours, &theirs, key)
+        } else {
+            merger.merge_values(
+                base.get(key),
+                ours.get(key),
+                theirs.get(key),
+                key.to_string(),
+            )
+        };
+        if let Some(v) = value {
+            merged.insert(key.to_string(), v);
+        }
+    }
+    let merge = CompartmentMerge {
+        merged: serde_json::from_value(Value::Object(merged))?,
+        conflicts: merger.conflicts,
+    };
+    Ok(merge)
+}
+
+impl CompartmentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+            && self.groups.is_empty()
+            && self.mappings.is_empty()
+            && self.properties.is_empty()
+    }
+}
+
+impl ItemChange {
+    pub fn item(&self) -> &ItemDescriptor {
+        match self {
+            ItemChange::Added(item) | ItemChange::Removed(item) => item,
+            ItemChange::Changed { item, .. } => item,
+        }
+    }
+}
+
+impl Display for CompartmentDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lists = [
+            ("Parameter", &self.parameters),
+            ("Group", &self.groups),
+            ("Mapping", &self.mappings),
+        ];
+        for (label, changes) in lists {
+            for change in changes {
+                writeln!(f, "{label} {change}")?;
+            }
+        }
+        for change in &self.properties {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ItemChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemChange::Added(item) => write!(f, "{item} added"),
+            ItemChange::Removed(item) => write!(f, "{item} removed"),
+            ItemChange::Changed { item, properties } => {
+                write!(f, "{item} changed")?;
+                for p in properties {
+                    write!(f, "\n    {p}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for ItemDescriptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            None => write!(f, "[{}]", self.key),
+            Some(name) => write!(f, "\"{name}\" [{}]", self.key),
+        }
+    }
+}
+
+impl Display for PropertyChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} => {}",
+            self.path,
+            format_optional_value(self.old.as_ref()),
+            format_optional_value(self.new.as_ref())
+        )
+    }
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: base {}, ours {}, theirs {}",
+            self.path,
+            format_optional_value(self.base.as_ref()),
+            format_optional_value(self.ours.as_ref()),
+            format_optional_value(self.theirs.as_ref())
+        )
+    }
+}
+
+fn format_optional_value(value: Option<&Value>) -> String {
+    match value {
+        None => "(none)".to_string(),
+        Some(v) => v.to_string(),
+    }
+}
+
+fn to_object(compartment: &Compartment) -> Result<Map<String, Value>, serde_json::Error> {
+    match serde_json::to_value(compartment)? {
+        Value::Object(map) => Ok(map),
+        _ => Ok(Map::new()),
+    }
+}
+
+fn union_of_keys<'a>(a: &'a Map<String, Value>, b: &'a Map<String, Value>) -> BTreeSet<&'a str> {
+    a.keys().chain(b.keys()).map(|k| k.as_str()).collect()
+}
+
+/// Returns the items of the given list field, each one together with its key.
+fn keyed_items<'a>(compartment: &'a Map<String, Value>, field: &str) -> Vec<(String, &'a Value)> {
+    let Some(Value::Array(items)) = compartment.get(field) else {
+        return vec![];
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item_key(field, i, item), item))
+        .collect()
+}
+
+fn item_key(field: &str, position: usize, item: &Value) -> String {
+    let key = if field == "parameters" {
+        item.get("index").map(|i| i.to_string())
+    } else {
+        item.get("id").and_then(|id| id.as_str()).map(String::from)
+    };
+    key.unwrap_or_else(|| format!("#{position}"))
+}
+
+fn find_item<'a>(items: &[(String, &'a Value)], key: &str) -> Option<&'a Value> {
+    items.iter().find(|(k, _)| k == key).map(|(_, v)| *v)
+}
+
+fn describe_item(key: &str, item: &Value) -> ItemDescriptor {
+    ItemDescriptor {
+        key: key.to_string(),
+        name: item.get("name").and_then(|n| n.as_str()).map(String::from),
+    }
+}
+
+fn diff_items(old: &Map<String, Value>, new: &Map<String, Value>, field: &str) -> Vec<ItemChange> {
+    let old_items = keyed_items(old, field);
+    let new_items = keyed_items(new, field);
+    let mut changes = vec![];
+    for (key, old_item) in &old_items {
+        if find_item(&new_items, key).is_none() {
+            changes.push(ItemChange::Removed(describe_item(key, old_item)));
+        }
+    }
+    for (key, new_item) in &new_items {
+        match find_item(&old_items, key) {
+            None => changes.push(ItemChange::Added(describe_item(key, new_item))),
+            Some(old_item) => {
+                let mut properties = vec![];
+                diff_values(
+                    Some(old_item),
+                    Some(new_item),
+                    String::new(),
+                    &mut properties,
+                );
+                if !properties.is_empty() {
+                    changes.push(ItemChange::Changed {
+                        item: describe_item(key, new_item),
+                        properties,
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Descends into objects and reports changed leaf values. Arrays are compared as a whole.
+fn diff_values(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    path: String,
+    changes: &mut Vec<PropertyChange>,
+) {
+    if old == new {
+        return;
+    }
+    if let (Some(Value::Object(old)), Some(Value::Object(new))) = (old, new) {
+        for key in union_of_keys(old, new) {
+            diff_values(old.get(key), new.get(key), join_path(&path, key), changes);
+        }
+        return;
+    }
+    changes.push(PropertyChange {
+        path,
+        old: old.cloned(),
+        new: new.cloned(),
+    });
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+struct Merger {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger {
+    fn merge_values(
+        &mut self,
+        base: Option<&Value>,
+        ours: Option<&Value>,
+        theirs: Option<&Value>,
+        path: String,
+    ) -> Option<Value> {
+        if ours == theirs || base == theirs {
+            return ours.cloned();
+        }
+        if base == ours {
+            return theirs.cloned();
+        }
+        if let (Some(Value::Object(o)), Some(Value::Object(t))) = (ours, theirs) {
+            let b = base.and_then(|b| b.as_object());
+            // Objects of a different kind (e.g. a MIDI and an OSC source) are not merged
+            // property by property
+            let same_kind = o.get("kind") == t.get("kind")
+                && (b.is_none() || b.and_then(|b| b.get("kind")) == o.get("kind"));
+            if !same_kind {
+                return self.conflict(path, base, ours, theirs);
+            }
+            let mut merged = Map::new();
+            for key in union_of_keys(o, t) {
+                let value = self.merge_values(
+                    b.and_then(|b| b.get(key)),
+                    o.get(key),
+                    t.get(key),
+                    join_path(&path, key),
+                );
+                if let Some(v) = value {
+                    merged.insert(key.to_string(), v);
+                }
+            }
+            return Some(Value::Object(merged));
+        }
+        self.conflict(path, base, ours, theirs)
+    }
+
+    /// Records a conflict and resolves it in favor of "ours".
+    fn conflict(
+        &mut self,
+        path: String,
+        base: Option<&Value>,
+        ours: Option<&Value>,
+        theirs: Option<&Value>,
+    ) -> Option<Value> {
+        self.conflicts.push(MergeConflict {
+            path,
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        });
+        ours.cloned()
+    }
+
+    /// Merges lists of identifiable items, keeping the order of "ours".
+    ///
+    /// Items added by "theirs" are inserted after their predecessor in "theirs".
+    fn merge_items(
+        &mut self,
+        base: &Map<String, Value>,
+        ours: &Map<String, Value>,
+        theirs: &Map<String, Value>,
+        field: &str,
+    ) -> Option<Value> {
+        let base_items = keyed_items(base, field);
+        let our_items = keyed_items(ours, field);
+        let their_items = keyed_items(theirs, field);
+        let mut merged: Vec<(String, Value)> = vec![];
+        for (key, our_item) in &our_items {
+            let value = self.merge_values(
+                find_item(&base_items, key),
+                Some(our_item),
+                find_item(&their_items, key),
+                format!("{field}[{key}]"),
+            );
+            if let Some(v) = value {
+                merged.push((key.clone(), v));
+            }
+        }
+        for (i, (key, their_item)) in their_items.iter().enumerate() {
+            if find_item(&our_items, key).is_some() {
+                continue;
+            }
+            let value = self.merge_values(
+                find_item(&base_items, key),
+                None,
+                Some(their_item),
+                format!("{field}[{key}]"),
+            );
+            let Some(value) = value else {
+                continue;
+            };
+            let insert_pos = their_items[..i]
+                .iter()
+                .rev()
+                .find_map(|(k, _)| merged.iter().position(|(mk, _)| mk == k))
+                .map(|pos| pos + 1)
+                .unwrap_or(0);
+            merged.insert(insert_pos, (key.clone(), value));
+        }
+        if merged.is_empty() && ours.get(field).is_none() {
+            return None;
+        }
+        Some(Value::Array(merged.into_iter().map(|(_, v)| v).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn compartment(value: Value) -> Compartment {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn diff_matches_mappings_by_id() {
+        let old = compartment(json!({
+            "mappings": [
+                { "id": "a", "name": "A", "glue": { "reverse": false } },
+                { "id": "b", "name": "B" }
+            ]
+        }));
+        let new = compartment(json!({
+            "mappings": [
+                { "id": "c", "name": "C" },
+                { "id": "a", "name": "A", "glue": { "reverse": true } }
+            ],
+            "notes": "Hello"
+        }));
+        let diff = diff_compartments(&old, &new).unwrap();
+        assert_eq!(
+            diff.mappings,
+            [
+                ItemChange::Removed(ItemDescriptor {
+                    key: "b".to_string(),
+                    name: Some("B".to_string())
+                }),
+                ItemChange::Added(ItemDescriptor {
+                    key: "c".to_string(),
+                    name: Some("C".to_string())
+                }),
+                ItemChange::Changed {
+                    item: ItemDescriptor {
+                        key: "a".to_string(),
+                        name: Some("A".to_string())
+                    },
+                    properties: vec![PropertyChange {
+                        path: "glue.reverse".to_string(),
+                        old: Some(json!(false)),
+                        new: Some(json!(true)),
+                    }]
+                }
+            ]
+        );
+        assert_eq!(diff.properties.len(), 1);
+        assert_eq!(diff.properties[0].path, "notes");
+    }
+
+    #[test]
+    fn merge_takes_over_changes_from_both_sides() {
+        let base = compartment(json!({
+            "mappings": [
+                { "id": "a", "name": "A", "glue": { "reverse": false, "step_factor_interval": [1, 5] } },
+                { "id": "b", "name": "B" }
+            ]
+        }));
+        let ours = compartment(json!({
+            "mappings": [
+                { "id": "a", "name": "A", "glue": { "reverse": true, "step_factor_interval": [1, 5] } },
+                { "id": "b", "name": "B" }
+            ]
+        }));
+        let theirs = compartment(json!({
+            "mappings": [
+                { "id": "a", "name": "A", "glue": { "reverse": false, "step_factor_interval": [2, 5] } },
+                { "id": "c", "name": "C" }
+            ]
+        }));
+        let merge = merge_compartments(&base, &ours, &theirs).unwrap();
+        assert!(merge.conflicts.is_empty());
+        let expected = compartment(json!({
+            "mappings": [
+                { "id": "a", "name": "A", "glue": { "reverse": true, "step_factor_interval": [2, 5] } },
+                { "id": "c", "name": "C" }
+            ]
+        }));
+        assert!(diff_compartments(&expected, &merge.merged)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn merge_reports_conflicts() {
+        let base = compartment(json!({
+            "mappings": [{ "id": "a", "name": "A" }, { "id": "b", "name": "B" }]
+        }));
+        let ours = compartment(json!({
+            "mappings": [{ "id": "a", "name": "Ours" }]
+        }));
+        let theirs = compartment(json!({
+            "mappings": [{ "id": "a", "name": "Theirs" }, { "id": "b", "name": "B2" }]
+        }));
+        let merge = merge_compartments(&base, &ours, &theirs).unwrap();
+        let paths: Vec<_> = merge.conflicts.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["mappings[a].name", "mappings[b]"]);
+        let mappings = merge.merged.mappings.unwrap();
+        assert_eq!(mappings.len(), 1);
+        assert_eq!(mappings[0].name.as_deref(), Some("Ours"));
+    }
+}
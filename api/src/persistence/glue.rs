@@ -1,5 +1,8 @@
+use derive_more::Display;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use strum::EnumIter;
 
 #[derive(PartialEq, Default, Serialize, Deserialize)]
 pub struct Glue {
@@ -14,6 +17,8 @@ pub struct Glue {
     pub reverse: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub out_of_range_behavior: Option<OutOfRangeBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_curve: Option<ResponseCurve>,
     //endregion
 
     //region Relevant for control only (might change in future)
@@ -21,6 +26,10 @@ pub struct Glue {
     pub target_value_sequence: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub round_target_value: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glide: Option<Glide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_quantization: Option<ScaleQuantization>,
     //endregion
 
     //region Relevant for control only (guaranteed)
@@ -33,6 +42,8 @@ pub struct Glue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub control_transformation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_transformation_kind: Option<ControlTransformationKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub step_size_interval: Option<Interval<f64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub step_factor_interval: Option<Interval<i32>>,
@@ -43,6 +54,8 @@ pub struct Glue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub relative_mode: Option<RelativeMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoder_acceleration: Option<EncoderAcceleration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub interaction: Option<Interaction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fire_mode: Option<FireMode>,
@@ -53,6 +66,8 @@ pub struct Glue {
     pub feedback: Option<Feedback>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub feedback_value_table: Option<FeedbackValueTable>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_animations: Option<Vec<FeedbackAnimation>>,
     //endregion
 }
 
@@ -95,6 +110,184 @@ impl Default for RelativeMode {
     }
 }
 
+/// Shapes the response between source and target interval.
+///
+/// In feedback direction, the curve is inverted.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ResponseCurve {
+    pub points: Vec<ResponseCurvePoint>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResponseCurvePoint {
+    pub x: f64,
+    pub y: f64,
+    /// Shape of the segment starting at this point, between -1.0 and 1.0 (0.0 = straight line).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curvature: Option<f64>,
+}
+
+/// Makes fast encoder movements cover more ground than slow ones.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct EncoderAcceleration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curve: Option<AccelerationCurve>,
+    /// Factor applied to increments that arrive immediately after each other.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_factor: Option<u32>,
+    /// Milliseconds after which the acceleration resets if no further increment arrives.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset_timeout: Option<u32>,
+}
+
+/// How the acceleration factor grows with encoder speed.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum AccelerationCurve {
+    #[default]
+    #[display(fmt = "Linear")]
+    Linear,
+    #[display(fmt = "Quadratic")]
+    Quadratic,
+    #[display(fmt = "Exponential")]
+    Exponential,
+}
+
+/// Makes the target value move smoothly to a new value instead of jumping there.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Glide {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<GlideDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curve: Option<GlideCurve>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum GlideDuration {
+    Millis(MillisGlideDuration),
+    Beats(BeatsGlideDuration),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MillisGlideDuration {
+    pub value: u32,
+}
+
+/// Duration in beats, taking the project tempo at the start of the glide into account.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BeatsGlideDuration {
+    pub value: f64,
+}
+
+/// Shape of the movement from the old to the new target value.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum GlideCurve {
+    #[default]
+    #[display(fmt = "Linear")]
+    Linear,
+    #[display(fmt = "Ease in")]
+    EaseIn,
+    #[display(fmt = "Ease out")]
+    EaseOut,
+    #[display(fmt = "Ease in/out")]
+    EaseInOut,
+}
+
+/// Snaps discrete target values (e.g. note numbers) to the notes of a musical scale.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ScaleQuantization {
+    /// Pitch class of the root note, from 0 (C) to 11 (B).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<Scale>,
+    /// Custom scale as 12 digits (0 or 1) starting at the root, e.g. "101011010101" for major.
+    ///
+    /// Takes precedence over `scale`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_scale: Option<String>,
+    /// Compartment parameter which determines the root instead of `root`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_parameter_index: Option<u32>,
+    /// Compartment parameter which selects one of the predefined scales instead of `scale`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_parameter_index: Option<u32>,
+}
+
+/// A predefined musical scale.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum Scale {
+    #[default]
+    #[display(fmt = "Major")]
+    Major,
+    #[display(fmt = "Natural minor")]
+    NaturalMinor,
+    #[display(fmt = "Harmonic minor")]
+    HarmonicMinor,
+    #[display(fmt = "Melodic minor")]
+    MelodicMinor,
+    #[display(fmt = "Dorian")]
+    Dorian,
+    #[display(fmt = "Phrygian")]
+    Phrygian,
+    #[display(fmt = "Lydian")]
+    Lydian,
+    #[display(fmt = "Mixolydian")]
+    Mixolydian,
+    #[display(fmt = "Locrian")]
+    Locrian,
+    #[display(fmt = "Major pentatonic")]
+    MajorPentatonic,
+    #[display(fmt = "Minor pentatonic")]
+    MinorPentatonic,
+    #[display(fmt = "Blues")]
+    Blues,
+    #[display(fmt = "Chromatic")]
+    Chromatic,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum FireMode {
@@ -103,6 +296,10 @@ pub enum FireMode {
     AfterTimeoutKeepFiring(AfterTimeoutKeepFiringFireMode),
     OnSinglePress(OnSinglePressFireMode),
     OnDoublePress,
+    OnMultiPress(OnMultiPressFireMode),
+    OnLongPressRelease(OnLongPressReleaseFireMode),
+    OnChord(OnChordFireMode),
+    OnSequence(OnSequenceFireMode),
 }
 
 impl Default for FireMode {
@@ -136,6 +333,54 @@ pub struct OnSinglePressFireMode {
     pub max_duration: Option<u32>,
 }
 
+/// Fires when the button has been pressed exactly the given number of times in quick succession.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OnMultiPressFireMode {
+    /// E.g. 3 for triple press.
+    pub press_count: u32,
+    /// Maximum time in milliseconds between two presses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_interval: Option<u32>,
+}
+
+/// Fires when the button is released after having been held for a minimum duration.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct OnLongPressReleaseFireMode {
+    /// Minimum press duration in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_duration: Option<u32>,
+}
+
+/// Fires when the button is pressed while the button of another mapping is held.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OnChordFireMode {
+    /// Key of the mapping whose button must be held.
+    pub held_mapping: String,
+}
+
+/// Fires when the button is pressed shortly after the button of another mapping.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OnSequenceFireMode {
+    /// Key of the mapping whose button must be pressed first.
+    pub previous_mapping: String,
+    /// Maximum time in milliseconds between the two presses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_interval: Option<u32>,
+}
+
+/// Fire modes which are detected by looking at button gestures, before the glue section
+/// processes the value.
+///
+/// This is how the corresponding [`FireMode`] variants are persisted.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FireGesture {
+    MultiPress(OnMultiPressFireMode),
+    LongPressRelease(OnLongPressReleaseFireMode),
+    Chord(OnChordFireMode),
+    Sequence(OnSequenceFireMode),
+}
+
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum VirtualColor {
@@ -151,6 +396,30 @@ pub struct PropColor {
     pub prop: String,
 }
 
+/// Language in which a control transformation is written
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum ControlTransformationKind {
+    #[default]
+    #[display(fmt = "EEL")]
+    Eel,
+    #[display(fmt = "Lua")]
+    Lua,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum OutOfRangeBehavior {
     MinOrMax,
@@ -202,6 +471,65 @@ pub enum Interaction {
     InverseTargetValueOffOnly,
 }
 
+/// Makes the feedback blink, pulse, breathe or chase while the target value is within a certain
+/// interval.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FeedbackAnimation {
+    pub effect: FeedbackAnimationEffect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<FeedbackAnimationPeriod>,
+    /// The animation only runs while the target value is within this interval.
+    ///
+    /// If not set, the animation runs all the time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_value_interval: Option<Interval<f64>>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FeedbackAnimationEffect {
+    Blink(BlinkFeedbackAnimationEffect),
+    Pulse,
+    Breathe,
+    Chase(ChaseFeedbackAnimationEffect),
+}
+
+/// Switches the feedback on and off.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct BlinkFeedbackAnimationEffect {
+    /// Fraction of the period during which the feedback is on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duty_cycle: Option<f64>,
+}
+
+/// Switches the feedback on only during one step of a sequence of steps.
+///
+/// Use multiple mappings with the same period and length but different positions in order to
+/// build a running light.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ChaseFeedbackAnimationEffect {
+    pub position: u32,
+    pub length: u32,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FeedbackAnimationPeriod {
+    Millis(MillisFeedbackAnimationPeriod),
+    Beats(BeatsFeedbackAnimationPeriod),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MillisFeedbackAnimationPeriod {
+    pub value: u32,
+}
+
+/// Period in beats, following the play position while the project is playing.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BeatsFeedbackAnimationPeriod {
+    pub value: f64,
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct FeedbackCommons {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -248,5 +576,5 @@ pub struct DynamicFeedback {
     pub script: Option<String>,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub struct Interval<T>(pub T, pub T);
@@ -1,5 +1,6 @@
 mod compartment;
 mod controller;
+mod diff;
 mod glue;
 mod group;
 mod instance;
@@ -13,6 +14,7 @@ mod target;
 
 pub use compartment::*;
 pub use controller::*;
+pub use diff::*;
 pub use glue::*;
 pub use group::*;
 pub use instance::*;
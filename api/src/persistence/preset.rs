@@ -49,6 +49,51 @@ pub struct CommonPresetMetaData {
     pub setup_instructions: Option<String>,
 }
 
+/// Manifest of a preset bundle.
+///
+/// A preset bundle is a zip file containing this manifest as `manifest.json` and the bundled
+/// files in the same structure as in ReaLearn's preset folder, e.g.
+/// `main/my-namespace/my-preset.preset.luau` or `controller/my-namespace/util.luau`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PresetBundleManifest {
+    /// Display name of the bundle.
+    pub name: String,
+    /// Version of the bundle itself.
+    pub version: Version,
+    /// The ReaLearn version for which this bundle was built.
+    ///
+    /// Bundles built for a newer ReaLearn version than the installed one are rejected.
+    pub realearn_version: Version,
+    /// Author of the bundle.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub author: Option<String>,
+    /// Bundle description (prose).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<String>,
+    /// Presets which are not part of this bundle but need to be installed in order to use it.
+    #[serde(default)]
+    pub dependencies: PresetBundleDependencies,
+}
+
+/// Presets on which a preset bundle depends.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct PresetBundleDependencies {
+    /// IDs of controller presets, e.g. `factory/akai/apc-key-25-mk2`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub controller_presets: Vec<String>,
+    /// IDs of main presets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub main_presets: Vec<String>,
+}
+
 /// Meta data that is specific to controller presets.
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub struct ControllerPresetMetaData {
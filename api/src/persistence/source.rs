@@ -17,6 +17,8 @@ pub enum Source {
     Timer(TimerSource),
     RealearnParameter(RealearnParameterSource),
     Speech,
+    AudioSignal(AudioSignalSource),
+    Modulator(ModulatorSource),
     // MIDI
     MidiNoteVelocity(MidiNoteVelocitySource),
     MidiNoteKeyNumber(MidiNoteKeyNumberSource),
@@ -31,6 +33,7 @@ pub enum Source {
     MidiClockTransport(MidiClockTransportSource),
     MidiRaw(MidiRawSource),
     MidiScript(MidiScriptSource),
+    MidiInputScript(MidiInputScriptSource),
     MackieLcd(MackieLcdSource),
     XTouchMackieLcd(XTouchMackieLcdSource),
     MackieSevenSegmentDisplay(MackieSevenSegmentDisplaySource),
@@ -176,6 +179,13 @@ pub struct MidiScriptSource {
     pub script: Option<String>,
 }
 
+#[derive(Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MidiInputScriptSource {
+    /// Lua script which turns incoming MIDI messages into control values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
 /// Kind of a MIDI script
 #[derive(
     Clone,
@@ -302,6 +312,171 @@ pub struct TimerSource {
     pub duration: u64,
 }
 
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioSignalSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal_kind: Option<AudioSignalKind>,
+    /// Attack time of the envelope follower in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attack: Option<u32>,
+    /// Release time of the envelope follower in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release: Option<u32>,
+    /// Detection threshold in dB (used for transient and pitch detection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+}
+
+/// What to derive from the audio signal arriving at the plug-in input pins.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum AudioSignalKind {
+    /// Follows the peak level of the signal.
+    #[default]
+    #[display(fmt = "Peak envelope")]
+    PeakEnvelope,
+    /// Follows the RMS level of the signal.
+    #[display(fmt = "RMS envelope")]
+    RmsEnvelope,
+    /// Fires whenever an onset/transient exceeding the threshold is detected.
+    #[display(fmt = "Transient")]
+    Transient,
+    /// Tracks the pitch of a monophonic signal and emits it as MIDI note number.
+    #[display(fmt = "Pitch")]
+    Pitch,
+}
+
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+pub struct ModulatorSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape: Option<ModulatorShape>,
+    /// LFO frequency in Hz. Ignored if synced to tempo.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<f64>,
+    /// Length of one LFO cycle in musical time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tempo_sync: Option<ModulatorNoteValue>,
+    /// Whether to restart the LFO cycle when the transport starts playing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset_on_transport_start: Option<bool>,
+    /// ADSR attack time in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attack: Option<u32>,
+    /// ADSR decay time in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decay: Option<u32>,
+    /// ADSR sustain level between 0.0 and 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sustain: Option<f64>,
+    /// ADSR release time in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release: Option<u32>,
+    /// Compartment parameter which opens the ADSR gate while its value is above 50%.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_parameter_index: Option<u32>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum ModulatorShape {
+    #[default]
+    #[display(fmt = "Sine")]
+    Sine,
+    #[display(fmt = "Triangle")]
+    Triangle,
+    #[display(fmt = "Saw")]
+    Saw,
+    #[display(fmt = "Square")]
+    Square,
+    #[display(fmt = "Sample & hold")]
+    SampleAndHold,
+    /// Attack-decay-sustain-release envelope, opened and closed by the gate parameter.
+    #[display(fmt = "ADSR envelope")]
+    Adsr,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum ModulatorNoteValue {
+    #[display(fmt = "4 bars")]
+    FourBars,
+    #[display(fmt = "2 bars")]
+    TwoBars,
+    #[display(fmt = "1 bar")]
+    Bar,
+    #[display(fmt = "1/2")]
+    Half,
+    #[display(fmt = "1/4")]
+    Quarter,
+    #[display(fmt = "1/4T")]
+    QuarterTriplet,
+    #[display(fmt = "1/8")]
+    Eighth,
+    #[display(fmt = "1/8T")]
+    EighthTriplet,
+    #[display(fmt = "1/16")]
+    Sixteenth,
+    #[display(fmt = "1/32")]
+    ThirtySecond,
+}
+
+impl ModulatorNoteValue {
+    /// Length in quarter notes, assuming 4/4 time.
+    pub fn beats(&self) -> f64 {
+        use ModulatorNoteValue::*;
+        match self {
+            FourBars => 16.0,
+            TwoBars => 8.0,
+            Bar => 4.0,
+            Half => 2.0,
+            Quarter => 1.0,
+            QuarterTriplet => 2.0 / 3.0,
+            Eighth => 0.5,
+            EighthTriplet => 1.0 / 3.0,
+            Sixteenth => 0.25,
+            ThirtySecond => 0.125,
+        }
+    }
+}
+
 #[derive(Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct KeySource {
     #[serde(skip_serializing_if = "Option::is_none")]
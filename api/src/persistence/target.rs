@@ -101,6 +101,8 @@ pub enum Target {
     Seek(SeekTarget),
     PlayRate(PlayRateTarget),
     Tempo(TempoTarget),
+    ArrangeView(ArrangeViewTarget),
+    MetronomeVolume(MetronomeVolumeTarget),
     GoToBookmark(GoToBookmarkTarget),
     TrackArmState(TrackArmStateTarget),
     TrackParentSendState(TrackParentSendStateTarget),
@@ -111,9 +113,12 @@ pub enum Target {
     TrackSelectionState(TrackSelectionStateTarget),
     TrackAutomationMode(TrackAutomationModeTarget),
     TrackMonitoringMode(TrackMonitoringModeTarget),
+    TrackRecordInput(TrackRecordInputTarget),
+    TrackRecordMode(TrackRecordModeTarget),
     TrackAutomationTouchState(TrackAutomationTouchStateTarget),
     TrackPan(TrackPanTarget),
     TrackWidth(TrackWidthTarget),
+    TrackHeight(TrackHeightTarget),
     TrackVolume(TrackVolumeTarget),
     #[serde(alias = "Track")]
     TrackTool(TrackToolTarget),
@@ -121,6 +126,7 @@ pub enum Target {
     TrackSoloState(TrackSoloStateTarget),
     #[serde(alias = "CycleThroughFx")]
     BrowseFxChain(BrowseFxChainTarget),
+    FxChainEdit(FxChainEditTarget),
     FxOnOffState(FxOnOffStateTarget),
     FxOnlineOfflineState(FxOnlineOfflineStateTarget),
     LoadFxSnapshot(LoadFxSnapshotTarget),
@@ -138,6 +144,7 @@ pub enum Target {
     RoutePan(RoutePanTarget),
     RouteVolume(RouteVolumeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    RouteMatrixCell(RouteMatrixCellTarget),
     #[serde(alias = "ClipTransportAction")]
     PlaytimeSlotTransportAction(PlaytimeSlotTransportActionTarget),
     #[serde(alias = "ClipColumnAction")]
@@ -296,6 +303,51 @@ pub struct TempoTarget {
     pub commons: TargetCommons,
 }
 
+/// Controls the zoom or scroll position of the arrange view.
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct ArrangeViewTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<ArrangeViewProperty>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum ArrangeViewProperty {
+    #[display(fmt = "Horizontal zoom")]
+    HorizontalZoom,
+    #[display(fmt = "Horizontal scroll")]
+    HorizontalScroll,
+    #[display(fmt = "Vertical scroll")]
+    VerticalScroll,
+}
+
+impl Default for ArrangeViewProperty {
+    fn default() -> Self {
+        Self::HorizontalZoom
+    }
+}
+
+/// Controls the volume of the metronome click.
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct MetronomeVolumeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct GoToBookmarkTarget {
     #[serde(flatten)]
@@ -367,6 +419,15 @@ pub struct TrackPeakTarget {
     pub track: Option<TrackDescriptor>,
 }
 
+/// Controls the height of a track in the arrange view.
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrackHeightTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct TrackPhaseTarget {
     #[serde(flatten)]
@@ -421,6 +482,52 @@ pub struct TrackMonitoringModeTarget {
     pub use_selection_ganging: Option<bool>,
 }
 
+/// Browses the record inputs of a track (audio and MIDI).
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackRecordInputTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
+/// Sets the record mode of a track.
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackRecordModeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+    pub mode: TrackRecordMode,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum TrackRecordMode {
+    #[default]
+    #[display(fmt = "Input (audio or MIDI)")]
+    Input,
+    #[display(fmt = "Output (stereo)")]
+    Output,
+    #[display(fmt = "MIDI overdub")]
+    MidiOverdub,
+    #[display(fmt = "MIDI replace")]
+    MidiReplace,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct TrackAutomationTouchStateTarget {
     #[serde(flatten)]
@@ -746,6 +853,59 @@ impl Default for FxToolAction {
     }
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct FxChainEditTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fx: Option<FxDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<FxChainEditAction>,
+    /// Plug-in ID in pipe format, e.g. `vst3|565354507438717069616E6F74657120`.
+    ///
+    /// Only relevant for actions which add new FX.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_id: Option<String>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum FxChainEditAction {
+    /// Adds the plug-in at the end of the FX chain.
+    #[display(fmt = "Add plug-in at end of chain")]
+    Add,
+    /// Inserts the plug-in right after the FX.
+    #[display(fmt = "Insert plug-in after FX")]
+    InsertAfter,
+    #[display(fmt = "Remove FX")]
+    Remove,
+    #[display(fmt = "Move FX up")]
+    MoveUp,
+    #[display(fmt = "Move FX down")]
+    MoveDown,
+    /// Replaces the FX with the plug-in (if it's not already of that type).
+    #[display(fmt = "Replace FX with plug-in")]
+    Replace,
+}
+
+impl Default for FxChainEditAction {
+    fn default() -> Self {
+        Self::Add
+    }
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct FxVisibilityTarget {
     #[serde(flatten)]
@@ -791,6 +951,53 @@ pub struct RouteAutomationModeTarget {
     pub poll_for_feedback: Option<bool>,
 }
 
+/// Creates or removes a send between two tracks.
+///
+/// The route descriptor must point to the related track via `ById` or `ByName` (the route
+/// doesn't need to exist).
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct RouteMatrixCellTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub route: RouteDescriptor,
+    /// Send mode which is applied when creating the send.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_mode: Option<SendMode>,
+    /// Zero-based index of the first source channel of the stereo pair.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_channel: Option<u32>,
+    /// Zero-based index of the first destination channel of the stereo pair.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_channel: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum SendMode {
+    #[default]
+    #[display(fmt = "Post-fader (post-pan)")]
+    PostFader,
+    #[display(fmt = "Pre-FX")]
+    PreFx,
+    #[display(fmt = "Pre-fader (post-FX)")]
+    PreFaderPostFx,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct RouteMonoStateTarget {
     #[serde(flatten)]
@@ -1355,6 +1562,17 @@ pub enum TransportAction {
     Pause,
     Record,
     Repeat,
+    Metronome,
+    CountIn,
+    PreRoll,
+    AutoCrossfade,
+    Snap,
+    RecordModeNormal,
+    RecordModeTimeSelectionAutoPunch,
+    RecordModeItemAutoPunch,
+    RippleOff,
+    RipplePerTrack,
+    RippleAllTracks,
 }
 
 #[derive(
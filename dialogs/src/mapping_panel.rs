@@ -553,10 +553,14 @@ pub fn create(context: ScopedContext, ids: &mut IdGenerator) -> Dialog {
             rect(146, 409, 73, 8),
         ) + WS_TABSTOP,
         ltext(
-            "Control transformation (EEL)",
+            "Control transformation",
             ids.named_id("ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL"),
-            context.rect(15, 423, 95, 9),
+            context.rect(15, 423, 80, 9),
         ) + NOT_WS_GROUP,
+        dropdown(
+            ids.named_id("ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX"),
+            context.rect(160, 420, 54, 15),
+        ) + WS_TABSTOP,
         edittext(
             ids.named_id("ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL"),
             context.rect(15, 435, 184, 14),
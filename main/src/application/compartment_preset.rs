@@ -1,9 +1,19 @@
 use crate::application::CompartmentModel;
-use crate::domain::CompartmentKind;
+use crate::domain::{CompartmentKind, Unit};
+use reaper_high::Project;
 use std::fmt;
 
 pub trait CompartmentPresetManager: fmt::Debug {
     fn find_by_id(&self, id: &str) -> Option<CompartmentPresetModel>;
+
+    /// Like [`Self::find_by_id`] but lets Lua presets read the custom compartment data of the given
+    /// unit and the tracks of the given project (the unit's project) while being built.
+    fn find_by_id_for_unit(
+        &self,
+        id: &str,
+        unit: &Unit,
+        project: Project,
+    ) -> Option<CompartmentPresetModel>;
 }
 
 #[derive(Clone, Debug)]
@@ -7,10 +7,11 @@ use crate::application::{
 };
 use crate::domain::{
     ActivationCondition, CompartmentKind, CompoundMappingSource, CompoundMappingTarget,
-    EelTransformation, ExtendedProcessorContext, ExtendedSourceCharacter, FeedbackSendBehavior,
-    GroupId, MainMapping, MappingId, MappingKey, Mode, PersistentMappingProcessingState,
-    ProcessorMappingOptions, QualifiedMappingId, RealearnTarget, ReaperTarget, Script, Tag,
-    TargetCharacter, UnresolvedCompoundMappingTarget, VirtualFx, VirtualTrack,
+    EncoderAcceleration, ExtendedProcessorContext, ExtendedSourceCharacter, FeedbackAnimation,
+    FeedbackSendBehavior, FireGesture, Glide, GroupId, MainMapping, MappingId, MappingKey, Mode,
+    PersistentMappingProcessingState, ProcessorMappingOptions, QualifiedMappingId, RealearnTarget,
+    ReaperTarget, ScaleQuantization, Tag, TargetCharacter, UnresolvedCompoundMappingTarget,
+    VirtualFx, VirtualTrack,
 };
 use helgoboss_learn::{
     AbsoluteMode, ControlType, DetailedSourceCharacter, DiscreteIncrement, Interval,
@@ -484,12 +485,7 @@ impl MappingModel {
             target_is_virtual: self.target_model.is_virtual(),
             // TODO-high-discrete Enable (also taking source into consideration!)
             target_supports_discrete_values: false,
-            control_transformation_uses_time: {
-                let transformation = EelTransformation::compile_for_control(
-                    self.mode_model.eel_control_transformation(),
-                );
-                transformation.map(|t| t.uses_time()).unwrap_or(false)
-            },
+            control_transformation_uses_time: self.mode_model.control_transformation_uses_time(),
             is_feedback: false,
             make_absolute: self.mode_model.make_absolute(),
             use_textual_feedback: self.mode_model.feedback_type().is_textual(),
@@ -587,6 +583,19 @@ impl MappingModel {
             source,
             mode,
             self.mode_model.group_interaction(),
+            self.mode_model
+                .create_encoder_acceleration_settings()
+                .map(EncoderAcceleration::new),
+            self.mode_model.create_glide_settings().map(Glide::new),
+            self.mode_model
+                .create_scale_quantization_settings()
+                .map(ScaleQuantization::new),
+            self.mode_model
+                .create_fire_gesture_settings()
+                .map(FireGesture::new),
+            self.mode_model
+                .create_feedback_animation_settings()
+                .map(FeedbackAnimation::new),
             unresolved_target,
             group_data.activation_condition,
             activation_condition,
@@ -1,4 +1,9 @@
-use crate::domain::{Backbone, EelTransformation, LuaFeedbackScript, Mode};
+use crate::domain::{
+    lua_script_uses_time, Backbone, EelTransformation, EncoderAccelerationSettings,
+    FeedbackAnimationEffect, FeedbackAnimationPeriod, FeedbackAnimationSettings,
+    FireGestureSettings, FlexibleTransformation, GlideDuration, GlideSettings, LuaFeedbackScript,
+    LuaTransformation, Mode, ScaleMask, ScaleQuantizationSettings, Script,
+};
 
 use helgoboss_learn::{
     check_mode_applicability, create_unit_value_interval, full_discrete_interval,
@@ -11,7 +16,10 @@ use helgoboss_learn::{
 use crate::application::{Affected, Change, GetProcessingRelevance, ProcessingRelevance};
 use crate::base::CloneAsDefault;
 use base::hash_util::clone_to_other_hash_map;
-use realearn_api::persistence::FeedbackValueTable;
+use realearn_api::persistence::{
+    ControlTransformationKind, EncoderAcceleration, FeedbackAnimation, FeedbackValueTable,
+    FireGesture, Glide, ResponseCurve, ScaleQuantization,
+};
 use std::time::Duration;
 
 pub enum ModeCommand {
@@ -29,12 +37,17 @@ pub enum ModeCommand {
     SetTurboRate(Duration),
     SetLegacyJumpInterval(Option<Interval<UnitValue>>),
     SetOutOfRangeBehavior(OutOfRangeBehavior),
+    SetResponseCurve(Option<ResponseCurve>),
     SetFireMode(FireMode),
+    SetFireGesture(Option<FireGesture>),
     SetRoundTargetValue(bool),
+    SetGlide(Option<Glide>),
+    SetScaleQuantization(Option<ScaleQuantization>),
     SetTakeoverMode(TakeoverMode),
     SetButtonUsage(ButtonUsage),
     SetEncoderUsage(EncoderUsage),
     SetEelControlTransformation(String),
+    SetControlTransformationKind(ControlTransformationKind),
     SetEelFeedbackTransformation(String),
     SetStepSizeInterval(Interval<UnitValue>),
     SetStepFactorInterval(Interval<DiscreteIncrement>),
@@ -44,6 +57,7 @@ pub enum ModeCommand {
     SetMaxStepFactor(DiscreteIncrement),
     SetRotate(bool),
     SetMakeAbsolute(bool),
+    SetEncoderAcceleration(Option<EncoderAcceleration>),
     SetGroupInteraction(GroupInteraction),
     SetTargetValueSequence(ValueSequence),
     SetFeedbackType(FeedbackType),
@@ -51,6 +65,7 @@ pub enum ModeCommand {
     SetFeedbackColor(Option<VirtualColor>),
     SetFeedbackBackgroundColor(Option<VirtualColor>),
     SetFeedbackValueTable(Option<FeedbackValueTable>),
+    SetFeedbackAnimations(Vec<FeedbackAnimation>),
     /// This doesn't reset the mode type, just all the values.
     ResetWithinType,
 }
@@ -65,17 +80,23 @@ pub enum ModeProp {
     TurboRate,
     LegacyJumpInterval,
     OutOfRangeBehavior,
+    ResponseCurve,
     FireMode,
+    FireGesture,
     RoundTargetValue,
+    Glide,
+    ScaleQuantization,
     TakeoverMode,
     ButtonUsage,
     EncoderUsage,
     EelControlTransformation,
+    ControlTransformationKind,
     EelFeedbackTransformation,
     StepSizeInterval,
     StepFactorInterval,
     Rotate,
     MakeAbsolute,
+    EncoderAcceleration,
     GroupInteraction,
     TargetValueSequence,
     FeedbackType,
@@ -83,6 +104,7 @@ pub enum ModeProp {
     FeedbackColor,
     FeedbackBackgroundColor,
     FeedbackValueTable,
+    FeedbackAnimations,
 }
 
 impl GetProcessingRelevance for ModeProp {
@@ -108,12 +130,22 @@ pub struct ModeModel {
     /// This is only set for old presets in order to not change behavior.
     legacy_jump_interval: Option<Interval<UnitValue>>,
     out_of_range_behavior: OutOfRangeBehavior,
+    /// Applied between source and target interval. Off if `None`.
+    response_curve: Option<ResponseCurve>,
     fire_mode: FireMode,
+    /// Takes precedence over `fire_mode` if set.
+    fire_gesture: Option<FireGesture>,
     round_target_value: bool,
+    /// Smooth transition to new target values. Off if `None`.
+    glide: Option<Glide>,
+    /// Snapping of discrete target values to a musical scale. Off if `None`.
+    scale_quantization: Option<ScaleQuantization>,
     takeover_mode: TakeoverMode,
     button_usage: ButtonUsage,
     encoder_usage: EncoderUsage,
+    /// Despite the name, this can also contain Lua code (see `control_transformation_kind`).
     eel_control_transformation: String,
+    control_transformation_kind: ControlTransformationKind,
     eel_feedback_transformation: String,
     // For relative control values.
     /// A step size is the positive, absolute size of an increment. 0.0 represents no increment,
@@ -133,6 +165,8 @@ pub struct ModeModel {
     step_factor_interval: Interval<DiscreteIncrement>,
     rotate: bool,
     make_absolute: bool,
+    /// Time-based acceleration of relative control. Off if `None`.
+    encoder_acceleration: Option<EncoderAcceleration>,
     group_interaction: GroupInteraction,
     target_value_sequence: ValueSequence,
     feedback_type: FeedbackType,
@@ -140,6 +174,8 @@ pub struct ModeModel {
     feedback_color: Option<VirtualColor>,
     feedback_background_color: Option<VirtualColor>,
     feedback_value_table: Option<FeedbackValueTable>,
+    /// Blinking, pulsing etc. depending on the target value. The first matching one wins.
+    feedback_animations: Vec<FeedbackAnimation>,
 }
 
 impl Default for ModeModel {
@@ -156,17 +192,23 @@ impl Default for ModeModel {
             turbo_rate: Duration::from_millis(0),
             legacy_jump_interval: None,
             out_of_range_behavior: Default::default(),
+            response_curve: None,
             fire_mode: Default::default(),
+            fire_gesture: None,
             round_target_value: false,
+            glide: None,
+            scale_quantization: None,
             takeover_mode: Default::default(),
             button_usage: Default::default(),
             encoder_usage: Default::default(),
             eel_control_transformation: String::new(),
+            control_transformation_kind: Default::default(),
             eel_feedback_transformation: String::new(),
             step_size_interval: Self::default_step_size_interval(),
             step_factor_interval: Self::default_step_factor_interval(),
             rotate: false,
             make_absolute: false,
+            encoder_acceleration: None,
             group_interaction: Default::default(),
             target_value_sequence: Default::default(),
             feedback_type: Default::default(),
@@ -174,6 +216,7 @@ impl Default for ModeModel {
             feedback_color: Default::default(),
             feedback_background_color: Default::default(),
             feedback_value_table: None,
+            feedback_animations: vec![],
         }
     }
 }
@@ -249,14 +292,30 @@ impl<'a> Change<'a> for ModeModel {
                 self.out_of_range_behavior = v;
                 One(P::OutOfRangeBehavior)
             }
+            C::SetResponseCurve(v) => {
+                self.response_curve = v;
+                One(P::ResponseCurve)
+            }
             C::SetFireMode(v) => {
                 self.fire_mode = v;
                 One(P::FireMode)
             }
+            C::SetFireGesture(v) => {
+                self.fire_gesture = v;
+                One(P::FireGesture)
+            }
             C::SetRoundTargetValue(v) => {
                 self.round_target_value = v;
                 One(P::RoundTargetValue)
             }
+            C::SetGlide(v) => {
+                self.glide = v;
+                One(P::Glide)
+            }
+            C::SetScaleQuantization(v) => {
+                self.scale_quantization = v;
+                One(P::ScaleQuantization)
+            }
             C::SetTakeoverMode(v) => {
                 self.takeover_mode = v;
                 One(P::TakeoverMode)
@@ -273,6 +332,10 @@ impl<'a> Change<'a> for ModeModel {
                 self.eel_control_transformation = v;
                 One(P::EelControlTransformation)
             }
+            C::SetControlTransformationKind(v) => {
+                self.control_transformation_kind = v;
+                One(P::ControlTransformationKind)
+            }
             C::SetEelFeedbackTransformation(v) => {
                 self.eel_feedback_transformation = v;
                 One(P::EelFeedbackTransformation)
@@ -309,6 +372,10 @@ impl<'a> Change<'a> for ModeModel {
                 self.make_absolute = v;
                 One(P::MakeAbsolute)
             }
+            C::SetEncoderAcceleration(v) => {
+                self.encoder_acceleration = v;
+                One(P::EncoderAcceleration)
+            }
             C::SetGroupInteraction(v) => {
                 self.group_interaction = v;
                 One(P::GroupInteraction)
@@ -337,6 +404,10 @@ impl<'a> Change<'a> for ModeModel {
                 self.feedback_value_table = v;
                 One(P::FeedbackValueTable)
             }
+            C::SetFeedbackAnimations(v) => {
+                self.feedback_animations = v;
+                One(P::FeedbackAnimations)
+            }
             C::ResetWithinType => {
                 *self = Default::default();
                 Multiple
@@ -365,6 +436,10 @@ impl ModeModel {
         self.feedback_value_table.as_ref()
     }
 
+    pub fn feedback_animations(&self) -> &[FeedbackAnimation] {
+        &self.feedback_animations
+    }
+
     pub fn absolute_mode(&self) -> AbsoluteMode {
         self.absolute_mode
     }
@@ -397,14 +472,30 @@ impl ModeModel {
         self.out_of_range_behavior
     }
 
+    pub fn response_curve(&self) -> Option<&ResponseCurve> {
+        self.response_curve.as_ref()
+    }
+
     pub fn fire_mode(&self) -> FireMode {
         self.fire_mode
     }
 
+    pub fn fire_gesture(&self) -> Option<&FireGesture> {
+        self.fire_gesture.as_ref()
+    }
+
     pub fn round_target_value(&self) -> bool {
         self.round_target_value
     }
 
+    pub fn glide(&self) -> Option<&Glide> {
+        self.glide.as_ref()
+    }
+
+    pub fn scale_quantization(&self) -> Option<&ScaleQuantization> {
+        self.scale_quantization.as_ref()
+    }
+
     pub fn takeover_mode(&self) -> TakeoverMode {
         self.takeover_mode
     }
@@ -421,6 +512,10 @@ impl ModeModel {
         &self.eel_control_transformation
     }
 
+    pub fn control_transformation_kind(&self) -> ControlTransformationKind {
+        self.control_transformation_kind
+    }
+
     pub fn eel_feedback_transformation(&self) -> &str {
         &self.eel_feedback_transformation
     }
@@ -441,6 +536,10 @@ impl ModeModel {
         self.make_absolute
     }
 
+    pub fn encoder_acceleration(&self) -> Option<&EncoderAcceleration> {
+        self.encoder_acceleration.as_ref()
+    }
+
     pub fn group_interaction(&self) -> GroupInteraction {
         self.group_interaction
     }
@@ -487,6 +586,194 @@ impl ModeModel {
         })
     }
 
+    /// Returns whether the control transformation needs to be invoked repeatedly.
+    pub fn control_transformation_uses_time(&self) -> bool {
+        match self.control_transformation_kind {
+            ControlTransformationKind::Eel => {
+                EelTransformation::compile_for_control(&self.eel_control_transformation)
+                    .map(|t| t.uses_time())
+                    .unwrap_or(false)
+            }
+            ControlTransformationKind::Lua => {
+                lua_script_uses_time(&self.eel_control_transformation)
+            }
+        }
+    }
+
+    /// The response curve is applied even if the script transformation is not relevant.
+    fn create_control_transformation(
+        &self,
+        script_is_relevant: bool,
+    ) -> Option<FlexibleTransformation> {
+        let script_transformation = if script_is_relevant {
+            self.create_script_control_transformation()
+        } else {
+            None
+        };
+        match &self.response_curve {
+            None => script_transformation,
+            Some(curve) => Some(FlexibleTransformation::response_curve(
+                curve.into(),
+                false,
+                script_transformation,
+            )),
+        }
+    }
+
+    /// The response curve is applied even if the script transformation is not relevant.
+    fn create_feedback_transformation(
+        &self,
+        script_is_relevant: bool,
+    ) -> Option<FlexibleTransformation> {
+        let script_transformation = if script_is_relevant {
+            EelTransformation::compile_for_feedback(&self.eel_feedback_transformation)
+                .ok()
+                .map(FlexibleTransformation::Eel)
+        } else {
+            None
+        };
+        match &self.response_curve {
+            None => script_transformation,
+            Some(curve) => Some(FlexibleTransformation::response_curve(
+                curve.into(),
+                true,
+                script_transformation,
+            )),
+        }
+    }
+
+    fn create_script_control_transformation(&self) -> Option<FlexibleTransformation> {
+        let transformation = match self.control_transformation_kind {
+            ControlTransformationKind::Eel => FlexibleTransformation::Eel(
+                EelTransformation::compile_for_control(&self.eel_control_transformation).ok()?,
+            ),
+            ControlTransformationKind::Lua => {
+                let lua = unsafe { Backbone::main_thread_lua() };
+                FlexibleTransformation::lua(
+                    LuaTransformation::compile_for_control(lua, &self.eel_control_transformation)
+                        .ok()?,
+                )
+            }
+        };
+        Some(transformation)
+    }
+
+    /// Creates the encoder acceleration settings, falling back to defaults for unset values.
+    pub fn create_encoder_acceleration_settings(&self) -> Option<EncoderAccelerationSettings> {
+        let acc = self.encoder_acceleration.as_ref()?;
+        let defaults = EncoderAccelerationSettings::default();
+        let settings = EncoderAccelerationSettings {
+            curve: acc.curve.unwrap_or(defaults.curve),
+            max_factor: acc.max_factor.unwrap_or(defaults.max_factor).max(1),
+            reset_timeout: acc
+                .reset_timeout
+                .map(|millis| Duration::from_millis(millis as _))
+                .unwrap_or(defaults.reset_timeout),
+        };
+        Some(settings)
+    }
+
+    /// Creates the glide settings, falling back to defaults for unset values.
+    pub fn create_glide_settings(&self) -> Option<GlideSettings> {
+        use realearn_api::persistence::GlideDuration as D;
+        let glide = self.glide.as_ref()?;
+        let duration = match &glide.duration {
+            None => GlideDuration::Time(Duration::from_millis(500)),
+            Some(D::Millis(d)) => GlideDuration::Time(Duration::from_millis(d.value as _)),
+            Some(D::Beats(d)) => GlideDuration::Beats(d.value),
+        };
+        let settings = GlideSettings {
+            duration,
+            curve: glide.curve.unwrap_or_default(),
+        };
+        Some(settings)
+    }
+
+    /// Creates the fire gesture settings, falling back to defaults for unset values.
+    pub fn create_fire_gesture_settings(&self) -> Option<FireGestureSettings> {
+        let millis = |v: Option<u32>, default: u64| {
+            Duration::from_millis(v.map(|v| v as u64).unwrap_or(default))
+        };
+        let settings = match self.fire_gesture.as_ref()? {
+            FireGesture::MultiPress(m) => FireGestureSettings::MultiPress {
+                press_count: m.press_count.max(1),
+                max_interval: millis(m.max_interval, 300),
+            },
+            FireGesture::LongPressRelease(m) => FireGestureSettings::LongPressRelease {
+                min_duration: millis(m.min_duration, 500),
+            },
+            FireGesture::Chord(m) => FireGestureSettings::Chord {
+                held_mapping_key: m.held_mapping.as_str().into(),
+            },
+            FireGesture::Sequence(m) => FireGestureSettings::Sequence {
+                previous_mapping_key: m.previous_mapping.as_str().into(),
+                max_interval: millis(m.max_interval, 1000),
+            },
+        };
+        Some(settings)
+    }
+
+    /// Creates the scale quantization settings, falling back to C major for unset values.
+    ///
+    /// An invalid custom scale falls back to the chosen predefined scale.
+    pub fn create_scale_quantization_settings(&self) -> Option<ScaleQuantizationSettings> {
+        let q = self.scale_quantization.as_ref()?;
+        let predefined_mask = ScaleMask::from_scale(q.scale.unwrap_or_default());
+        let settings = ScaleQuantizationSettings {
+            root: q.root.unwrap_or(0) % 12,
+            mask: q
+                .custom_scale
+                .as_deref()
+                .and_then(|s| ScaleMask::parse(s).ok())
+                .unwrap_or(predefined_mask),
+            root_param: q.root_parameter_index.and_then(|i| i.try_into().ok()),
+            scale_param: q.scale_parameter_index.and_then(|i| i.try_into().ok()),
+        };
+        Some(settings)
+    }
+
+    /// Creates the feedback animation settings, falling back to defaults for unset values.
+    ///
+    /// Returns `None` if there are no animations.
+    pub fn create_feedback_animation_settings(&self) -> Option<Vec<FeedbackAnimationSettings>> {
+        use realearn_api::persistence::FeedbackAnimationEffect as E;
+        use realearn_api::persistence::FeedbackAnimationPeriod as P;
+        if self.feedback_animations.is_empty() {
+            return None;
+        }
+        let settings = self
+            .feedback_animations
+            .iter()
+            .map(|a| FeedbackAnimationSettings {
+                effect: match &a.effect {
+                    E::Blink(b) => FeedbackAnimationEffect::Blink {
+                        duty_cycle: b.duty_cycle.unwrap_or(0.5).clamp(0.0, 1.0),
+                    },
+                    E::Pulse => FeedbackAnimationEffect::Pulse,
+                    E::Breathe => FeedbackAnimationEffect::Breathe,
+                    E::Chase(c) => FeedbackAnimationEffect::Chase {
+                        position: c.position,
+                        length: c.length,
+                    },
+                },
+                period: match &a.period {
+                    None => FeedbackAnimationPeriod::Time(Duration::from_millis(500)),
+                    Some(P::Millis(p)) => {
+                        FeedbackAnimationPeriod::Time(Duration::from_millis(p.value as _))
+                    }
+                    Some(P::Beats(p)) => FeedbackAnimationPeriod::Beats(p.value),
+                },
+                target_value_interval: match a.target_value_interval {
+                    None => full_unit_interval(),
+                    Some(i) => {
+                        Interval::new_auto(UnitValue::new_clamped(i.0), UnitValue::new_clamped(i.1))
+                    }
+                },
+            })
+            .collect();
+        Some(settings)
+    }
+
     /// Creates a mode reflecting this model's current values
     #[allow(clippy::if_same_then_else)]
     pub fn create_mode(
@@ -608,16 +895,10 @@ impl ModeModel {
             } else {
                 OutOfRangeBehavior::default()
             },
-            control_transformation: if is_relevant(ModeParameter::ControlTransformation) {
-                EelTransformation::compile_for_control(&self.eel_control_transformation).ok()
-            } else {
-                None
-            },
-            feedback_transformation: if is_relevant(ModeParameter::FeedbackTransformation) {
-                EelTransformation::compile_for_feedback(&self.eel_feedback_transformation).ok()
-            } else {
-                None
-            },
+            control_transformation: self
+                .create_control_transformation(is_relevant(ModeParameter::ControlTransformation)),
+            feedback_transformation: self
+                .create_feedback_transformation(is_relevant(ModeParameter::FeedbackTransformation)),
             feedback_value_table: self.feedback_value_table.as_ref().map(|t| match t {
                 FeedbackValueTable::FromTextToDiscrete(v) => {
                     helgoboss_learn::FeedbackValueTable::FromTextToDiscrete(
@@ -3,10 +3,13 @@ use crate::application::{
 };
 use crate::base::CloneAsDefault;
 use crate::domain::{
-    Backbone, CompartmentKind, CompartmentParamIndex, CompoundMappingSource, EelMidiSourceScript,
-    ExtendedSourceCharacter, FlexibleMidiSourceScript, KeySource, Keystroke, LuaMidiSourceScript,
-    MidiSource, RealearnParameterSource, ReaperSource, SpeechSource, TimerSource,
-    VirtualControlElement, VirtualControlElementId, VirtualSource, VirtualTarget,
+    AdsrSettings, AudioSignalSource, Backbone, CompartmentKind, CompartmentParamIndex,
+    CompoundMappingSource, EelMidiSourceScript, ExtendedSourceCharacter, FlexibleMidiSourceScript,
+    KeySource, Keystroke, LuaMidiInputScript, LuaMidiSourceScript, MidiInputScriptSource,
+    MidiSource, ModulatorSettings, ModulatorSource, RealearnParameterSource, ReaperSource,
+    SpeechSource, TimerSource, VirtualControlElement, VirtualControlElementId, VirtualSource,
+    VirtualTarget, DEFAULT_AUDIO_SIGNAL_ATTACK_MS, DEFAULT_AUDIO_SIGNAL_RELEASE_MS,
+    DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB, DEFAULT_MODULATOR_FREQUENCY,
 };
 use derive_more::Display;
 use helgoboss_learn::{
@@ -17,7 +20,9 @@ use helgoboss_learn::{
 };
 use helgoboss_midi::{Channel, U14, U7};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use realearn_api::persistence::MidiScriptKind;
+use realearn_api::persistence::{
+    AudioSignalKind, MidiScriptKind, ModulatorNoteValue, ModulatorShape,
+};
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 use std::borrow::Cow;
@@ -52,6 +57,15 @@ pub enum SourceCommand {
     SetOscFeedbackArgs(Vec<String>),
     SetReaperSourceType(ReaperSourceType),
     SetTimerMillis(u64),
+    SetAudioSignalKind(AudioSignalKind),
+    SetAudioSignalAttackMillis(u32),
+    SetAudioSignalReleaseMillis(u32),
+    SetAudioSignalThreshold(f64),
+    SetModulatorShape(ModulatorShape),
+    SetModulatorFrequency(f64),
+    SetModulatorTempoSync(Option<ModulatorNoteValue>),
+    SetModulatorResetOnTransportStart(bool),
+    SetModulatorAdsr(AdsrSettings),
     SetParameterIndex(CompartmentParamIndex),
     SetKeystroke(Option<Keystroke>),
     SetControlElementType(VirtualControlElementType),
@@ -85,6 +99,15 @@ pub enum SourceProp {
     ControlElementType,
     ControlElementId,
     TimerMillis,
+    AudioSignalKind,
+    AudioSignalAttackMillis,
+    AudioSignalReleaseMillis,
+    AudioSignalThreshold,
+    ModulatorShape,
+    ModulatorFrequency,
+    ModulatorTempoSync,
+    ModulatorResetOnTransportStart,
+    ModulatorAdsr,
     ParameterIndex,
     Keystroke,
 }
@@ -205,6 +228,42 @@ impl<'a> Change<'a> for SourceModel {
                 self.timer_millis = v;
                 One(P::TimerMillis)
             }
+            C::SetAudioSignalKind(v) => {
+                self.audio_signal_kind = v;
+                One(P::AudioSignalKind)
+            }
+            C::SetAudioSignalAttackMillis(v) => {
+                self.audio_signal_attack_millis = v;
+                One(P::AudioSignalAttackMillis)
+            }
+            C::SetAudioSignalReleaseMillis(v) => {
+                self.audio_signal_release_millis = v;
+                One(P::AudioSignalReleaseMillis)
+            }
+            C::SetAudioSignalThreshold(v) => {
+                self.audio_signal_threshold = v;
+                One(P::AudioSignalThreshold)
+            }
+            C::SetModulatorShape(v) => {
+                self.modulator_shape = v;
+                One(P::ModulatorShape)
+            }
+            C::SetModulatorFrequency(v) => {
+                self.modulator_frequency = v;
+                One(P::ModulatorFrequency)
+            }
+            C::SetModulatorTempoSync(v) => {
+                self.modulator_tempo_sync = v;
+                One(P::ModulatorTempoSync)
+            }
+            C::SetModulatorResetOnTransportStart(v) => {
+                self.modulator_reset_on_transport_start = v;
+                One(P::ModulatorResetOnTransportStart)
+            }
+            C::SetModulatorAdsr(v) => {
+                self.modulator_adsr = v;
+                One(P::ModulatorAdsr)
+            }
             C::SetParameterIndex(v) => {
                 self.parameter_index = v;
                 One(P::ParameterIndex)
@@ -247,6 +306,18 @@ pub struct SourceModel {
     // REAPER
     reaper_source_type: ReaperSourceType,
     timer_millis: u64,
+    audio_signal_kind: AudioSignalKind,
+    audio_signal_attack_millis: u32,
+    audio_signal_release_millis: u32,
+    /// In dB.
+    audio_signal_threshold: f64,
+    modulator_shape: ModulatorShape,
+    /// In Hz.
+    modulator_frequency: f64,
+    modulator_tempo_sync: Option<ModulatorNoteValue>,
+    modulator_reset_on_transport_start: bool,
+    modulator_adsr: AdsrSettings,
+    /// Also used as gate parameter of the modulator.
     parameter_index: CompartmentParamIndex,
     // Key
     keystroke: Option<Keystroke>,
@@ -289,6 +360,15 @@ impl SourceModel {
             osc_feedback_args: vec![],
             reaper_source_type: Default::default(),
             timer_millis: Default::default(),
+            audio_signal_kind: Default::default(),
+            audio_signal_attack_millis: DEFAULT_AUDIO_SIGNAL_ATTACK_MS,
+            audio_signal_release_millis: DEFAULT_AUDIO_SIGNAL_RELEASE_MS,
+            audio_signal_threshold: DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB,
+            modulator_shape: Default::default(),
+            modulator_frequency: DEFAULT_MODULATOR_FREQUENCY,
+            modulator_tempo_sync: None,
+            modulator_reset_on_transport_start: true,
+            modulator_adsr: Default::default(),
             parameter_index: Default::default(),
             keystroke: None,
         }
@@ -394,6 +474,42 @@ impl SourceModel {
         self.timer_millis
     }
 
+    pub fn audio_signal_kind(&self) -> AudioSignalKind {
+        self.audio_signal_kind
+    }
+
+    pub fn audio_signal_attack_millis(&self) -> u32 {
+        self.audio_signal_attack_millis
+    }
+
+    pub fn audio_signal_release_millis(&self) -> u32 {
+        self.audio_signal_release_millis
+    }
+
+    pub fn audio_signal_threshold(&self) -> f64 {
+        self.audio_signal_threshold
+    }
+
+    pub fn modulator_shape(&self) -> ModulatorShape {
+        self.modulator_shape
+    }
+
+    pub fn modulator_frequency(&self) -> f64 {
+        self.modulator_frequency
+    }
+
+    pub fn modulator_tempo_sync(&self) -> Option<ModulatorNoteValue> {
+        self.modulator_tempo_sync
+    }
+
+    pub fn modulator_reset_on_transport_start(&self) -> bool {
+        self.modulator_reset_on_transport_start
+    }
+
+    pub fn modulator_adsr(&self) -> AdsrSettings {
+        self.modulator_adsr
+    }
+
     pub fn control_element_type(&self) -> VirtualControlElementType {
         self.control_element_type
     }
@@ -510,6 +626,18 @@ impl SourceModel {
                     RealearnParameter(p) => {
                         self.parameter_index = p.parameter_index;
                     }
+                    AudioSignal(s) => {
+                        self.audio_signal_kind = s.kind();
+                    }
+                    Modulator(s) => {
+                        let settings = s.settings();
+                        self.modulator_shape = settings.shape;
+                        self.modulator_frequency = settings.frequency;
+                        self.modulator_tempo_sync = settings.tempo_sync;
+                        self.modulator_reset_on_transport_start = settings.reset_on_transport_start;
+                        self.modulator_adsr = settings.adsr;
+                        self.parameter_index = settings.gate_parameter_index;
+                    }
                     MidiDeviceChanges | RealearnInstanceStart | Timer(_) | Speech(_) => {}
                 }
             }
@@ -520,6 +648,11 @@ impl SourceModel {
                 self.category = SourceCategory::Keyboard;
                 self.keystroke = Some(s.stroke());
             }
+            MidiInputScript(_) => {
+                // The script itself can't be derived from the compiled source.
+                self.category = SourceCategory::Midi;
+                self.midi_source_type = MidiSourceType::InputScript;
+            }
         };
         Some(Affected::Multiple)
     }
@@ -563,6 +696,14 @@ impl SourceModel {
                 DetailedSourceCharacter::Relative,
             ],
             CompoundMappingSource::Key(_) => vec![DetailedSourceCharacter::MomentaryOnOffButton],
+            // Can be anything, depending on what the script returns.
+            CompoundMappingSource::MidiInputScript(_) => vec![
+                DetailedSourceCharacter::MomentaryVelocitySensitiveButton,
+                DetailedSourceCharacter::MomentaryOnOffButton,
+                DetailedSourceCharacter::Trigger,
+                DetailedSourceCharacter::RangeControl,
+                DetailedSourceCharacter::Relative,
+            ],
         }
     }
 
@@ -651,6 +792,10 @@ impl SourceModel {
                     Display => MidiSource::Display {
                         spec: self.display_spec(),
                     },
+                    InputScript => {
+                        let source = self.create_midi_input_script_source()?;
+                        return Some(CompoundMappingSource::MidiInputScript(source));
+                    }
                 };
                 CompoundMappingSource::Midi(midi_source)
             }
@@ -679,6 +824,8 @@ impl SourceModel {
                         ReaperSource::RealearnParameter(self.create_realearn_parameter_source())
                     }
                     Speech => ReaperSource::Speech(SpeechSource::new()),
+                    AudioSignal => ReaperSource::AudioSignal(self.create_audio_signal_source()),
+                    Modulator => ReaperSource::Modulator(self.create_modulator_source()),
                 };
                 CompoundMappingSource::Reaper(reaper_source)
             }
@@ -688,6 +835,12 @@ impl SourceModel {
         Some(source)
     }
 
+    fn create_midi_input_script_source(&self) -> Option<MidiInputScriptSource> {
+        let lua = unsafe { Backbone::main_thread_lua() };
+        let script = LuaMidiInputScript::compile(lua, &self.midi_script).ok()?;
+        Some(MidiInputScriptSource::new(script, &self.midi_script))
+    }
+
     pub fn create_key_source(&self) -> Option<KeySource> {
         Some(KeySource::new(self.keystroke?))
     }
@@ -696,6 +849,26 @@ impl SourceModel {
         TimerSource::new(Duration::from_millis(self.timer_millis))
     }
 
+    fn create_audio_signal_source(&self) -> AudioSignalSource {
+        AudioSignalSource::new(
+            self.audio_signal_kind,
+            Duration::from_millis(self.audio_signal_attack_millis as _),
+            Duration::from_millis(self.audio_signal_release_millis as _),
+            self.audio_signal_threshold,
+        )
+    }
+
+    fn create_modulator_source(&self) -> ModulatorSource {
+        ModulatorSource::new(ModulatorSettings {
+            shape: self.modulator_shape,
+            frequency: self.modulator_frequency,
+            tempo_sync: self.modulator_tempo_sync,
+            reset_on_transport_start: self.modulator_reset_on_transport_start,
+            adsr: self.modulator_adsr,
+            gate_parameter_index: self.parameter_index,
+        })
+    }
+
     fn create_realearn_parameter_source(&self) -> RealearnParameterSource {
         RealearnParameterSource {
             parameter_index: self.parameter_index,
@@ -918,7 +1091,9 @@ impl Display for SourceModel {
                     };
                     vec![t.to_string().into(), self.channel_label(), line_3, line_4]
                 }
-                t @ MidiSourceType::Display => vec![t.to_string().into()],
+                t @ (MidiSourceType::Display | MidiSourceType::InputScript) => {
+                    vec![t.to_string().into()]
+                }
                 t => vec![t.to_string().into(), self.channel_label()],
             },
             Virtual => vec![
@@ -938,6 +1113,23 @@ impl Display for SourceModel {
                             format!("Parameter #{}", self.parameter_index.get() + 1).into(),
                         ]
                     }
+                    ReaperSourceType::AudioSignal => {
+                        vec![type_label, self.audio_signal_kind.to_string().into()]
+                    }
+                    ReaperSourceType::Modulator => {
+                        let rate = match self.modulator_tempo_sync {
+                            _ if self.modulator_shape == ModulatorShape::Adsr => {
+                                format!("Gate: parameter #{}", self.parameter_index.get() + 1)
+                            }
+                            None => format!("{:.2} Hz", self.modulator_frequency),
+                            Some(v) => v.to_string(),
+                        };
+                        vec![
+                            type_label,
+                            self.modulator_shape.to_string().into(),
+                            rate.into(),
+                        ]
+                    }
                     _ => {
                         vec![type_label]
                     }
@@ -1067,6 +1259,8 @@ pub enum MidiSourceType {
     Display = 12,
     #[display(fmt = "Specific program change")]
     SpecificProgramChange = 13,
+    #[display(fmt = "MIDI input script (control only)")]
+    InputScript = 14,
 }
 
 impl MidiSourceType {
@@ -1155,7 +1349,7 @@ impl MidiSourceType {
 
     pub fn supports_feedback(self) -> bool {
         use MidiSourceType::*;
-        !matches!(self, ClockTempo | ClockTransport)
+        !matches!(self, ClockTempo | ClockTransport | InputScript)
     }
 }
 
@@ -1244,6 +1438,12 @@ pub enum ReaperSourceType {
     #[serde(rename = "speech")]
     #[display(fmt = "Speech (feedback only, no Linux)")]
     Speech,
+    #[serde(rename = "audio-signal")]
+    #[display(fmt = "Audio signal (plug-in input)")]
+    AudioSignal,
+    #[serde(rename = "modulator")]
+    #[display(fmt = "Modulator (LFO/ADSR)")]
+    Modulator,
 }
 
 impl ReaperSourceType {
@@ -1255,13 +1455,16 @@ impl ReaperSourceType {
             Timer(_) => Self::Timer,
             RealearnParameter(_) => Self::RealearnParameter,
             Speech(_) => Self::Speech,
+            AudioSignal(_) => Self::AudioSignal,
+            Modulator(_) => Self::Modulator,
         }
     }
 
     pub fn supports_control(self) -> bool {
         use ReaperSourceType::*;
         match self {
-            MidiDeviceChanges | RealearnUnitStart | Timer | RealearnParameter => true,
+            MidiDeviceChanges | RealearnUnitStart | Timer | RealearnParameter | AudioSignal
+            | Modulator => true,
             Speech => false,
         }
     }
@@ -1269,7 +1472,8 @@ impl ReaperSourceType {
     pub fn supports_feedback(self) -> bool {
         use ReaperSourceType::*;
         match self {
-            MidiDeviceChanges | RealearnUnitStart | Timer | RealearnParameter => false,
+            MidiDeviceChanges | RealearnUnitStart | Timer | RealearnParameter | AudioSignal
+            | Modulator => false,
             Speech => true,
         }
     }
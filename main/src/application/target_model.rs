@@ -28,23 +28,26 @@ use crate::domain::{
     TouchedTrackParameterType, TrackDescriptor, TrackExclusivity, TrackGangBehavior,
     TrackRouteDescriptor, TrackRouteSelector, TrackRouteType, TransportAction,
     UnresolvedActionTarget, UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
-    UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
-    UnresolvedBrowseTracksTarget, UnresolvedCompartmentParameterValueTarget,
-    UnresolvedCompoundMappingTarget, UnresolvedDummyTarget, UnresolvedEnableInstancesTarget,
-    UnresolvedEnableMappingsTarget, UnresolvedFxEnableTarget, UnresolvedFxOnlineTarget,
+    UnresolvedArrangeViewTarget, UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget,
+    UnresolvedBrowseGroupTarget, UnresolvedBrowsePotFilterItemsTarget,
+    UnresolvedBrowsePotPresetsTarget, UnresolvedBrowseTracksTarget,
+    UnresolvedCompartmentParameterValueTarget, UnresolvedCompoundMappingTarget,
+    UnresolvedDummyTarget, UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget,
+    UnresolvedFxChainEditTarget, UnresolvedFxEnableTarget, UnresolvedFxOnlineTarget,
     UnresolvedFxOpenTarget, UnresolvedFxParameterTarget, UnresolvedFxParameterTouchStateTarget,
     UnresolvedFxPresetTarget, UnresolvedFxToolTarget, UnresolvedGoToBookmarkTarget,
     UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
-    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget,
-    UnresolvedModifyMappingTarget, UnresolvedMouseTarget, UnresolvedOscSendTarget,
-    UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget, UnresolvedReaperTarget,
-    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
+    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget,
+    UnresolvedMetronomeVolumeTarget, UnresolvedMidiSendTarget, UnresolvedModifyMappingTarget,
+    UnresolvedMouseTarget, UnresolvedOscSendTarget, UnresolvedPlayrateTarget,
+    UnresolvedPreviewPotPresetTarget, UnresolvedReaperTarget, UnresolvedRouteAutomationModeTarget,
+    UnresolvedRouteMatrixCellTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
     UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget,
     UnresolvedRouteVolumeTarget, UnresolvedSeekTarget, UnresolvedTakeMappingSnapshotTarget,
     UnresolvedTempoTarget, UnresolvedTrackArmTarget, UnresolvedTrackAutomationModeTarget,
-    UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget,
-    UnresolvedTrackParentSendTarget, UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget,
+    UnresolvedTrackHeightTarget, UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget,
+    UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget, UnresolvedTrackPeakTarget,
+    UnresolvedTrackPhaseTarget, UnresolvedTrackRecordInputTarget, UnresolvedTrackRecordModeTarget,
     UnresolvedTrackSelectionTarget, UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget,
     UnresolvedTrackToolTarget, UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget,
     UnresolvedTrackWidthTarget, UnresolvedTransportTarget, VirtualChainFx, VirtualControlElement,
@@ -60,15 +63,17 @@ use std::error::Error;
 use crate::domain::ui_util::format_tags_as_csv;
 use base::hash_util::NonCryptoHashSet;
 use playtime_api::persistence::ColumnAddress;
+use pot::PluginId;
 use realearn_api::persistence::{
-    Axis, BrowseTracksMode, ClipColumnTrackContext, FxChainDescriptor, FxDescriptorCommons,
-    FxToolAction, LearnTargetMappingModification, LearnableTargetKind, MappingModification,
-    MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MonitoringMode, MouseAction,
-    MouseButton, PlaytimeColumnAction, PlaytimeColumnDescriptor, PlaytimeMatrixAction,
-    PlaytimeRowAction, PlaytimeRowDescriptor, PlaytimeSlotDescriptor, PlaytimeSlotManagementAction,
-    PlaytimeSlotTransportAction, PotFilterKind, SeekBehavior,
-    SetTargetToLastTouchedMappingModification, TargetTouchCause, TrackDescriptorCommons,
-    TrackFxChain, TrackScope, TrackToolAction,
+    ArrangeViewProperty, Axis, BrowseTracksMode, ClipColumnTrackContext, FxChainDescriptor,
+    FxChainEditAction, FxDescriptorCommons, FxToolAction, LearnTargetMappingModification,
+    LearnableTargetKind, MappingModification, MappingSnapshotDescForLoad,
+    MappingSnapshotDescForTake, MonitoringMode, MouseAction, MouseButton, PlaytimeColumnAction,
+    PlaytimeColumnDescriptor, PlaytimeMatrixAction, PlaytimeRowAction, PlaytimeRowDescriptor,
+    PlaytimeSlotDescriptor, PlaytimeSlotManagementAction, PlaytimeSlotTransportAction,
+    PotFilterKind, SeekBehavior, SendMode, SetTargetToLastTouchedMappingModification,
+    TargetTouchCause, TrackDescriptorCommons, TrackFxChain, TrackRecordMode, TrackScope,
+    TrackToolAction,
 };
 use reaper_medium::{
     AutomationMode, BookmarkId, GlobalAutomationModeOverride, InputMonitoringMode, TrackArea,
@@ -110,6 +115,9 @@ pub enum TargetCommand {
     SetRouteIndex(u32),
     SetRouteName(String),
     SetRouteExpression(String),
+    SetRouteSendMode(SendMode),
+    SetRouteSourceChannel(u32),
+    SetRouteDestinationChannel(u32),
     SetSeekBehavior(SeekBehavior),
     SetSoloBehavior(SoloBehavior),
     SetTrackExclusivity(TrackExclusivity),
@@ -117,6 +125,8 @@ pub enum TargetCommand {
     SetGangBehavior(TrackGangBehavior),
     SetBrowseTracksMode(BrowseTracksMode),
     SetFxToolAction(FxToolAction),
+    SetFxChainEditAction(FxChainEditAction),
+    SetPluginId(String),
     SetTransportAction(TransportAction),
     SetAnyOnParameter(AnyOnParameter),
     SetFxSnapshot(Option<FxSnapshot>),
@@ -135,6 +145,8 @@ pub enum TargetCommand {
     SetTrackArea(RealearnTrackArea),
     SetAutomationMode(RealearnAutomationMode),
     SetMonitoringMode(MonitoringMode),
+    SetTrackRecordMode(TrackRecordMode),
+    SetArrangeViewProperty(ArrangeViewProperty),
     SetAutomationModeOverrideType(AutomationModeOverrideType),
     SetFxDisplayType(FxDisplayType),
     SetScrollArrangeView(bool),
@@ -208,6 +220,9 @@ pub enum TargetProp {
     RouteIndex,
     RouteName,
     RouteExpression,
+    RouteSendMode,
+    RouteSourceChannel,
+    RouteDestinationChannel,
     SoloBehavior,
     SeekBehavior,
     TrackExclusivity,
@@ -215,6 +230,8 @@ pub enum TargetProp {
     GangBehavior,
     BrowseTracksMode,
     FxToolAction,
+    FxChainEditAction,
+    PluginId,
     TransportAction,
     AnyOnParameter,
     FxSnapshot,
@@ -233,6 +250,8 @@ pub enum TargetProp {
     TrackArea,
     AutomationMode,
     MonitoringMode,
+    TrackRecordMode,
+    ArrangeViewProperty,
     AutomationModeOverrideType,
     FxDisplayType,
     ScrollArrangeView,
@@ -400,6 +419,18 @@ impl<'a> Change<'a> for TargetModel {
                 self.route_expression = v;
                 One(P::RouteExpression)
             }
+            C::SetRouteSendMode(v) => {
+                self.route_send_mode = v;
+                One(P::RouteSendMode)
+            }
+            C::SetRouteSourceChannel(v) => {
+                self.route_source_channel = v;
+                One(P::RouteSourceChannel)
+            }
+            C::SetRouteDestinationChannel(v) => {
+                self.route_destination_channel = v;
+                One(P::RouteDestinationChannel)
+            }
             C::SetSoloBehavior(v) => {
                 self.solo_behavior = v;
                 One(P::SoloBehavior)
@@ -428,6 +459,14 @@ impl<'a> Change<'a> for TargetModel {
                 self.fx_tool_action = v;
                 One(P::FxToolAction)
             }
+            C::SetFxChainEditAction(v) => {
+                self.fx_chain_edit_action = v;
+                One(P::FxChainEditAction)
+            }
+            C::SetPluginId(v) => {
+                self.plugin_id = v;
+                One(P::PluginId)
+            }
             C::SetTransportAction(v) => {
                 self.transport_action = v;
                 One(P::TransportAction)
@@ -500,6 +539,14 @@ impl<'a> Change<'a> for TargetModel {
                 self.monitoring_mode = v;
                 One(P::MonitoringMode)
             }
+            C::SetTrackRecordMode(v) => {
+                self.track_record_mode = v;
+                One(P::TrackRecordMode)
+            }
+            C::SetArrangeViewProperty(v) => {
+                self.arrange_view_property = v;
+                One(P::ArrangeViewProperty)
+            }
             C::SetAutomationModeOverrideType(v) => {
                 self.automation_mode_override_type = v;
                 One(P::AutomationModeOverrideType)
@@ -690,6 +737,10 @@ pub struct TargetModel {
     fx_expression: String,
     enable_only_if_fx_has_focus: bool,
     fx_tool_action: FxToolAction,
+    // # For FX chain edit targets
+    fx_chain_edit_action: FxChainEditAction,
+    /// Plug-in ID in pipe format.
+    plugin_id: String,
     // # For track FX or compartment parameter targets
     param_index: u32,
     // # For track FX parameter targets
@@ -705,6 +756,12 @@ pub struct TargetModel {
     route_name: String,
     route_expression: String,
     touched_route_parameter_type: TouchedRouteParameterType,
+    // # For route matrix cell targets
+    route_send_mode: SendMode,
+    /// Zero-based.
+    route_source_channel: u32,
+    /// Zero-based.
+    route_destination_channel: u32,
     // # For track solo targets
     solo_behavior: SoloBehavior,
     // # For seek and goto bookmark targets
@@ -738,6 +795,10 @@ pub struct TargetModel {
     automation_mode: RealearnAutomationMode,
     // # For track monitoring mode target
     monitoring_mode: MonitoringMode,
+    // # For track record mode target
+    track_record_mode: TrackRecordMode,
+    // # For arrange view target
+    arrange_view_property: ArrangeViewProperty,
     // # For automation mode override target
     automation_mode_override_type: AutomationModeOverrideType,
     // # For FX Open and Browse FXs target
@@ -867,6 +928,9 @@ impl Default for TargetModel {
             route_index: 0,
             route_name: Default::default(),
             route_expression: Default::default(),
+            route_send_mode: Default::default(),
+            route_source_channel: 0,
+            route_destination_channel: 0,
             touched_route_parameter_type: Default::default(),
             solo_behavior: Default::default(),
             seek_behavior: Default::default(),
@@ -888,6 +952,8 @@ impl Default for TargetModel {
             track_area: Default::default(),
             automation_mode: Default::default(),
             monitoring_mode: Default::default(),
+            track_record_mode: Default::default(),
+            arrange_view_property: Default::default(),
             automation_mode_override_type: Default::default(),
             fx_display_type: Default::default(),
             scroll_arrange_view: false,
@@ -923,6 +989,8 @@ impl Default for TargetModel {
             playtime_row_action: Default::default(),
             track_tool_action: Default::default(),
             fx_tool_action: Default::default(),
+            fx_chain_edit_action: Default::default(),
+            plugin_id: String::new(),
             gang_behavior: Default::default(),
             browse_tracks_mode: Default::default(),
             pot_filter_item_kind: Default::default(),
@@ -1059,6 +1127,18 @@ impl TargetModel {
         &self.route_expression
     }
 
+    pub fn route_send_mode(&self) -> SendMode {
+        self.route_send_mode
+    }
+
+    pub fn route_source_channel(&self) -> u32 {
+        self.route_source_channel
+    }
+
+    pub fn route_destination_channel(&self) -> u32 {
+        self.route_destination_channel
+    }
+
     pub fn solo_behavior(&self) -> SoloBehavior {
         self.solo_behavior
     }
@@ -1079,6 +1159,14 @@ impl TargetModel {
         self.fx_tool_action
     }
 
+    pub fn fx_chain_edit_action(&self) -> FxChainEditAction {
+        self.fx_chain_edit_action
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
     pub fn transport_action(&self) -> TransportAction {
         self.transport_action
     }
@@ -1175,6 +1263,14 @@ impl TargetModel {
         self.monitoring_mode
     }
 
+    pub fn track_record_mode(&self) -> TrackRecordMode {
+        self.track_record_mode
+    }
+
+    pub fn arrange_view_property(&self) -> ArrangeViewProperty {
+        self.arrange_view_property
+    }
+
     pub fn automation_mode_override_type(&self) -> AutomationModeOverrideType {
         self.automation_mode_override_type
     }
@@ -1742,9 +1838,20 @@ impl TargetModel {
             TrackMonitoringMode(t) => {
                 self.monitoring_mode = convert_monitoring_mode_to_realearn(t.mode);
             }
+            TrackRecordMode(t) => {
+                self.track_record_mode = t.mode;
+            }
+            ArrangeView(t) => {
+                self.arrange_view_property = t.property;
+            }
             RouteAutomationMode(t) => {
                 self.automation_mode = RealearnAutomationMode::from_reaper(t.mode);
             }
+            RouteMatrixCell(t) => {
+                self.route_send_mode = t.send_mode;
+                self.route_source_channel = t.source_channel;
+                self.route_destination_channel = t.destination_channel;
+            }
             AutomationModeOverride(t) => match t.mode_override {
                 None => {
                     self.automation_mode_override_type = AutomationModeOverrideType::None;
@@ -2279,6 +2386,11 @@ impl TargetModel {
                         track_descriptor: self.track_descriptor()?,
                         gang_behavior: self.fixed_gang_behavior(),
                     }),
+                    TrackHeight => {
+                        UnresolvedReaperTarget::TrackHeight(UnresolvedTrackHeightTarget {
+                            track_descriptor: self.track_descriptor()?,
+                        })
+                    }
                     TrackArm => UnresolvedReaperTarget::TrackArm(UnresolvedTrackArmTarget {
                         track_descriptor: self.track_descriptor()?,
                         exclusivity: self.track_exclusivity,
@@ -2332,6 +2444,17 @@ impl TargetModel {
                             gang_behavior: self.fixed_gang_behavior(),
                         },
                     ),
+                    TrackRecordInput => {
+                        UnresolvedReaperTarget::TrackRecordInput(UnresolvedTrackRecordInputTarget {
+                            track_descriptor: self.track_descriptor()?,
+                        })
+                    }
+                    TrackRecordMode => {
+                        UnresolvedReaperTarget::TrackRecordMode(UnresolvedTrackRecordModeTarget {
+                            track_descriptor: self.track_descriptor()?,
+                            mode: self.track_record_mode,
+                        })
+                    }
                     TrackSolo => UnresolvedReaperTarget::TrackSolo(UnresolvedTrackSoloTarget {
                         track_descriptor: self.track_descriptor()?,
                         behavior: self.solo_behavior,
@@ -2366,7 +2489,24 @@ impl TargetModel {
                             parameter_type: self.touched_route_parameter_type,
                         })
                     }
+                    RouteMatrixCell => {
+                        UnresolvedReaperTarget::RouteMatrixCell(UnresolvedRouteMatrixCellTarget {
+                            descriptor: self.route_descriptor()?,
+                            send_mode: self.route_send_mode,
+                            source_channel: self.route_source_channel,
+                            destination_channel: self.route_destination_channel,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
                     Tempo => UnresolvedReaperTarget::Tempo(UnresolvedTempoTarget),
+                    ArrangeView => {
+                        UnresolvedReaperTarget::ArrangeView(UnresolvedArrangeViewTarget {
+                            property: self.arrange_view_property,
+                        })
+                    }
+                    MetronomeVolume => {
+                        UnresolvedReaperTarget::MetronomeVolume(UnresolvedMetronomeVolumeTarget)
+                    }
                     PlayRate => UnresolvedReaperTarget::Playrate(UnresolvedPlayrateTarget),
                     AutomationModeOverride => UnresolvedReaperTarget::AutomationModeOverride(
                         UnresolvedAutomationModeOverrideTarget {
@@ -2388,6 +2528,13 @@ impl TargetModel {
                         action: self.fx_tool_action,
                         scope: self.tag_scope(),
                     }),
+                    FxChainEdit => {
+                        UnresolvedReaperTarget::FxChainEdit(UnresolvedFxChainEditTarget {
+                            fx_descriptor: self.fx_descriptor()?,
+                            action: self.fx_chain_edit_action,
+                            plugin_id: PluginId::parse_from_pipe_format(&self.plugin_id).ok(),
+                        })
+                    }
                     FxEnable => UnresolvedReaperTarget::FxEnable(UnresolvedFxEnableTarget {
                         fx_descriptor: self.fx_descriptor()?,
                     }),
@@ -3275,7 +3422,8 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                     ),
                     TrackTool | TrackVolume | TrackPeak | TrackPan | TrackWidth | TrackArm
                     | TrackSelection | TrackMute | TrackPhase | TrackSolo | TrackShow
-                    | BrowseFxs | AllTrackFxEnable | TrackParentSend => {
+                    | BrowseFxs | AllTrackFxEnable | TrackParentSend | TrackRecordInput
+                    | TrackHeight => {
                         write!(f, "{}\nTrack {}", tt, self.track_label())
                     }
                     TrackAutomationMode => {
@@ -3287,8 +3435,15 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                             self.target.automation_mode
                         )
                     }
+                    TrackRecordMode => write!(
+                        f,
+                        "{}\nTrack {}\n{}",
+                        tt,
+                        self.track_label(),
+                        self.target.track_record_mode
+                    ),
                     RouteVolume | RoutePan | RouteMute | RoutePhase | RouteMono
-                    | RouteAutomationMode => write!(
+                    | RouteAutomationMode | RouteMatrixCell => write!(
                         f,
                         "{}\nTrack {}\n{} {}",
                         tt,
@@ -3296,7 +3451,7 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                         self.target.route_type,
                         self.route_label()
                     ),
-                    FxOpen | FxEnable | FxPreset | FxTool => write!(
+                    FxOpen | FxEnable | FxPreset | FxTool | FxChainEdit => write!(
                         f,
                         "{}\nTrack {}\nFX {}",
                         tt,
@@ -3305,6 +3460,7 @@ impl<'a> Display for TargetModelFormatMultiLine<'a> {
                     ),
                     Transport => write!(f, "{}\n{}", tt, self.target.transport_action),
                     AnyOn => write!(f, "{}\n{}", tt, self.target.any_on_parameter),
+                    ArrangeView => write!(f, "{}\n{}", tt, self.target.arrange_view_property),
                     AutomationModeOverride => {
                         write!(f, "{}\n{}", tt, self.target.automation_mode_override_type)
                     }
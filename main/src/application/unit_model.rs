@@ -13,14 +13,15 @@ use crate::domain::{
     CompartmentSettings, CompoundMappingSource, ControlContext, ControlInput, DomainEvent,
     DomainEventHandler, ExtendedProcessorContext, FeedbackAudioHookTask, FeedbackOutput,
     FeedbackRealTimeTask, FinalSourceFeedbackValue, GroupId, GroupKey, IncomingCompoundSourceValue,
-    InputDescriptor, InstanceId, InternalInfoEvent, LastTouchedTargetFilter, MainMapping,
+    InputDescriptor, Instance, InstanceId, InternalInfoEvent, LastTouchedTargetFilter, MainMapping,
     MappingId, MappingKey, MappingMatchedEvent, MessageCaptureEvent, MidiControlInput,
     NormalMainTask, OscFeedbackTask, ParamSetting, PluginParams, ProcessorContext,
     ProjectionFeedbackValue, QualifiedMappingId, RealearnControlSurfaceMainTask, RealearnTarget,
     ReaperTarget, ReaperTargetType, SharedInstance, SharedUnit, SourceFeedbackEvent,
     StayActiveWhenProjectInBackground, Tag, TargetControlEvent, TargetTouchEvent,
-    TargetValueChangedEvent, Unit, UnitContainer, UnitId, VirtualControlElementId, VirtualFx,
-    VirtualSource, VirtualSourceValue, LUA_MIDI_SCRIPT_SOURCE_RUNTIME_NAME,
+    TargetValueChangedEvent, Unit, UnitContainer, UnitId, VirtualControlElementId,
+    VirtualFeedbackValue, VirtualFx, VirtualSource, VirtualSourceValue,
+    LUA_MIDI_SCRIPT_SOURCE_RUNTIME_NAME,
 };
 use base::{Global, NamedChannelSender, SenderToNormalThread, SenderToRealTimeThread};
 use derivative::Derivative;
@@ -56,6 +57,7 @@ pub trait SessionUi {
     fn celebrate_success(&self);
     fn conditions_changed(&self);
     fn send_projection_feedback(&self, session: &UnitModel, value: ProjectionFeedbackValue);
+    fn send_virtual_feedback(&self, session: &UnitModel, value: &VirtualFeedbackValue);
     fn mapping_matched(&self, event: MappingMatchedEvent);
     fn handle_target_control(&self, event: TargetControlEvent);
     fn handle_source_feedback(&self, event: SourceFeedbackEvent);
@@ -558,6 +560,12 @@ impl UnitModel {
             .send_complaining(NormalMainTask::HitTarget { id, value });
     }
 
+    /// Instructs the main processor to control all main mappings with the given virtual source.
+    pub fn control_virtual_element(&self, value: VirtualSourceValue) {
+        self.normal_main_task_sender
+            .send_complaining(NormalMainTask::ControlVirtualElement(value));
+    }
+
     /// Connects the dots.
     // TODO-low Too large. Split this into several methods.
     pub fn activate(&mut self, weak_session: WeakUnitModel) {
@@ -2065,8 +2073,20 @@ impl UnitModel {
     }
 
     pub fn active_main_preset(&self) -> Option<CompartmentPresetModel> {
-        let id = self.active_preset_id(CompartmentKind::Main)?;
-        self.main_preset_manager.find_by_id(id)
+        self.active_preset(CompartmentKind::Main)
+    }
+
+    pub fn active_preset(&self, compartment: CompartmentKind) -> Option<CompartmentPresetModel> {
+        let id = self.active_preset_id(compartment)?;
+        let preset_manager = match compartment {
+            CompartmentKind::Controller => &self.controller_preset_manager,
+            CompartmentKind::Main => &self.main_preset_manager,
+        };
+        preset_manager.find_by_id_for_unit(
+            id,
+            &self.unit.borrow(),
+            self.processor_context.project_or_current_project(),
+        )
     }
 
     /// Returns `true` if the preset has unsaved changes (if a preset is active) or if at least one
@@ -2085,7 +2105,11 @@ impl UnitModel {
         let compartment = CompartmentKind::Controller;
         let model = if let Some(id) = id.as_ref() {
             self.controller_preset_manager
-                .find_by_id(id)
+                .find_by_id_for_unit(
+                    id,
+                    &self.unit.borrow(),
+                    self.processor_context.project_or_current_project(),
+                )
                 .map(|preset| preset.model().clone())
         } else {
             // <None> preset
@@ -2117,7 +2141,11 @@ impl UnitModel {
     pub fn activate_main_preset(&mut self, id: Option<String>) {
         let model = if let Some(id) = id.as_ref() {
             self.main_preset_manager
-                .find_by_id(id)
+                .find_by_id_for_unit(
+                    id,
+                    &self.unit.borrow(),
+                    self.processor_context.project_or_current_project(),
+                )
                 .map(|preset| preset.model().clone())
         } else {
             // <None> preset
@@ -2133,7 +2161,11 @@ impl UnitModel {
                     Some(self.extract_compartment_model(CompartmentKind::Main));
             }
             self.main_preset_manager
-                .find_by_id(id)
+                .find_by_id_for_unit(
+                    id,
+                    &self.unit.borrow(),
+                    self.processor_context.project_or_current_project(),
+                )
                 .map(|preset| preset.model().clone())
         } else {
             self.memorized_main_compartment.take()
@@ -2590,6 +2622,29 @@ impl UnitModel {
         &self.instance
     }
 
+    /// Lets the instance report a mapping event (e.g. to gRPC clients), resolving the mapping key.
+    fn notify_instance_about_mapping_event(
+        &self,
+        compartment: CompartmentKind,
+        mapping_id: MappingId,
+        notify: impl FnOnce(&Instance, &MappingKey),
+    ) {
+        // Mapping events can occur while the instance is borrowed. Then we just skip them.
+        let Ok(instance) = self.instance.try_borrow() else {
+            return;
+        };
+        if !instance.wants_mapping_events() {
+            return;
+        }
+        let Some(mapping) = self.find_mapping_by_id(compartment, mapping_id) else {
+            return;
+        };
+        let Ok(mapping) = mapping.try_borrow() else {
+            return;
+        };
+        notify(&instance, mapping.key());
+    }
+
     fn find_group_of_mapping(&self, mapping: &MappingModel) -> Option<&SharedGroup> {
         let group_id = mapping.group_id();
         if group_id.is_default() {
@@ -2764,7 +2819,16 @@ impl DomainEventHandler for WeakUnitModel {
                 // particular case of reentrancy (because of a quirk in REAPER related to master
                 // tempo notification, https://github.com/helgoboss/realearn/issues/199). If the
                 // target value slider is not updated then ... so what.
-                session.try_borrow()?.ui().target_value_changed(e);
+                let s = session.try_borrow()?;
+                s.notify_instance_about_mapping_event(e.compartment, e.mapping_id, |i, key| {
+                    i.notify_mapping_target_value_changed(
+                        s.unit_id,
+                        e.compartment,
+                        key,
+                        e.new_value,
+                    )
+                });
+                s.ui().target_value_changed(e);
             }
             UpdatedSingleParameterValue { index, value } => {
                 let mut session = session.borrow_mut();
@@ -2787,12 +2851,28 @@ impl DomainEventHandler for WeakUnitModel {
                 let s = session.try_borrow()?;
                 s.ui().send_projection_feedback(&s, value);
             }
+            VirtualFeedback(value) => {
+                let s = session.try_borrow()?;
+                s.ui().send_virtual_feedback(&s, value);
+            }
             MappingMatched(event) => {
                 let s = session.try_borrow()?;
                 s.ui().mapping_matched(event);
             }
             HandleTargetControl(event) => {
                 let s = session.try_borrow()?;
+                s.notify_instance_about_mapping_event(
+                    event.id.compartment,
+                    event.id.id,
+                    |i, key| {
+                        i.notify_mapping_target_controlled(
+                            s.unit_id,
+                            event.id.compartment,
+                            key,
+                            event.log_entry,
+                        )
+                    },
+                );
                 s.ui().handle_target_control(event);
             }
             HandleSourceFeedback(event) => {
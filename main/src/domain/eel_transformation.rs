@@ -8,6 +8,8 @@ use std::sync::Arc;
 #[derive(Default)]
 pub struct AdditionalTransformationInput {
     pub y_last: f64,
+    /// Only used by Lua transformations.
+    pub compartment_lua: Option<mlua::Value<'static>>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,215 @@
+use crate::domain::{ControlEvent, ControlEventTimestamp};
+use helgoboss_learn::{ContinuousIncrement, ControlValue, DiscreteIncrement};
+use realearn_api::persistence::AccelerationCurve;
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct EncoderAccelerationSettings {
+    pub curve: AccelerationCurve,
+    /// Factor applied to increments that arrive immediately after each other. Must be at least 1.
+    pub max_factor: u32,
+    /// If no increment arrives within this time, the acceleration starts from scratch.
+    pub reset_timeout: Duration,
+}
+
+impl Default for EncoderAccelerationSettings {
+    fn default() -> Self {
+        Self {
+            curve: Default::default(),
+            max_factor: 4,
+            reset_timeout: Duration::from_millis(150),
+        }
+    }
+}
+
+/// Time-based encoder acceleration.
+///
+/// Looks at the time between consecutive increments and multiplies the incoming increment
+/// accordingly. This is applied *before* the mode, so the mode computes the step from the
+/// accelerated increment and _Step size Max_ / _Speed Max_ limit the effect. Absolute sources
+/// used with "Make relative" are converted to relative increments first, so they take the same
+/// path as native relative sources.
+#[derive(Clone, Debug)]
+pub struct EncoderAcceleration {
+    settings: EncoderAccelerationSettings,
+    /// Timestamp and direction (`true` = increment) of the last increment.
+    last_increment: Option<(ControlEventTimestamp, bool)>,
+    /// Only used for absolute sources with "Make relative" in order to derive the increment.
+    last_absolute_input: Option<f64>,
+}
+
+impl EncoderAcceleration {
+    pub fn new(settings: EncoderAccelerationSettings) -> Self {
+        Self {
+            settings,
+            last_increment: None,
+            last_absolute_input: None,
+        }
+    }
+
+    /// Records the given incoming source event and returns the event which should be passed to
+    /// the mode instead.
+    ///
+    /// Returns `None` if the event shouldn't be processed at all (e.g. the first absolute value
+    /// with "Make relative", from which no increment can be derived yet).
+    pub fn process(
+        &mut self,
+        event: ControlEvent<ControlValue>,
+        make_relative: bool,
+    ) -> Option<ControlEvent<ControlValue>> {
+        let relative_value = match event.payload() {
+            v @ (ControlValue::RelativeDiscrete(_) | ControlValue::RelativeContinuous(_)) => v,
+            v @ (ControlValue::AbsoluteContinuous(_) | ControlValue::AbsoluteDiscrete(_)) => {
+                if !make_relative {
+                    return Some(event);
+                }
+                let current = v.to_unit_value().ok()?.get();
+                let last = self.last_absolute_input.replace(current);
+                let diff = current - last?;
+                if diff == 0.0 {
+                    return None;
+                }
+                ControlValue::RelativeContinuous(ContinuousIncrement::new(diff))
+            }
+        };
+        let accelerated_value = match relative_value {
+            ControlValue::RelativeDiscrete(i) => {
+                let factor = self.factor(event.timestamp(), i.is_positive());
+                ControlValue::RelativeDiscrete(DiscreteIncrement::new(i.get() * factor as i32))
+            }
+            ControlValue::RelativeContinuous(i) => {
+                let factor = self.factor(event.timestamp(), i.get() > 0.0);
+                let accelerated = (i.get() * factor as f64).clamp(-1.0, 1.0);
+                ControlValue::RelativeContinuous(ContinuousIncrement::new(accelerated))
+            }
+            v => v,
+        };
+        Some(event.with_payload(accelerated_value))
+    }
+
+    /// Records an increment in the given direction and returns the factor by which it should be
+    /// multiplied.
+    fn factor(&mut self, timestamp: ControlEventTimestamp, is_increment: bool) -> u32 {
+        let last_increment = self.last_increment.replace((timestamp, is_increment));
+        let Some((last_timestamp, last_is_increment)) = last_increment else {
+            return 1;
+        };
+        if last_is_increment != is_increment {
+            // Direction change resets the acceleration
+            return 1;
+        }
+        let elapsed = timestamp - last_timestamp;
+        if elapsed >= self.settings.reset_timeout {
+            return 1;
+        }
+        let speed = 1.0 - elapsed.as_secs_f64() / self.settings.reset_timeout.as_secs_f64();
+        let curved_speed = match self.settings.curve {
+            AccelerationCurve::Linear => speed,
+            AccelerationCurve::Quadratic => speed * speed,
+            AccelerationCurve::Exponential => (5.0 * speed).exp_m1() / 5.0f64.exp_m1(),
+        };
+        let max_additional_factor = self.settings.max_factor.saturating_sub(1) as f64;
+        1 + (curved_speed * max_additional_factor).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::{AbstractTimestamp, UnitValue};
+
+    fn acceleration() -> EncoderAcceleration {
+        EncoderAcceleration::new(EncoderAccelerationSettings {
+            curve: AccelerationCurve::Linear,
+            max_factor: 4,
+            reset_timeout: Duration::from_millis(100),
+        })
+    }
+
+    fn increment(i: i32, timestamp: ControlEventTimestamp) -> ControlEvent<ControlValue> {
+        ControlEvent::new(
+            ControlValue::RelativeDiscrete(DiscreteIncrement::new(i)),
+            timestamp,
+        )
+    }
+
+    fn absolute(v: f64, timestamp: ControlEventTimestamp) -> ControlEvent<ControlValue> {
+        ControlEvent::new(
+            ControlValue::AbsoluteContinuous(UnitValue::new(v)),
+            timestamp,
+        )
+    }
+
+    fn discrete_increment(event: Option<ControlEvent<ControlValue>>) -> Option<i32> {
+        match event?.payload() {
+            ControlValue::RelativeDiscrete(i) => Some(i.get()),
+            _ => None,
+        }
+    }
+
+    fn continuous_increment(event: Option<ControlEvent<ControlValue>>) -> Option<f64> {
+        match event?.payload() {
+            ControlValue::RelativeContinuous(i) => Some(i.get()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn fast_increments_in_same_direction_accelerate() {
+        // Given
+        let mut acceleration = acceleration();
+        let start = ControlEventTimestamp::now();
+        // When
+        let first = acceleration.process(increment(1, start), false);
+        let fast = acceleration.process(increment(1, start + Duration::from_millis(10)), false);
+        let medium = acceleration.process(increment(2, start + Duration::from_millis(50)), false);
+        let direction_change =
+            acceleration.process(increment(-1, start + Duration::from_millis(60)), false);
+        let slow = acceleration.process(increment(-1, start + Duration::from_millis(200)), false);
+        // Then
+        assert_eq!(discrete_increment(first), Some(1));
+        assert_eq!(discrete_increment(fast), Some(4));
+        assert_eq!(discrete_increment(medium), Some(6));
+        assert_eq!(discrete_increment(direction_change), Some(-1));
+        assert_eq!(discrete_increment(slow), Some(-1));
+    }
+
+    #[test]
+    fn make_relative_takes_same_path_as_relative_sources() {
+        // Given
+        let mut acceleration = acceleration();
+        let start = ControlEventTimestamp::now();
+        // When
+        let first = acceleration.process(absolute(0.5, start), true);
+        let slow = acceleration.process(absolute(0.51, start + Duration::from_millis(200)), true);
+        let fast = acceleration.process(absolute(0.52, start + Duration::from_millis(210)), true);
+        let unchanged =
+            acceleration.process(absolute(0.52, start + Duration::from_millis(215)), true);
+        let direction_change =
+            acceleration.process(absolute(0.5, start + Duration::from_millis(220)), true);
+        // Then
+        assert!(
+            first.is_none(),
+            "no increment can be derived from first value"
+        );
+        assert!((continuous_increment(slow).unwrap() - 0.01).abs() < 1e-9);
+        assert!((continuous_increment(fast).unwrap() - 0.04).abs() < 1e-9);
+        assert!(unchanged.is_none());
+        assert!((continuous_increment(direction_change).unwrap() + 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn leave_absolute_values_alone_without_make_relative() {
+        // Given
+        let mut acceleration = acceleration();
+        let start = ControlEventTimestamp::now();
+        // When
+        acceleration.process(absolute(0.5, start), false);
+        let result = acceleration.process(absolute(0.6, start + Duration::from_millis(10)), false);
+        // Then
+        assert_eq!(
+            result.map(|e| e.payload()),
+            Some(ControlValue::AbsoluteContinuous(UnitValue::new(0.6)))
+        );
+    }
+}
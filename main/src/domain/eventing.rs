@@ -1,7 +1,7 @@
 use crate::domain::{
     CompartmentKind, CompoundMappingTarget, ControlLogContext, ControlLogEntry, FeedbackLogEntry,
     InternalInfoEvent, MappingId, MessageCaptureResult, PluginParamIndex, PluginParams,
-    ProjectionFeedbackValue, QualifiedMappingId, RawParamValue,
+    ProjectionFeedbackValue, QualifiedMappingId, RawParamValue, VirtualFeedbackValue,
 };
 use base::hash_util::NonCryptoHashSet;
 use helgoboss_learn::{AbsoluteValue, ControlValue};
@@ -24,6 +24,7 @@ pub enum DomainEvent<'a> {
     TargetValueChanged(TargetValueChangedEvent<'a>),
     Info(&'a InternalInfoEvent),
     ProjectionFeedback(ProjectionFeedbackValue),
+    VirtualFeedback(&'a VirtualFeedbackValue),
     MappingMatched(MappingMatchedEvent),
     HandleTargetControl(TargetControlEvent),
     HandleSourceFeedback(SourceFeedbackEvent<'a>),
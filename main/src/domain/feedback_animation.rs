@@ -0,0 +1,185 @@
+use helgoboss_learn::{AbsoluteValue, FeedbackValue, Fraction, Interval, UnitValue};
+use once_cell::sync::Lazy;
+use reaper_high::Project;
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use std::time::{Duration, Instant};
+
+/// Common time reference for all animations, so that animations with the same period run in sync
+/// (required for chases spanning multiple mappings).
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FeedbackAnimationSettings {
+    pub effect: FeedbackAnimationEffect,
+    pub period: FeedbackAnimationPeriod,
+    /// The animation only runs while the target value is within this interval.
+    pub target_value_interval: Interval<UnitValue>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FeedbackAnimationEffect {
+    /// On for the given fraction of the period, off for the rest.
+    Blink { duty_cycle: f64 },
+    /// Full brightness at the start of the period, fading out until its end.
+    Pulse,
+    /// Smoothly fades in and out.
+    Breathe,
+    /// On during the step with the given position only. One step takes one period.
+    Chase { position: u32, length: u32 },
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FeedbackAnimationPeriod {
+    Time(Duration),
+    /// Follows the play position if the project is playing, otherwise the project tempo.
+    Beats(f64),
+}
+
+/// Animates feedback values (blinking, pulsing, ...) depending on the current target value.
+///
+/// This is stateless. The phase is derived from the current time or the current project beat
+/// position, so the main processor just needs to send the feedback regularly while an animation
+/// is running. Duplicate feedback is blocked further down the line anyway.
+#[derive(Clone, Debug)]
+pub struct FeedbackAnimation {
+    /// The first animation whose interval contains the current target value wins.
+    animations: Vec<FeedbackAnimationSettings>,
+}
+
+impl FeedbackAnimation {
+    pub fn new(animations: Vec<FeedbackAnimationSettings>) -> Self {
+        Self { animations }
+    }
+
+    /// Returns the animation which should currently run, if any.
+    fn active_animation(
+        &self,
+        target_value: Option<AbsoluteValue>,
+    ) -> Option<&FeedbackAnimationSettings> {
+        let v = target_value?.to_unit_value();
+        self.animations.iter().find(|a| {
+            let interval = &a.target_value_interval;
+            v >= interval.min_val() && v <= interval.max_val()
+        })
+    }
+
+    pub fn is_running(&self, target_value: Option<AbsoluteValue>) -> bool {
+        self.active_animation(target_value).is_some()
+    }
+
+    /// Applies the currently running animation (if any) to the given feedback value.
+    ///
+    /// The feedback value is expected to be in source space, that is, already processed by the
+    /// mode. Numeric values are scaled by the current brightness. Other values are switched off
+    /// while the brightness is below 50%.
+    pub fn apply<'a, 'b>(
+        &self,
+        feedback_value: Cow<'a, FeedbackValue<'b>>,
+        target_value: Option<AbsoluteValue>,
+        project: Project,
+    ) -> Cow<'a, FeedbackValue<'b>> {
+        let Some(animation) = self.active_animation(target_value) else {
+            return feedback_value;
+        };
+        let cycles = animation.period.elapsed_cycles(project);
+        let brightness = animation.effect.brightness(cycles);
+        if brightness >= 1.0 {
+            return feedback_value;
+        }
+        let animated_value = match feedback_value.into_owned() {
+            FeedbackValue::Numeric(mut v) => {
+                v.value = match v.value {
+                    AbsoluteValue::Continuous(u) => {
+                        AbsoluteValue::Continuous(UnitValue::new_clamped(u.get() * brightness))
+                    }
+                    AbsoluteValue::Discrete(f) => {
+                        let actual = (f.actual() as f64 * brightness).round() as u32;
+                        AbsoluteValue::Discrete(Fraction::new(actual, f.max_val()))
+                    }
+                };
+                FeedbackValue::Numeric(v)
+            }
+            _ if brightness < 0.5 => FeedbackValue::Off,
+            v => v,
+        };
+        Cow::Owned(animated_value)
+    }
+}
+
+impl FeedbackAnimationPeriod {
+    /// Returns the number of periods elapsed since some fixed point in the past.
+    fn elapsed_cycles(&self, project: Project) -> f64 {
+        match *self {
+            FeedbackAnimationPeriod::Time(d) => {
+                if d.is_zero() {
+                    return 0.0;
+                }
+                EPOCH.elapsed().as_secs_f64() / d.as_secs_f64()
+            }
+            FeedbackAnimationPeriod::Beats(beats) => {
+                if beats <= 0.0 {
+                    return 0.0;
+                }
+                if project.is_playing() {
+                    let pos = project.play_position_latency_compensated();
+                    project.beat_info_at(pos).full_beats.get() / beats
+                } else {
+                    let bpm = project.tempo().bpm().get();
+                    EPOCH.elapsed().as_secs_f64() * bpm / 60.0 / beats
+                }
+            }
+        }
+    }
+}
+
+impl FeedbackAnimationEffect {
+    /// Returns the brightness (between 0.0 and 1.0) at the given number of elapsed periods.
+    fn brightness(&self, cycles: f64) -> f64 {
+        let phase = cycles.fract();
+        match *self {
+            FeedbackAnimationEffect::Blink { duty_cycle } => {
+                if phase < duty_cycle {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            FeedbackAnimationEffect::Pulse => (1.0 - phase) * (1.0 - phase),
+            FeedbackAnimationEffect::Breathe => (1.0 - (2.0 * PI * phase).cos()) / 2.0,
+            FeedbackAnimationEffect::Chase { position, length } => {
+                if length == 0 {
+                    return 1.0;
+                }
+                let step = (cycles.floor() as u64 % length as u64) as u32;
+                if step == position {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brightness() {
+        let blink = FeedbackAnimationEffect::Blink { duty_cycle: 0.5 };
+        assert_eq!(blink.brightness(3.25), 1.0);
+        assert_eq!(blink.brightness(3.75), 0.0);
+        let breathe = FeedbackAnimationEffect::Breathe;
+        assert_eq!(breathe.brightness(2.0), 0.0);
+        assert!((breathe.brightness(2.5) - 1.0).abs() < 0.000001);
+        let chase = FeedbackAnimationEffect::Chase {
+            position: 2,
+            length: 4,
+        };
+        assert_eq!(chase.brightness(5.5), 0.0);
+        assert_eq!(chase.brightness(6.5), 1.0);
+        assert_eq!(chase.brightness(10.0), 1.0);
+    }
+}
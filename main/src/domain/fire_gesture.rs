@@ -0,0 +1,391 @@
+use crate::domain::{CompartmentKind, ControlEvent, ControlEventTimestamp};
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
+use helgoboss_learn::ControlValue;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum FireGestureSettings {
+    /// Fires after the n-th press if the presses follow each other quickly enough and no further
+    /// press follows within the maximum interval.
+    ///
+    /// Waiting for the series to end makes it possible to put gestures with different press counts
+    /// on the same button.
+    MultiPress {
+        press_count: u32,
+        max_interval: Duration,
+    },
+    /// Fires on release if the button was held long enough.
+    LongPressRelease { min_duration: Duration },
+    /// Fires on press if the button of the given mapping is held at that time.
+    Chord { held_mapping_key: Rc<str> },
+    /// Fires on press if the button of the given mapping was pressed shortly before.
+    Sequence {
+        previous_mapping_key: Rc<str>,
+        max_interval: Duration,
+    },
+}
+
+/// Detects button gestures which go beyond the fire modes built into the mode.
+///
+/// This is applied *before* the mode. Once the gesture is complete, the mode receives the value
+/// of the press which started or completed it. All other button events are swallowed. Non-button
+/// events (relative values) are passed through.
+#[derive(Clone, Debug)]
+pub struct FireGesture {
+    settings: FireGestureSettings,
+    /// Number of presses so far (multi-press).
+    press_count: u32,
+    /// Time and value of the last press.
+    last_press: Option<(ControlEventTimestamp, ControlValue)>,
+    /// Press which completed a multi-press series. Fires when polled after the series ended.
+    pending_press: Option<ControlValue>,
+}
+
+impl FireGestureSettings {
+    /// Returns the key of the mapping whose button state this gesture depends on, if any.
+    pub fn referenced_mapping_key(&self) -> Option<&Rc<str>> {
+        match self {
+            FireGestureSettings::Chord { held_mapping_key } => Some(held_mapping_key),
+            FireGestureSettings::Sequence {
+                previous_mapping_key,
+                ..
+            } => Some(previous_mapping_key),
+            FireGestureSettings::MultiPress { .. }
+            | FireGestureSettings::LongPressRelease { .. } => None,
+        }
+    }
+}
+
+impl FireGesture {
+    pub fn new(settings: FireGestureSettings) -> Self {
+        Self {
+            settings,
+            press_count: 0,
+            last_press: None,
+            pending_press: None,
+        }
+    }
+
+    pub fn settings(&self) -> &FireGestureSettings {
+        &self.settings
+    }
+
+    /// Returns the event which should be passed to the mode, if any.
+    pub fn process(
+        &mut self,
+        compartment: CompartmentKind,
+        event: ControlEvent<ControlValue>,
+        button_states: &ButtonStates,
+    ) -> Option<ControlEvent<ControlValue>> {
+        let Some(is_press) = is_press(event.payload()) else {
+            return Some(event);
+        };
+        let previous_press = if is_press {
+            self.last_press
+                .replace((event.timestamp(), event.payload()))
+        } else {
+            self.last_press
+        };
+        let fires = match &self.settings {
+            FireGestureSettings::MultiPress {
+                press_count,
+                max_interval,
+            } => {
+                if !is_press {
+                    return None;
+                }
+                let continues_series =
+                    previous_press.is_some_and(|(t, _)| event.timestamp() - t <= *max_interval);
+                self.press_count = if continues_series {
+                    self.press_count + 1
+                } else {
+                    1
+                };
+                // Exactly n presses are necessary. Another press cancels the series.
+                self.pending_press = if self.press_count == *press_count {
+                    Some(event.payload())
+                } else {
+                    None
+                };
+                false
+            }
+            FireGestureSettings::LongPressRelease { min_duration } => {
+                if is_press {
+                    return None;
+                }
+                let (press_time, press_value) = self.last_press.take()?;
+                if event.timestamp() - press_time < *min_duration {
+                    return None;
+                }
+                return Some(ControlEvent::new(press_value, event.timestamp()));
+            }
+            FireGestureSettings::Chord { held_mapping_key } => {
+                is_press
+                    && button_states
+                        .get(compartment, held_mapping_key)
+                        .is_some_and(|s| s.is_pressed)
+            }
+            FireGestureSettings::Sequence {
+                previous_mapping_key,
+                max_interval,
+            } => {
+                is_press
+                    && button_states
+                        .get(compartment, previous_mapping_key)
+                        .and_then(|s| s.last_press)
+                        .is_some_and(|t| event.timestamp() - t <= *max_interval)
+            }
+        };
+        if fires {
+            Some(event)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether this gesture needs to be polled in order to fire.
+    pub fn wants_to_be_polled(&self) -> bool {
+        matches!(self.settings, FireGestureSettings::MultiPress { .. })
+    }
+
+    /// Returns the event which should be passed to the mode because a multi-press series has
+    /// ended, if any.
+    pub fn poll(&mut self, timestamp: ControlEventTimestamp) -> Option<ControlEvent<ControlValue>> {
+        let FireGestureSettings::MultiPress { max_interval, .. } = &self.settings else {
+            return None;
+        };
+        self.pending_press?;
+        let (last_press_time, _) = self.last_press?;
+        if timestamp - last_press_time <= *max_interval {
+            // Another press could still follow
+            return None;
+        }
+        self.press_count = 0;
+        let value = self.pending_press.take()?;
+        Some(ControlEvent::new(value, timestamp))
+    }
+}
+
+/// Keeps track of which mapping's button is currently pressed. Used for chords and sequences.
+///
+/// Only the buttons of mappings referenced by a chord or sequence are tracked. They are tracked
+/// even if the referenced mapping doesn't have a target or control is disabled, because modifier
+/// mappings often don't do anything on their own.
+#[derive(Debug, Default)]
+pub struct ButtonStates {
+    states: NonCryptoHashMap<(CompartmentKind, Rc<str>), ButtonState>,
+    referenced_keys: NonCryptoHashSet<(CompartmentKind, Rc<str>)>,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ButtonState {
+    pub is_pressed: bool,
+    pub last_press: Option<ControlEventTimestamp>,
+}
+
+impl ButtonStates {
+    /// Replaces the keys of the mappings which are referenced by chords or sequences.
+    ///
+    /// If `reset` is `true`, all recorded states of the compartment are forgotten as well. That's
+    /// what should happen when all mappings are reloaded.
+    pub fn update_referenced_keys<'a>(
+        &mut self,
+        compartment: CompartmentKind,
+        keys: impl Iterator<Item = &'a Rc<str>>,
+        reset: bool,
+    ) {
+        self.referenced_keys.retain(|(c, _)| *c != compartment);
+        self.referenced_keys
+            .extend(keys.map(|key| (compartment, key.clone())));
+        if reset {
+            self.states.retain(|(c, _), _| *c != compartment);
+        }
+    }
+
+    /// Returns whether the button state of the given mapping is relevant for some chord or
+    /// sequence.
+    pub fn is_referenced(&self, compartment: CompartmentKind, mapping_key: &Rc<str>) -> bool {
+        self.referenced_keys
+            .contains(&(compartment, mapping_key.clone()))
+    }
+
+    /// Records the given source event of the mapping with the given key if it's referenced by
+    /// some chord or sequence.
+    pub fn record_if_referenced(
+        &mut self,
+        compartment: CompartmentKind,
+        mapping_key: &Rc<str>,
+        event: ControlEvent<ControlValue>,
+    ) {
+        if self.is_referenced(compartment, mapping_key) {
+            self.record(compartment, mapping_key, event);
+        }
+    }
+
+    /// Records the given source event of the mapping with the given key.
+    pub fn record(
+        &mut self,
+        compartment: CompartmentKind,
+        mapping_key: &Rc<str>,
+        event: ControlEvent<ControlValue>,
+    ) {
+        let Some(is_press) = is_press(event.payload()) else {
+            return;
+        };
+        let state = self
+            .states
+            .entry((compartment, mapping_key.clone()))
+            .or_default();
+        state.is_pressed = is_press;
+        if is_press {
+            state.last_press = Some(event.timestamp());
+        }
+    }
+
+    pub fn get(&self, compartment: CompartmentKind, mapping_key: &Rc<str>) -> Option<&ButtonState> {
+        self.states.get(&(compartment, mapping_key.clone()))
+    }
+}
+
+/// Returns `None` if the given value doesn't look like it comes from a button.
+fn is_press(value: ControlValue) -> Option<bool> {
+    match value {
+        ControlValue::AbsoluteContinuous(v) => Some(!v.is_zero()),
+        ControlValue::AbsoluteDiscrete(f) => Some(f.actual() > 0),
+        ControlValue::RelativeDiscrete(_) | ControlValue::RelativeContinuous(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::{AbstractTimestamp, UnitValue};
+
+    fn event(value: f64, millis: u64, start: ControlEventTimestamp) -> ControlEvent<ControlValue> {
+        ControlEvent::new(
+            ControlValue::AbsoluteContinuous(UnitValue::new(value)),
+            start + Duration::from_millis(millis),
+        )
+    }
+
+    #[test]
+    fn triple_press() {
+        // Given
+        let start = ControlEventTimestamp::now();
+        let mut gesture = FireGesture::new(FireGestureSettings::MultiPress {
+            press_count: 3,
+            max_interval: Duration::from_millis(300),
+        });
+        let states = ButtonStates::default();
+        let mut process = |value, millis| {
+            gesture.process(CompartmentKind::Main, event(value, millis, start), &states)
+        };
+        // When
+        let results = [
+            process(1.0, 0),
+            process(0.0, 50),
+            process(1.0, 100),
+            process(0.0, 150),
+            // Too slow, starts a new series
+            process(1.0, 1000),
+            process(1.0, 1100),
+            process(1.0, 1200),
+        ];
+        let poll_during_series = gesture.poll(start + Duration::from_millis(1300));
+        let poll_after_series = gesture.poll(start + Duration::from_millis(1600));
+        let poll_again = gesture.poll(start + Duration::from_millis(1700));
+        // Then
+        assert!(results.iter().all(|r| r.is_none()));
+        assert!(poll_during_series.is_none());
+        assert!(poll_after_series.is_some());
+        assert!(poll_again.is_none());
+    }
+
+    #[test]
+    fn double_and_triple_press_on_same_button() {
+        // Given
+        let start = ControlEventTimestamp::now();
+        let multi_press = |press_count| {
+            FireGesture::new(FireGestureSettings::MultiPress {
+                press_count,
+                max_interval: Duration::from_millis(300),
+            })
+        };
+        let mut double_press = multi_press(2);
+        let mut triple_press = multi_press(3);
+        let states = ButtonStates::default();
+        let mut fired = vec![];
+        // When
+        let presses = [
+            // Double press
+            0, 100, //
+            // Triple press
+            1000, 1100, 1200, //
+            // Quadruple press
+            2000, 2100, 2200, 2300,
+        ];
+        let mut millis = 0;
+        let mut presses = presses.into_iter().peekable();
+        while millis < 3000 {
+            if presses.peek() == Some(&millis) {
+                presses.next();
+                for gesture in [&mut double_press, &mut triple_press] {
+                    let result =
+                        gesture.process(CompartmentKind::Main, event(1.0, millis, start), &states);
+                    assert!(result.is_none());
+                }
+            }
+            for (name, gesture) in [("double", &mut double_press), ("triple", &mut triple_press)] {
+                if gesture
+                    .poll(start + Duration::from_millis(millis))
+                    .is_some()
+                {
+                    fired.push((name, millis));
+                }
+            }
+            millis += 50;
+        }
+        // Then
+        assert_eq!(fired, [("double", 450), ("triple", 1550)]);
+    }
+
+    #[test]
+    fn chord() {
+        // Given
+        let start = ControlEventTimestamp::now();
+        let shift: Rc<str> = "shift".into();
+        let mut gesture = FireGesture::new(FireGestureSettings::Chord {
+            held_mapping_key: shift.clone(),
+        });
+        let mut states = ButtonStates::default();
+        // When
+        let alone = gesture.process(CompartmentKind::Main, event(1.0, 0, start), &states);
+        states.record(CompartmentKind::Main, &shift, event(1.0, 10, start));
+        let with_shift = gesture.process(CompartmentKind::Main, event(1.0, 20, start), &states);
+        // Then
+        assert!(alone.is_none());
+        assert!(with_shift.is_some());
+    }
+
+    #[test]
+    fn only_referenced_buttons_are_recorded() {
+        // Given
+        let start = ControlEventTimestamp::now();
+        let shift: Rc<str> = "shift".into();
+        let other: Rc<str> = "other".into();
+        let mut states = ButtonStates::default();
+        states.update_referenced_keys(CompartmentKind::Main, [&shift].into_iter(), true);
+        // When
+        states.record_if_referenced(CompartmentKind::Main, &shift, event(1.0, 0, start));
+        states.record_if_referenced(CompartmentKind::Main, &other, event(1.0, 0, start));
+        let shift_pressed_before_reset = states
+            .get(CompartmentKind::Main, &shift)
+            .is_some_and(|s| s.is_pressed);
+        states.update_referenced_keys(CompartmentKind::Main, [&shift].into_iter(), true);
+        // Then
+        assert!(shift_pressed_before_reset);
+        assert!(states.get(CompartmentKind::Main, &other).is_none());
+        assert!(states.get(CompartmentKind::Main, &shift).is_none());
+    }
+}
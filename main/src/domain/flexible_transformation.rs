@@ -0,0 +1,130 @@
+use crate::base::CloneAsDefault;
+use crate::domain::{
+    AdditionalTransformationInput, EelTransformation, LuaTransformation, ResponseCurve,
+};
+use helgoboss_learn::{Transformation, TransformationInput, TransformationOutput};
+
+/// See [`crate::domain::MidiSource`] for an explanation of the Lua script wrapping.
+type LuaTransformationType = CloneAsDefault<Option<LuaTransformation<'static>>>;
+
+/// A transformation written either in EEL or Lua, optionally shaped by a response curve.
+///
+/// EEL transformations survive cloning and can therefore also be executed in the real-time
+/// processor. Lua transformations are removed when cloned, so mappings using them must be
+/// controlled in the main thread.
+#[derive(Clone, Debug)]
+pub enum FlexibleTransformation {
+    Eel(EelTransformation),
+    Lua(LuaTransformationType),
+    ResponseCurve(Box<ResponseCurveTransformation>),
+}
+
+/// Applies a response curve and, if available, a script transformation.
+///
+/// In control direction, the curve is applied first and the script receives the curved value.
+/// In feedback direction, it's the other way around and the curve is inverted.
+#[derive(Clone, Debug)]
+pub struct ResponseCurveTransformation {
+    curve: ResponseCurve,
+    is_feedback: bool,
+    script: Option<FlexibleTransformation>,
+}
+
+impl FlexibleTransformation {
+    pub fn lua(transformation: LuaTransformation<'static>) -> Self {
+        Self::Lua(CloneAsDefault::new(Some(transformation)))
+    }
+
+    pub fn response_curve(
+        curve: ResponseCurve,
+        is_feedback: bool,
+        script: Option<FlexibleTransformation>,
+    ) -> Self {
+        let transformation = ResponseCurveTransformation {
+            curve,
+            is_feedback,
+            script,
+        };
+        Self::ResponseCurve(Box::new(transformation))
+    }
+
+    pub fn is_main_thread_only(&self) -> bool {
+        match self {
+            Self::Eel(_) => false,
+            Self::Lua(_) => true,
+            Self::ResponseCurve(t) => t.script.as_ref().is_some_and(|s| s.is_main_thread_only()),
+        }
+    }
+}
+
+impl ResponseCurveTransformation {
+    fn transform(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<f64>, &'static str> {
+        if self.is_feedback {
+            let output = match &self.script {
+                None => TransformationOutput::Control(input.value),
+                Some(s) => s.transform(input, output_value, additional_input)?,
+            };
+            let inverted_output = match output {
+                TransformationOutput::Control(v) => {
+                    TransformationOutput::Control(self.curve.evaluate_inverse(v))
+                }
+                TransformationOutput::ControlAndStop(v) => {
+                    TransformationOutput::ControlAndStop(self.curve.evaluate_inverse(v))
+                }
+                o => o,
+            };
+            Ok(inverted_output)
+        } else {
+            let curved_value = self.curve.evaluate(input.value);
+            match &self.script {
+                None => Ok(TransformationOutput::Control(curved_value)),
+                Some(s) => {
+                    let curved_input = TransformationInput::new(curved_value, input.meta_data);
+                    s.transform(curved_input, output_value, additional_input)
+                }
+            }
+        }
+    }
+}
+
+impl Transformation for FlexibleTransformation {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<f64>, &'static str> {
+        match self {
+            FlexibleTransformation::Eel(t) => t.transform(input, output_value, additional_input),
+            FlexibleTransformation::Lua(t) => t
+                .get()
+                .as_ref()
+                .ok_or("transformation was removed on clone")?
+                .transform(input, output_value, additional_input),
+            FlexibleTransformation::ResponseCurve(t) => {
+                t.transform(input, output_value, additional_input)
+            }
+        }
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        match self {
+            FlexibleTransformation::Eel(t) => t.wants_to_be_polled(),
+            FlexibleTransformation::Lua(t) => t
+                .get()
+                .as_ref()
+                .map(|t| t.wants_to_be_polled())
+                .unwrap_or(false),
+            FlexibleTransformation::ResponseCurve(t) => {
+                t.script.as_ref().is_some_and(|s| s.wants_to_be_polled())
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use helgoboss_learn::{AbsoluteValue, ControlValue, UnitValue};
+use realearn_api::persistence::GlideCurve;
+use reaper_high::Project;
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GlideSettings {
+    pub duration: GlideDuration,
+    pub curve: GlideCurve,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GlideDuration {
+    Time(Duration),
+    /// Depends on the project tempo at the time the glide starts.
+    Beats(f64),
+}
+
+impl GlideDuration {
+    fn resolve(&self, project: Project) -> Duration {
+        match self {
+            GlideDuration::Time(d) => *d,
+            GlideDuration::Beats(beats) => {
+                let bpm = project.tempo().bpm().get();
+                Duration::from_secs_f64((beats * 60.0 / bpm).max(0.0))
+            }
+        }
+    }
+}
+
+/// Makes the target value move smoothly to a new absolute value instead of jumping there.
+///
+/// The glide is started when the mapping hits its target and driven by the main processor's
+/// control polling. New control input interrupts a running glide: Either by starting a new one
+/// from the current (intermediate) target value or, if the new value can't be glided to, by
+/// cancelling it.
+#[derive(Clone, Debug)]
+pub struct Glide {
+    settings: GlideSettings,
+    active: Option<ActiveGlide>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ActiveGlide {
+    from: UnitValue,
+    to: UnitValue,
+    start: Instant,
+    duration: Duration,
+}
+
+impl ActiveGlide {
+    /// Returns the value at the current point in time and whether the glide is finished.
+    fn current_value(&self, curve: GlideCurve, now: Instant) -> (UnitValue, bool) {
+        let elapsed = now.saturating_duration_since(self.start);
+        if elapsed >= self.duration {
+            return (self.to, true);
+        }
+        let progress = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        let shaped_progress = shape(progress, curve);
+        let value = self.from.get() + (self.to.get() - self.from.get()) * shaped_progress;
+        (UnitValue::new_clamped(value), false)
+    }
+}
+
+impl Glide {
+    pub fn new(settings: GlideSettings) -> Self {
+        Self {
+            settings,
+            active: None,
+        }
+    }
+
+    /// Processes a value which is about to hit the target and returns the value which should
+    /// actually hit it.
+    ///
+    /// Only the first target of a mapping starts the glide. Further targets follow the same
+    /// trajectory.
+    pub fn process(
+        &mut self,
+        target_index: usize,
+        value: ControlValue,
+        current_target_value: Option<AbsoluteValue>,
+        project: Project,
+    ) -> ControlValue {
+        self.process_at(
+            target_index,
+            value,
+            current_target_value,
+            |d| d.resolve(project),
+            Instant::now(),
+        )
+    }
+
+    fn process_at(
+        &mut self,
+        target_index: usize,
+        value: ControlValue,
+        current_target_value: Option<AbsoluteValue>,
+        resolve_duration: impl FnOnce(GlideDuration) -> Duration,
+        now: Instant,
+    ) -> ControlValue {
+        let (ControlValue::AbsoluteContinuous(to), Some(AbsoluteValue::Continuous(from))) =
+            (value, current_target_value)
+        else {
+            self.active = None;
+            return value;
+        };
+        if target_index == 0 {
+            let duration = resolve_duration(self.settings.duration);
+            if duration.is_zero() || from == to {
+                self.active = None;
+                return value;
+            }
+            self.active = Some(ActiveGlide {
+                from,
+                to,
+                start: now,
+                duration,
+            });
+        }
+        match &self.active {
+            None => value,
+            Some(g) => {
+                ControlValue::AbsoluteContinuous(g.current_value(self.settings.curve, now).0)
+            }
+        }
+    }
+
+    /// Returns the next value while gliding. The last returned value is the final one.
+    pub fn poll(&mut self) -> Option<UnitValue> {
+        self.poll_at(Instant::now())
+    }
+
+    fn poll_at(&mut self, now: Instant) -> Option<UnitValue> {
+        let (value, finished) = self
+            .active
+            .as_ref()?
+            .current_value(self.settings.curve, now);
+        if finished {
+            self.active = None;
+        }
+        Some(value)
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = None;
+    }
+}
+
+fn shape(progress: f64, curve: GlideCurve) -> f64 {
+    match curve {
+        GlideCurve::Linear => progress,
+        GlideCurve::EaseIn => progress * progress,
+        GlideCurve::EaseOut => 1.0 - (1.0 - progress) * (1.0 - progress),
+        GlideCurve::EaseInOut => progress * progress * (3.0 - 2.0 * progress),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::Fraction;
+
+    #[test]
+    fn curves_start_and_end_at_bounds() {
+        for curve in [
+            GlideCurve::Linear,
+            GlideCurve::EaseIn,
+            GlideCurve::EaseOut,
+            GlideCurve::EaseInOut,
+        ] {
+            assert_eq!(shape(0.0, curve), 0.0);
+            assert_eq!(shape(1.0, curve), 1.0);
+        }
+        assert_eq!(shape(0.5, GlideCurve::EaseInOut), 0.5);
+        assert!(shape(0.5, GlideCurve::EaseIn) < 0.5);
+        assert!(shape(0.5, GlideCurve::EaseOut) > 0.5);
+    }
+
+    #[test]
+    fn glides_to_new_value_and_can_be_redirected() {
+        // Given
+        let mut glide = Glide::new(GlideSettings {
+            duration: GlideDuration::Time(Duration::from_millis(100)),
+            curve: GlideCurve::Linear,
+        });
+        let start = Instant::now();
+        let resolve = |d: GlideDuration| match d {
+            GlideDuration::Time(d) => d,
+            GlideDuration::Beats(_) => unreachable!(),
+        };
+        // When
+        let first_value = glide.process_at(
+            0,
+            ControlValue::AbsoluteContinuous(UnitValue::new(1.0)),
+            Some(AbsoluteValue::Continuous(UnitValue::new(0.0))),
+            resolve,
+            start,
+        );
+        let halfway_value = glide.poll_at(start + Duration::from_millis(50));
+        // Then
+        assert_eq!(
+            first_value,
+            ControlValue::AbsoluteContinuous(UnitValue::new(0.0))
+        );
+        assert_eq!(halfway_value, Some(UnitValue::new(0.5)));
+        // When
+        let redirect_time = start + Duration::from_millis(50);
+        glide.process_at(
+            0,
+            ControlValue::AbsoluteContinuous(UnitValue::new(0.0)),
+            Some(AbsoluteValue::Continuous(UnitValue::new(0.5))),
+            resolve,
+            redirect_time,
+        );
+        let redirected_value = glide.poll_at(redirect_time + Duration::from_millis(50));
+        let final_value = glide.poll_at(redirect_time + Duration::from_millis(150));
+        // Then
+        assert_eq!(redirected_value, Some(UnitValue::new(0.25)));
+        assert_eq!(final_value, Some(UnitValue::new(0.0)));
+        assert_eq!(
+            glide.poll_at(redirect_time + Duration::from_millis(200)),
+            None
+        );
+    }
+
+    #[test]
+    fn non_continuous_values_cancel_glide() {
+        // Given
+        let mut glide = Glide::new(GlideSettings {
+            duration: GlideDuration::Time(Duration::from_millis(100)),
+            curve: GlideCurve::Linear,
+        });
+        let start = Instant::now();
+        glide.process_at(
+            0,
+            ControlValue::AbsoluteContinuous(UnitValue::new(1.0)),
+            Some(AbsoluteValue::Continuous(UnitValue::new(0.0))),
+            |_| Duration::from_millis(100),
+            start,
+        );
+        // When
+        let value = glide.process_at(
+            0,
+            ControlValue::AbsoluteDiscrete(Fraction::new(1, 10)),
+            Some(AbsoluteValue::Continuous(UnitValue::new(0.2))),
+            |_| Duration::from_millis(100),
+            start + Duration::from_millis(10),
+        );
+        // Then
+        assert_eq!(value, ControlValue::AbsoluteDiscrete(Fraction::new(1, 10)));
+        assert_eq!(glide.poll_at(start + Duration::from_millis(20)), None);
+    }
+}
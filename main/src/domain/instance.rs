@@ -1,14 +1,19 @@
-use crate::domain::{AnyThreadBackboneState, Backbone, ProcessorContext, RealTimeInstance, UnitId};
+use crate::domain::{
+    AnyThreadBackboneState, Backbone, CompartmentKind, ControlLogEntry, MappingKey,
+    ProcessorContext, RealTimeInstance, UnitId,
+};
 #[allow(unused_imports)]
 use anyhow::Context;
 use base::hash_util::NonCryptoHashMap;
 use base::{NamedChannelSender, SenderToNormalThread, SenderToRealTimeThread};
+use helgoboss_learn::AbsoluteValue;
 use pot::{
     CurrentPreset, OptFilter, PotFavorites, PotFilterExcludes, PotIntegration, PotUnit, PresetId,
     SharedRuntimePotUnit,
 };
 use realearn_api::persistence::PotFilterKind;
 use reaper_high::{ChangeEvent, Fx};
+use rosc::OscMessage;
 use std::cell::{Ref, RefCell, RefMut};
 use std::fmt;
 use std::num::ParseIntError;
@@ -29,7 +34,6 @@ pub struct Instance {
     processor_context: ProcessorContext,
     feedback_event_sender: SenderToNormalThread<QualifiedInstanceEvent>,
     main_unit_id: UnitId,
-    #[allow(unused)]
     handler: Box<dyn InstanceHandler>,
     /// Saves the current state for Pot preset navigation.
     ///
@@ -51,6 +55,37 @@ pub struct PlaytimeInstance {
 }
 
 pub trait InstanceHandler: fmt::Debug {
+    /// Returns whether anyone is interested in mapping events (target value changes and
+    /// controlled targets).
+    ///
+    /// Reporting these events involves looking up the mapping, which is skipped if this returns
+    /// `false`.
+    fn wants_mapping_events(&self) -> bool;
+    fn mapping_target_value_changed(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        new_value: AbsoluteValue,
+    );
+    fn mapping_target_controlled(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        log_entry: ControlLogEntry,
+    );
+    /// Returns whether anyone is interested in the values of OSC sources (e.g. OSCQuery clients).
+    ///
+    /// Computing these values involves processing the target value through the mode, which is
+    /// skipped if this returns `false`.
+    fn wants_osc_source_values(&self) -> bool;
+    /// Reports the message that the OSC source of a mapping would send in order to arrive at the
+    /// current target value (with the mode applied).
+    fn osc_source_value_changed(&self, unit_id: UnitId, msg: &OscMessage);
+    fn mappings_in_unit_changed(&self, unit_id: UnitId);
     #[cfg(feature = "playtime")]
     fn clip_matrix_changed(
         &self,
@@ -228,10 +263,7 @@ impl Instance {
     }
 
     pub fn notify_mappings_in_unit_changed(&self, unit_id: UnitId) {
-        #[cfg(not(feature = "playtime"))]
-        {
-            let _ = unit_id;
-        }
+        self.handler.mappings_in_unit_changed(unit_id);
         #[cfg(feature = "playtime")]
         if unit_id == self.main_unit_id {
             if let Some(matrix) = self.clip_matrix() {
@@ -240,6 +272,50 @@ impl Instance {
         }
     }
 
+    pub fn wants_mapping_events(&self) -> bool {
+        self.handler.wants_mapping_events()
+    }
+
+    pub fn notify_mapping_target_value_changed(
+        &self,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        new_value: AbsoluteValue,
+    ) {
+        self.handler.mapping_target_value_changed(
+            self.id,
+            unit_id,
+            compartment,
+            mapping_key,
+            new_value,
+        );
+    }
+
+    pub fn notify_mapping_target_controlled(
+        &self,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        log_entry: ControlLogEntry,
+    ) {
+        self.handler.mapping_target_controlled(
+            self.id,
+            unit_id,
+            compartment,
+            mapping_key,
+            log_entry,
+        );
+    }
+
+    pub fn wants_osc_source_values(&self) -> bool {
+        self.handler.wants_osc_source_values()
+    }
+
+    pub fn notify_osc_source_value_changed(&self, unit_id: UnitId, msg: &OscMessage) {
+        self.handler.osc_source_value_changed(unit_id, msg);
+    }
+
     pub fn notify_learning_target_in_unit_changed(&self, unit_id: UnitId) {
         #[cfg(not(feature = "playtime"))]
         {
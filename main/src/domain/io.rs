@@ -6,6 +6,9 @@ pub enum ControlInput {
     Midi(MidiControlInput),
     Osc(OscDeviceId),
     Keyboard,
+    /// Clients connected via WebSocket which act as controller by sending values for virtual
+    /// control elements.
+    WebClients,
 }
 
 impl ControlInput {
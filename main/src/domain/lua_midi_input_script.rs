@@ -0,0 +1,217 @@
+use crate::domain::{SafeLua, VirtualControlElement, VirtualControlElementId};
+use anyhow::ensure;
+use helgoboss_learn::{ControlValue, DiscreteIncrement, UnitValue};
+use mlua::{FromLua, Function, IntoLua, Lua, Table, Value};
+
+/// A MIDI input script written in Lua.
+///
+/// Parses arbitrary incoming MIDI messages (including SysEx) and turns them into control values.
+/// This is the control counterpart of [`crate::domain::LuaMidiSourceScript`], which can only be
+/// used for feedback. It can only be executed in the main thread.
+#[derive(Debug)]
+pub struct LuaMidiInputScript<'lua> {
+    lua: &'lua SafeLua,
+    function: Function<'lua>,
+    env: Table<'lua>,
+    /// Survives invocations, so scripts can assemble values from multiple messages.
+    state: Table<'lua>,
+    msg_key: Value<'lua>,
+    context_key: Value<'lua>,
+}
+
+unsafe impl<'a> Send for LuaMidiInputScript<'a> {}
+
+/// A control value produced by a MIDI input script.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MidiInputScriptValue {
+    /// If `None`, the value is meant for the mapping which contains the script. Otherwise it's
+    /// directly meant for the given virtual control element.
+    pub control_element: Option<VirtualControlElement>,
+    pub control_value: ControlValue,
+}
+
+impl<'lua> LuaMidiInputScript<'lua> {
+    pub fn compile(lua: &'lua SafeLua, lua_script: &str) -> anyhow::Result<Self> {
+        ensure!(!lua_script.trim().is_empty(), "script empty");
+        let env = lua.create_fresh_environment(false)?;
+        let function = lua.compile_as_function("MIDI input script", lua_script, env.clone())?;
+        let script = Self {
+            lua,
+            function,
+            env,
+            state: lua.as_ref().create_table()?,
+            msg_key: "msg".into_lua(lua.as_ref())?,
+            context_key: "context".into_lua(lua.as_ref())?,
+        };
+        Ok(script)
+    }
+
+    /// Executes the script for the given raw MIDI message (short message or SysEx).
+    pub fn execute(&self, bytes: &[u8]) -> anyhow::Result<Vec<MidiInputScriptValue>> {
+        let lua = self.lua.as_ref();
+        // Set input data as variables "msg" and "context"
+        let msg = lua.create_sequence_from(bytes.iter().copied())?;
+        self.env.raw_set(self.msg_key.clone(), msg)?;
+        let context_table = lua.create_table()?;
+        context_table.set("state", self.state.clone())?;
+        self.env.raw_set(self.context_key.clone(), context_table)?;
+        // Invoke script
+        let value: Value = self
+            .lua
+            .with_execution_time_limit(|| self.function.call(()))?;
+        let output = LuaMidiInputScriptOutput::from_lua(value, lua)?;
+        Ok(output.0)
+    }
+}
+
+/// A script can return:
+///
+/// - `nil` in order to ignore the message
+/// - a number in order to send that absolute value to the mapping
+/// - a table `{ value = ... }` or `{ increment = ... }` in order to send an absolute or relative
+///   value, optionally with `id` (and `character`) in order to address a virtual control element
+///   directly
+/// - a list of such tables in order to send multiple values at once
+struct LuaMidiInputScriptOutput(Vec<MidiInputScriptValue>);
+
+impl<'lua> FromLua<'lua> for LuaMidiInputScriptOutput {
+    fn from_lua(value: Value<'lua>, _: &'lua Lua) -> mlua::Result<Self> {
+        let values = match value {
+            Value::Nil => vec![],
+            Value::Integer(i) => vec![create_own_absolute_value(i as f64)],
+            Value::Number(n) => vec![create_own_absolute_value(n)],
+            Value::Table(t) => {
+                if t.raw_len() > 0 {
+                    let mut values = Vec::with_capacity(t.raw_len());
+                    for entry in t.sequence_values::<Table>() {
+                        values.extend(convert_table_to_value(entry?)?);
+                    }
+                    values
+                } else {
+                    convert_table_to_value(t)?.into_iter().collect()
+                }
+            }
+            _ => {
+                return Err(mlua::Error::runtime(
+                    "MIDI input script must return nil, a number or a table",
+                ))
+            }
+        };
+        Ok(Self(values))
+    }
+}
+
+fn create_own_absolute_value(value: f64) -> MidiInputScriptValue {
+    MidiInputScriptValue {
+        control_element: None,
+        control_value: ControlValue::AbsoluteContinuous(UnitValue::new_clamped(value)),
+    }
+}
+
+fn convert_table_to_value(table: Table) -> mlua::Result<Option<MidiInputScriptValue>> {
+    let value: Option<f64> = table.get("value")?;
+    let increment: Option<i32> = table.get("increment")?;
+    let control_value = match (value, increment) {
+        (Some(v), None) => ControlValue::AbsoluteContinuous(UnitValue::new_clamped(v)),
+        (None, Some(0)) => return Ok(None),
+        (None, Some(i)) => ControlValue::RelativeDiscrete(DiscreteIncrement::new(i)),
+        (None, None) => return Ok(None),
+        (Some(_), Some(_)) => {
+            return Err(mlua::Error::runtime(
+                "MIDI input script value must not have both 'value' and 'increment'",
+            ))
+        }
+    };
+    let control_element = match table.get::<_, Option<String>>("id")? {
+        None => None,
+        Some(id) => {
+            let id: VirtualControlElementId = id.parse().map_err(mlua::Error::runtime)?;
+            let character: Option<String> = table.get("character")?;
+            let element = match character.as_deref() {
+                None | Some("Multi") => VirtualControlElement::Multi(id),
+                Some("Button") => VirtualControlElement::Button(id),
+                Some(_) => {
+                    return Err(mlua::Error::runtime(
+                        "character must be either 'Multi' or 'Button'",
+                    ))
+                }
+            };
+            Some(element)
+        }
+    };
+    let value = MidiInputScriptValue {
+        control_element,
+        control_value,
+    };
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_value() {
+        // Given
+        let text = "
+            if msg[1] ~= 0xf0 or msg[2] ~= 0x02 then
+                return nil
+            end
+            return msg[3] / 127
+        ";
+        let lua = SafeLua::new().unwrap();
+        let script = LuaMidiInputScript::compile(&lua, text).unwrap();
+        // When
+        let matched = script.execute(&[0xf0, 0x02, 0x7f, 0xf7]).unwrap();
+        let unmatched = script.execute(&[0xb0, 0x02, 0x7f]).unwrap();
+        // Then
+        assert_eq!(
+            matched,
+            vec![MidiInputScriptValue {
+                control_element: None,
+                control_value: ControlValue::AbsoluteContinuous(UnitValue::MAX),
+            }]
+        );
+        assert_eq!(unmatched, vec![]);
+    }
+
+    #[test]
+    fn multiple_virtual_control_elements() {
+        // Given
+        let text = "
+            context.state.count = (context.state.count or 0) + 1
+            return {
+                { id = \"strip/position\", value = msg[2] / 127 },
+                { id = \"strip/pressure\", value = msg[3] / 127 },
+                { id = 5, increment = -context.state.count },
+            }
+        ";
+        let lua = SafeLua::new().unwrap();
+        let script = LuaMidiInputScript::compile(&lua, text).unwrap();
+        // When
+        script.execute(&[0xf0, 0x00, 0x00, 0xf7]).unwrap();
+        let values = script.execute(&[0xf0, 0x7f, 0x00, 0xf7]).unwrap();
+        // Then
+        assert_eq!(values.len(), 3);
+        assert_eq!(
+            values[0].control_element,
+            Some(VirtualControlElement::Multi(
+                "strip/position".parse().unwrap()
+            ))
+        );
+        assert_eq!(
+            values[0].control_value,
+            ControlValue::AbsoluteContinuous(UnitValue::MAX)
+        );
+        assert_eq!(
+            values[2].control_element,
+            Some(VirtualControlElement::Multi(
+                VirtualControlElementId::Indexed(4)
+            ))
+        );
+        assert_eq!(
+            values[2].control_value,
+            ControlValue::RelativeDiscrete(DiscreteIncrement::new(-2))
+        );
+    }
+}
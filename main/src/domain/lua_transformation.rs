@@ -0,0 +1,203 @@
+use crate::domain::{lua_module_path_without_ext, AdditionalTransformationInput, SafeLua};
+use anyhow::ensure;
+use helgoboss_learn::{Transformation, TransformationInput, TransformationOutput};
+use mlua::{FromLua, Function, IntoLua, Lua, Table, Value};
+use std::time::Instant;
+
+/// Represents a control transformation written in Lua.
+///
+/// In contrast to [`crate::domain::EelTransformation`], this can only be executed in the main
+/// thread.
+#[derive(Debug)]
+pub struct LuaTransformation<'lua> {
+    lua: &'lua SafeLua,
+    function: Function<'lua>,
+    env: Table<'lua>,
+    /// Survives invocations, so scripts can remember things between control events.
+    state: Table<'lua>,
+    x_key: Value<'lua>,
+    y_key: Value<'lua>,
+    context_key: Value<'lua>,
+    compiled_at: Instant,
+    wants_to_be_polled: bool,
+}
+
+unsafe impl<'a> Send for LuaTransformation<'a> {}
+
+impl<'lua> LuaTransformation<'lua> {
+    pub fn compile_for_control(lua: &'lua SafeLua, lua_script: &str) -> anyhow::Result<Self> {
+        ensure!(!lua_script.trim().is_empty(), "script empty");
+        let env = lua.create_fresh_environment(false)?;
+        let function =
+            lua.compile_as_function("Control transformation", lua_script, env.clone())?;
+        let transformation = Self {
+            lua,
+            function,
+            env,
+            state: lua.as_ref().create_table()?,
+            x_key: "x".into_lua(lua.as_ref())?,
+            y_key: "y".into_lua(lua.as_ref())?,
+            context_key: "context".into_lua(lua.as_ref())?,
+            compiled_at: Instant::now(),
+            wants_to_be_polled: lua_script_uses_time(lua_script),
+        };
+        Ok(transformation)
+    }
+
+    fn transform_internal(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> mlua::Result<TransformationOutput<f64>> {
+        let lua = self.lua.as_ref();
+        // Set input data as variables "x", "y" and "context"
+        self.env.raw_set(self.x_key.clone(), input.value)?;
+        self.env.raw_set(self.y_key.clone(), output_value)?;
+        let context_table = lua.create_table()?;
+        context_table.set("y_last", additional_input.y_last)?;
+        context_table.set("rel_time", input.meta_data.rel_time.as_millis() as f64)?;
+        context_table.set("timestamp", self.compiled_at.elapsed().as_secs_f64())?;
+        context_table.set("state", self.state.clone())?;
+        self.env.raw_set(self.context_key.clone(), context_table)?;
+        // The rest is scoped because we want to create a scoped function
+        let value = lua.scope(|scope| {
+            let require = scope.create_function(move |_, path: String| {
+                let val: Value = match lua_module_path_without_ext(&path) {
+                    "compartment" => additional_input
+                        .compartment_lua
+                        .clone()
+                        .unwrap_or(Value::Nil),
+                    _ => {
+                        return Err(mlua::Error::runtime(
+                            "Control transformations don't support the usage of 'require' for anything else than 'compartment'!",
+                        ))
+                    }
+                };
+                Ok(val)
+            })?;
+            self.env.raw_set("require", require)?;
+            let value: Value = self.function.call(())?;
+            Ok(value)
+        })?;
+        let output = LuaTransformationOutput::from_lua(value, lua)?;
+        Ok(output.0)
+    }
+}
+
+impl<'lua> Transformation for LuaTransformation<'lua> {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<f64>,
+        output_value: f64,
+        additional_input: AdditionalTransformationInput,
+    ) -> Result<TransformationOutput<f64>, &'static str> {
+        self.transform_internal(input, output_value, additional_input)
+            .map_err(|e| {
+                let error = e.to_string();
+                tracing::debug!(msg = "Failed to execute Lua control transformation", %error);
+                "Lua control transformation failed"
+            })
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        self.wants_to_be_polled
+    }
+}
+
+/// Returns whether the given Lua control transformation script uses the time since the last
+/// control event, in which case it must be invoked repeatedly.
+pub fn lua_script_uses_time(lua_script: &str) -> bool {
+    lua_script.contains("rel_time")
+}
+
+/// A script can return:
+///
+/// - `nil` in order to not do anything
+/// - a number in order to set the target to that value
+/// - a table `{ value = ..., stop = ... }` in order to stop repeated invocation (optionally
+///   setting the target to a final value)
+struct LuaTransformationOutput(TransformationOutput<f64>);
+
+impl<'lua> FromLua<'lua> for LuaTransformationOutput {
+    fn from_lua(value: Value<'lua>, _: &'lua Lua) -> mlua::Result<Self> {
+        let output = match value {
+            Value::Nil => TransformationOutput::None,
+            Value::Integer(i) => TransformationOutput::Control(i as f64),
+            Value::Number(n) => TransformationOutput::Control(n),
+            Value::Table(t) => {
+                let value: Option<f64> = t.get("value")?;
+                let stop: Option<bool> = t.get("stop")?;
+                match (value, stop.unwrap_or(false)) {
+                    (None, false) => TransformationOutput::None,
+                    (None, true) => TransformationOutput::Stop,
+                    (Some(v), false) => TransformationOutput::Control(v),
+                    (Some(v), true) => TransformationOutput::ControlAndStop(v),
+                }
+            }
+            _ => {
+                return Err(mlua::Error::runtime(
+                    "control transformation must return nil, a number or a table",
+                ))
+            }
+        };
+        Ok(Self(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::TransformationInputMetaData;
+    use std::time::Duration;
+
+    fn input(x: f64, rel_time_millis: u64) -> TransformationInput<f64> {
+        TransformationInput::new(
+            x,
+            TransformationInputMetaData {
+                rel_time: Duration::from_millis(rel_time_millis),
+            },
+        )
+    }
+
+    #[test]
+    fn basics() {
+        // Given
+        let lua = SafeLua::new().unwrap();
+        let transformation = LuaTransformation::compile_for_control(&lua, "return 1 - x").unwrap();
+        // When
+        let output = transformation
+            .transform(input(0.25, 0), 0.0, Default::default())
+            .unwrap();
+        // Then
+        assert!(matches!(output, TransformationOutput::Control(v) if v == 0.75));
+        assert!(!transformation.wants_to_be_polled());
+    }
+
+    #[test]
+    fn state_and_stop() {
+        // Given
+        let text = "
+            context.state.count = (context.state.count or 0) + 1
+            if context.rel_time >= 1000 then
+                return { value = context.state.count / 10, stop = true }
+            end
+            return nil
+        ";
+        let lua = SafeLua::new().unwrap();
+        let transformation = LuaTransformation::compile_for_control(&lua, text).unwrap();
+        // When
+        let first = transformation
+            .transform(input(1.0, 0), 0.0, Default::default())
+            .unwrap();
+        let second = transformation
+            .transform(input(1.0, 1000), 0.0, Default::default())
+            .unwrap();
+        // Then
+        assert!(transformation.wants_to_be_polled());
+        assert!(matches!(first, TransformationOutput::None));
+        assert!(matches!(second, TransformationOutput::ControlAndStop(v) if v == 0.2));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::domain::{
     aggregate_target_values, format_as_pretty_hex, get_project_options, say,
-    AdditionalFeedbackEvent, AdditionalLuaMidiSourceScriptInput, Backbone, CompartmentKind,
-    CompoundChangeEvent, CompoundFeedbackValue, CompoundMappingSource,
+    AdditionalFeedbackEvent, AdditionalLuaMidiSourceScriptInput, Backbone, ButtonStates,
+    CompartmentKind, CompoundChangeEvent, CompoundFeedbackValue, CompoundMappingSource,
     CompoundMappingSourceAddress, CompoundMappingTarget, ControlContext, ControlEvent,
     ControlEventTimestamp, ControlInput, ControlLogContext, ControlLogEntry, ControlLogEntryKind,
     ControlMode, ControlOutcome, DeviceFeedbackOutput, DomainEvent, DomainEventHandler,
@@ -112,6 +112,8 @@ struct Basics<EH: DomainEventHandler> {
         RefCell<NonCryptoHashMap<CompoundMappingSourceAddress, FeedbackChecksum>>,
     target_based_conditional_activation_processors:
         EnumMap<CompartmentKind, TargetBasedConditionalActivationProcessor>,
+    /// Needed for detecting chord and sequence fire gestures.
+    button_states: RefCell<ButtonStates>,
 }
 
 /// Used for detecting and preventing subsequent duplicate feedback.
@@ -236,6 +238,9 @@ struct Collections {
     ///  could be optimized. However, this is what makes the seek target work currently when
     ///  changing cursor position while stopped.
     milli_dependent_feedback_mappings: EnumMap<CompartmentKind, OrderedMappingIdSet>,
+    /// Contains IDs of those mappings which have feedback animations. While an animation is
+    /// running, feedback is sent in each main loop cycle.
+    feedback_animation_mappings: EnumMap<CompartmentKind, OrderedMappingIdSet>,
     parameters: PluginParams,
     previous_target_values: EnumMap<CompartmentKind, NonCryptoHashMap<MappingId, AbsoluteValue>>,
 }
@@ -326,6 +331,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 },
                 last_feedback_checksum_by_address: Default::default(),
                 target_based_conditional_activation_processors: Default::default(),
+                button_states: Default::default(),
             },
             collections: Collections {
                 mappings: Default::default(),
@@ -333,6 +339,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 target_touch_dependent_mappings: Default::default(),
                 beat_dependent_feedback_mappings: Default::default(),
                 milli_dependent_feedback_mappings: Default::default(),
+                feedback_animation_mappings: Default::default(),
                 parameters: Default::default(),
                 previous_target_values: Default::default(),
             },
@@ -445,6 +452,28 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             } => {
                 let _ = self.control_from_real_time(compartment, mapping_id, event, options);
             }
+            RecordButtonState {
+                compartment,
+                mapping_id,
+                event,
+            } => {
+                if let Some(m) = self.get_normal_or_virtual_target_mapping(compartment, mapping_id)
+                {
+                    self.basics.button_states.borrow_mut().record_if_referenced(
+                        compartment,
+                        m.key(),
+                        event,
+                    );
+                }
+            }
+            ControlFromMidiInputScript { event } => {
+                let timestamp = event.timestamp();
+                let raw_midi_event = event.into_payload();
+                self.process_incoming_midi_for_input_scripts(ControlEvent::new(
+                    &raw_midi_event,
+                    timestamp,
+                ));
+            }
             LogVirtualControlInput {
                 event: value,
                 match_outcome: match_result,
@@ -495,76 +524,108 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
     fn poll_control(&mut self, timestamp: ControlEventTimestamp) {
         for compartment in CompartmentKind::enum_iter() {
             for id in self.poll_control_mappings[compartment].iter() {
-                let (is_source_poll, control_result, group_interaction) = if let Some(m) =
-                    self.collections.mappings[compartment].get_mut(id)
-                {
-                    let control_context = self.basics.control_context(compartment);
-                    let processor_context = ExtendedProcessorContext::new(
-                        &self.basics.context,
-                        &self.collections.parameters,
-                        control_context,
-                    );
-                    let mode_poll_result = if m.mode().wants_to_be_polled() {
-                        m.poll_mode(
+                let (is_source_poll, control_result, group_interaction) =
+                    if let Some(m) = self.collections.mappings[compartment].get_mut(id) {
+                        let control_context = self.basics.control_context(compartment);
+                        let processor_context = ExtendedProcessorContext::new(
+                            &self.basics.context,
+                            &self.collections.parameters,
                             control_context,
-                            &self.basics.logger,
-                            processor_context,
-                            timestamp,
-                            self.basics.target_control_logger(
-                                ControlLogContext::Polling,
-                                m.qualified_id(),
-                            ),
-                        )
-                    } else {
-                        Default::default()
-                    };
-                    let (is_source_poll, mut final_poll_result) = if mode_poll_result
-                        .at_least_one_target_was_reached
-                    {
-                        // Mode was polled successfully. This one has precedence.
-                        // We poll even if control is effectively off because it might have been
-                        // on before and user might have pressed a button which started some
-                        // timer - and we still want that timer to fire. This is practical e.g.
-                        // when having a single-press button with a modifier. It's not uncommon
-                        // to shortly press the modifier, press the single-press button and
-                        // release the modifier. If we wouldn't poll anymore in that case, the
-                        // single press would be discarded - or worse, fired when the mapping
-                        // is enabled again.
-                        (false, mode_poll_result)
-                    } else if m.source().wants_to_be_polled() && m.control_is_effectively_on() {
-                        // Mode was either not polled at all or without result, poll source.
-                        let res = if let Some(source_control_value) = m.poll_source() {
-                            let control_event = ControlEvent::new(source_control_value, timestamp);
-                            control_mapping_stage_one(
-                                &self.basics,
-                                &self.collections.parameters,
-                                m,
-                                control_event,
-                                ControlOptions::default(),
+                        );
+                        let mode_poll_result = if m.mode().wants_to_be_polled() {
+                            m.poll_mode(
+                                control_context,
+                                &self.basics.logger,
+                                processor_context,
+                                timestamp,
+                                self.basics.target_control_logger(
+                                    ControlLogContext::Polling,
+                                    m.qualified_id(),
+                                ),
                             )
                         } else {
                             Default::default()
                         };
-                        (true, res)
+                        let (is_source_poll, mut final_poll_result) =
+                            if mode_poll_result.at_least_one_target_was_reached {
+                                // Mode was polled successfully. This one has precedence.
+                                // We poll even if control is effectively off because it might have been
+                                // on before and user might have pressed a button which started some
+                                // timer - and we still want that timer to fire. This is practical e.g.
+                                // when having a single-press button with a modifier. It's not uncommon
+                                // to shortly press the modifier, press the single-press button and
+                                // release the modifier. If we wouldn't poll anymore in that case, the
+                                // single press would be discarded - or worse, fired when the mapping
+                                // is enabled again.
+                                (false, mode_poll_result)
+                            } else if let Some(control_event) = m.poll_fire_gesture(timestamp) {
+                                // A multi-press series has ended. This is like a delayed source
+                                // control event.
+                                let result = control_mapping_from_mode(
+                                    &self.basics,
+                                    &self.collections.parameters,
+                                    m,
+                                    control_event,
+                                    ControlOptions::default(),
+                                );
+                                (true, result)
+                            } else {
+                                // Mode was either not polled at all or without result. Poll the source
+                                // independently of a running glide. A new source value hits the target
+                                // via the glide, so it just redirects it.
+                                let source_wants_to_be_polled = m.source().wants_to_be_polled()
+                                    && m.control_is_effectively_on();
+                                let source_poll_result = if source_wants_to_be_polled {
+                                    let project =
+                                        processor_context.context().project_or_current_project();
+                                    m.poll_source(project).map(|source_control_value| {
+                                        let control_event =
+                                            ControlEvent::new(source_control_value, timestamp);
+                                        control_mapping_stage_one(
+                                            &self.basics,
+                                            &self.collections.parameters,
+                                            m,
+                                            control_event,
+                                            ControlOptions::default(),
+                                        )
+                                    })
+                                } else {
+                                    None
+                                };
+                                if let Some(res) = source_poll_result {
+                                    (true, res)
+                                } else if let Some(glide_poll_result) = m.poll_glide(
+                                    control_context,
+                                    &self.basics.logger,
+                                    processor_context,
+                                    self.basics.target_control_logger(
+                                        ControlLogContext::Polling,
+                                        m.qualified_id(),
+                                    ),
+                                ) {
+                                    // Target value is gliding. Treated like a mode poll.
+                                    (false, glide_poll_result)
+                                } else if source_wants_to_be_polled {
+                                    (true, Default::default())
+                                } else {
+                                    // Source doesn't want to be polled and nothing is gliding.
+                                    (false, mode_poll_result)
+                                }
+                            };
+                        control_mapping_stage_two(
+                            &self.basics,
+                            &mut final_poll_result,
+                            m,
+                            ManualFeedbackProcessing::On {
+                                mappings_with_virtual_targets: &self
+                                    .collections
+                                    .mappings_with_virtual_targets,
+                            },
+                        );
+                        (is_source_poll, final_poll_result, m.group_interaction())
                     } else {
-                        // Mode was either not polled at all or without result, source doesn't
-                        // want to be polled.
-                        (false, mode_poll_result)
+                        continue;
                     };
-                    control_mapping_stage_two(
-                        &self.basics,
-                        &mut final_poll_result,
-                        m,
-                        ManualFeedbackProcessing::On {
-                            mappings_with_virtual_targets: &self
-                                .collections
-                                .mappings_with_virtual_targets,
-                        },
-                    );
-                    (is_source_poll, final_poll_result, m.group_interaction())
-                } else {
-                    continue;
-                };
 
                 // When this is a mode poll, we only do target-value based group interaction after
                 // polling (makes sense because control-value based one has been done at control
@@ -603,6 +664,14 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         control_event: ControlEvent<ControlValue>,
         options: ControlOptions,
     ) -> Result<(), &'static str> {
+        if compartment == CompartmentKind::Controller
+            && self
+                .collections
+                .mappings_with_virtual_targets
+                .contains_key(&mapping_id)
+        {
+            return self.control_virtual_target_from_real_time(mapping_id, control_event);
+        }
         // Resolving mappings with virtual targets is not necessary anymore. It has
         // been done in the real-time processor already.
         let (control_result, group_interaction) = {
@@ -645,6 +714,42 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         Ok(())
     }
 
+    /// Processes controller mappings with virtual targets which the real-time processor couldn't
+    /// process itself because their transformation can only be executed in the main thread.
+    fn control_virtual_target_from_real_time(
+        &mut self,
+        mapping_id: MappingId,
+        control_event: ControlEvent<ControlValue>,
+    ) -> Result<(), &'static str> {
+        let m = self
+            .collections
+            .mappings_with_virtual_targets
+            .get_mut(&mapping_id)
+            .ok_or("mapping not found")?;
+        if !m.control_is_effectively_on() {
+            return Ok(());
+        }
+        let Some(virtual_source_value) = m.control_virtualizing_from_value(control_event) else {
+            return Ok(());
+        };
+        self.basics
+            .event_handler
+            .notify_mapping_matched(CompartmentKind::Controller, mapping_id);
+        let options = ControlOptions {
+            enforce_send_feedback_after_control: m.options().feedback_send_behavior
+                == FeedbackSendBehavior::SendFeedbackAfterControl,
+            mode_control_options: m.mode_control_options(),
+            enforce_target_refresh: false,
+            // The main mappings haven't been touched by the real-time processor
+            coming_from_real_time: false,
+        };
+        self.process_virtual_source_value_with_options(
+            control_event.with_payload(virtual_source_value),
+            options,
+        );
+        Ok(())
+    }
+
     /// This should be regularly called by the control surface, even during global target learning.
     pub fn run_essential(&mut self, timestamp: ControlEventTimestamp) {
         self.process_normal_tasks_from_real_time_processor();
@@ -653,6 +758,40 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         self.process_feedback_tasks();
         self.process_unit_events();
         self.poll_for_feedback();
+        self.poll_feedback_animations();
+    }
+
+    /// Sends feedback for all mappings whose feedback animation is currently running.
+    ///
+    /// Feedback values which didn't change since the last cycle are blocked when sending.
+    fn poll_feedback_animations(&mut self) {
+        for compartment in CompartmentKind::enum_iter() {
+            let control_context = self.basics.control_context(compartment);
+            let feedback_values: Vec<_> = self.collections.feedback_animation_mappings[compartment]
+                .iter()
+                .filter_map(|id| self.collections.mappings[compartment].get(id))
+                .filter(|m| m.feedback_is_effectively_on())
+                .filter_map(|m| {
+                    let target_value = m.current_aggregated_target_value(control_context);
+                    if !m.feedback_animation_is_running(target_value) {
+                        return None;
+                    }
+                    m.feedback_entry_point(
+                        false,
+                        self.basics.instance_feedback_is_effectively_enabled(),
+                        target_value,
+                        control_context,
+                        NoopLogger,
+                    )
+                    .map(CompoundFeedbackValue::normal)
+                })
+                .collect();
+            self.basics.send_feedback(
+                &self.collections.mappings_with_virtual_targets,
+                FeedbackReason::Normal,
+                feedback_values,
+            );
+        }
     }
 
     /// This goes through all mappings that returned "high" feedback resolution - which they do if
@@ -1058,6 +1197,10 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             })
             .collect();
         self.process_activation_effects(compartment, activation_effects, true);
+        // Scale quantization
+        for m in self.all_mappings_in_compartment(compartment) {
+            m.update_scale_quantization_from_params(&self.collections.parameters);
+        }
         // Control ("Helgobox parameter source")
         let control_payload = RealearnParameterChangePayload {
             compartment,
@@ -1191,6 +1334,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                         changed_mappings.push(m.id())
                     }
                 }
+                m.update_scale_quantization_from_params(&self.collections.parameters);
                 if m.target_can_be_affected_by_parameters() {
                     let control_context = self.basics.control_context(m.compartment());
                     let context = ExtendedProcessorContext::new(
@@ -1246,6 +1390,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 HitTarget { id, value } => {
                     self.hit_target(id, value);
                 }
+                ControlVirtualElement(value) => {
+                    self.control_virtual_element(ControlEvent::new(value, timestamp));
+                }
                 NotifyConditionsChanged => {
                     conditions_changed = true;
                 }
@@ -1473,6 +1620,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         self.collections.target_touch_dependent_mappings[compartment].clear();
         self.collections.beat_dependent_feedback_mappings[compartment].clear();
         self.collections.milli_dependent_feedback_mappings[compartment].clear();
+        self.collections.feedback_animation_mappings[compartment].clear();
         self.basics.target_based_conditional_activation_processors[compartment].clear();
         self.collections.previous_target_values[compartment].clear();
         self.poll_control_mappings[compartment].clear();
@@ -1513,6 +1661,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 if feedback_resolution == Some(FeedbackResolution::High) {
                     self.collections.milli_dependent_feedback_mappings[compartment].insert(m.id());
                 }
+                if m.has_feedback_animation() {
+                    self.collections.feedback_animation_mappings[compartment].insert(m.id());
+                }
                 if m.wants_to_be_polled_for_control() {
                     self.poll_control_mappings[compartment].insert(m.id());
                 }
@@ -1547,6 +1698,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 compartment,
                 real_time_mappings,
             ));
+        self.update_button_state_recording(compartment, true);
         // Important to send IO event first ...
         self.notify_feedback_dev_usage_might_have_changed(compartment);
         // ... and then mapping update. Otherwise, if this is an upper-floor instance
@@ -1879,6 +2031,8 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             self.basics
                 .event_handler
                 .handle_event_ignoring_error(DomainEvent::MidiDevicesChanged);
+            // Targets which offer MIDI devices (e.g. record inputs) need to be resolved again
+            self.basics.notify_conditions_changed();
         }
         // Then let mappings with REAPER sources process them, if controlling enabled.
         if self.basics.control_mode != ControlMode::Controlling {
@@ -1968,6 +2122,101 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         virtual_match_outcome.merge_with(real_match_outcome)
     }
 
+    /// Processes a MIDI message which the real-time processor forwarded because at least one
+    /// mapping has a MIDI input script source.
+    fn process_incoming_midi_for_input_scripts(&mut self, evt: ControlEvent<&RawMidiEvent>) {
+        let msg = MainSourceMessage::Midi(evt.payload());
+        self.process_incoming_message_internal(evt.with_payload(msg));
+        if self.basics.control_mode != ControlMode::Controlling {
+            return;
+        }
+        // Scripts in the controller compartment can address virtual control elements directly
+        let virtual_source_values: Vec<_> = self
+            .collections
+            .mappings_with_virtual_targets
+            .values_mut()
+            .chain(self.collections.mappings[CompartmentKind::Controller].values_mut())
+            .flat_map(|m| m.take_virtual_source_values_from_source())
+            .collect();
+        for value in virtual_source_values {
+            self.process_virtual_source_value(evt.with_payload(value));
+        }
+    }
+
+    /// Processes a virtual control element value sent by a web client.
+    ///
+    /// The client acts as controller, so this only has an effect if the web clients are the
+    /// control input of this unit. Also, the client can only control elements which the
+    /// controller compartment provides, that is, elements which are the target of at least one
+    /// controller mapping with control enabled.
+    fn control_virtual_element(&mut self, evt: ControlEvent<VirtualSourceValue>) {
+        if self.basics.control_mode != ControlMode::Controlling
+            || self.basics.settings.control_input != ControlInput::WebClients
+            || !self.basics.instance_control_is_effectively_enabled()
+        {
+            return;
+        }
+        let element = evt.payload().control_element();
+        let element_is_provided_by_controller = self
+            .collections
+            .mappings_with_virtual_targets
+            .values()
+            .any(|m| {
+                m.control_is_effectively_on() && m.virtual_target_control_element() == Some(element)
+            });
+        if !element_is_provided_by_controller {
+            return;
+        }
+        self.process_virtual_source_value(evt);
+    }
+
+    /// Controls main mappings with virtual sources directly, without going through the controller
+    /// compartment.
+    fn process_virtual_source_value(&mut self, evt: ControlEvent<VirtualSourceValue>) {
+        self.process_virtual_source_value_with_options(evt, ControlOptions::default());
+    }
+
+    fn process_virtual_source_value_with_options(
+        &mut self,
+        evt: ControlEvent<VirtualSourceValue>,
+        options: ControlOptions,
+    ) {
+        let value = evt.payload();
+        let mut results = self.basics.process_main_mappings_with_virtual_sources(
+            &mut self.collections.mappings[CompartmentKind::Main],
+            evt,
+            options,
+            &self.collections.parameters,
+        );
+        if self.basics.settings.virtual_input_logging_enabled {
+            let match_outcome = if results.is_empty() {
+                MatchOutcome::Unmatched
+            } else {
+                MatchOutcome::Matched
+            };
+            log_virtual_control_input(
+                self.unit_id(),
+                format_control_input_with_match_result(value, match_outcome),
+            );
+        }
+        self.basics.send_feedback(
+            &self.collections.mappings_with_virtual_targets,
+            FeedbackReason::Normal,
+            results
+                .iter_mut()
+                .filter_map(|r| r.control_result.feedback_value.take()),
+        );
+        for r in results {
+            control_mapping_stage_three(
+                &self.basics,
+                &mut self.collections,
+                r.compartment,
+                r.control_result,
+                GroupInteractionProcessing::On(r.group_interaction_input),
+            )
+        }
+    }
+
     /// This doesn't check if control enabled! You need to check before.
     pub fn process_incoming_osc_packet(&mut self, evt: ControlEvent<&OscPacket>) {
         if self.basics.settings.real_input_logging_enabled {
@@ -2049,17 +2298,35 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             let mut enforce_target_refresh = false;
             // Search for 958 to know why we use a for loop here instead of collect().
             let mut results = vec![];
-            for m in self.collections.mappings[compartment]
-                .values_mut()
-                .filter(|m| m.control_is_effectively_on())
-            {
+            for m in self.collections.mappings[compartment].values_mut() {
+                let control_is_on = m.control_is_effectively_on();
+                let records_button_state = self
+                    .basics
+                    .button_states
+                    .borrow()
+                    .is_referenced(compartment, m.key());
+                if !control_is_on && !records_button_state {
+                    continue;
+                }
                 let control_outcome = m.control_source(evt.payload());
-                match_outcome.upgrade_from(control_outcome.into());
+                if control_is_on {
+                    match_outcome.upgrade_from(control_outcome.into());
+                }
                 let control_value = match control_outcome {
                     Some(ControlOutcome::Matched(v)) => v,
                     _ => continue,
                 };
                 let control_event = evt.with_payload(control_value);
+                if records_button_state {
+                    self.basics.button_states.borrow_mut().record(
+                        compartment,
+                        m.key(),
+                        control_event,
+                    );
+                }
+                if !control_is_on {
+                    continue;
+                }
                 let options = ControlOptions {
                     enforce_target_refresh,
                     ..Default::default()
@@ -2220,6 +2487,33 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         );
     }
 
+    /// Lets the button state tracking know which mappings are referenced by chords and sequences.
+    ///
+    /// The real-time processor needs to know as well, because it must report the button presses
+    /// of these mappings even if it doesn't control them.
+    fn update_button_state_recording(&mut self, compartment: CompartmentKind, reset: bool) {
+        let referenced_keys: NonCryptoHashSet<Rc<str>> = self
+            .all_mappings_in_compartment(compartment)
+            .filter_map(|m| m.fire_gesture_referenced_mapping_key().cloned())
+            .collect();
+        let recording_mapping_ids = self
+            .all_mappings_in_compartment(compartment)
+            .filter(|m| referenced_keys.contains(m.key()))
+            .map(|m| m.id())
+            .collect();
+        self.basics
+            .button_states
+            .borrow_mut()
+            .update_referenced_keys(compartment, referenced_keys.iter(), reset);
+        self.basics
+            .channels
+            .normal_real_time_task_sender
+            .send_complaining(NormalRealTimeTask::UpdateButtonStateRecording(
+                compartment,
+                recording_mapping_ids,
+            ));
+    }
+
     fn all_mappings(&self) -> impl Iterator<Item = &MainMapping> {
         self.all_mappings_without_virtual_targets()
             .chain(self.collections.mappings_with_virtual_targets.values())
@@ -2432,6 +2726,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             FeedbackReason::Normal,
             self.feedback_all_in_compartment(compartment),
         );
+        self.notify_instance_about_current_osc_source_values(
+            self.all_mappings_in_compartment(compartment),
+        );
         // It's important to send that *after* sending normal feedback since #660 because we might
         // have global source state (e.g. X-Touch Color state) and the order how we apply feedback
         // values to that global source state matters. It must correspond to the order in which
@@ -2446,9 +2743,15 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         now_unused_sources: UnusedSources,
         mapping_ids: impl Iterator<Item = MappingId>,
     ) {
+        let mapping_ids: Vec<_> = mapping_ids.collect();
         self.send_feedback(
             FeedbackReason::Normal,
-            self.feedback_particular_mappings(compartment, mapping_ids),
+            self.feedback_particular_mappings(compartment, mapping_ids.iter().copied()),
+        );
+        self.notify_instance_about_current_osc_source_values(
+            mapping_ids
+                .iter()
+                .filter_map(|id| self.get_normal_or_virtual_target_mapping(compartment, *id)),
         );
         // It's important to send that *after* sending normal feedback since #660 because we might
         // have global source state (e.g. X-Touch Color state) and the order how we apply feedback
@@ -2458,6 +2761,21 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         self.send_off_feedback_for_unused_sources(now_unused_sources);
     }
 
+    /// Makes sure that OSCQuery clients know the values of OSC sources right after mappings have
+    /// been updated, not just when the target values change.
+    fn notify_instance_about_current_osc_source_values<'a>(
+        &self,
+        mappings: impl Iterator<Item = &'a MainMapping>,
+    ) {
+        self.basics
+            .notify_instance_about_osc_source_values(mappings.filter_map(|m| {
+                let target_value = m.current_aggregated_target_value(
+                    self.basics.control_context(m.compartment()),
+                )?;
+                Some((m, target_value))
+            }));
+    }
+
     /// Indicate via off feedback the sources which are not in use anymore.
     fn send_off_feedback_for_unused_sources(&self, now_unused_sources: UnusedSources) {
         for feedback_value in now_unused_sources.into_values() {
@@ -2583,6 +2901,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         );
         let target_is_active = mapping.target_is_active();
         self.update_map_entries(compartment, *mapping);
+        self.update_button_state_recording(compartment, false);
         self.send_diff_feedback(diff_feedback);
         self.update_single_mapping_on_state(id);
         // This could be a lead mapping in terms of target-based conditional activation. If so,
@@ -2766,6 +3085,11 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             self.collections.milli_dependent_feedback_mappings[compartment].shift_remove(&m.id());
             self.collections.previous_target_values[compartment].remove(&m.id());
         }
+        if m.has_feedback_animation() {
+            self.collections.feedback_animation_mappings[compartment].insert(m.id());
+        } else {
+            self.collections.feedback_animation_mappings[compartment].shift_remove(&m.id());
+        }
         if m.wants_to_be_polled_for_control() {
             self.poll_control_mappings[compartment].insert(m.id());
         } else {
@@ -2866,6 +3190,11 @@ pub enum NormalMainTask {
         id: QualifiedMappingId,
         value: ControlValue,
     },
+    /// Controls the main mappings with the corresponding virtual source, just as if a controller
+    /// mapping with a virtual target had been matched.
+    ///
+    /// Used by control surfaces which don't need a controller compartment (e.g. web clients).
+    ControlVirtualElement(VirtualSourceValue),
     /// This should be sent on events such as track list change, FX focus etc.
     ///
     /// It will trigger a refresh of all targets (re-resolve) or even a preset change (if
@@ -3024,6 +3353,7 @@ pub enum FeedbackMainTask {
 }
 
 /// A control-related task (which is potentially sent very frequently).
+#[allow(clippy::large_enum_variant)]
 pub enum ControlMainTask {
     /// Control event coming in from real-time processor (MIDI).
     ControlFromRealTime {
@@ -3032,6 +3362,20 @@ pub enum ControlMainTask {
         event: ControlEvent<ControlValue>,
         options: ControlOptions,
     },
+    /// Source value of a mapping which is referenced by a chord or sequence.
+    ///
+    /// Sent even if the mapping itself is not controlled (e.g. because it has no target).
+    RecordButtonState {
+        compartment: CompartmentKind,
+        mapping_id: MappingId,
+        event: ControlEvent<ControlValue>,
+    },
+    /// Raw MIDI event coming in from real-time processor, to be parsed by MIDI input scripts.
+    ///
+    /// Not boxed because we must not allocate in the real-time thread.
+    ControlFromMidiInputScript {
+        event: ControlEvent<RawMidiEvent>,
+    },
     LogVirtualControlInput {
         event: ControlEvent<VirtualSourceValue>,
         match_outcome: MatchOutcome,
@@ -3603,6 +3947,28 @@ impl<EH: DomainEventHandler> Basics<EH> {
     fn notify_target_value_changed(&self, m: &MainMapping, new_value: AbsoluteValue) {
         self.process_target_value_change_for_conditional_activation(m.qualified_id(), new_value);
         self.notify_session_about_target_value_change(m, new_value);
+        self.notify_instance_about_osc_source_values([(m, new_value)].into_iter());
+    }
+
+    /// Lets the instance report which values the OSC sources of the given mappings would send
+    /// in order to arrive at the given target values (e.g. to OSCQuery clients).
+    fn notify_instance_about_osc_source_values<'a>(
+        &self,
+        mappings: impl Iterator<Item = (&'a MainMapping, AbsoluteValue)>,
+    ) {
+        let Ok(instance) = self.instance.try_borrow() else {
+            return;
+        };
+        if !instance.wants_osc_source_values() {
+            return;
+        }
+        for (m, target_value) in mappings {
+            if let Some(msg) =
+                m.osc_source_value(target_value, self.control_context(m.compartment()))
+            {
+                instance.notify_osc_source_value_changed(self.unit_id, &msg);
+            }
+        }
     }
 
     fn process_target_value_change_for_conditional_activation(
@@ -3649,15 +4015,39 @@ impl<EH: DomainEventHandler> Basics<EH> {
         let mut match_outcome = MatchOutcome::Unmatched;
         let mut extended_control_results: Vec<_> = mappings_with_virtual_targets
             .values_mut()
-            .filter(|m| m.control_is_effectively_on())
             .flat_map(|m| {
-                let virtual_source_value = match m.control_virtualizing(evt) {
+                let control_is_on = m.control_is_effectively_on();
+                let records_button_state = self
+                    .button_states
+                    .borrow()
+                    .is_referenced(CompartmentKind::Controller, m.key());
+                if !control_is_on && !records_button_state {
+                    return vec![];
+                }
+                let control_value = match m.control_source(evt.payload()) {
                     Some(ControlOutcome::Matched(v)) => v,
                     unmatched_or_consumed => {
-                        match_outcome.upgrade_from(unmatched_or_consumed.into());
+                        if control_is_on {
+                            match_outcome.upgrade_from(unmatched_or_consumed.into());
+                        }
                         return vec![];
                     }
                 };
+                if records_button_state {
+                    self.button_states.borrow_mut().record(
+                        CompartmentKind::Controller,
+                        m.key(),
+                        evt.with_payload(control_value),
+                    );
+                }
+                if !control_is_on {
+                    return vec![];
+                }
+                let Some(virtual_source_value) =
+                    m.control_virtualizing_from_value(evt.with_payload(control_value))
+                else {
+                    return vec![];
+                };
                 self.event_handler
                     .notify_mapping_matched(CompartmentKind::Controller, m.id());
                 let results = self.process_main_mappings_with_virtual_sources(
@@ -3735,6 +4125,9 @@ impl<EH: DomainEventHandler> Basics<EH> {
                     if self.settings.virtual_output_logging_enabled {
                         log_virtual_feedback_output(self.unit_id, &value);
                     }
+                    // Virtual control surfaces (e.g. web clients) are interested in this
+                    self.event_handler
+                        .handle_event_ignoring_error(DomainEvent::VirtualFeedback(&value));
                     // Iterate over (controller) mappings with virtual targets.
                     for m in mappings_with_virtual_targets
                         .values()
@@ -3999,11 +4392,27 @@ impl<EH: DomainEventHandler> Basics<EH> {
         let mut enforce_target_refresh = false;
         main_mappings
             .values_mut()
-            .filter(|m| m.control_is_effectively_on())
             .filter_map(|m| {
                 if let CompoundMappingSource::Virtual(s) = &m.source() {
+                    let control_is_on = m.control_is_effectively_on();
+                    if !control_is_on
+                        && !self
+                            .button_states
+                            .borrow()
+                            .is_referenced(CompartmentKind::Main, m.key())
+                    {
+                        return None;
+                    }
                     let control_value = s.control(&evt.payload())?;
                     let control_event = evt.with_payload(control_value);
+                    self.button_states.borrow_mut().record_if_referenced(
+                        CompartmentKind::Main,
+                        m.key(),
+                        control_event,
+                    );
+                    if !control_is_on {
+                        return None;
+                    }
                     let options = ControlOptions {
                         enforce_target_refresh,
                         ..options
@@ -4170,6 +4579,24 @@ fn control_mapping_stage_one<EH: DomainEventHandler>(
     basics
         .event_handler
         .notify_mapping_matched(m.compartment(), m.id());
+    // Button states have been recorded already when the source matched (even if the mapping
+    // itself is not controlled).
+    let Some(control_event) = m.process_fire_gesture(control_event, &basics.button_states.borrow())
+    else {
+        return MappingControlResult::default();
+    };
+    control_mapping_from_mode(basics, params, m, control_event, options)
+}
+
+/// Controls the mapping starting from the mode, i.e. after the fire gesture has been processed.
+#[must_use]
+fn control_mapping_from_mode<EH: DomainEventHandler>(
+    basics: &Basics<EH>,
+    params: &PluginParams,
+    m: &mut MainMapping,
+    control_event: ControlEvent<ControlValue>,
+    options: ControlOptions,
+) -> MappingControlResult {
     let result = m.control_from_mode(
         control_event,
         options,
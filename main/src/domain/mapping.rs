@@ -1,25 +1,27 @@
 use crate::domain::{
     prop_feedback_resolution, prop_is_affected_by, ActivationChange, ActivationCondition,
-    BoxedHitInstruction, CompartmentParamIndex, CompoundChangeEvent, ControlContext, ControlEvent,
-    ControlEventTimestamp, ControlOptions, ExtendedProcessorContext, FeedbackResolution, GroupId,
+    BoxedHitInstruction, ButtonStates, CompartmentParamIndex, CompoundChangeEvent, ControlContext,
+    ControlEvent, ControlEventTimestamp, ControlOptions, EncoderAcceleration,
+    ExtendedProcessorContext, FeedbackAnimation, FeedbackResolution, FireGesture, Glide, GroupId,
     HitResponse, KeyMessage, KeySource, MappingActivationEffect, MappingControlContext,
-    MappingData, MappingInfo, MappingPropProvider, MessageCaptureEvent, MidiScanResult, MidiSource,
-    Mode, OscDeviceId, OscScanResult, PersistentMappingProcessingState, PluginParamIndex,
-    PluginParams, RealTimeMappingUpdate, RealTimeReaperTarget, RealTimeTargetUpdate,
-    RealearnParameterChangePayload, RealearnParameterSource, RealearnSourceContext, RealearnTarget,
-    ReaperMessage, ReaperSource, ReaperSourceFeedbackValue, ReaperTarget, ReaperTargetType, Tag,
-    TargetCharacter, TrackExclusivity, UnresolvedReaperTarget, VirtualControlElement,
-    VirtualFeedbackValue, VirtualSource, VirtualSourceAddress, VirtualSourceValue, VirtualTarget,
-    COMPARTMENT_PARAMETER_COUNT,
+    MappingData, MappingInfo, MappingPropProvider, MessageCaptureEvent, MidiInputScriptSource,
+    MidiScanResult, MidiSource, Mode, OscDeviceId, OscScanResult, PersistentMappingProcessingState,
+    PluginParamIndex, PluginParams, RealTimeMappingUpdate, RealTimeReaperTarget,
+    RealTimeTargetUpdate, RealearnParameterChangePayload, RealearnParameterSource,
+    RealearnSourceContext, RealearnTarget, ReaperMessage, ReaperSource, ReaperSourceFeedbackValue,
+    ReaperTarget, ReaperTargetType, ScaleQuantization, Tag, TargetCharacter, TrackExclusivity,
+    UnresolvedReaperTarget, VirtualControlElement, VirtualFeedbackValue, VirtualSource,
+    VirtualSourceAddress, VirtualSourceValue, VirtualTarget, COMPARTMENT_PARAMETER_COUNT,
 };
 use derive_more::Display;
 use enum_map::Enum;
 use helgoboss_learn::{
-    format_percentage_without_unit, parse_percentage_without_unit, AbsoluteValue, ControlResult,
-    ControlType, ControlValue, FeedbackValue, GroupInteraction, MidiSourceAddress, MidiSourceValue,
-    ModeControlOptions, ModeControlResult, ModeFeedbackOptions, NumericFeedbackValue, NumericValue,
-    OscSource, OscSourceAddress, PreliminaryMidiSourceFeedbackValue, PropValue, RawMidiEvent,
-    SourceCharacter, Target, UnitValue, ValueFormatter, ValueParser,
+    format_percentage_without_unit, parse_percentage_without_unit, AbsoluteMode, AbsoluteValue,
+    ControlResult, ControlType, ControlValue, FeedbackValue, GroupInteraction, MidiSourceAddress,
+    MidiSourceValue, ModeControlOptions, ModeControlResult, ModeFeedbackOptions,
+    NumericFeedbackValue, NumericValue, OscSource, OscSourceAddress,
+    PreliminaryMidiSourceFeedbackValue, PropValue, RawMidiEvent, SourceCharacter, Target,
+    UnitValue, ValueFormatter, ValueParser,
 };
 use helgoboss_midi::{Channel, RawShortMessage, ShortMessage};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -30,7 +32,7 @@ use crate::domain::unresolved_reaper_target::UnresolvedReaperTargetDef;
 use base::hash_util::{NonCryptoHashSet, NonCryptoIndexMap, NonCryptoIndexSet};
 use playtime_api::persistence::{ColumnAddress, RowAddress, SlotAddress};
 use reaper_high::{Fx, Project, Track, TrackRoute};
-use reaper_medium::MidiInputDeviceId;
+use reaper_medium::{Hz, MidiInputDeviceId};
 use rosc::OscMessage;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -41,6 +43,7 @@ use std::rc::Rc;
 use std::time::{Duration, Instant};
 use strum::{EnumIter, IntoEnumIterator};
 use uuid::Uuid;
+use vst::buffer::Inputs;
 
 #[derive(Copy, Clone, Debug)]
 pub struct ProcessorMappingOptions {
@@ -198,6 +201,8 @@ pub struct MainMapping {
     initial_target_value: Option<AbsoluteValue>,
     /// Called "y_last" in the control transformation formula.
     last_non_performance_target_value: Cell<Option<AbsoluteValue>>,
+    /// Feedback only, so it doesn't need to be part of the core.
+    feedback_animation: Option<FeedbackAnimation>,
 }
 
 #[derive(Default, Debug)]
@@ -224,6 +229,11 @@ impl MainMapping {
         source: CompoundMappingSource,
         mode: Mode,
         group_interaction: GroupInteraction,
+        encoder_acceleration: Option<EncoderAcceleration>,
+        glide: Option<Glide>,
+        scale_quantization: Option<ScaleQuantization>,
+        fire_gesture: Option<FireGesture>,
+        feedback_animation: Option<FeedbackAnimation>,
         unresolved_target: Option<UnresolvedCompoundMappingTarget>,
         activation_condition_1: ActivationCondition,
         activation_condition_2: ActivationCondition,
@@ -238,6 +248,10 @@ impl MainMapping {
                 source,
                 mode,
                 group_interaction,
+                encoder_acceleration,
+                glide,
+                scale_quantization,
+                fire_gesture,
                 options,
                 time_of_last_control: None,
                 invocation_count: 0,
@@ -256,6 +270,7 @@ impl MainMapping {
             extension,
             initial_target_value: None,
             last_non_performance_target_value: Cell::new(None),
+            feedback_animation,
         }
     }
 
@@ -366,6 +381,40 @@ impl MainMapping {
         self.core.id
     }
 
+    pub fn key(&self) -> &Rc<str> {
+        &self.key
+    }
+
+    /// Lets the fire gesture (if any) decide whether the given source event reaches the mode.
+    pub fn process_fire_gesture(
+        &mut self,
+        event: ControlEvent<ControlValue>,
+        button_states: &ButtonStates,
+    ) -> Option<ControlEvent<ControlValue>> {
+        match &mut self.core.fire_gesture {
+            None => Some(event),
+            Some(g) => g.process(self.core.compartment, event, button_states),
+        }
+    }
+
+    /// Returns the event which should be passed to the mode because a delayed fire gesture has
+    /// completed, if any.
+    pub fn poll_fire_gesture(
+        &mut self,
+        timestamp: ControlEventTimestamp,
+    ) -> Option<ControlEvent<ControlValue>> {
+        self.core.fire_gesture.as_mut()?.poll(timestamp)
+    }
+
+    /// Returns the key of the mapping whose button state the fire gesture depends on, if any.
+    pub fn fire_gesture_referenced_mapping_key(&self) -> Option<&Rc<str>> {
+        self.core
+            .fire_gesture
+            .as_ref()?
+            .settings()
+            .referenced_mapping_key()
+    }
+
     pub fn qualified_id(&self) -> QualifiedMappingId {
         QualifiedMappingId::new(self.core.compartment, self.core.id)
     }
@@ -396,6 +445,7 @@ impl MainMapping {
             }),
             target_is_resolved: !self.targets.is_empty(),
             resolved_target: self.splinter_first_real_time_target(),
+            records_button_state: false,
             lifecycle_midi_data: self
                 .extension
                 .lifecycle_midi_data
@@ -405,9 +455,28 @@ impl MainMapping {
     }
 
     pub fn splinter_first_real_time_target(&self) -> Option<RealTimeCompoundMappingTarget> {
-        self.targets
-            .first()
-            .and_then(|t| t.splinter_real_time_target())
+        let transformation_is_main_thread_only = self
+            .core
+            .mode
+            .settings()
+            .control_transformation
+            .as_ref()
+            .is_some_and(|t| t.is_main_thread_only());
+        if transformation_is_main_thread_only {
+            // Lua transformations can't be executed in the real-time processor. Without a
+            // real-time target, control is forwarded to the main thread. This also applies to
+            // virtual targets, because the real-time clone of the mode doesn't contain the
+            // transformation anymore.
+            return None;
+        }
+        let first_target = self.targets.first()?;
+        if (self.core.fire_gesture.is_some() || self.core.glide.is_some())
+            && matches!(first_target, CompoundMappingTarget::Reaper(_))
+        {
+            // Fire gestures and glides are only evaluated in the main processor.
+            return None;
+        }
+        first_target.splinter_real_time_target()
     }
 
     pub fn has_virtual_target(&self) -> bool {
@@ -542,6 +611,7 @@ impl MainMapping {
         self.targets = targets;
         self.core.options.target_is_active = is_active;
         self.update_activation_from_params(context.params());
+        self.update_scale_quantization_from_params(context.params());
         let target_value = self.current_aggregated_target_value(control_context);
         self.initial_target_value = target_value;
         self.last_non_performance_target_value = Cell::new(target_value);
@@ -604,8 +674,27 @@ impl MainMapping {
         }
     }
 
+    pub fn has_feedback_animation(&self) -> bool {
+        self.feedback_animation.is_some()
+    }
+
+    /// Returns `true` if the feedback is currently animated, so it needs to be sent regularly.
+    pub fn feedback_animation_is_running(&self, target_value: Option<AbsoluteValue>) -> bool {
+        match &self.feedback_animation {
+            None => false,
+            Some(a) => a.is_running(target_value),
+        }
+    }
+
     pub fn wants_to_be_polled_for_control(&self) -> bool {
-        self.core.source.wants_to_be_polled() || self.core.mode.wants_to_be_polled()
+        self.core.source.wants_to_be_polled()
+            || self.core.mode.wants_to_be_polled()
+            || self.core.glide.is_some()
+            || self
+                .core
+                .fire_gesture
+                .as_ref()
+                .is_some_and(|g| g.wants_to_be_polled())
     }
 
     /// The boolean return value tells if the resolved target changed in some way, the activation
@@ -662,6 +751,12 @@ impl MainMapping {
         )
     }
 
+    pub fn update_scale_quantization_from_params(&self, params: &PluginParams) {
+        if let Some(q) = &self.core.scale_quantization {
+            q.update_from_params(params.compartment_params(self.core.compartment));
+        }
+    }
+
     fn update_activation(
         &mut self,
         is_active_1: Option<bool>,
@@ -757,6 +852,32 @@ impl MainMapping {
         )
     }
 
+    /// This makes the target value glide. Returns `None` if not gliding at the moment.
+    #[must_use]
+    pub fn poll_glide(
+        &mut self,
+        context: ControlContext,
+        logger: &slog::Logger,
+        processor_context: ExtendedProcessorContext,
+        log_mode_control_result: impl Fn(ControlLogEntry),
+    ) -> Option<MappingControlResult> {
+        let value = self.core.glide.as_mut()?.poll()?;
+        let result = self.control_internal(
+            ControlOptions::default(),
+            context,
+            logger,
+            processor_context,
+            true,
+            log_mode_control_result,
+            |_, _, _, _| {
+                Some(ModeControlResult::hit_target(
+                    ControlValue::AbsoluteContinuous(value),
+                ))
+            },
+        );
+        Some(result)
+    }
+
     pub fn group_interaction(&self) -> GroupInteraction {
         self.core.group_interaction
     }
@@ -777,6 +898,7 @@ impl MainMapping {
         last_non_performance_target_value: Option<AbsoluteValue>,
         log_mode_control_result: impl Fn(ControlLogEntry),
     ) -> MappingControlResult {
+        let source_control_event = self.core.accelerate_encoder(source_control_event);
         let result = self.control_internal(
             options,
             context,
@@ -786,7 +908,7 @@ impl MainMapping {
             log_mode_control_result,
             |options, context, mode, target| {
                 mode.control_with_options(
-                    source_control_event,
+                    source_control_event?,
                     target,
                     context,
                     options.mode_control_options,
@@ -960,6 +1082,18 @@ impl MainMapping {
                         if !is_polling {
                             self.core.time_of_last_control = Some(Instant::now());
                         }
+                        let value = self
+                            .core
+                            .quantize_to_scale(value, target.control_type(context));
+                        let value = match &mut self.core.glide {
+                            Some(glide) if !is_polling => glide.process(
+                                target_index,
+                                value,
+                                target.current_value(context),
+                                processor_context.context().project_or_current_project(),
+                            ),
+                            _ => value,
+                        };
                         // Be graceful here.
                         let (log_entry_kind, error) = match target.hit(value, ctx) {
                             Ok(response) => {
@@ -1139,8 +1273,15 @@ impl MainMapping {
         } else {
             true
         };
-        self.feedback_given_target_value(
+        let animation_context = FeedbackAnimationContext {
+            target_value: combined_target_value,
+            project: control_context
+                .processor_context
+                .project_or_current_project(),
+        };
+        self.feedback_given_target_value_internal(
             Cow::Owned(feedback_value),
+            Some(animation_context),
             FeedbackDestinations {
                 with_projection_feedback,
                 with_source_feedback: with_source_feedback && source_feedback_is_okay,
@@ -1150,6 +1291,34 @@ impl MainMapping {
         )
     }
 
+    /// Returns the message which the OSC source would send in order to arrive at the given target
+    /// value.
+    ///
+    /// Returns `None` if this mapping doesn't have an OSC source.
+    pub fn osc_source_value(
+        &self,
+        target_value: AbsoluteValue,
+        control_context: ControlContext,
+    ) -> Option<OscMessage> {
+        if !matches!(&self.core.source, CompoundMappingSource::Osc(_)) {
+            return None;
+        }
+        let feedback_value = self.feedback_entry_point(
+            false,
+            true,
+            Some(target_value),
+            control_context,
+            NoopLogger,
+        )?;
+        match feedback_value {
+            SpecificCompoundFeedbackValue::Real(PreliminaryRealFeedbackValue {
+                source: Some(PreliminarySourceFeedbackValue::Osc(msg)),
+                ..
+            }) => Some(msg),
+            _ => None,
+        }
+    }
+
     pub fn current_aggregated_target_value(
         &self,
         context: ControlContext,
@@ -1177,6 +1346,26 @@ impl MainMapping {
         destinations: FeedbackDestinations,
         source_context: RealearnSourceContext,
         logger: impl SourceFeedbackLogger,
+    ) -> Option<SpecificCompoundFeedbackValue> {
+        self.feedback_given_target_value_internal(
+            feedback_value,
+            None,
+            destinations,
+            source_context,
+            logger,
+        )
+    }
+
+    /// If an animation context is given, the feedback animation is applied to the mode value, so
+    /// the animation works in source space (after target-to-source interval mapping, reverse
+    /// etc.).
+    fn feedback_given_target_value_internal(
+        &self,
+        feedback_value: Cow<FeedbackValue>,
+        animation_context: Option<FeedbackAnimationContext>,
+        destinations: FeedbackDestinations,
+        source_context: RealearnSourceContext,
+        logger: impl SourceFeedbackLogger,
     ) -> Option<SpecificCompoundFeedbackValue> {
         let options = ModeFeedbackOptions {
             source_is_virtual: self.core.source.is_virtual(),
@@ -1187,6 +1376,12 @@ impl MainMapping {
             options,
             Default::default(),
         )?;
+        let mode_value = match (&self.feedback_animation, animation_context) {
+            (Some(animation), Some(context)) => {
+                animation.apply(mode_value, context.target_value, context.project)
+            }
+            _ => mode_value,
+        };
         logger.log(FeedbackLogEntry {
             feedback_value: mode_value.as_ref(),
         });
@@ -1281,39 +1476,46 @@ impl MainMapping {
                 s.control(m, compartment).map(ControlOutcome::Matched)
             }
             (MainSourceMessage::Key(m), CompoundMappingSource::Key(s)) => s.control(m),
+            (MainSourceMessage::Midi(e), CompoundMappingSource::MidiInputScript(s)) => {
+                // Whether the message is let through or not is decided in the real-time
+                // processor, so there's no consumption.
+                s.control(e.bytes()).map(ControlOutcome::Matched)
+            }
             _ => None,
         }
     }
 
+    /// Takes the values which the last execution of a MIDI input script addressed directly to
+    /// virtual control elements.
+    pub fn take_virtual_source_values_from_source(&mut self) -> Vec<VirtualSourceValue> {
+        match &mut self.core.source {
+            CompoundMappingSource::MidiInputScript(s) => s.take_virtual_source_values(),
+            _ => vec![],
+        }
+    }
+
     /// Polls the source.
-    pub fn poll_source(&mut self) -> Option<ControlValue> {
+    pub fn poll_source(&mut self, project: Project) -> Option<ControlValue> {
         match &mut self.core.source {
-            CompoundMappingSource::Reaper(s) => s.poll(),
+            CompoundMappingSource::Reaper(s) => s.poll(project),
             _ => None,
         }
     }
 
-    pub fn control_virtualizing(
+    /// Lets the virtual target process a control value that has already been extracted from the
+    /// source.
+    ///
+    /// Apart from main-thread sources, this is also used for values forwarded by the real-time
+    /// processor if the mapping has a transformation which can only be executed in the main thread.
+    pub fn control_virtualizing_from_value(
         &mut self,
-        evt: ControlEvent<MainSourceMessage>,
-    ) -> Option<ControlOutcome<VirtualSourceValue>> {
-        if self.targets.is_empty() {
-            return None;
-        }
-        let control_value = match self.control_source(evt.payload())? {
-            ControlOutcome::Consumed => {
-                return Some(ControlOutcome::Consumed);
-            }
-            ControlOutcome::Matched(v) => v,
-        };
+        evt: ControlEvent<ControlValue>,
+    ) -> Option<VirtualSourceValue> {
         // First target is enough because this does nothing yet.
-        let virtual_source_value = match self.targets.first()? {
-            CompoundMappingTarget::Virtual(t) => {
-                match_partially(&mut self.core, t, evt.with_payload(control_value))
-            }
+        match self.targets.first()? {
+            CompoundMappingTarget::Virtual(t) => match_partially(&mut self.core, t, evt),
             CompoundMappingTarget::Reaper(_) => None,
-        };
-        Some(ControlOutcome::Matched(virtual_source_value?))
+        }
     }
 }
 
@@ -1322,6 +1524,8 @@ pub enum MainSourceMessage<'a> {
     Osc(&'a OscMessage),
     Reaper(&'a ReaperMessage),
     Key(KeyMessage),
+    /// Forwarded from the real-time processor for being processed by MIDI input scripts.
+    Midi(&'a RawMidiEvent),
 }
 
 impl<'a> MainSourceMessage<'a> {
@@ -1335,6 +1539,8 @@ impl<'a> MainSourceMessage<'a> {
                 dev_id: None,
             }),
             Key(msg) => MessageCaptureResult::Keyboard(msg),
+            // MIDI is learned in the real-time processor.
+            Midi(_) => return None,
             Reaper(msg) => {
                 use ReaperMessage::*;
                 match msg {
@@ -1360,6 +1566,9 @@ pub struct RealTimeMapping {
     target_is_resolved: bool,
     /// Is `Some` if virtual or this target needs to be processed in real-time.
     pub resolved_target: Option<RealTimeCompoundMappingTarget>,
+    /// If `true`, matched source values are reported to the main processor even if the mapping is
+    /// not controlled, because a chord or sequence depends on this mapping's button state.
+    records_button_state: bool,
     pub lifecycle_midi_data: LifecycleMidiData,
 }
 
@@ -1468,6 +1677,14 @@ impl RealTimeMapping {
         matches!(self.target_category, Some(UnresolvedTargetCategory::Reaper))
     }
 
+    pub fn records_button_state(&self) -> bool {
+        self.records_button_state
+    }
+
+    pub fn set_records_button_state(&mut self, value: bool) {
+        self.records_button_state = value;
+    }
+
     pub fn consumes(&self, msg: RawShortMessage) -> bool {
         self.core.source.consumes(&msg)
     }
@@ -1494,11 +1711,42 @@ impl RealTimeMapping {
         } else {
             return None;
         };
+        self.virtualize(evt.with_payload(control_value))
+    }
+
+    pub fn control_audio_signal_virtualizing(
+        &mut self,
+        inputs: &Inputs<f64>,
+        sample_rate: Hz,
+        timestamp: ControlEventTimestamp,
+    ) -> Option<PartialControlMatch> {
+        if !self.target_is_resolved {
+            return None;
+        }
+        let control_value = self.control_audio_signal(inputs, sample_rate)?;
+        self.virtualize(ControlEvent::new(control_value, timestamp))
+    }
+
+    /// Analyzes the given audio block if this mapping has an audio signal source.
+    pub fn control_audio_signal(
+        &mut self,
+        inputs: &Inputs<f64>,
+        sample_rate: Hz,
+    ) -> Option<ControlValue> {
+        if let CompoundMappingSource::Reaper(ReaperSource::AudioSignal(s)) = &mut self.core.source {
+            s.process(inputs, sample_rate)
+        } else {
+            None
+        }
+    }
+
+    fn virtualize(&mut self, evt: ControlEvent<ControlValue>) -> Option<PartialControlMatch> {
         if let Some(RealTimeCompoundMappingTarget::Virtual(t)) = self.resolved_target.as_ref() {
-            match_partially(&mut self.core, t, evt.with_payload(control_value))
-                .map(PartialControlMatch::ProcessVirtual)
+            match_partially(&mut self.core, t, evt).map(PartialControlMatch::ProcessVirtual)
         } else {
-            Some(PartialControlMatch::ProcessDirect(control_value))
+            // Also the case for virtual targets whose transformation can only be executed in the
+            // main thread. Processing them directly means forwarding them to the main processor.
+            Some(PartialControlMatch::ProcessDirect(evt.payload()))
         }
     }
 }
@@ -1516,6 +1764,14 @@ pub struct MappingCore {
     pub source: CompoundMappingSource,
     pub mode: Mode,
     group_interaction: GroupInteraction,
+    /// Stateful, that's why it lives here and not in the mode.
+    encoder_acceleration: Option<EncoderAcceleration>,
+    /// Stateful as well. Only driven in the main processor.
+    glide: Option<Glide>,
+    /// Shares the root and scale resolved from parameters with the real-time clone.
+    scale_quantization: Option<ScaleQuantization>,
+    /// Stateful. Only applied when controlled from the main processor.
+    fire_gesture: Option<FireGesture>,
     options: ProcessorMappingOptions,
     /// Used for preventing echo feedback.
     time_of_last_control: Option<Instant>,
@@ -1532,7 +1788,11 @@ impl MappingCore {
         // This clone is special. Some compiled feedback scripts deep inside the object graph (in
         // source and mode, in particular) will not really be cloned because the real-time
         // processor will not use them anyway! Look up `CloneAsDefault`.
-        self.clone()
+        Self {
+            // Only evaluated in the main processor
+            fire_gesture: None,
+            ..self.clone()
+        }
     }
 
     pub fn invocation_count(&self) -> u32 {
@@ -1543,6 +1803,32 @@ impl MappingCore {
         self.invocation_count = self.invocation_count.wrapping_add(1);
     }
 
+    /// Applies encoder acceleration (if any) to the given source event before it enters the mode.
+    ///
+    /// Returns `None` if the event should be ignored.
+    pub fn accelerate_encoder(
+        &mut self,
+        event: ControlEvent<ControlValue>,
+    ) -> Option<ControlEvent<ControlValue>> {
+        let Some(acceleration) = &mut self.encoder_acceleration else {
+            return Some(event);
+        };
+        let make_relative = self.mode.settings().absolute_mode == AbsoluteMode::MakeRelative;
+        acceleration.process(event, make_relative)
+    }
+
+    /// Snaps the given value to the configured musical scale, if any.
+    pub fn quantize_to_scale(
+        &self,
+        value: ControlValue,
+        target_control_type: ControlType,
+    ) -> ControlValue {
+        match &self.scale_quantization {
+            None => value,
+            Some(q) => q.quantize(value, target_control_type),
+        }
+    }
+
     fn is_echo(&self) -> bool {
         if let Some(t) = self.time_of_last_control {
             t.elapsed() <= MAX_ECHO_FEEDBACK_DELAY
@@ -1562,6 +1848,9 @@ impl MappingCore {
     fn on_deactivate(&mut self) {
         self.source.on_deactivate();
         self.mode.on_deactivate();
+        if let Some(glide) = &mut self.glide {
+            glide.cancel();
+        }
     }
 }
 
@@ -1574,6 +1863,7 @@ pub enum CompoundMappingSource {
     Virtual(VirtualSource),
     Reaper(ReaperSource),
     Key(KeySource),
+    MidiInputScript(MidiInputScriptSource),
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -1744,7 +2034,9 @@ impl CompoundMappingSource {
             Virtual(s) => s.format_control_value(value),
             Osc(s) => s.format_control_value(value),
             Reaper(s) => s.format_control_value(value),
-            Never | Key(_) => Ok(format_percentage_without_unit(value.to_unit_value()?.get())),
+            Never | Key(_) | MidiInputScript(_) => {
+                Ok(format_percentage_without_unit(value.to_unit_value()?.get()))
+            }
         }
     }
 
@@ -1755,7 +2047,7 @@ impl CompoundMappingSource {
             Virtual(s) => s.parse_control_value(text),
             Osc(s) => s.parse_control_value(text),
             Reaper(s) => s.parse_control_value(text),
-            Never | Key(_) => parse_percentage_without_unit(text)?.try_into(),
+            Never | Key(_) | MidiInputScript(_) => parse_percentage_without_unit(text)?.try_into(),
         }
     }
 
@@ -1768,6 +2060,7 @@ impl CompoundMappingSource {
             Reaper(s) => ExtendedSourceCharacter::Normal(s.character()),
             Never => ExtendedSourceCharacter::VirtualContinuous,
             Key(_) => ExtendedSourceCharacter::Normal(SourceCharacter::MomentaryButton),
+            MidiInputScript(_) => ExtendedSourceCharacter::Normal(SourceCharacter::RangeElement),
         }
     }
 
@@ -1790,7 +2083,7 @@ impl CompoundMappingSource {
             // This is handled in a special way by consumers.
             Virtual(_) => None,
             // No feedback for other sources.
-            Key(_) | Never => None,
+            Key(_) | MidiInputScript(_) | Never => None,
         }
     }
 
@@ -1798,7 +2091,7 @@ impl CompoundMappingSource {
         use CompoundMappingSource::*;
         match self {
             Midi(s) => s.consumes(msg),
-            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) => false,
+            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) | MidiInputScript(_) => false,
         }
     }
 
@@ -1812,7 +2105,7 @@ impl CompoundMappingSource {
             Midi(s) => s.max_discrete_value(),
             // TODO-medium OSC will also support discrete values as soon as we allow integers and
             //  configuring max values
-            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) => None,
+            Reaper(_) | Virtual(_) | Osc(_) | Never | Key(_) | MidiInputScript(_) => None,
         }
     }
 }
@@ -1848,6 +2141,15 @@ pub enum SpecificCompoundFeedbackValue {
     Real(PreliminaryRealFeedbackValue),
 }
 
+/// What's needed to determine the current frame of a feedback animation.
+#[derive(Copy, Clone, Debug)]
+struct FeedbackAnimationContext {
+    /// Decides which animation is running.
+    target_value: Option<AbsoluteValue>,
+    /// Provides the beat position for animations in sync with the music.
+    project: Project,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct FeedbackDestinations {
     /// Feedback to projection clients.
@@ -2529,6 +2831,7 @@ fn match_partially(
     // TODO-medium If we want to support fire after timeout and turbo for mappings with
     //  virtual targets one day, we need to poll this in real-time processor and OSC
     //  processing, too!
+    let control_event = core.accelerate_encoder(control_event)?;
     let res = core.mode.control_with_options(
         control_event,
         target,
@@ -2814,3 +3117,69 @@ pub enum ControlLogEntryKind {
     #[display(fmt = "Failed to execute hit instruction")]
     FailedExecutingHitInstruction,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ActivationCondition, ControlEventTimestamp, FlexibleTransformation, LuaTransformation,
+        SafeLua, VirtualControlElementId,
+    };
+    use helgoboss_learn::{AbstractTimestamp, ModeSettings, UnitValue};
+
+    #[test]
+    fn virtual_target_with_lua_transformation_is_controlled_in_main_thread() {
+        // Given
+        let lua: &'static SafeLua = Box::leak(Box::new(SafeLua::new().unwrap()));
+        let transformation = LuaTransformation::compile_for_control(lua, "return 1 - x").unwrap();
+        let mode = Mode::new(ModeSettings {
+            control_transformation: Some(FlexibleTransformation::lua(transformation)),
+            ..Default::default()
+        });
+        let control_element = VirtualControlElement::Multi(VirtualControlElementId::Indexed(0));
+        let virtual_target = VirtualTarget::new(control_element);
+        let mut mapping = MainMapping::new(
+            CompartmentKind::Controller,
+            MappingId::random(),
+            &MappingKey::random(),
+            GroupId::default(),
+            "Test".to_string(),
+            vec![],
+            CompoundMappingSource::Never,
+            mode,
+            GroupInteraction::None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(UnresolvedCompoundMappingTarget::Virtual(virtual_target)),
+            ActivationCondition::Always,
+            ActivationCondition::Always,
+            ProcessorMappingOptions {
+                target_is_active: true,
+                persistent_processing_state: PersistentMappingProcessingState { is_enabled: true },
+                control_is_enabled: true,
+                feedback_is_enabled: true,
+                feedback_send_behavior: FeedbackSendBehavior::Normal,
+                beep_on_success: false,
+            },
+            MappingExtension::default(),
+        );
+        mapping.targets = vec![CompoundMappingTarget::Virtual(virtual_target)];
+        let event = ControlEvent::new(
+            ControlValue::AbsoluteContinuous(UnitValue::new(0.25)),
+            ControlEventTimestamp::now(),
+        );
+        // When
+        let real_time_target = mapping.splinter_first_real_time_target();
+        let virtual_source_value = mapping.control_virtualizing_from_value(event).unwrap();
+        // Then
+        assert!(real_time_target.is_none());
+        assert_eq!(virtual_source_value.control_element(), control_element);
+        assert_eq!(
+            virtual_source_value.control_value(),
+            ControlValue::AbsoluteContinuous(UnitValue::new(0.75))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::base::CloneAsDefault;
+use crate::domain::{LuaMidiInputScript, VirtualSourceValue};
+use helgoboss_learn::ControlValue;
+use std::sync::Arc;
+
+/// See [`crate::domain::MidiSource`] for an explanation of the Lua script wrapping.
+type LuaMidiInputScriptType = CloneAsDefault<Option<LuaMidiInputScript<'static>>>;
+
+/// A source which parses incoming MIDI messages (including SysEx) using a Lua script.
+///
+/// The real-time processor doesn't execute the script. It just forwards incoming MIDI messages to
+/// the main processor as long as at least one mapping uses this source.
+#[derive(Clone, Debug)]
+pub struct MidiInputScriptSource {
+    script: LuaMidiInputScriptType,
+    /// The code from which the script has been compiled. Used for comparison.
+    script_code: Arc<str>,
+    /// Values which the last script execution addressed directly to virtual control elements.
+    virtual_source_values: Vec<VirtualSourceValue>,
+}
+
+impl PartialEq for MidiInputScriptSource {
+    fn eq(&self, other: &Self) -> bool {
+        // Compiled scripts can't be compared, but their code can.
+        self.script_code == other.script_code
+    }
+}
+
+impl MidiInputScriptSource {
+    pub fn new(script: LuaMidiInputScript<'static>, script_code: &str) -> Self {
+        Self {
+            script: CloneAsDefault::new(Some(script)),
+            script_code: script_code.into(),
+            virtual_source_values: vec![],
+        }
+    }
+
+    /// Executes the script and returns the value meant for the containing mapping.
+    ///
+    /// Values meant for virtual control elements are kept and can be taken via
+    /// [`Self::take_virtual_source_values`].
+    pub fn control(&mut self, bytes: &[u8]) -> Option<ControlValue> {
+        self.virtual_source_values.clear();
+        let script = self.script.get().as_ref()?;
+        let values = match script.execute(bytes) {
+            Ok(values) => values,
+            Err(e) => {
+                let error = e.to_string();
+                tracing::debug!(msg = "Failed to execute MIDI input script", %error);
+                return None;
+            }
+        };
+        let mut own_value = None;
+        for v in values {
+            match v.control_element {
+                None => own_value = Some(v.control_value),
+                Some(element) => self
+                    .virtual_source_values
+                    .push(VirtualSourceValue::new(element, v.control_value)),
+            }
+        }
+        own_value
+    }
+
+    pub fn take_virtual_source_values(&mut self) -> Vec<VirtualSourceValue> {
+        std::mem::take(&mut self.virtual_source_values)
+    }
+}
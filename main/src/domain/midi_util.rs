@@ -41,3 +41,30 @@ pub fn classify_midi_message(msg: IncomingMidiMessage) -> MidiMessageClassificat
         }
     }
 }
+
+/// Returns the number of bytes the given short message consists of on the wire.
+pub fn short_message_len(msg: &impl ShortMessage) -> usize {
+    use ShortMessageType::*;
+    match msg.r#type() {
+        NoteOff
+        | NoteOn
+        | PolyphonicKeyPressure
+        | ControlChange
+        | PitchBendChange
+        | SongPositionPointer => 3,
+        ProgramChange | ChannelPressure | TimeCodeQuarterFrame | SongSelect => 2,
+        SystemExclusiveStart
+        | SystemCommonUndefined1
+        | SystemCommonUndefined2
+        | TuneRequest
+        | SystemExclusiveEnd
+        | TimingClock
+        | SystemRealTimeUndefined1
+        | Start
+        | Continue
+        | Stop
+        | SystemRealTimeUndefined2
+        | ActiveSensing
+        | SystemReset => 1,
+    }
+}
@@ -43,6 +43,36 @@ pub use lua_feedback_script::*;
 mod flexible_midi_source_script;
 pub use flexible_midi_source_script::*;
 
+mod lua_transformation;
+pub use lua_transformation::*;
+
+mod lua_midi_input_script;
+pub use lua_midi_input_script::*;
+
+mod midi_input_script_source;
+pub use midi_input_script_source::*;
+
+mod encoder_acceleration;
+pub use encoder_acceleration::*;
+
+mod glide;
+pub use glide::*;
+
+mod fire_gesture;
+pub use fire_gesture::*;
+
+mod feedback_animation;
+pub use feedback_animation::*;
+
+mod flexible_transformation;
+pub use flexible_transformation::*;
+
+mod response_curve;
+pub use response_curve::*;
+
+mod scale_quantization;
+pub use scale_quantization::*;
+
 mod realearn_target;
 pub use realearn_target::*;
 
@@ -108,12 +138,21 @@ pub use midi_types::*;
 mod reaper_source;
 pub use reaper_source::*;
 
+mod pitch_detection;
+pub use pitch_detection::*;
+
+mod modulator_source;
+pub use modulator_source::*;
+
 mod key_source;
 pub use key_source::*;
 
 mod device_change_detector;
 pub use device_change_detector::*;
 
+mod recording_input_util;
+pub use recording_input_util::*;
+
 mod reaper_config_change_detector;
 pub use reaper_config_change_detector::*;
 
@@ -144,11 +183,7 @@ pub use parameter_manager::*;
 mod control_event;
 pub use control_event::*;
 
-mod lua_support;
-pub use lua_support::*;
-
-mod lua_module_container;
-pub use lua_module_container::*;
+pub use realearn_api::lua::*;
 
 mod internal_info_event;
 pub use internal_info_event::*;
@@ -1,5 +1,5 @@
 use crate::base::CloneAsDefault;
-use crate::domain::{ControlEventTimestamp, EelTransformation, LuaFeedbackScript};
+use crate::domain::{ControlEventTimestamp, FlexibleTransformation, LuaFeedbackScript};
 use base::hash_util::NonCryptoHashSet;
 use helgoboss_learn::{FeedbackScript, FeedbackScriptInput, FeedbackScriptOutput};
 use std::borrow::Cow;
@@ -8,7 +8,8 @@ use std::error::Error;
 /// See [`crate::domain::MidiSource`] for an explanation of the feedback script wrapping.
 type FeedbackScriptType = CloneAsDefault<Option<LuaFeedbackScript<'static>>>;
 
-pub type Mode = helgoboss_learn::Mode<EelTransformation, FeedbackScriptType, ControlEventTimestamp>;
+pub type Mode =
+    helgoboss_learn::Mode<FlexibleTransformation, FeedbackScriptType, ControlEventTimestamp>;
 
 impl FeedbackScriptType {
     fn get_script(&self) -> Result<&LuaFeedbackScript<'static>, Cow<'static, str>> {
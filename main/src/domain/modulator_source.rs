@@ -0,0 +1,458 @@
+use crate::domain::CompartmentParamIndex;
+use helgoboss_learn::{ControlValue, UnitValue};
+use realearn_api::persistence::{ModulatorNoteValue, ModulatorShape};
+use reaper_high::Project;
+use std::f64::consts::TAU;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MODULATOR_FREQUENCY: f64 = 1.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ModulatorSettings {
+    pub shape: ModulatorShape,
+    /// In Hz. Only relevant if not synced to tempo.
+    pub frequency: f64,
+    pub tempo_sync: Option<ModulatorNoteValue>,
+    pub reset_on_transport_start: bool,
+    pub adsr: AdsrSettings,
+    pub gate_parameter_index: CompartmentParamIndex,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AdsrSettings {
+    pub attack: Duration,
+    pub decay: Duration,
+    /// Between 0.0 and 1.0.
+    pub sustain: f64,
+    pub release: Duration,
+}
+
+impl Default for AdsrSettings {
+    fn default() -> Self {
+        Self {
+            attack: Duration::from_millis(10),
+            decay: Duration::from_millis(200),
+            sustain: 0.7,
+            release: Duration::from_millis(300),
+        }
+    }
+}
+
+/// A source that continuously produces values on its own: Either an LFO or an ADSR envelope.
+///
+/// Values are produced whenever the source is polled (in the main thread).
+#[derive(Clone, Debug)]
+pub struct ModulatorSource {
+    settings: ModulatorSettings,
+    /// Position within the current LFO cycle (0.0 to 1.0).
+    phase: f64,
+    last_poll: Option<Instant>,
+    was_playing: bool,
+    sample_and_hold_value: f64,
+    random_state: u64,
+    gate_is_open: bool,
+    adsr_stage: AdsrStage,
+    adsr_level: f64,
+    last_value: Option<UnitValue>,
+}
+
+impl PartialEq for ModulatorSource {
+    fn eq(&self, other: &Self) -> bool {
+        // The modulation state is not relevant for comparison, only the settings.
+        self.settings == other.settings
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum AdsrStage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+impl ModulatorSource {
+    pub fn new(settings: ModulatorSettings) -> Self {
+        Self {
+            settings,
+            phase: 0.0,
+            last_poll: None,
+            was_playing: false,
+            sample_and_hold_value: 0.5,
+            random_state: 0x2545_f491_4f6c_dd1d,
+            gate_is_open: false,
+            adsr_stage: AdsrStage::Idle,
+            adsr_level: 0.0,
+            last_value: None,
+        }
+    }
+
+    pub fn settings(&self) -> &ModulatorSettings {
+        &self.settings
+    }
+
+    pub fn on_deactivate(&mut self) {
+        *self = Self::new(self.settings);
+    }
+
+    /// Opens or closes the ADSR gate if the given compartment parameter is the gate parameter.
+    pub fn process_parameter_change(&mut self, index: CompartmentParamIndex, value: f64) {
+        if self.settings.shape != ModulatorShape::Adsr
+            || index != self.settings.gate_parameter_index
+        {
+            return;
+        }
+        let open = value > 0.5;
+        if open == self.gate_is_open {
+            return;
+        }
+        self.gate_is_open = open;
+        self.adsr_stage = if open {
+            AdsrStage::Attack
+        } else {
+            AdsrStage::Release
+        };
+    }
+
+    /// Advances the modulator to the current point in time and returns the new value if it
+    /// changed.
+    pub fn poll(&mut self, project: Project) -> Option<ControlValue> {
+        let now = Instant::now();
+        let elapsed = self
+            .last_poll
+            .map(|last_poll| now - last_poll)
+            .unwrap_or_default()
+            .as_secs_f64();
+        self.last_poll = Some(now);
+        let value = if self.settings.shape == ModulatorShape::Adsr {
+            self.advance_adsr(elapsed)
+        } else {
+            self.advance_lfo(project, elapsed)
+        };
+        let value = UnitValue::new_clamped(value);
+        if self.last_value == Some(value) {
+            return None;
+        }
+        self.last_value = Some(value);
+        Some(ControlValue::AbsoluteContinuous(value))
+    }
+
+    fn advance_lfo(&mut self, project: Project, elapsed: f64) -> f64 {
+        let is_playing = project.is_playing();
+        let transport_started = is_playing && !self.was_playing;
+        self.was_playing = is_playing;
+        let prev_phase = self.phase;
+        match self.settings.tempo_sync {
+            Some(note_value) if is_playing => {
+                // Follow the play position so that the LFO stays locked to the grid
+                let pos = project.play_position_latency_compensated();
+                let beats = project.beat_info_at(pos).full_beats.get();
+                self.phase = (beats / note_value.beats()).rem_euclid(1.0);
+            }
+            _ => {
+                if transport_started && self.settings.reset_on_transport_start {
+                    self.phase = 0.0;
+                } else {
+                    self.phase = (self.phase + elapsed * self.frequency(project)).fract();
+                }
+            }
+        }
+        let cycle_restarted = self.phase < prev_phase || self.last_value.is_none();
+        self.lfo_value(cycle_restarted)
+    }
+
+    /// Returns the LFO value at the current phase.
+    fn lfo_value(&mut self, cycle_restarted: bool) -> f64 {
+        let phase = self.phase;
+        use ModulatorShape::*;
+        match self.settings.shape {
+            Sine => 0.5 - 0.5 * (TAU * phase).cos(),
+            Triangle => 1.0 - (2.0 * phase - 1.0).abs(),
+            Saw => phase,
+            Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            SampleAndHold => {
+                if cycle_restarted {
+                    self.sample_and_hold_value = self.next_random();
+                }
+                self.sample_and_hold_value
+            }
+            Adsr => unreachable!("ADSR is not an LFO shape"),
+        }
+    }
+
+    fn frequency(&self, project: Project) -> f64 {
+        match self.settings.tempo_sync {
+            None => self.settings.frequency,
+            Some(note_value) => project.tempo().bpm().get() / 60.0 / note_value.beats(),
+        }
+    }
+
+    fn advance_adsr(&mut self, elapsed: f64) -> f64 {
+        let adsr = &self.settings.adsr;
+        let sustain = adsr.sustain.clamp(0.0, 1.0);
+        use AdsrStage::*;
+        match self.adsr_stage {
+            Idle => {
+                self.adsr_level = 0.0;
+            }
+            Attack => {
+                self.adsr_level += step(elapsed, adsr.attack, 1.0);
+                if self.adsr_level >= 1.0 {
+                    self.adsr_level = 1.0;
+                    self.adsr_stage = Decay;
+                }
+            }
+            Decay => {
+                self.adsr_level -= step(elapsed, adsr.decay, 1.0 - sustain);
+                if self.adsr_level <= sustain {
+                    self.adsr_level = sustain;
+                    self.adsr_stage = Sustain;
+                }
+            }
+            Sustain => {
+                self.adsr_level = sustain;
+            }
+            Release => {
+                self.adsr_level -= step(elapsed, adsr.release, 1.0);
+                if self.adsr_level <= 0.0 {
+                    self.adsr_level = 0.0;
+                    self.adsr_stage = Idle;
+                }
+            }
+        }
+        self.adsr_level
+    }
+
+    /// Xorshift, good enough for modulation purposes.
+    fn next_random(&mut self) -> f64 {
+        let mut x = self.random_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.random_state = x;
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Returns how much the level moves within the elapsed time if moving the given distance takes
+/// the given duration.
+fn step(elapsed: f64, duration: Duration, distance: f64) -> f64 {
+    let secs = duration.as_secs_f64();
+    if secs == 0.0 {
+        distance
+    } else {
+        elapsed / secs * distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(shape: ModulatorShape, adsr: AdsrSettings) -> ModulatorSource {
+        ModulatorSource::new(ModulatorSettings {
+            shape,
+            frequency: DEFAULT_MODULATOR_FREQUENCY,
+            tempo_sync: None,
+            reset_on_transport_start: false,
+            adsr,
+            gate_parameter_index: CompartmentParamIndex::default(),
+        })
+    }
+
+    fn lfo_values(shape: ModulatorShape, phases: &[f64]) -> Vec<f64> {
+        let mut source = source(shape, Default::default());
+        phases
+            .iter()
+            .map(|phase| {
+                source.phase = *phase;
+                source.lfo_value(false)
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a - e).abs() < 1e-9,
+                "expected {expected:?} but got {actual:?}"
+            );
+        }
+    }
+
+    const PHASES: [f64; 4] = [0.0, 0.25, 0.5, 0.75];
+
+    #[test]
+    fn sine() {
+        assert_close(
+            &lfo_values(ModulatorShape::Sine, &PHASES),
+            &[0.0, 0.5, 1.0, 0.5],
+        );
+    }
+
+    #[test]
+    fn triangle() {
+        assert_close(
+            &lfo_values(ModulatorShape::Triangle, &PHASES),
+            &[0.0, 0.5, 1.0, 0.5],
+        );
+    }
+
+    #[test]
+    fn saw() {
+        assert_close(
+            &lfo_values(ModulatorShape::Saw, &PHASES),
+            &[0.0, 0.25, 0.5, 0.75],
+        );
+    }
+
+    #[test]
+    fn square() {
+        assert_close(
+            &lfo_values(ModulatorShape::Square, &PHASES),
+            &[1.0, 1.0, 0.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn sample_and_hold() {
+        // Given
+        let mut source = source(ModulatorShape::SampleAndHold, Default::default());
+        // When
+        source.phase = 0.1;
+        let first = source.lfo_value(true);
+        source.phase = 0.6;
+        let held = source.lfo_value(false);
+        source.phase = 0.2;
+        let second = source.lfo_value(true);
+        // Then
+        assert!((0.0..1.0).contains(&first));
+        assert!((0.0..1.0).contains(&second));
+        assert_eq!(held, first, "value must be held within one cycle");
+        assert_ne!(second, first, "new cycle must sample a new value");
+    }
+
+    fn adsr_source(attack: f64, decay: f64, sustain: f64, release: f64) -> ModulatorSource {
+        source(
+            ModulatorShape::Adsr,
+            AdsrSettings {
+                attack: Duration::from_secs_f64(attack),
+                decay: Duration::from_secs_f64(decay),
+                sustain,
+                release: Duration::from_secs_f64(release),
+            },
+        )
+    }
+
+    #[test]
+    fn adsr() {
+        // Given
+        let mut source = adsr_source(1.0, 1.0, 0.5, 1.0);
+        // When
+        let idle = source.advance_adsr(0.5);
+        source.process_parameter_change(CompartmentParamIndex::default(), 1.0);
+        let attack_1 = source.advance_adsr(0.5);
+        let attack_2 = source.advance_adsr(0.5);
+        let stage_after_attack = source.adsr_stage;
+        let decay_1 = source.advance_adsr(0.5);
+        let decay_2 = source.advance_adsr(0.5);
+        let stage_after_decay = source.adsr_stage;
+        let sustain = source.advance_adsr(10.0);
+        source.process_parameter_change(CompartmentParamIndex::default(), 0.0);
+        let release_1 = source.advance_adsr(0.25);
+        let release_2 = source.advance_adsr(0.25);
+        let stage_after_release = source.adsr_stage;
+        let idle_again = source.advance_adsr(0.5);
+        // Then
+        assert_eq!(idle, 0.0);
+        assert_eq!(attack_1, 0.5);
+        assert_eq!(attack_2, 1.0);
+        assert_eq!(stage_after_attack, AdsrStage::Decay);
+        assert_eq!(decay_1, 0.75);
+        assert_eq!(decay_2, 0.5);
+        assert_eq!(stage_after_decay, AdsrStage::Sustain);
+        assert_eq!(sustain, 0.5);
+        assert_eq!(release_1, 0.25);
+        assert_eq!(release_2, 0.0);
+        assert_eq!(stage_after_release, AdsrStage::Idle);
+        assert_eq!(idle_again, 0.0);
+    }
+
+    #[test]
+    fn adsr_with_zero_length_stages() {
+        // Given
+        let mut source = adsr_source(0.0, 0.0, 0.7, 0.0);
+        // When
+        source.process_parameter_change(CompartmentParamIndex::default(), 1.0);
+        let attack = source.advance_adsr(0.0);
+        let decay = source.advance_adsr(0.0);
+        let stage_after_decay = source.adsr_stage;
+        source.process_parameter_change(CompartmentParamIndex::default(), 0.0);
+        let release = source.advance_adsr(0.0);
+        let stage_after_release = source.adsr_stage;
+        // Then
+        assert_eq!(attack, 1.0);
+        assert_eq!(decay, 0.7);
+        assert_eq!(stage_after_decay, AdsrStage::Sustain);
+        assert_eq!(release, 0.0);
+        assert_eq!(stage_after_release, AdsrStage::Idle);
+    }
+
+    #[test]
+    fn adsr_release_before_reaching_sustain() {
+        // Given
+        let mut source = adsr_source(1.0, 1.0, 0.5, 1.0);
+        source.process_parameter_change(CompartmentParamIndex::default(), 1.0);
+        source.advance_adsr(0.5);
+        // When
+        source.process_parameter_change(CompartmentParamIndex::default(), 0.0);
+        let release = source.advance_adsr(0.25);
+        // Then
+        assert_eq!(release, 0.25);
+        assert_eq!(source.adsr_stage, AdsrStage::Release);
+    }
+
+    #[test]
+    fn ignore_other_parameters() {
+        // Given
+        let mut source = adsr_source(1.0, 1.0, 0.5, 1.0);
+        // When
+        source.process_parameter_change(CompartmentParamIndex::try_from(1).unwrap(), 1.0);
+        // Then
+        assert_eq!(source.adsr_stage, AdsrStage::Idle);
+    }
+
+    #[test]
+    fn step_is_proportional_to_elapsed_time() {
+        assert_eq!(step(0.5, Duration::from_secs(2), 1.0), 0.25);
+        assert_eq!(step(2.0, Duration::from_secs(1), 0.5), 1.0);
+        assert_eq!(step(0.0, Duration::from_secs(1), 1.0), 0.0);
+    }
+
+    #[test]
+    fn step_with_zero_duration_covers_whole_distance() {
+        assert_eq!(step(0.0, Duration::ZERO, 0.3), 0.3);
+        assert_eq!(step(1.0, Duration::ZERO, 1.0), 1.0);
+    }
+
+    #[test]
+    fn compare_settings_only() {
+        // Given
+        let mut a = source(ModulatorShape::Sine, Default::default());
+        let b = a.clone();
+        // When
+        a.phase = 0.3;
+        a.next_random();
+        // Then
+        assert_eq!(a, b);
+        assert_ne!(a, source(ModulatorShape::Saw, Default::default()));
+    }
+}
@@ -0,0 +1,284 @@
+use std::f64::consts::TAU;
+
+/// Number of samples analyzed at once by the pitch detector. Must be a power of two.
+pub const PITCH_WINDOW_SIZE: usize = 2048;
+const PITCH_MIN_FREQUENCY: f64 = 50.0;
+const PITCH_MAX_FREQUENCY: f64 = 1500.0;
+/// Threshold of the cumulative mean normalized difference function (see YIN algorithm).
+const PITCH_DIP_THRESHOLD: f64 = 0.15;
+
+/// Simplified YIN pitch detection.
+///
+/// The difference function is derived from the autocorrelation, which is calculated via FFT.
+/// All buffers are allocated on construction, so detection doesn't allocate.
+#[derive(Clone, Debug)]
+pub struct PitchDetector {
+    window: Vec<f64>,
+    window_pos: usize,
+    /// `exp(-2πik/n)` for `k` in `0..n/2`.
+    twiddles: Vec<Complex>,
+    spectrum_a: Vec<Complex>,
+    spectrum_b: Vec<Complex>,
+}
+
+impl PitchDetector {
+    pub fn new() -> Self {
+        Self {
+            window: vec![0.0; PITCH_WINDOW_SIZE],
+            window_pos: 0,
+            twiddles: create_twiddles(PITCH_WINDOW_SIZE),
+            spectrum_a: vec![Complex::default(); PITCH_WINDOW_SIZE],
+            spectrum_b: vec![Complex::default(); PITCH_WINDOW_SIZE],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.window_pos = 0;
+    }
+
+    /// Returns `true` if the window is full and ready for detection.
+    pub fn feed(&mut self, sample: f64) -> bool {
+        self.window[self.window_pos] = sample;
+        self.window_pos += 1;
+        if self.window_pos < PITCH_WINDOW_SIZE {
+            return false;
+        }
+        self.window_pos = 0;
+        true
+    }
+
+    /// Returns the fundamental frequency of the current window or `None` if the window is
+    /// quieter than the given RMS threshold or no clear pitch has been found.
+    pub fn detect_frequency(&mut self, sample_rate: f64, threshold: f64) -> Option<f64> {
+        let window = &self.window;
+        let rms = (window.iter().map(|s| s * s).sum::<f64>() / PITCH_WINDOW_SIZE as f64).sqrt();
+        if rms < threshold {
+            return None;
+        }
+        let integration_size = PITCH_WINDOW_SIZE / 2;
+        let min_lag = ((sample_rate / PITCH_MAX_FREQUENCY) as usize).max(2);
+        let max_lag = ((sample_rate / PITCH_MIN_FREQUENCY) as usize).min(integration_size - 1);
+        // Cross-correlation between the first half of the window and the whole window
+        for (i, sample) in window.iter().enumerate() {
+            let a = if i < integration_size { *sample } else { 0.0 };
+            self.spectrum_a[i] = Complex::new(a, 0.0);
+            self.spectrum_b[i] = Complex::new(*sample, 0.0);
+        }
+        fft(&mut self.spectrum_a, &self.twiddles, false);
+        fft(&mut self.spectrum_b, &self.twiddles, false);
+        for (a, b) in self.spectrum_a.iter_mut().zip(self.spectrum_b.iter()) {
+            *a = a.conj().mul(*b);
+        }
+        fft(&mut self.spectrum_a, &self.twiddles, true);
+        let correlation = &self.spectrum_a;
+        // d(lag) = energy(0) + energy(lag) - 2 * correlation(lag)
+        let initial_energy: f64 = window[..integration_size].iter().map(|s| s * s).sum();
+        let mut lagged_energy = initial_energy;
+        let mut running_sum = 0.0;
+        let mut prev = f64::MAX;
+        for lag in 1..=max_lag {
+            let incoming = window[lag + integration_size - 1];
+            let outgoing = window[lag - 1];
+            lagged_energy += incoming * incoming - outgoing * outgoing;
+            let difference = (initial_energy + lagged_energy - 2.0 * correlation[lag].re).max(0.0);
+            running_sum += difference;
+            let normalized = if running_sum == 0.0 {
+                1.0
+            } else {
+                difference * lag as f64 / running_sum
+            };
+            if lag > min_lag && prev < PITCH_DIP_THRESHOLD && normalized >= prev {
+                // Previous lag is a local minimum below the threshold
+                return Some(sample_rate / (lag - 1) as f64);
+            }
+            prev = normalized;
+        }
+        None
+    }
+}
+
+impl Default for PitchDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+/// Returns `exp(-2πik/n)` for `k` in `0..n/2`, as needed by [`fft`].
+fn create_twiddles(n: usize) -> Vec<Complex> {
+    (0..n / 2)
+        .map(|k| {
+            let angle = -TAU * k as f64 / n as f64;
+            Complex::new(angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT. The length of `data` must be a power of two.
+fn fft(data: &mut [Complex], twiddles: &[Complex], inverse: bool) {
+    let n = data.len();
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    // Butterflies
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for k in 0..half {
+                let twiddle = twiddles[k * step];
+                let twiddle = if inverse { twiddle.conj() } else { twiddle };
+                let u = data[start + k];
+                let v = data[start + k + half].mul(twiddle);
+                data[start + k] = u.add(v);
+                data[start + k + half] = u.sub(v);
+            }
+        }
+        len <<= 1;
+    }
+    if inverse {
+        let factor = 1.0 / n as f64;
+        for c in data.iter_mut() {
+            c.re *= factor;
+            c.im *= factor;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fft_of_impulse_is_flat() {
+        // Given
+        let n = 8;
+        let twiddles = create_twiddles(n);
+        let mut data = vec![Complex::default(); n];
+        data[0] = Complex::new(1.0, 0.0);
+        // When
+        fft(&mut data, &twiddles, false);
+        // Then
+        for c in data {
+            assert!((c.re - 1.0).abs() < 1e-12);
+            assert!(c.im.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn fft_of_cosine_peaks_at_its_bin() {
+        // Given
+        let n = 16;
+        let bin = 3;
+        let twiddles = create_twiddles(n);
+        let mut data: Vec<_> = (0..n)
+            .map(|i| Complex::new((TAU * bin as f64 * i as f64 / n as f64).cos(), 0.0))
+            .collect();
+        // When
+        fft(&mut data, &twiddles, false);
+        // Then
+        for (i, c) in data.iter().enumerate() {
+            let expected_re = if i == bin || i == n - bin {
+                n as f64 / 2.0
+            } else {
+                0.0
+            };
+            assert!((c.re - expected_re).abs() < 1e-9, "bin {i}: {c:?}");
+            assert!(c.im.abs() < 1e-9, "bin {i}: {c:?}");
+        }
+    }
+
+    #[test]
+    fn inverse_fft_restores_input() {
+        // Given
+        let n = 32;
+        let twiddles = create_twiddles(n);
+        let input: Vec<_> = (0..n)
+            .map(|i| Complex::new((i as f64 * 0.7).sin(), (i as f64 * 0.3).cos()))
+            .collect();
+        let mut data = input.clone();
+        // When
+        fft(&mut data, &twiddles, false);
+        fft(&mut data, &twiddles, true);
+        // Then
+        for (actual, expected) in data.iter().zip(input.iter()) {
+            assert!((actual.re - expected.re).abs() < 1e-12);
+            assert!((actual.im - expected.im).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn detect_frequency() {
+        // Given
+        let sample_rate = 48000.0;
+        let frequency = 440.0;
+        let mut detector = PitchDetector::new();
+        // When
+        let mut window_is_full = false;
+        for i in 0..PITCH_WINDOW_SIZE {
+            let t = i as f64 / sample_rate;
+            let sample =
+                0.5 * (TAU * frequency * t).sin() + 0.2 * (TAU * 2.0 * frequency * t).sin();
+            window_is_full = detector.feed(sample);
+        }
+        let detected_frequency = detector.detect_frequency(sample_rate, 0.01).unwrap();
+        // Then
+        assert!(window_is_full);
+        assert!((detected_frequency - frequency).abs() < 1.0);
+    }
+
+    #[test]
+    fn ignore_quiet_window() {
+        // Given
+        let sample_rate = 48000.0;
+        let mut detector = PitchDetector::new();
+        // When
+        for i in 0..PITCH_WINDOW_SIZE {
+            let t = i as f64 / sample_rate;
+            detector.feed(0.001 * (TAU * 440.0 * t).sin());
+        }
+        // Then
+        assert_eq!(detector.detect_frequency(sample_rate, 0.01), None);
+    }
+}
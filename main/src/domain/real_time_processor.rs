@@ -1,15 +1,16 @@
 use crate::domain::{
-    classify_midi_message, BasicSettings, CompartmentKind, CompoundMappingSource, ControlEvent,
-    ControlEventTimestamp, ControlLogEntry, ControlLogEntryKind, ControlMainTask, ControlMode,
-    ControlOptions, FeedbackSendBehavior, LifecycleMidiMessage, LifecyclePhase, MappingCore,
-    MappingId, MatchOutcome, MidiClockCalculator, MidiEvent, MidiMessageClassification,
-    MidiScanResult, MidiScanner, MidiSendTarget, NormalRealTimeToMainThreadTask, OrderedMappingMap,
-    OwnedIncomingMidiMessage, PartialControlMatch, PersistentMappingProcessingState,
-    QualifiedMappingId, RealTimeCompoundMappingTarget, RealTimeControlContext, RealTimeMapping,
-    RealTimeReaperTarget, SampleOffset, SendMidiDestination, UnitId, VirtualSourceValue,
+    classify_midi_message, short_message_len, BasicSettings, CompartmentKind,
+    CompoundMappingSource, ControlEvent, ControlEventTimestamp, ControlLogEntry,
+    ControlLogEntryKind, ControlMainTask, ControlMode, ControlOptions, FeedbackSendBehavior,
+    LifecycleMidiMessage, LifecyclePhase, MappingCore, MappingId, MatchOutcome,
+    MidiClockCalculator, MidiEvent, MidiMessageClassification, MidiScanResult, MidiScanner,
+    MidiSendTarget, NormalRealTimeToMainThreadTask, OrderedMappingMap, OwnedIncomingMidiMessage,
+    PartialControlMatch, PersistentMappingProcessingState, QualifiedMappingId,
+    RealTimeCompoundMappingTarget, RealTimeControlContext, RealTimeMapping, RealTimeReaperTarget,
+    ReaperSource, SampleOffset, SendMidiDestination, UnitId, VirtualSourceValue,
     WeakRealTimeInstance,
 };
-use helgoboss_learn::{ControlValue, MidiSourceValue, ModeControlResult, RawMidiEvent};
+use helgoboss_learn::{ControlValue, MidiSourceValue, ModeControlResult, RawMidiEvent, Target};
 use helgoboss_midi::{
     Channel, ControlChange14BitMessage, ControlChange14BitMessageScanner, DataEntryByteOrder,
     ParameterNumberMessage, PollingParameterNumberMessageScanner, RawShortMessage, ShortMessage,
@@ -28,6 +29,7 @@ use std::convert::TryInto;
 use std::ptr::null_mut;
 use std::time::Duration;
 use vst::api::{EventType, Events, SysExEvent};
+use vst::buffer::Inputs;
 use vst::host::Host;
 use vst::plugin::HostCallback;
 
@@ -42,6 +44,12 @@ pub struct RealTimeProcessor {
     settings: BasicSettings,
     control_mode: ControlMode,
     mappings: EnumMap<CompartmentKind, OrderedMappingMap<RealTimeMapping>>,
+    /// Derived from mappings whenever they are synced. Saves us from checking all mappings for
+    /// each incoming MIDI message.
+    has_midi_input_script_sources: bool,
+    /// Derived from mappings whenever they are synced. Saves us from checking all mappings for
+    /// each audio block.
+    has_audio_signal_sources: bool,
     // State
     control_is_globally_enabled: bool,
     feedback_is_globally_enabled: bool,
@@ -90,6 +98,8 @@ impl RealTimeProcessor {
                 Controller => ordered_map_with_capacity(1000),
                 Main => ordered_map_with_capacity(5000),
             },
+            has_midi_input_script_sources: false,
+            has_audio_signal_sources: false,
             nrpn_scanner: PollingParameterNumberMessageScanner::new(Duration::from_millis(1)),
             cc_14_bit_scanner: Default::default(),
             midi_scanner: Default::default(),
@@ -133,6 +143,65 @@ impl RealTimeProcessor {
         self.process_feedback_tasks(Caller::Vst(host));
     }
 
+    /// Feeds the audio arriving at the plug-in input pins into all mappings with audio signal
+    /// sources.
+    pub fn process_incoming_audio_from_vst(
+        &mut self,
+        inputs: &Inputs<f64>,
+        timestamp: ControlEventTimestamp,
+        host: &HostCallback,
+    ) {
+        if !self.control_is_globally_enabled || !self.has_audio_signal_sources {
+            return;
+        }
+        let caller = Caller::Vst(host);
+        let is_rendering = is_rendering();
+        let offset_event = ControlEvent::new(SampleOffset::ZERO, timestamp);
+        let sample_rate = self.sample_rate;
+        if let [ref mut controller_mappings, ref mut main_mappings] = self.mappings.as_mut_slice() {
+            control_controller_mappings(
+                &self.control_main_task_sender,
+                &self.feedback_task_sender,
+                controller_mappings,
+                main_mappings,
+                offset_event,
+                |m| m.control_audio_signal_virtualizing(inputs, sample_rate, timestamp),
+                caller,
+                self.settings.midi_destination(),
+                LogOptions::from_basic_settings(&self.settings),
+                &self.instance,
+                is_rendering,
+            );
+        }
+        let compartment = CompartmentKind::Main;
+        for m in self.mappings[compartment]
+            .values_mut()
+            .filter(|m| m.control_is_effectively_on() && m.has_reaper_target())
+        {
+            if let Some(control_value) = m.control_audio_signal(inputs, sample_rate) {
+                let args = ProcessRtMappingArgs {
+                    main_task_sender: &self.control_main_task_sender,
+                    rt_feedback_sender: &self.feedback_task_sender,
+                    compartment,
+                    value_event: offset_event
+                        .with_payload(MidiEvent::new(SampleOffset::ZERO, control_value)),
+                    options: ControlOptions {
+                        enforce_send_feedback_after_control: false,
+                        mode_control_options: Default::default(),
+                        enforce_target_refresh: false,
+                        coming_from_real_time: true,
+                    },
+                    caller,
+                    midi_feedback_output: self.settings.midi_destination(),
+                    log_options: LogOptions::from_basic_settings(&self.settings),
+                    instance: &self.instance,
+                    is_rendering,
+                };
+                process_real_mapping(m, args);
+            }
+        }
+    }
+
     /// This should be regularly called by audio hook in normal mode.
     pub fn run_from_audio_hook_all(
         &mut self,
@@ -247,6 +316,7 @@ impl RealTimeProcessor {
                     self.mappings[compartment].clear();
                     // Set new mappings
                     self.mappings[compartment].extend(mappings.into_iter().map(|m| (m.id(), m)));
+                    self.update_source_flags();
                     // Handle activation MIDI
                     if self.processor_feedback_is_effectively_on() {
                         self.send_lifecycle_midi_for_all_mappings_in(
@@ -274,6 +344,7 @@ impl RealTimeProcessor {
                     }
                     // Update
                     self.mappings[compartment].insert(m.id(), *m);
+                    self.update_source_flags();
                 }
                 UpdatePersistentMappingProcessingState { id, state } => {
                     permit_alloc(|| {
@@ -298,6 +369,11 @@ impl RealTimeProcessor {
                         }
                     }
                 }
+                UpdateButtonStateRecording(compartment, mapping_ids) => {
+                    for m in self.mappings[compartment].values_mut() {
+                        m.set_records_button_state(mapping_ids.contains(&m.id()));
+                    }
+                }
                 UpdateTargetsPartially(compartment, mut target_updates) => {
                     // Also log sample count in order to be sure about invocation order
                     // (timestamp is not accurate enough on e.g. selection changes).
@@ -629,6 +705,7 @@ impl RealTimeProcessor {
         match self.control_mode {
             ControlMode::Controlling => {
                 if self.control_is_globally_enabled {
+                    self.forward_to_midi_input_scripts(event);
                     // Even if an composite message ((N)RPN or CC 14-bit) was scanned, we still
                     // process the plain short MIDI message. This is desired.
                     // Rationale: If there's no mapping with a composite source
@@ -873,6 +950,44 @@ impl RealTimeProcessor {
         match_outcome
     }
 
+    /// MIDI input scripts can only be executed in the main thread, so if at least one mapping has
+    /// such a source, we forward the message to the main processor.
+    fn forward_to_midi_input_scripts(&self, event: ControlEvent<MidiEvent<IncomingMidiMessage>>) {
+        // Whether control is enabled for these mappings is checked in the main processor.
+        if !self.has_midi_input_script_sources {
+            return;
+        }
+        let raw_midi_event = match event.payload().payload() {
+            IncomingMidiMessage::Short(short_msg) => {
+                let bytes = short_msg.to_bytes();
+                let bytes = [bytes.0, bytes.1.get(), bytes.2.get()];
+                RawMidiEvent::try_from_slice(0, &bytes[..short_message_len(&short_msg)])
+            }
+            IncomingMidiMessage::SysEx(bytes) => RawMidiEvent::try_from_slice(0, bytes),
+        };
+        // Too long sys-ex messages can't be forwarded without allocation.
+        let Ok(raw_midi_event) = raw_midi_event else {
+            return;
+        };
+        self.control_main_task_sender.send_complaining(
+            ControlMainTask::ControlFromMidiInputScript {
+                event: event.with_payload(raw_midi_event),
+            },
+        );
+    }
+
+    fn update_source_flags(&mut self) {
+        self.has_midi_input_script_sources = self
+            .all_mappings()
+            .any(|m| matches!(m.source(), CompoundMappingSource::MidiInputScript(_)));
+        self.has_audio_signal_sources = self.all_mappings().any(|m| {
+            matches!(
+                m.source(),
+                CompoundMappingSource::Reaper(ReaperSource::AudioSignal(_))
+            )
+        });
+    }
+
     fn all_mappings(&self) -> impl Iterator<Item = &RealTimeMapping> {
         CompartmentKind::enum_iter()
             .flat_map(move |compartment| self.mappings[compartment].values())
@@ -884,6 +999,7 @@ impl RealTimeProcessor {
         caller: Caller,
     ) -> MatchOutcome {
         let is_rendering = is_rendering();
+        self.record_button_states_midi(value_event);
         // We do pattern matching in order to use Rust's borrow splitting.
         let controller_outcome = if let [ref mut controller_mappings, ref mut main_mappings] =
             self.mappings.as_mut_slice()
@@ -907,6 +1023,31 @@ impl RealTimeProcessor {
         controller_outcome.merge_with(main_outcome)
     }
 
+    /// Reports matched source values of mappings whose button state is relevant for chords and
+    /// sequences, no matter if these mappings are controlled or not.
+    fn record_button_states_midi(
+        &self,
+        value_event: ControlEvent<MidiEvent<&MidiSourceValue<RawShortMessage>>>,
+    ) {
+        for compartment in CompartmentKind::enum_iter() {
+            for m in self.mappings[compartment]
+                .values()
+                .filter(|m| m.records_button_state())
+            {
+                if let CompoundMappingSource::Midi(s) = m.source() {
+                    if let Some(control_value) = s.control(value_event.payload().payload()) {
+                        forward_button_state_to_main_processor(
+                            &self.control_main_task_sender,
+                            compartment,
+                            m.id(),
+                            value_event.with_payload(control_value),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     fn control_main_mappings_midi(
         &mut self,
         source_value_event: ControlEvent<MidiEvent<&MidiSourceValue<RawShortMessage>>>,
@@ -1180,6 +1321,9 @@ pub enum NormalRealTimeTask {
     /// The given vector contains updates just for affected mappings. This is because when a
     /// parameter update occurs we can determine in a very granular way which targets are affected.
     UpdateMappingsPartially(CompartmentKind, Vec<RealTimeMappingUpdate>),
+    /// Contains the IDs of all mappings in the compartment whose button state is relevant for
+    /// chords and sequences.
+    UpdateButtonStateRecording(CompartmentKind, Vec<MappingId>),
     LogDebugInfo,
     LogMapping(CompartmentKind, MappingId),
     UpdateSampleRate(Hz),
@@ -1299,6 +1443,39 @@ fn control_controller_mappings_midi(
     log_options: LogOptions,
     instance: &WeakRealTimeInstance,
     is_rendering: bool,
+) -> MatchOutcome {
+    control_controller_mappings(
+        main_task_sender,
+        rt_feedback_sender,
+        controller_mappings,
+        main_mappings,
+        value_event.map_payload(|e| e.offset()),
+        |m| m.control_midi_virtualizing(flatten_control_midi_event(value_event)),
+        caller,
+        midi_feedback_output,
+        log_options,
+        instance,
+        is_rendering,
+    )
+}
+
+/// Lets the given function match each controller mapping against an incoming event and processes
+/// the matches.
+#[allow(clippy::too_many_arguments)]
+fn control_controller_mappings(
+    main_task_sender: &SenderToNormalThread<ControlMainTask>,
+    rt_feedback_sender: &SenderToRealTimeThread<FeedbackRealTimeTask>,
+    // Mappings with virtual targets
+    controller_mappings: &mut OrderedMappingMap<RealTimeMapping>,
+    // Mappings with virtual sources
+    main_mappings: &mut OrderedMappingMap<RealTimeMapping>,
+    offset_event: ControlEvent<SampleOffset>,
+    mut control: impl FnMut(&mut RealTimeMapping) -> Option<PartialControlMatch>,
+    caller: Caller,
+    midi_feedback_output: Option<MidiDestination>,
+    log_options: LogOptions,
+    instance: &WeakRealTimeInstance,
+    is_rendering: bool,
 ) -> MatchOutcome {
     let mut match_outcome = MatchOutcome::Unmatched;
     let mut enforce_target_refresh = false;
@@ -1306,9 +1483,7 @@ fn control_controller_mappings_midi(
         .values_mut()
         .filter(|m| m.control_is_effectively_on())
     {
-        if let Some(control_match) =
-            m.control_midi_virtualizing(flatten_control_midi_event(value_event))
-        {
+        if let Some(control_match) = control(m) {
             use PartialControlMatch::*;
             let child_match_outcome = match control_match {
                 ProcessVirtual(virtual_source_value) => {
@@ -1316,8 +1491,8 @@ fn control_controller_mappings_midi(
                         main_task_sender,
                         rt_feedback_sender,
                         main_mappings,
-                        value_event.with_payload(MidiEvent::new(
-                            value_event.payload().offset(),
+                        offset_event.with_payload(MidiEvent::new(
+                            offset_event.payload(),
                             virtual_source_value,
                         )),
                         ControlOptions {
@@ -1344,7 +1519,7 @@ fn control_controller_mappings_midi(
                     if log_options.virtual_input_logging_enabled {
                         log_virtual_control_input(
                             main_task_sender,
-                            value_event.with_payload(virtual_source_value),
+                            offset_event.with_payload(virtual_source_value),
                             virtual_match_outcome,
                         );
                     }
@@ -1355,10 +1530,8 @@ fn control_controller_mappings_midi(
                         main_task_sender,
                         rt_feedback_sender,
                         compartment: CompartmentKind::Controller,
-                        value_event: value_event.with_payload(MidiEvent::new(
-                            value_event.payload().offset(),
-                            control_value,
-                        )),
+                        value_event: offset_event
+                            .with_payload(MidiEvent::new(offset_event.payload(), control_value)),
                         options: ControlOptions {
                             enforce_send_feedback_after_control: false,
                             mode_control_options: Default::default(),
@@ -1445,14 +1618,18 @@ fn process_real_mapping_in_real_time(
         instance: args.instance,
         _p: &(),
     };
-    let mode_control_result = mapping_core.mode.control_with_options(
-        pure_control_event,
-        reaper_target,
-        control_context,
-        args.options.mode_control_options,
-        // Performance control not supported when controlling real-time
-        None,
-    );
+    let mode_control_result = mapping_core
+        .accelerate_encoder(pure_control_event)
+        .and_then(|control_event| {
+            mapping_core.mode.control_with_options(
+                control_event,
+                reaper_target,
+                control_context,
+                args.options.mode_control_options,
+                // Performance control not supported when controlling real-time
+                None,
+            )
+        });
     let (log_entry_kind, control_value, error) = match mode_control_result {
         None => (ControlLogEntryKind::IgnoredByGlue, None, ""),
         Some(ModeControlResult::LeaveTargetUntouched(v)) => {
@@ -1461,6 +1638,8 @@ fn process_real_mapping_in_real_time(
         Some(ModeControlResult::HitTarget {
             value: control_value,
         }) => {
+            let control_value = mapping_core
+                .quantize_to_scale(control_value, reaper_target.control_type(control_context));
             let hit_result = match reaper_target {
                 RealTimeReaperTarget::SendMidi(t) => real_time_target_send_midi(
                     t,
@@ -1608,6 +1787,20 @@ fn forward_control_to_main_processor(
     sender.send_if_space(task);
 }
 
+fn forward_button_state_to_main_processor(
+    sender: &SenderToNormalThread<ControlMainTask>,
+    compartment: CompartmentKind,
+    mapping_id: MappingId,
+    event: ControlEvent<ControlValue>,
+) {
+    let task = ControlMainTask::RecordButtonState {
+        compartment,
+        mapping_id,
+        event,
+    };
+    sender.send_if_space(task);
+}
+
 #[allow(clippy::too_many_arguments)]
 fn control_main_mappings_virtual(
     main_task_sender: &SenderToNormalThread<ControlMainTask>,
@@ -1626,11 +1819,22 @@ fn control_main_mappings_virtual(
     let mut match_outcome = MatchOutcome::Unmatched;
     for m in main_mappings
         .values_mut()
-        .filter(|m| m.control_is_effectively_on())
+        .filter(|m| m.control_is_effectively_on() || m.records_button_state())
     {
         if let CompoundMappingSource::Virtual(s) = &m.source() {
             let midi_event = value_event.payload();
             if let Some(control_value) = s.control(&midi_event.payload()) {
+                if m.records_button_state() {
+                    forward_button_state_to_main_processor(
+                        main_task_sender,
+                        CompartmentKind::Main,
+                        m.id(),
+                        value_event.with_payload(control_value),
+                    );
+                }
+                if !m.control_is_effectively_on() {
+                    continue;
+                }
                 let args = ProcessRtMappingArgs {
                     main_task_sender,
                     rt_feedback_sender,
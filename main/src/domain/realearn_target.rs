@@ -11,21 +11,22 @@ use crate::domain::{
     ProcessorContext, QualifiedMappingId, RealTimeReaperTarget, RealearnSourceContext,
     ReaperTarget, SharedInstance, SharedUnit, Tag, TagScope, TargetCharacter, TrackExclusivity,
     UnitEvent, UnitId, WeakRealTimeInstance, ACTION_TARGET, ALL_TRACK_FX_ENABLE_TARGET,
-    ANY_ON_TARGET, AUTOMATION_MODE_OVERRIDE_TARGET, BROWSE_FXS_TARGET,
+    ANY_ON_TARGET, ARRANGE_VIEW_TARGET, AUTOMATION_MODE_OVERRIDE_TARGET, BROWSE_FXS_TARGET,
     BROWSE_GROUP_MAPPINGS_TARGET, BROWSE_POT_FILTER_ITEMS_TARGET, BROWSE_POT_PRESETS_TARGET,
     COMPARTMENT_PARAMETER_VALUE_TARGET, DUMMY_TARGET, ENABLE_INSTANCES_TARGET,
-    ENABLE_MAPPINGS_TARGET, FX_ENABLE_TARGET, FX_ONLINE_TARGET, FX_OPEN_TARGET,
-    FX_PARAMETER_TARGET, FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET, FX_TOOL_TARGET,
-    GO_TO_BOOKMARK_TARGET, LAST_TOUCHED_TARGET, LEARN_MAPPING_TARGET, LOAD_FX_SNAPSHOT_TARGET,
-    LOAD_MAPPING_SNAPSHOT_TARGET, LOAD_POT_PRESET_TARGET, MIDI_SEND_TARGET, MOUSE_TARGET,
-    OSC_SEND_TARGET, PLAYRATE_TARGET, PREVIEW_POT_PRESET_TARGET, ROUTE_AUTOMATION_MODE_TARGET,
+    ENABLE_MAPPINGS_TARGET, FX_CHAIN_EDIT_TARGET, FX_ENABLE_TARGET, FX_ONLINE_TARGET,
+    FX_OPEN_TARGET, FX_PARAMETER_TARGET, FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET,
+    FX_TOOL_TARGET, GO_TO_BOOKMARK_TARGET, LAST_TOUCHED_TARGET, LEARN_MAPPING_TARGET,
+    LOAD_FX_SNAPSHOT_TARGET, LOAD_MAPPING_SNAPSHOT_TARGET, LOAD_POT_PRESET_TARGET,
+    METRONOME_VOLUME_TARGET, MIDI_SEND_TARGET, MOUSE_TARGET, OSC_SEND_TARGET, PLAYRATE_TARGET,
+    PREVIEW_POT_PRESET_TARGET, ROUTE_AUTOMATION_MODE_TARGET, ROUTE_MATRIX_CELL_TARGET,
     ROUTE_MONO_TARGET, ROUTE_MUTE_TARGET, ROUTE_PAN_TARGET, ROUTE_PHASE_TARGET,
     ROUTE_TOUCH_STATE_TARGET, ROUTE_VOLUME_TARGET, SAVE_MAPPING_SNAPSHOT_TARGET, SEEK_TARGET,
     SELECTED_TRACK_TARGET, TEMPO_TARGET, TRACK_ARM_TARGET, TRACK_AUTOMATION_MODE_TARGET,
-    TRACK_MONITORING_MODE_TARGET, TRACK_MUTE_TARGET, TRACK_PAN_TARGET, TRACK_PARENT_SEND_TARGET,
-    TRACK_PEAK_TARGET, TRACK_PHASE_TARGET, TRACK_SELECTION_TARGET, TRACK_SHOW_TARGET,
-    TRACK_SOLO_TARGET, TRACK_TOOL_TARGET, TRACK_TOUCH_STATE_TARGET, TRACK_VOLUME_TARGET,
-    TRACK_WIDTH_TARGET, TRANSPORT_TARGET,
+    TRACK_HEIGHT_TARGET, TRACK_MONITORING_MODE_TARGET, TRACK_MUTE_TARGET, TRACK_PAN_TARGET,
+    TRACK_PARENT_SEND_TARGET, TRACK_PEAK_TARGET, TRACK_PHASE_TARGET, TRACK_RECORD_INPUT_TARGET,
+    TRACK_SELECTION_TARGET, TRACK_SHOW_TARGET, TRACK_SOLO_TARGET, TRACK_TOOL_TARGET,
+    TRACK_TOUCH_STATE_TARGET, TRACK_VOLUME_TARGET, TRACK_WIDTH_TARGET, TRANSPORT_TARGET,
 };
 use base::hash_util::NonCryptoHashSet;
 use base::{SenderToNormalThread, SenderToRealTimeThread};
@@ -510,6 +511,12 @@ impl<'a> TransformationInputProvider<AdditionalTransformationInput> for MappingC
                 .last_non_performance_target_value
                 .map(|v| v.to_unit_value().get())
                 .unwrap_or_default(),
+            compartment_lua: self
+                .control_context
+                .source_context
+                .additional_script_input
+                .compartment_lua
+                .cloned(),
         }
     }
 }
@@ -638,6 +645,8 @@ pub enum ReaperTargetType {
     Seek = 23,
     PlayRate = 11,
     Tempo = 10,
+    ArrangeView = 69,
+    MetronomeVolume = 71,
 
     // Marker/region targets
     GoToBookmark = 22,
@@ -654,14 +663,18 @@ pub enum ReaperTargetType {
     TrackAutomationMode = 25,
     TrackTouchState = 21,
     TrackMonitoringMode = 49,
+    TrackRecordInput = 68,
+    TrackRecordMode = 72,
     TrackPan = 4,
     TrackWidth = 17,
+    TrackHeight = 70,
     TrackVolume = 2,
     TrackShow = 24,
     TrackSolo = 8,
 
     // FX chain targets
     BrowseFxs = 28,
+    FxChainEdit = 66,
 
     // FX targets
     FxTool = 54,
@@ -690,6 +703,7 @@ pub enum ReaperTargetType {
     RouteAutomationMode = 45,
     RoutePan = 9,
     RouteVolume = 3,
+    RouteMatrixCell = 67,
 
     // Clip targets
     PlaytimeSlotManagementAction = 46,
@@ -796,6 +810,8 @@ impl ReaperTargetType {
             Seek => &SEEK_TARGET,
             PlayRate => &PLAYRATE_TARGET,
             Tempo => &TEMPO_TARGET,
+            ArrangeView => &ARRANGE_VIEW_TARGET,
+            MetronomeVolume => &METRONOME_VOLUME_TARGET,
             GoToBookmark => &GO_TO_BOOKMARK_TARGET,
             TrackArm => &TRACK_ARM_TARGET,
             TrackParentSend => &TRACK_PARENT_SEND_TARGET,
@@ -807,14 +823,18 @@ impl ReaperTargetType {
             TrackSelection => &TRACK_SELECTION_TARGET,
             TrackAutomationMode => &TRACK_AUTOMATION_MODE_TARGET,
             TrackMonitoringMode => &TRACK_MONITORING_MODE_TARGET,
+            TrackRecordInput => &TRACK_RECORD_INPUT_TARGET,
+            TrackRecordMode => &TRACK_RECORD_MODE_TARGET,
             TrackTouchState => &TRACK_TOUCH_STATE_TARGET,
             TrackPan => &TRACK_PAN_TARGET,
             TrackWidth => &TRACK_WIDTH_TARGET,
+            TrackHeight => &TRACK_HEIGHT_TARGET,
             TrackVolume => &TRACK_VOLUME_TARGET,
             TrackShow => &TRACK_SHOW_TARGET,
             TrackSolo => &TRACK_SOLO_TARGET,
             FxTool => &FX_TOOL_TARGET,
             BrowseFxs => &BROWSE_FXS_TARGET,
+            FxChainEdit => &FX_CHAIN_EDIT_TARGET,
             FxEnable => &FX_ENABLE_TARGET,
             FxOnline => &FX_ONLINE_TARGET,
             LoadFxSnapshot => &LOAD_FX_SNAPSHOT_TARGET,
@@ -829,6 +849,7 @@ impl ReaperTargetType {
             RoutePan => &ROUTE_PAN_TARGET,
             RouteVolume => &ROUTE_VOLUME_TARGET,
             RouteTouchState => &ROUTE_TOUCH_STATE_TARGET,
+            RouteMatrixCell => &ROUTE_MATRIX_CELL_TARGET,
             PlaytimeSlotTransportAction => &crate::domain::PLAYTIME_SLOT_TRANSPORT_TARGET,
             PlaytimeColumnAction => &crate::domain::PLAYTIME_COLUMN_TARGET,
             PlaytimeRowAction => &crate::domain::PLAYTIME_ROW_TARGET,
@@ -1,4 +1,7 @@
-use crate::domain::{CompartmentKind, CompartmentParamIndex, RawParamValue, ReaperSourceAddress};
+use crate::domain::{
+    CompartmentKind, CompartmentParamIndex, ModulatorSource, PitchDetector, RawParamValue,
+    ReaperSourceAddress,
+};
 use base::hash_util::NonCryptoHashSet;
 use core::fmt;
 use derive_more::Display;
@@ -6,19 +9,24 @@ use helgoboss_learn::{
     format_percentage_without_unit, parse_percentage_without_unit, ControlValue,
     DetailedSourceCharacter, FeedbackValue, SourceCharacter, UnitValue,
 };
-use reaper_medium::{MidiInputDeviceId, MidiOutputDeviceId};
+use realearn_api::persistence::AudioSignalKind;
+use reaper_high::Project;
+use reaper_medium::{Hz, MidiInputDeviceId, MidiOutputDeviceId};
 use std::convert::TryInto;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::time::{Duration, Instant};
+use vst::buffer::Inputs;
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum ReaperSource {
     MidiDeviceChanges,
     RealearnInstanceStart,
     Timer(TimerSource),
     RealearnParameter(RealearnParameterSource),
     Speech(SpeechSource),
+    AudioSignal(AudioSignalSource),
+    Modulator(ModulatorSource),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
@@ -122,6 +130,207 @@ impl TimerSource {
     }
 }
 
+pub const DEFAULT_AUDIO_SIGNAL_ATTACK_MS: u32 = 10;
+pub const DEFAULT_AUDIO_SIGNAL_RELEASE_MS: u32 = 100;
+pub const DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB: f64 = -30.0;
+
+/// Levels below this are mapped to 0%, 0 dB is mapped to 100%.
+const AUDIO_SIGNAL_FLOOR_DB: f64 = -60.0;
+/// How much faster the short-term envelope must rise compared to the long-term envelope in
+/// order to count as onset.
+const TRANSIENT_RISE_RATIO: f64 = 2.0;
+const TRANSIENT_REARM_RATIO: f64 = 1.2;
+
+/// Derives control values from the audio signal arriving at the plug-in input pins.
+///
+/// All channels are mixed down to mono before analysis. Processing happens in the real-time
+/// thread, so this must not allocate after construction.
+#[derive(Clone, Debug)]
+pub struct AudioSignalSource {
+    kind: AudioSignalKind,
+    attack: Duration,
+    release: Duration,
+    threshold: f64,
+    envelope: f64,
+    transient_fast_envelope: f64,
+    transient_slow_envelope: f64,
+    transient_is_armed: bool,
+    pitch_detector: Option<PitchDetector>,
+    last_value: Option<UnitValue>,
+}
+
+impl PartialEq for AudioSignalSource {
+    fn eq(&self, other: &Self) -> bool {
+        // The analysis state is not relevant for comparison, only the settings.
+        self.kind == other.kind
+            && self.attack == other.attack
+            && self.release == other.release
+            && self.threshold == other.threshold
+    }
+}
+
+impl AudioSignalSource {
+    pub fn new(
+        kind: AudioSignalKind,
+        attack: Duration,
+        release: Duration,
+        threshold_db: f64,
+    ) -> Self {
+        let pitch_detector = if kind == AudioSignalKind::Pitch {
+            Some(PitchDetector::new())
+        } else {
+            None
+        };
+        Self {
+            kind,
+            attack,
+            release,
+            threshold: db_to_amplitude(threshold_db),
+            envelope: 0.0,
+            transient_fast_envelope: 0.0,
+            transient_slow_envelope: 0.0,
+            transient_is_armed: true,
+            pitch_detector,
+            last_value: None,
+        }
+    }
+
+    pub fn kind(&self) -> AudioSignalKind {
+        self.kind
+    }
+
+    pub fn on_deactivate(&mut self) {
+        self.envelope = 0.0;
+        self.transient_fast_envelope = 0.0;
+        self.transient_slow_envelope = 0.0;
+        self.transient_is_armed = true;
+        if let Some(d) = &mut self.pitch_detector {
+            d.reset();
+        }
+        self.last_value = None;
+    }
+
+    /// Analyzes the given block of audio and returns a control value if there's something new
+    /// to report.
+    ///
+    /// Envelopes and pitch report at most one value per block, and only if it changed.
+    pub fn process(&mut self, inputs: &Inputs<f64>, sample_rate: Hz) -> Option<ControlValue> {
+        let channel_count = inputs.len();
+        if channel_count == 0 {
+            return None;
+        }
+        let frame_count = inputs.get(0).len();
+        let mono_samples = (0..frame_count).map(|frame| {
+            (0..channel_count)
+                .map(|ch| inputs.get(ch)[frame])
+                .sum::<f64>()
+                / channel_count as f64
+        });
+        self.process_mono(mono_samples, sample_rate.get())
+    }
+
+    fn process_mono(
+        &mut self,
+        samples: impl Iterator<Item = f64>,
+        sample_rate: f64,
+    ) -> Option<ControlValue> {
+        let mut fired = false;
+        let mut pitch_value = None;
+        for sample in samples {
+            use AudioSignalKind::*;
+            match self.kind {
+                PeakEnvelope => self.follow_envelope(sample.abs(), sample_rate),
+                RmsEnvelope => self.follow_envelope(sample * sample, sample_rate),
+                Transient => fired |= self.detect_transient(sample.abs(), sample_rate),
+                Pitch => {
+                    if let Some(v) = self.track_pitch(sample, sample_rate) {
+                        pitch_value = Some(v);
+                    }
+                }
+            }
+        }
+        use AudioSignalKind::*;
+        let value = match self.kind {
+            PeakEnvelope => amplitude_to_unit_value(self.envelope),
+            RmsEnvelope => amplitude_to_unit_value(self.envelope.sqrt()),
+            Transient => {
+                return fired.then_some(ControlValue::AbsoluteContinuous(UnitValue::MAX));
+            }
+            Pitch => pitch_value?,
+        };
+        if self.last_value == Some(value) {
+            return None;
+        }
+        self.last_value = Some(value);
+        Some(ControlValue::AbsoluteContinuous(value))
+    }
+
+    fn follow_envelope(&mut self, level: f64, sample_rate: f64) {
+        let time = if level > self.envelope {
+            self.attack
+        } else {
+            self.release
+        };
+        let coefficient = smoothing_coefficient(time.as_secs_f64(), sample_rate);
+        self.envelope = coefficient * self.envelope + (1.0 - coefficient) * level;
+    }
+
+    /// Returns `true` if an onset has been detected.
+    fn detect_transient(&mut self, level: f64, sample_rate: f64) -> bool {
+        let fast = smoothing_coefficient(0.001, sample_rate);
+        let slow = smoothing_coefficient(0.1, sample_rate);
+        self.transient_fast_envelope = fast * self.transient_fast_envelope + (1.0 - fast) * level;
+        self.transient_slow_envelope = slow * self.transient_slow_envelope + (1.0 - slow) * level;
+        let fast_env = self.transient_fast_envelope;
+        let slow_env = self.transient_slow_envelope;
+        if self.transient_is_armed {
+            if fast_env >= self.threshold && fast_env >= TRANSIENT_RISE_RATIO * slow_env {
+                self.transient_is_armed = false;
+                return true;
+            }
+        } else if fast_env < self.threshold || fast_env < TRANSIENT_REARM_RATIO * slow_env {
+            self.transient_is_armed = true;
+        }
+        false
+    }
+
+    /// Collects samples and returns a value as soon as a full window has been analyzed and the
+    /// detected note differs from the last one.
+    fn track_pitch(&mut self, sample: f64, sample_rate: f64) -> Option<UnitValue> {
+        let detector = self.pitch_detector.as_mut()?;
+        if !detector.feed(sample) {
+            return None;
+        }
+        let frequency = detector.detect_frequency(sample_rate, self.threshold)?;
+        let note_number = (69.0 + 12.0 * (frequency / 440.0).log2()).round();
+        if !(0.0..=127.0).contains(&note_number) {
+            return None;
+        }
+        Some(UnitValue::new(note_number / 127.0))
+    }
+}
+
+fn smoothing_coefficient(time_in_secs: f64, sample_rate: f64) -> f64 {
+    if time_in_secs <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / (time_in_secs * sample_rate)).exp()
+}
+
+fn db_to_amplitude(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+fn amplitude_to_unit_value(amplitude: f64) -> UnitValue {
+    if amplitude <= 0.0 {
+        return UnitValue::MIN;
+    }
+    let db = 20.0 * amplitude.log10();
+    // Rounding prevents reporting tiny changes that nobody would notice anyway
+    let value = ((db - AUDIO_SIGNAL_FLOOR_DB) / -AUDIO_SIGNAL_FLOOR_DB * 1000.0).round() / 1000.0;
+    UnitValue::new_clamped(value)
+}
+
 impl ReaperSource {
     pub fn extract_feedback_address(&self) -> Option<ReaperSourceAddress> {
         use ReaperSource::*;
@@ -134,13 +343,15 @@ impl ReaperSource {
     pub fn on_deactivate(&mut self) {
         match self {
             ReaperSource::Timer(s) => s.on_deactivate(),
+            ReaperSource::AudioSignal(s) => s.on_deactivate(),
+            ReaperSource::Modulator(s) => s.on_deactivate(),
             _ => {}
         }
     }
 
     /// If this returns `true`, the `poll` method should be called, on a regular basis.
     pub fn wants_to_be_polled(&self) -> bool {
-        matches!(self, ReaperSource::Timer(_))
+        matches!(self, ReaperSource::Timer(_) | ReaperSource::Modulator(_))
     }
 
     pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
@@ -156,6 +367,11 @@ impl ReaperSource {
                 DetailedSourceCharacter::Trigger,
             ],
             Speech(_) => vec![DetailedSourceCharacter::RangeControl],
+            AudioSignal(s) => match s.kind() {
+                AudioSignalKind::Transient => vec![DetailedSourceCharacter::Trigger],
+                _ => vec![DetailedSourceCharacter::RangeControl],
+            },
+            Modulator(_) => vec![DetailedSourceCharacter::RangeControl],
         }
     }
 
@@ -176,14 +392,19 @@ impl ReaperSource {
             }
             RealearnParameter(_) => SourceCharacter::RangeElement,
             Speech(_) => SourceCharacter::RangeElement,
+            AudioSignal(s) => match s.kind() {
+                AudioSignalKind::Transient => SourceCharacter::MomentaryButton,
+                _ => SourceCharacter::RangeElement,
+            },
+            Modulator(_) => SourceCharacter::RangeElement,
         }
     }
 
-    pub fn poll(&mut self) -> Option<ControlValue> {
-        if let ReaperSource::Timer(t) = self {
-            t.poll()
-        } else {
-            None
+    pub fn poll(&mut self, project: Project) -> Option<ControlValue> {
+        match self {
+            ReaperSource::Timer(t) => t.poll(),
+            ReaperSource::Modulator(m) => m.poll(project),
+            _ => None,
         }
     }
 
@@ -214,6 +435,12 @@ impl ReaperSource {
                 {
                     ControlValue::AbsoluteContinuous(UnitValue::new_clamped(c.value as f64))
                 }
+                ReaperSource::Modulator(s) if c.compartment == compartment => {
+                    // Parameter changes don't produce values directly, they just open or close
+                    // the gate. The values are produced when polling.
+                    s.process_parameter_change(c.parameter_index, c.value as f64);
+                    return None;
+                }
                 _ => return None,
             },
         };
@@ -223,7 +450,12 @@ impl ReaperSource {
     pub fn feedback(&self, feedback_value: &FeedbackValue) -> Option<ReaperSourceFeedbackValue> {
         use ReaperSource::*;
         match self {
-            MidiDeviceChanges | RealearnInstanceStart | Timer(_) | RealearnParameter(_) => None,
+            MidiDeviceChanges
+            | RealearnInstanceStart
+            | Timer(_)
+            | RealearnParameter(_)
+            | AudioSignal(_)
+            | Modulator(_) => None,
             Speech(s) => Some(ReaperSourceFeedbackValue::Speech(
                 s.feedback(feedback_value),
             )),
@@ -291,3 +523,190 @@ impl Display for MidiDeviceChangePayload {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 1000.0;
+
+    fn envelope_source(
+        kind: AudioSignalKind,
+        attack_ms: u64,
+        release_ms: u64,
+    ) -> AudioSignalSource {
+        AudioSignalSource::new(
+            kind,
+            Duration::from_millis(attack_ms),
+            Duration::from_millis(release_ms),
+            AUDIO_SIGNAL_FLOOR_DB,
+        )
+    }
+
+    fn constant(level: f64, count: usize) -> impl Iterator<Item = f64> {
+        std::iter::repeat(level).take(count)
+    }
+
+    #[test]
+    fn peak_envelope_attack_and_release() {
+        // Given
+        let mut source = envelope_source(AudioSignalKind::PeakEnvelope, 10, 100);
+        // When
+        // Alternating signs because the peak envelope follows the absolute value
+        let attack_samples = (0..10).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 });
+        let attack_result = source.process_mono(attack_samples, SAMPLE_RATE);
+        let level_after_attack = source.envelope;
+        let release_result = source.process_mono(constant(0.0, 100), SAMPLE_RATE);
+        let level_after_release = source.envelope;
+        // Then
+        // After one time constant, the envelope has covered 63% of the distance
+        let expected_level_after_attack = 1.0 - (-1.0f64).exp();
+        assert!((level_after_attack - expected_level_after_attack).abs() < 1e-9);
+        assert_eq!(
+            attack_result,
+            Some(ControlValue::AbsoluteContinuous(amplitude_to_unit_value(
+                expected_level_after_attack
+            )))
+        );
+        let expected_level_after_release = expected_level_after_attack * (-1.0f64).exp();
+        assert!((level_after_release - expected_level_after_release).abs() < 1e-9);
+        assert_eq!(
+            release_result,
+            Some(ControlValue::AbsoluteContinuous(amplitude_to_unit_value(
+                expected_level_after_release
+            )))
+        );
+    }
+
+    #[test]
+    fn peak_envelope_without_attack_and_release_follows_immediately() {
+        // Given
+        let mut source = envelope_source(AudioSignalKind::PeakEnvelope, 0, 0);
+        // When
+        source.process_mono(constant(1.0, 1), SAMPLE_RATE);
+        let level_after_attack = source.envelope;
+        source.process_mono(constant(0.25, 1), SAMPLE_RATE);
+        let level_after_release = source.envelope;
+        // Then
+        assert_eq!(level_after_attack, 1.0);
+        assert_eq!(level_after_release, 0.25);
+    }
+
+    #[test]
+    fn rms_envelope() {
+        // Given
+        let mut source = envelope_source(AudioSignalKind::RmsEnvelope, 0, 0);
+        // When
+        let first_result = source.process_mono(constant(-0.5, 10), SAMPLE_RATE);
+        let second_result = source.process_mono(constant(0.5, 10), SAMPLE_RATE);
+        // Then
+        assert_eq!(source.envelope, 0.25);
+        assert_eq!(
+            first_result,
+            Some(ControlValue::AbsoluteContinuous(amplitude_to_unit_value(
+                0.5
+            )))
+        );
+        assert_eq!(second_result, None, "unchanged value must not be reported");
+    }
+
+    #[test]
+    fn rms_envelope_attack_and_release() {
+        // Given
+        let mut source = envelope_source(AudioSignalKind::RmsEnvelope, 10, 100);
+        // When
+        source.process_mono(constant(1.0, 10), SAMPLE_RATE);
+        let level_after_attack = source.envelope;
+        source.process_mono(constant(0.0, 100), SAMPLE_RATE);
+        let level_after_release = source.envelope;
+        // Then
+        // The envelope is built from the squared signal
+        let expected_level_after_attack = 1.0 - (-1.0f64).exp();
+        assert!((level_after_attack - expected_level_after_attack).abs() < 1e-9);
+        let expected_level_after_release = expected_level_after_attack * (-1.0f64).exp();
+        assert!((level_after_release - expected_level_after_release).abs() < 1e-9);
+    }
+
+    const TRANSIENT_SAMPLE_RATE: f64 = 48000.0;
+
+    fn transient_source(threshold_db: f64) -> AudioSignalSource {
+        AudioSignalSource::new(
+            AudioSignalKind::Transient,
+            Duration::ZERO,
+            Duration::ZERO,
+            threshold_db,
+        )
+    }
+
+    fn process_transient_blocks(
+        source: &mut AudioSignalSource,
+        level: f64,
+        block_count: usize,
+    ) -> usize {
+        (0..block_count)
+            .filter(|_| {
+                source
+                    .process_mono(constant(level, 48), TRANSIENT_SAMPLE_RATE)
+                    .is_some()
+            })
+            .count()
+    }
+
+    #[test]
+    fn transient_fires_once_on_onset() {
+        // Given
+        let mut source = transient_source(-20.0);
+        process_transient_blocks(&mut source, 0.0, 100);
+        // When
+        let onset_result = source.process_mono(constant(1.0, 48), TRANSIENT_SAMPLE_RATE);
+        let sustain_fire_count = process_transient_blocks(&mut source, 1.0, 1000);
+        // Then
+        assert_eq!(
+            onset_result,
+            Some(ControlValue::AbsoluteContinuous(UnitValue::MAX))
+        );
+        assert_eq!(
+            sustain_fire_count, 0,
+            "sustained signal must not fire again"
+        );
+    }
+
+    #[test]
+    fn transient_ignores_onsets_below_threshold() {
+        // Given
+        let mut source = transient_source(-20.0);
+        // When
+        let fire_count = process_transient_blocks(&mut source, 0.05, 100);
+        // Then
+        assert_eq!(fire_count, 0);
+        assert!(source.transient_is_armed);
+    }
+
+    #[test]
+    fn transient_rearms_when_signal_settles() {
+        // Given
+        let mut source = transient_source(-20.0);
+        process_transient_blocks(&mut source, 1.0, 1);
+        assert!(!source.transient_is_armed);
+        // When
+        process_transient_blocks(&mut source, 1.0, 1000);
+        let is_armed_after_sustain = source.transient_is_armed;
+        // Then
+        assert!(is_armed_after_sustain);
+    }
+
+    #[test]
+    fn transient_rearms_when_signal_drops_below_threshold() {
+        // Given
+        let mut source = transient_source(-20.0);
+        let first_fire_count = process_transient_blocks(&mut source, 1.0, 1);
+        // When
+        process_transient_blocks(&mut source, 0.0, 10);
+        let is_armed_after_silence = source.transient_is_armed;
+        let second_fire_count = process_transient_blocks(&mut source, 1.0, 1);
+        // Then
+        assert_eq!(first_fire_count, 1);
+        assert!(is_armed_after_silence);
+        assert_eq!(second_fire_count, 1);
+    }
+}
@@ -10,8 +10,8 @@ use reaper_high::{
     GroupingBehavior, Pan, PanExt, PlayRate, Project, Reaper, Tempo, Track, TrackRoute, Width,
 };
 use reaper_medium::{
-    AutomationMode, Bpm, GangBehavior, GlobalAutomationModeOverride, NormalizedPlayRate, ParamId,
-    PlaybackSpeedFactor, PositionInSeconds, SectionContext,
+    AutomationMode, Bpm, CommandId, GangBehavior, GlobalAutomationModeOverride, NormalizedPlayRate,
+    ParamId, PlaybackSpeedFactor, PositionInSeconds, SectionContext,
 };
 use rxrust::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -26,18 +26,20 @@ use realearn_api::persistence::{SeekBehavior, TrackScope};
 use crate::domain::ui_util::convert_bool_to_unit_value;
 use crate::domain::{
     get_reaper_track_area_of_scope, handle_exclusivity, ActionTarget, AdditionalFeedbackEvent,
-    AllTrackFxEnableTarget, AutomationModeOverrideTarget, BrowseFxsTarget,
+    AllTrackFxEnableTarget, ArrangeViewTarget, AutomationModeOverrideTarget, BrowseFxsTarget,
     BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget, Caller,
-    CompartmentParameterValueTarget, ControlContext, DummyTarget, EnigoMouseTarget, FxEnableTarget,
-    FxOnlineTarget, FxOpenTarget, FxParameterTarget, FxParameterTouchStateTarget, FxPresetTarget,
-    FxToolTarget, GoToBookmarkTarget, HierarchyEntry, HierarchyEntryProvider, LoadFxSnapshotTarget,
-    LoadPotPresetTarget, MappingControlContext, MidiSendTarget, ModifyMappingTarget, OscSendTarget,
-    PlayrateTarget, PlaytimeColumnActionTarget, PlaytimeMatrixActionTarget,
-    PlaytimeRowActionTarget, PlaytimeSlotTransportTarget, PreviewPotPresetTarget,
-    RealTimeControlContext, RealTimeFxParameterTarget, RouteMuteTarget, RoutePanTarget,
+    CompartmentParameterValueTarget, ControlContext, DummyTarget, EnigoMouseTarget,
+    FxChainEditTarget, FxEnableTarget, FxOnlineTarget, FxOpenTarget, FxParameterTarget,
+    FxParameterTouchStateTarget, FxPresetTarget, FxToolTarget, GoToBookmarkTarget, HierarchyEntry,
+    HierarchyEntryProvider, LoadFxSnapshotTarget, LoadPotPresetTarget, MappingControlContext,
+    MetronomeVolumeTarget, MidiSendTarget, ModifyMappingTarget, OscSendTarget, PlayrateTarget,
+    PlaytimeColumnActionTarget, PlaytimeMatrixActionTarget, PlaytimeRowActionTarget,
+    PlaytimeSlotTransportTarget, PreviewPotPresetTarget, RealTimeControlContext,
+    RealTimeFxParameterTarget, RouteMatrixCellTarget, RouteMuteTarget, RoutePanTarget,
     RouteTouchStateTarget, RouteVolumeTarget, SeekTarget, TakeMappingSnapshotTarget, TargetTypeDef,
-    TempoTarget, TrackArmTarget, TrackAutomationModeTarget, TrackMonitoringModeTarget,
-    TrackMuteTarget, TrackPanTarget, TrackParentSendTarget, TrackPeakTarget, TrackSelectionTarget,
+    TempoTarget, TrackArmTarget, TrackAutomationModeTarget, TrackHeightTarget,
+    TrackMonitoringModeTarget, TrackMuteTarget, TrackPanTarget, TrackParentSendTarget,
+    TrackPeakTarget, TrackRecordInputTarget, TrackRecordModeTarget, TrackSelectionTarget,
     TrackShowTarget, TrackSoloTarget, TrackTouchStateTarget, TrackVolumeTarget, TrackWidthTarget,
     TransportTarget, UnitEvent,
 };
@@ -106,6 +108,7 @@ pub enum ReaperTarget {
     TrackRouteVolume(RouteVolumeTarget),
     TrackPan(TrackPanTarget),
     TrackWidth(TrackWidthTarget),
+    TrackHeight(TrackHeightTarget),
     TrackArm(TrackArmTarget),
     TrackParentSend(TrackParentSendTarget),
     TrackSelection(TrackSelectionTarget),
@@ -115,13 +118,18 @@ pub enum ReaperTarget {
     TrackSolo(TrackSoloTarget),
     TrackAutomationMode(TrackAutomationModeTarget),
     TrackMonitoringMode(TrackMonitoringModeTarget),
+    TrackRecordInput(TrackRecordInputTarget),
+    TrackRecordMode(TrackRecordModeTarget),
     RoutePan(RoutePanTarget),
     RouteMute(RouteMuteTarget),
     RoutePhase(RoutePhaseTarget),
     RouteMono(RouteMonoTarget),
     RouteAutomationMode(RouteAutomationModeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    RouteMatrixCell(RouteMatrixCellTarget),
     Tempo(TempoTarget),
+    ArrangeView(ArrangeViewTarget),
+    MetronomeVolume(MetronomeVolumeTarget),
     Playrate(PlayrateTarget),
     AutomationModeOverride(AutomationModeOverrideTarget),
     FxEnable(FxEnableTarget),
@@ -130,6 +138,7 @@ pub enum ReaperTarget {
     FxPreset(FxPresetTarget),
     BrowseTracks(BrowseTracksTarget),
     BrowseFxs(BrowseFxsTarget),
+    FxChainEdit(FxChainEditTarget),
     AllTrackFxEnable(AllTrackFxEnableTarget),
     Transport(TransportTarget),
     AnyOn(AnyOnTarget),
@@ -631,6 +640,7 @@ impl<'a> Target<'a> for ReaperTarget {
             TrackTool(t) => t.current_value(context),
             TrackPan(t) => t.current_value(context),
             TrackWidth(t) => t.current_value(context),
+            TrackHeight(t) => t.current_value(context),
             TrackArm(t) => t.current_value(context),
             TrackParentSend(t) => t.current_value(context),
             TrackRouteVolume(t) => t.current_value(context),
@@ -641,13 +651,18 @@ impl<'a> Target<'a> for ReaperTarget {
             TrackSolo(t) => t.current_value(context),
             TrackAutomationMode(t) => t.current_value(context),
             TrackMonitoringMode(t) => t.current_value(context),
+            TrackRecordInput(t) => t.current_value(context),
+            TrackRecordMode(t) => t.current_value(context),
             RoutePan(t) => t.current_value(context),
             RouteMute(t) => t.current_value(context),
             RoutePhase(t) => t.current_value(context),
             RouteMono(t) => t.current_value(context),
             RouteAutomationMode(t) => t.current_value(context),
             RouteTouchState(t) => t.current_value(context),
+            RouteMatrixCell(t) => t.current_value(context),
             Tempo(t) => t.current_value(context),
+            ArrangeView(t) => t.current_value(context),
+            MetronomeVolume(t) => t.current_value(context),
             Playrate(t) => t.current_value(context),
             AutomationModeOverride(t) => t.current_value(context),
             FxTool(t) => t.current_value(context),
@@ -661,6 +676,7 @@ impl<'a> Target<'a> for ReaperTarget {
             BrowseTracks(t) => t.current_value(context),
             // Discrete
             BrowseFxs(t) => t.current_value(context),
+            FxChainEdit(t) => t.current_value(context),
             AllTrackFxEnable(t) => t.current_value(context),
             Transport(t) => t.current_value(context),
             AnyOn(t) => t.current_value(context),
@@ -1014,6 +1030,39 @@ pub enum TransportAction {
     #[serde(rename = "repeat")]
     #[display(fmt = "Repeat")]
     Repeat,
+    #[serde(rename = "metronome")]
+    #[display(fmt = "Metronome")]
+    Metronome,
+    #[serde(rename = "countIn")]
+    #[display(fmt = "Count-in before recording")]
+    CountIn,
+    #[serde(rename = "preRoll")]
+    #[display(fmt = "Pre-roll before recording")]
+    PreRoll,
+    #[serde(rename = "autoCrossfade")]
+    #[display(fmt = "Auto-crossfade")]
+    AutoCrossfade,
+    #[serde(rename = "snap")]
+    #[display(fmt = "Snap")]
+    Snap,
+    #[serde(rename = "recordModeNormal")]
+    #[display(fmt = "Record mode: Normal")]
+    RecordModeNormal,
+    #[serde(rename = "recordModeTimeSelectionAutoPunch")]
+    #[display(fmt = "Record mode: Time selection auto-punch")]
+    RecordModeTimeSelectionAutoPunch,
+    #[serde(rename = "recordModeItemAutoPunch")]
+    #[display(fmt = "Record mode: Item auto-punch")]
+    RecordModeItemAutoPunch,
+    #[serde(rename = "rippleOff")]
+    #[display(fmt = "Ripple editing: Off")]
+    RippleOff,
+    #[serde(rename = "ripplePerTrack")]
+    #[display(fmt = "Ripple editing: Per track")]
+    RipplePerTrack,
+    #[serde(rename = "rippleAllTracks")]
+    #[display(fmt = "Ripple editing: All tracks")]
+    RippleAllTracks,
 }
 
 impl TransportAction {
@@ -1025,11 +1074,53 @@ impl TransportAction {
                 ControlType::AbsoluteContinuousRetriggerable,
                 TargetCharacter::Switch,
             ),
-            Stop | Pause | RecordStop | Repeat => {
-                (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
-            }
+            _ => (ControlType::AbsoluteContinuous, TargetCharacter::Switch),
         }
     }
+
+    /// Returns whether this action is about a setting that REAPER doesn't notify us about when
+    /// it changes. Feedback for these must be polled.
+    pub fn needs_polling(&self) -> bool {
+        use TransportAction::*;
+        !matches!(
+            self,
+            PlayStop | PlayPause | Stop | Pause | RecordStop | Repeat
+        )
+    }
+
+    /// Returns the REAPER action whose toggle state reflects the setting behind this action.
+    pub fn reaper_command_id(&self) -> Option<CommandId> {
+        use TransportAction::*;
+        let id = match self {
+            Metronome => 40364,
+            PreRoll => 41819,
+            AutoCrossfade => 40041,
+            Snap => 1157,
+            RecordModeNormal => 40252,
+            RecordModeTimeSelectionAutoPunch => 40076,
+            RecordModeItemAutoPunch => 40253,
+            RippleOff => 40309,
+            RipplePerTrack => 40310,
+            RippleAllTracks => 40311,
+            PlayStop | PlayPause | Stop | Pause | RecordStop | Repeat | CountIn => return None,
+        };
+        Some(CommandId::new(id))
+    }
+
+    /// Returns whether this action selects one of several mutually exclusive modes (as opposed
+    /// to switching a setting on and off).
+    pub fn is_mode_selector(&self) -> bool {
+        use TransportAction::*;
+        matches!(
+            self,
+            RecordModeNormal
+                | RecordModeTimeSelectionAutoPunch
+                | RecordModeItemAutoPunch
+                | RippleOff
+                | RipplePerTrack
+                | RippleAllTracks
+        )
+    }
 }
 
 fn determine_target_for_action(action: Action) -> ReaperTarget {
@@ -1264,6 +1355,22 @@ fn query_track_sel_on_mouse_is_enabled() -> bool {
     }
 }
 
+/// Returns a pointer to the project-specific config variable with the given name (which must be
+/// nul-terminated), provided that it exists and has the size of `T`.
+pub(crate) fn project_config_var_ptr<T>(project: Project, name: &[u8]) -> Option<*mut T> {
+    let low = Reaper::get().medium_reaper().low();
+    let mut size = 0;
+    let offset = unsafe { low.projectconfig_var_getoffs(name.as_ptr() as _, &mut size) };
+    if offset == 0 || size as usize != std::mem::size_of::<T>() {
+        return None;
+    }
+    let ptr = unsafe { low.projectconfig_var_addr(project.raw().as_ptr(), offset) };
+    if ptr.is_null() {
+        return None;
+    }
+    Some(ptr as *mut T)
+}
+
 #[derive(
     Clone,
     Copy,
@@ -0,0 +1,77 @@
+use helgoboss_midi::Channel;
+use reaper_high::{MidiInputDevice, Reaper};
+use reaper_medium::{MidiInputDeviceId, ReaperString, RecordingInput};
+
+/// Returns the MIDI input devices which can currently be used.
+pub fn available_midi_input_devices() -> impl Iterator<Item = MidiInputDevice> {
+    Reaper::get()
+        .midi_input_devices()
+        .filter(|dev| dev.is_available())
+}
+
+/// Returns the names of the audio input channels, ordered by channel index.
+pub fn audio_input_channel_names() -> impl Iterator<Item = ReaperString> {
+    Reaper::get().input_channels()
+}
+
+/// Returns all recording inputs that can be browsed, in the order in which they are browsed.
+///
+/// Audio inputs come first (mono, then stereo pairs), followed by MIDI inputs. For MIDI, each
+/// device (including "all devices") is offered with all channels and with each single channel.
+pub fn available_recording_inputs() -> Vec<RecordingInput> {
+    let audio_channel_count = audio_input_channel_names().count() as u32;
+    let mono_inputs = (0..audio_channel_count).map(RecordingInput::Mono);
+    let stereo_inputs = (0..audio_channel_count.saturating_sub(1)).map(RecordingInput::Stereo);
+    let midi_devices =
+        std::iter::once(None).chain(available_midi_input_devices().map(|dev| Some(dev.id())));
+    let midi_inputs = midi_devices.flat_map(|device_id| {
+        std::iter::once(None)
+            .chain((0..16).map(|ch| Some(Channel::new(ch))))
+            .map(move |channel| RecordingInput::Midi { device_id, channel })
+    });
+    mono_inputs
+        .chain(stereo_inputs)
+        .chain(midi_inputs)
+        .collect()
+}
+
+/// Formats the given recording input for display, e.g. "Input 1 / Input 2".
+pub fn format_recording_input(input: RecordingInput) -> String {
+    match input {
+        RecordingInput::Mono(ch) => format_audio_input_channel(ch),
+        RecordingInput::Stereo(ch) => {
+            format!(
+                "{} / {}",
+                format_audio_input_channel(ch),
+                format_audio_input_channel(ch + 1)
+            )
+        }
+        RecordingInput::Midi { device_id, channel } => {
+            let device_label = match device_id {
+                None => "All devices".to_string(),
+                Some(id) => format_midi_input_device(id),
+            };
+            let channel_label = match channel {
+                None => "all channels".to_string(),
+                Some(ch) => format!("channel {}", ch.get() + 1),
+            };
+            format!("MIDI: {device_label}, {channel_label}")
+        }
+        _ => "<Other>".to_string(),
+    }
+}
+
+fn format_audio_input_channel(index: u32) -> String {
+    audio_input_channel_names()
+        .nth(index as usize)
+        .map(|name| name.into_string())
+        .unwrap_or_else(|| format!("Input {}", index + 1))
+}
+
+fn format_midi_input_device(id: MidiInputDeviceId) -> String {
+    Reaper::get()
+        .midi_input_device_by_id(id)
+        .name()
+        .map(|name| name.into_inner().to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("Device {}", id.get()))
+}
@@ -0,0 +1,184 @@
+use realearn_api::persistence;
+
+/// A response curve defined by breakpoints.
+///
+/// Maps normalized input values (0.0 to 1.0) to normalized output values. Between two
+/// breakpoints, the curve follows a segment whose shape is defined by the curvature of the
+/// breakpoint at the start of the segment.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResponseCurve {
+    /// Sorted by x. Contains at least two points.
+    points: Vec<ResponseCurvePoint>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ResponseCurvePoint {
+    pub x: f64,
+    pub y: f64,
+    /// Shape of the segment starting at this point, between -1.0 and 1.0.
+    ///
+    /// 0.0 is a straight line. Positive values make the segment start slowly and end fast,
+    /// negative values do the opposite.
+    pub curvature: f64,
+}
+
+impl Default for ResponseCurve {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl ResponseCurve {
+    /// Creates a curve from the given points. Invalid values are clamped.
+    ///
+    /// If less than two points are given, the result is a straight line from (0, 0) to (1, 1).
+    pub fn new(points: impl IntoIterator<Item = ResponseCurvePoint>) -> Self {
+        let mut points: Vec<_> = points
+            .into_iter()
+            .map(|p| ResponseCurvePoint {
+                x: p.x.clamp(0.0, 1.0),
+                y: p.y.clamp(0.0, 1.0),
+                curvature: p.curvature.clamp(-1.0, 1.0),
+            })
+            .collect();
+        if points.len() < 2 {
+            points = vec![
+                ResponseCurvePoint {
+                    x: 0.0,
+                    y: 0.0,
+                    curvature: 0.0,
+                },
+                ResponseCurvePoint {
+                    x: 1.0,
+                    y: 1.0,
+                    curvature: 0.0,
+                },
+            ];
+        }
+        points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[ResponseCurvePoint] {
+        &self.points
+    }
+
+    /// Returns the output value for the given input value.
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if x <= first.x {
+            return first.y;
+        }
+        if x >= last.x {
+            return last.y;
+        }
+        for segment in self.points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            if x > end.x {
+                continue;
+            }
+            let width = end.x - start.x;
+            if width <= 0.0 {
+                return end.y;
+            }
+            let t = (x - start.x) / width;
+            let shaped_t = t.powf(exponent(start.curvature));
+            return start.y + (end.y - start.y) * shaped_t;
+        }
+        last.y
+    }
+
+    /// Returns the input value which leads to the given output value.
+    ///
+    /// This is used for feedback, so that e.g. motor faders land at the correct position. If the
+    /// curve is not monotonic, the first matching input value wins. If no input value leads to
+    /// the given output value, the input value of the point with the closest output value is
+    /// returned.
+    pub fn evaluate_inverse(&self, y: f64) -> f64 {
+        for segment in self.points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let (min, max) = if start.y <= end.y {
+                (start.y, end.y)
+            } else {
+                (end.y, start.y)
+            };
+            if y < min || y > max {
+                continue;
+            }
+            let height = end.y - start.y;
+            if height == 0.0 {
+                return start.x;
+            }
+            let shaped_t = (y - start.y) / height;
+            let t = shaped_t.powf(1.0 / exponent(start.curvature));
+            return start.x + (end.x - start.x) * t;
+        }
+        self.points
+            .iter()
+            .min_by(|a, b| (a.y - y).abs().total_cmp(&(b.y - y).abs()))
+            .map(|p| p.x)
+            .unwrap_or(y)
+    }
+}
+
+impl From<&persistence::ResponseCurve> for ResponseCurve {
+    fn from(curve: &persistence::ResponseCurve) -> Self {
+        Self::new(curve.points.iter().map(|p| ResponseCurvePoint {
+            x: p.x,
+            y: p.y,
+            curvature: p.curvature.unwrap_or(0.0),
+        }))
+    }
+}
+
+fn exponent(curvature: f64) -> f64 {
+    4.0f64.powf(curvature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64, curvature: f64) -> ResponseCurvePoint {
+        ResponseCurvePoint { x, y, curvature }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.000001,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn straight_segments() {
+        // Given
+        let curve = ResponseCurve::new([
+            point(1.0, 1.0, 0.0),
+            point(0.0, 0.0, 0.0),
+            point(0.5, 0.8, 0.0),
+        ]);
+        // Then
+        assert_close(curve.evaluate(0.0), 0.0);
+        assert_close(curve.evaluate(0.25), 0.4);
+        assert_close(curve.evaluate(0.5), 0.8);
+        assert_close(curve.evaluate(0.75), 0.9);
+        assert_close(curve.evaluate(1.0), 1.0);
+    }
+
+    #[test]
+    fn inverse() {
+        // Given
+        let curve = ResponseCurve::new([
+            point(0.0, 0.0, 0.7),
+            point(0.6, 0.3, -0.4),
+            point(1.0, 1.0, 0.0),
+        ]);
+        // Then
+        for i in 0..=10 {
+            let x = i as f64 / 10.0;
+            assert_close(curve.evaluate_inverse(curve.evaluate(x)), x);
+        }
+    }
+}
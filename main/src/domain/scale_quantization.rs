@@ -0,0 +1,219 @@
+use crate::domain::{CompartmentParamIndex, CompartmentParams, EffectiveParamValue};
+use helgoboss_learn::{ControlType, ControlValue, Fraction, UnitValue};
+use realearn_api::persistence::Scale;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+
+/// The pitch classes which are part of a scale, relative to its root.
+///
+/// Bit 0 represents the root itself, bit 11 the major seventh.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ScaleMask(u16);
+
+impl ScaleMask {
+    pub fn from_scale(scale: Scale) -> Self {
+        use Scale::*;
+        let intervals: &[u16] = match scale {
+            Major => &[0, 2, 4, 5, 7, 9, 11],
+            NaturalMinor => &[0, 2, 3, 5, 7, 8, 10],
+            HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            MajorPentatonic => &[0, 2, 4, 7, 9],
+            MinorPentatonic => &[0, 3, 5, 7, 10],
+            Blues => &[0, 3, 5, 6, 7, 10],
+            Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        };
+        Self(intervals.iter().fold(0, |mask, i| mask | (1 << i)))
+    }
+
+    /// Parses a mask such as "101011010101" (major scale), starting at the root.
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let text = text.trim();
+        if text.len() != 12 {
+            return Err("scale mask must consist of exactly 12 digits");
+        }
+        let mut mask = 0;
+        for (i, c) in text.chars().enumerate() {
+            match c {
+                '1' => mask |= 1 << i,
+                '0' => {}
+                _ => return Err("scale mask must only consist of 0 and 1"),
+            }
+        }
+        if mask & 1 == 0 {
+            return Err("scale mask must contain the root");
+        }
+        Ok(Self(mask))
+    }
+
+    fn contains(&self, semitones_above_root: u32) -> bool {
+        self.0 & (1 << semitones_above_root) != 0
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ScaleQuantizationSettings {
+    /// Pitch class of the root note, from 0 (C) to 11 (B).
+    pub root: u8,
+    pub mask: ScaleMask,
+    /// If set, the root is taken from this compartment parameter instead.
+    pub root_param: Option<CompartmentParamIndex>,
+    /// If set, the scale is taken from this compartment parameter instead (its value selects
+    /// one of the predefined scales).
+    pub scale_param: Option<CompartmentParamIndex>,
+}
+
+/// Snaps discrete target values to the notes of a musical scale.
+///
+/// Discrete values are interpreted as semitones, so this is most useful for note numbers (e.g.
+/// "MIDI: Send message" with a 7-bit value) and targets with 12 values per octave. Continuous
+/// values are only quantized if the target is discrete.
+///
+/// Root and scale can come from compartment parameters. Their resolved values are shared with
+/// the real-time clone of the mapping, so parameter changes apply without resyncing mappings.
+#[derive(Clone, Debug)]
+pub struct ScaleQuantization {
+    settings: ScaleQuantizationSettings,
+    /// Resolved root (upper bits) and mask (lower 12 bits).
+    resolved: Arc<AtomicU32>,
+}
+
+impl ScaleQuantization {
+    pub fn new(settings: ScaleQuantizationSettings) -> Self {
+        Self {
+            settings,
+            resolved: Arc::new(AtomicU32::new(encode(settings.root, settings.mask))),
+        }
+    }
+
+    pub fn can_be_affected_by_parameters(&self) -> bool {
+        self.settings.root_param.is_some() || self.settings.scale_param.is_some()
+    }
+
+    /// Resolves root and scale from the given parameters (if configured to do so).
+    pub fn update_from_params(&self, params: &CompartmentParams) {
+        if !self.can_be_affected_by_parameters() {
+            return;
+        }
+        let root = match self.settings.root_param {
+            None => self.settings.root,
+            Some(i) => (discrete_param_value(params, i, 12) % 12) as u8,
+        };
+        let mask = match self.settings.scale_param {
+            None => self.settings.mask,
+            Some(i) => {
+                let scale_count = Scale::iter().count() as u32;
+                let scale_index = discrete_param_value(params, i, scale_count) as usize;
+                Scale::try_from(scale_index)
+                    .map(ScaleMask::from_scale)
+                    .unwrap_or(self.settings.mask)
+            }
+        };
+        self.resolved.store(encode(root, mask), Ordering::Relaxed);
+    }
+
+    /// Returns the in-scale value closest to the given one. Ties are resolved downwards.
+    pub fn quantize(&self, value: ControlValue, target_control_type: ControlType) -> ControlValue {
+        let (root, mask) = decode(self.resolved.load(Ordering::Relaxed));
+        match value {
+            ControlValue::AbsoluteDiscrete(f) => {
+                let quantized = quantize(f.actual(), f.max_val(), root, mask);
+                ControlValue::AbsoluteDiscrete(Fraction::new(quantized, f.max_val()))
+            }
+            ControlValue::AbsoluteContinuous(v) => {
+                let ControlType::AbsoluteDiscrete {
+                    atomic_step_size, ..
+                } = target_control_type
+                else {
+                    return value;
+                };
+                let max_val = (1.0 / atomic_step_size.get()).round() as u32;
+                if max_val == 0 {
+                    return value;
+                }
+                let actual = (v.get() * max_val as f64).round() as u32;
+                let quantized = quantize(actual, max_val, root, mask);
+                ControlValue::AbsoluteContinuous(UnitValue::new_clamped(
+                    quantized as f64 / max_val as f64,
+                ))
+            }
+            _ => value,
+        }
+    }
+}
+
+fn quantize(value: u32, max_val: u32, root: u8, mask: ScaleMask) -> u32 {
+    let is_in_scale = |v: u32| mask.contains((v + 12 - root as u32) % 12);
+    (0..12)
+        .flat_map(|distance| [value.checked_sub(distance), value.checked_add(distance)])
+        .flatten()
+        .find(|v| *v <= max_val && is_in_scale(*v))
+        .unwrap_or(value)
+}
+
+fn discrete_param_value(
+    params: &CompartmentParams,
+    index: CompartmentParamIndex,
+    value_count: u32,
+) -> u32 {
+    match params.at(index).effective_value() {
+        EffectiveParamValue::Discrete(v) => v,
+        EffectiveParamValue::Continuous(v) => (v * (value_count - 1) as f64).round() as u32,
+    }
+}
+
+fn encode(root: u8, mask: ScaleMask) -> u32 {
+    ((root as u32 % 12) << 12) | mask.0 as u32
+}
+
+fn decode(value: u32) -> (u8, ScaleMask) {
+    ((value >> 12) as u8, ScaleMask(value as u16 & 0xfff))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(value: u32) -> ControlValue {
+        ControlValue::AbsoluteDiscrete(Fraction::new(value, 127))
+    }
+
+    #[test]
+    fn snap_to_fixed_scale() {
+        // Given
+        let quantization = ScaleQuantization::new(ScaleQuantizationSettings {
+            // D
+            root: 2,
+            mask: ScaleMask::from_scale(Scale::MinorPentatonic),
+            root_param: None,
+            scale_param: None,
+        });
+        let control_type = ControlType::AbsoluteContinuous;
+        // When
+        let quantize = |v| quantization.quantize(note(v), control_type);
+        // Then
+        // D, F, G, A, C
+        assert_eq!(quantize(62), note(62));
+        assert_eq!(quantize(63), note(62));
+        assert_eq!(quantize(64), note(65));
+        assert_eq!(quantize(66), note(65));
+        assert_eq!(quantize(71), note(72));
+        assert_eq!(quantize(126), note(125));
+    }
+
+    #[test]
+    fn parse_mask() {
+        assert_eq!(
+            ScaleMask::parse("101011010101"),
+            Ok(ScaleMask::from_scale(Scale::Major))
+        );
+        assert!(ScaleMask::parse("011011010101").is_err());
+        assert!(ScaleMask::parse("1010").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::domain::{
+    CompartmentKind, ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use realearn_api::persistence::ArrangeViewProperty;
+use reaper_high::{Project, Reaper};
+use reaper_low::{raw, Swell};
+use reaper_medium::PositionInSeconds;
+use std::borrow::Cow;
+use swell_ui::Window;
+
+#[derive(Debug)]
+pub struct UnresolvedArrangeViewTarget {
+    pub property: ArrangeViewProperty,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedArrangeViewTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let project = context.context().project_or_current_project();
+        Ok(vec![ReaperTarget::ArrangeView(ArrangeViewTarget {
+            project,
+            property: self.property,
+        })])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about zoom or scroll changes
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArrangeViewTarget {
+    pub project: Project,
+    pub property: ArrangeViewProperty,
+}
+
+impl RealearnTarget for ArrangeViewTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let value = value.to_unit_value()?;
+        match self.property {
+            ArrangeViewProperty::HorizontalZoom => {
+                let level = convert_unit_value_to_zoom_level(value);
+                Reaper::get()
+                    .medium_reaper()
+                    .low()
+                    .adjustZoom(level, 1, true, -1);
+            }
+            ArrangeViewProperty::HorizontalScroll => {
+                let view = self.horizontal_view();
+                let visible_length = view.end - view.start;
+                let start = value.get() * self.max_horizontal_scroll_pos(visible_length);
+                Reaper::get().medium_reaper().get_set_arrange_view_2_set(
+                    self.project.context(),
+                    0,
+                    0,
+                    PositionInSeconds::new_panic(start),
+                    PositionInSeconds::new_panic(start + visible_length),
+                );
+            }
+            ArrangeViewProperty::VerticalScroll => {
+                let window = arrange_window().ok_or("arrange view not found")?;
+                let info = vertical_scroll_info(window);
+                let pos = (value.get() * max_vertical_scroll_pos(&info) as f64).round() as i32;
+                unsafe {
+                    Reaper::get().medium_reaper().low().CoolSB_SetScrollPos(
+                        window.raw() as _,
+                        raw::SB_VERT as _,
+                        pos,
+                        1,
+                    );
+                    Swell::get().SendMessage(
+                        window.raw(),
+                        raw::WM_VSCROLL,
+                        ((pos as usize) << 16) | raw::SB_THUMBPOSITION as usize,
+                        0,
+                    );
+                }
+            }
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        let text = match self.property {
+            ArrangeViewProperty::HorizontalZoom => format!("{:.2} px/s", current_zoom_level()),
+            ArrangeViewProperty::HorizontalScroll => {
+                format!("{:.3} s", self.horizontal_view().start)
+            }
+            ArrangeViewProperty::VerticalScroll => {
+                let value = self.current_value(context)?.to_unit_value();
+                format!("{:.1} %", value.get() * 100.0)
+            }
+        };
+        Some(text.into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let value = match self.property {
+            ArrangeViewProperty::HorizontalZoom => current_zoom_level(),
+            ArrangeViewProperty::HorizontalScroll => self.horizontal_view().start,
+            ArrangeViewProperty::VerticalScroll => {
+                vertical_scroll_info(arrange_window()?).nPos as f64
+            }
+        };
+        Some(NumericValue::Decimal(value))
+    }
+
+    fn numeric_value_unit(&self, _: ControlContext) -> &'static str {
+        match self.property {
+            ArrangeViewProperty::HorizontalZoom => "px/s",
+            ArrangeViewProperty::HorizontalScroll => "s",
+            ArrangeViewProperty::VerticalScroll => "px",
+        }
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::ArrangeView)
+    }
+}
+
+impl ArrangeViewTarget {
+    fn horizontal_view(&self) -> HorizontalView {
+        let result =
+            Reaper::get()
+                .medium_reaper()
+                .get_set_arrange_view_2_get(self.project.context(), 0, 0);
+        HorizontalView {
+            start: result.start_time.get(),
+            end: result.end_time.get(),
+        }
+    }
+
+    /// The project length determines how far we can scroll. If the project is empty, we allow
+    /// scrolling by one screen width.
+    fn max_horizontal_scroll_pos(&self, visible_length: f64) -> f64 {
+        let project_length = self.project.length().get();
+        (project_length - visible_length).max(visible_length)
+    }
+}
+
+impl<'a> Target<'a> for ArrangeViewTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let value = match self.property {
+            ArrangeViewProperty::HorizontalZoom => {
+                convert_zoom_level_to_unit_value(current_zoom_level())
+            }
+            ArrangeViewProperty::HorizontalScroll => {
+                let view = self.horizontal_view();
+                let max_pos = self.max_horizontal_scroll_pos(view.end - view.start);
+                UnitValue::new_clamped(view.start / max_pos)
+            }
+            ArrangeViewProperty::VerticalScroll => {
+                let info = vertical_scroll_info(arrange_window()?);
+                let max_pos = max_vertical_scroll_pos(&info);
+                if max_pos == 0 {
+                    UnitValue::MIN
+                } else {
+                    UnitValue::new_clamped(info.nPos as f64 / max_pos as f64)
+                }
+            }
+        };
+        Some(AbsoluteValue::Continuous(value))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ARRANGE_VIEW_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Project,
+    name: "Navigate arrange view",
+    short_name: "Arrange view",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    ..DEFAULT_TARGET
+};
+
+struct HorizontalView {
+    start: f64,
+    end: f64,
+}
+
+/// Zoom levels in pixels per second. Roughly the range that REAPER allows.
+const MIN_ZOOM_LEVEL: f64 = 0.001;
+const MAX_ZOOM_LEVEL: f64 = 100_000.0;
+
+fn current_zoom_level() -> f64 {
+    Reaper::get().medium_reaper().low().GetHZoomLevel()
+}
+
+/// Zoom levels are mapped logarithmically, otherwise the low zoom levels would be squeezed into a
+/// tiny part of the fader.
+fn convert_zoom_level_to_unit_value(level: f64) -> UnitValue {
+    let level = level.clamp(MIN_ZOOM_LEVEL, MAX_ZOOM_LEVEL);
+    UnitValue::new_clamped((level / MIN_ZOOM_LEVEL).ln() / (MAX_ZOOM_LEVEL / MIN_ZOOM_LEVEL).ln())
+}
+
+fn convert_unit_value_to_zoom_level(value: UnitValue) -> f64 {
+    MIN_ZOOM_LEVEL * (MAX_ZOOM_LEVEL / MIN_ZOOM_LEVEL).powf(value.get())
+}
+
+/// The track view in REAPER's main window.
+fn arrange_window() -> Option<Window> {
+    const ARRANGE_VIEW_CONTROL_ID: u32 = 1000;
+    Window::from_hwnd(Reaper::get().main_window()).find_control(ARRANGE_VIEW_CONTROL_ID)
+}
+
+fn vertical_scroll_info(window: Window) -> raw::SCROLLINFO {
+    let mut si = raw::SCROLLINFO {
+        cbSize: std::mem::size_of::<raw::SCROLLINFO>() as _,
+        fMask: raw::SIF_PAGE | raw::SIF_POS | raw::SIF_RANGE,
+        nMin: 0,
+        nMax: 0,
+        nPage: 0,
+        nPos: 0,
+        nTrackPos: 0,
+    };
+    unsafe {
+        Reaper::get().medium_reaper().low().CoolSB_GetScrollInfo(
+            window.raw() as _,
+            raw::SB_VERT as _,
+            &mut si as *mut raw::SCROLLINFO as _,
+        );
+    }
+    si
+}
+
+fn max_vertical_scroll_pos(info: &raw::SCROLLINFO) -> i32 {
+    (info.nMax - (info.nPage as i32 - 1)).max(0)
+}
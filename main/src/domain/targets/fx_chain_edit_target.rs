@@ -0,0 +1,243 @@
+use crate::domain::{
+    get_fx_chains, get_fx_name, CompartmentKind, ControlContext, ExtendedProcessorContext,
+    FxDescriptor, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, UnresolvedReaperTargetDef,
+    VirtualFx, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target};
+use pot::{Destination, PluginId};
+use realearn_api::persistence::FxChainEditAction;
+use reaper_high::{Fx, FxChain, Project, Reaper, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedFxChainEditTarget {
+    pub fx_descriptor: FxDescriptor,
+    pub action: FxChainEditAction,
+    pub plugin_id: Option<PluginId>,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedFxChainEditTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let fxs = self
+            .fx_descriptor
+            .resolve(context, compartment)
+            .and_then(|fxs| {
+                if fxs.is_empty() {
+                    Err("resolved to zero FXs")
+                } else {
+                    Ok(fxs)
+                }
+            });
+        let targets = match fxs {
+            Ok(fxs) => fxs
+                .into_iter()
+                .map(|fx| {
+                    ReaperTarget::FxChainEdit(FxChainEditTarget {
+                        fx_chain: fx.chain().clone(),
+                        fx: Some(fx),
+                        action: self.action,
+                        plugin_id: self.plugin_id,
+                    })
+                })
+                .collect(),
+            Err(e) => {
+                // Adding an FX doesn't need an existing FX, the FX chain is enough. That way we
+                // can also add FX to empty chains.
+                if self.action != FxChainEditAction::Add {
+                    return Err(e);
+                }
+                let VirtualFx::ChainFx { is_input_fx, .. } = &self.fx_descriptor.fx else {
+                    return Err(e);
+                };
+                get_fx_chains(
+                    context,
+                    &self.fx_descriptor.track_descriptor.track,
+                    *is_input_fx,
+                    compartment,
+                )?
+                .into_iter()
+                .map(|fx_chain| {
+                    ReaperTarget::FxChainEdit(FxChainEditTarget {
+                        fx_chain,
+                        fx: None,
+                        action: self.action,
+                        plugin_id: self.plugin_id,
+                    })
+                })
+                .collect()
+            }
+        };
+        Ok(targets)
+    }
+
+    fn fx_descriptor(&self) -> Option<&FxDescriptor> {
+        Some(&self.fx_descriptor)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FxChainEditTarget {
+    pub fx_chain: FxChain,
+    /// Only `None` if the action doesn't need an existing FX.
+    pub fx: Option<Fx>,
+    pub action: FxChainEditAction,
+    pub plugin_id: Option<PluginId>,
+}
+
+impl RealearnTarget for FxChainEditTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        match &self.fx {
+            None => self.fx_chain.is_available(),
+            Some(fx) => fx.is_available(),
+        }
+    }
+
+    fn project(&self) -> Option<Project> {
+        self.fx_chain.project()
+    }
+
+    fn track(&self) -> Option<&Track> {
+        self.fx_chain.track()
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        self.fx.as_ref()
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(get_fx_name(self.fx.as_ref()?).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::FxChainEdit)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if !value.is_on() {
+            return Ok(HitResponse::ignored());
+        }
+        let protected_fx = context.control_context.processor_context.containing_fx();
+        let project = self
+            .fx_chain
+            .project()
+            .unwrap_or_else(|| Reaper::get().current_project());
+        let label = format!("ReaLearn: {}", self.action);
+        project.undoable(label.as_str(), || self.edit_fx_chain(protected_fx))?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        false
+    }
+}
+
+impl FxChainEditTarget {
+    fn edit_fx_chain(&self, protected_fx: &Fx) -> Result<(), &'static str> {
+        match self.action {
+            FxChainEditAction::Add => {
+                let destination = Destination {
+                    chain: self.fx_chain.clone(),
+                    fx_index: self.fx_chain.fx_count(),
+                };
+                pot::insert_fx_by_plugin_id(self.required_plugin_id()?, &destination)
+                    .map_err(|_| "couldn't add FX")?;
+            }
+            FxChainEditAction::InsertAfter => {
+                let destination = Destination {
+                    chain: self.fx_chain.clone(),
+                    fx_index: self.required_fx()?.index() + 1,
+                };
+                pot::insert_fx_by_plugin_id(self.required_plugin_id()?, &destination)
+                    .map_err(|_| "couldn't insert FX")?;
+            }
+            FxChainEditAction::Remove => {
+                let fx = self.required_fx()?;
+                if fx == protected_fx {
+                    return Err("can't remove ReaLearn itself");
+                }
+                self.fx_chain
+                    .remove_fx(fx)
+                    .map_err(|_| "couldn't remove FX")?;
+            }
+            FxChainEditAction::MoveUp => {
+                let fx = self.required_fx()?;
+                let new_index = fx.index().checked_sub(1).ok_or("FX is already first")?;
+                self.fx_chain
+                    .move_fx(fx, new_index)
+                    .map_err(|_| "couldn't move FX")?;
+            }
+            FxChainEditAction::MoveDown => {
+                let fx = self.required_fx()?;
+                let new_index = fx.index() + 1;
+                if new_index >= self.fx_chain.fx_count() {
+                    return Err("FX is already last");
+                }
+                self.fx_chain
+                    .move_fx(fx, new_index)
+                    .map_err(|_| "couldn't move FX")?;
+            }
+            FxChainEditAction::Replace => {
+                let fx = self.required_fx()?;
+                let destination = Destination {
+                    chain: self.fx_chain.clone(),
+                    fx_index: fx.index(),
+                };
+                pot::ensure_fx_has_correct_type(
+                    self.required_plugin_id()?,
+                    &destination,
+                    Some(fx.clone()),
+                    protected_fx,
+                )
+                .map_err(|_| "couldn't replace FX")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn required_fx(&self) -> Result<&Fx, &'static str> {
+        self.fx.as_ref().ok_or("FX could not be resolved")
+    }
+
+    fn required_plugin_id(&self) -> Result<PluginId, &'static str> {
+        self.plugin_id.ok_or("no valid plug-in ID given")
+    }
+}
+
+impl<'a> Target<'a> for FxChainEditTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        None
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const FX_CHAIN_EDIT_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::FxChain,
+    name: "Edit FX chain",
+    short_name: "Edit FX chain",
+    hint: "Adds undo points",
+    supports_track: true,
+    supports_fx: true,
+    supports_feedback: false,
+    ..DEFAULT_TARGET
+};
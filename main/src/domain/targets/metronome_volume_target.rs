@@ -0,0 +1,143 @@
+use crate::domain::{
+    format_value_as_db, format_value_as_db_without_unit, parse_value_from_db,
+    project_config_var_ptr, volume_unit_value, CompartmentKind, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Project, SliderVolume};
+use reaper_medium::ReaperVolumeValue;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedMetronomeVolumeTarget;
+
+impl UnresolvedReaperTargetDef for UnresolvedMetronomeVolumeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::MetronomeVolume(MetronomeVolumeTarget {
+            project: context.context().project_or_current_project(),
+        })])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about metronome volume changes
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetronomeVolumeTarget {
+    pub project: Project,
+}
+
+impl RealearnTarget for MetronomeVolumeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn parse_as_value(&self, text: &str, _: ControlContext) -> Result<UnitValue, &'static str> {
+        parse_value_from_db(text)
+    }
+
+    fn format_value_without_unit(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_db_without_unit(value)
+    }
+
+    fn hide_formatted_value(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn hide_formatted_step_size(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn value_unit(&self, _: ControlContext) -> &'static str {
+        "dB"
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_db(value)
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let volume = SliderVolume::try_from_normalized_slider_value(value.to_unit_value()?.get());
+        let ptr = metronome_volume_ptr(self.project).ok_or("metronome volume not found")?;
+        let new_value = volume.unwrap_or(SliderVolume::MIN).reaper_value().get();
+        if unsafe { *ptr } == new_value {
+            return Ok(HitResponse::ignored());
+        }
+        // REAPER doesn't offer an API function for setting the metronome volume, so we write the
+        // config variable directly. No undo point because this is a continuous target.
+        unsafe {
+            *ptr = new_value;
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(self.volume()?.to_string().into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Decimal(self.volume()?.db().get()))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::MetronomeVolume)
+    }
+}
+
+impl MetronomeVolumeTarget {
+    fn volume(&self) -> Option<SliderVolume> {
+        let value = unsafe { *metronome_volume_ptr(self.project)? };
+        Some(SliderVolume::from_reaper_value(
+            ReaperVolumeValue::new_panic(value.max(0.0)),
+        ))
+    }
+}
+
+impl<'a> Target<'a> for MetronomeVolumeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = volume_unit_value(self.volume()?);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const METRONOME_VOLUME_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Project,
+    name: "Set metronome volume",
+    short_name: "Metronome volume",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    ..DEFAULT_TARGET
+};
+
+/// Returns a pointer to the volume of the primary beat click ("projmetrov1"), as linear amplitude.
+///
+/// This is the first volume in REAPER's metronome settings. It's stored as part of the project.
+fn metronome_volume_ptr(project: Project) -> Option<*mut f64> {
+    project_config_var_ptr(project, b"projmetrov1\0")
+}
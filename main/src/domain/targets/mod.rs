@@ -195,3 +195,24 @@ pub use load_pot_preset_target::*;
 
 mod compartment_parameter_value_target;
 pub use compartment_parameter_value_target::*;
+
+mod fx_chain_edit_target;
+pub use fx_chain_edit_target::*;
+
+mod route_matrix_cell_target;
+pub use route_matrix_cell_target::*;
+
+mod track_record_input_target;
+pub use track_record_input_target::*;
+
+mod track_record_mode_target;
+pub use track_record_mode_target::*;
+
+mod arrange_view_target;
+pub use arrange_view_target::*;
+
+mod track_height_target;
+pub use track_height_target::*;
+
+mod metronome_volume_target;
+pub use metronome_volume_target::*;
@@ -0,0 +1,238 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, get_effective_tracks, CompartmentKind, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    TrackRouteDescriptor, TrackRouteType, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use realearn_api::persistence::SendMode;
+use reaper_high::{Project, Reaper, Track, TrackRoute};
+use reaper_medium::{SendTarget, TrackSendAttributeKey, TrackSendCategory};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedRouteMatrixCellTarget {
+    pub descriptor: TrackRouteDescriptor,
+    pub send_mode: SendMode,
+    pub source_channel: u32,
+    pub destination_channel: u32,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedRouteMatrixCellTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let tracks = get_effective_tracks(
+            context,
+            &self.descriptor.track_descriptor.track,
+            compartment,
+        )?;
+        if self.descriptor.route.r#type == TrackRouteType::HardwareOutput {
+            return Err("hardware outputs are not supported");
+        }
+        let targets: Vec<_> = tracks
+            .into_iter()
+            .filter_map(|track| {
+                // Skip tracks for which the related track can't be found instead of letting the
+                // whole target fail
+                let related_track = self
+                    .descriptor
+                    .route
+                    .selector
+                    .resolve_related_track(&track)
+                    .ok()?;
+                let (source_track, destination_track) = match self.descriptor.route.r#type {
+                    TrackRouteType::Send => (track, related_track),
+                    TrackRouteType::Receive => (related_track, track),
+                    // Already checked above
+                    TrackRouteType::HardwareOutput => return None,
+                };
+                let target = RouteMatrixCellTarget {
+                    source_track,
+                    destination_track,
+                    route_type: self.descriptor.route.r#type,
+                    send_mode: self.send_mode,
+                    source_channel: self.source_channel,
+                    destination_channel: self.destination_channel,
+                    poll_for_feedback: self.poll_for_feedback,
+                };
+                Some(ReaperTarget::RouteMatrixCell(target))
+            })
+            .collect();
+        if targets.is_empty() {
+            return Err("related track not found");
+        }
+        Ok(targets)
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteMatrixCellTarget {
+    pub source_track: Track,
+    pub destination_track: Track,
+    /// Decides from which perspective we look at the route (which track is "ours").
+    pub route_type: TrackRouteType,
+    pub send_mode: SendMode,
+    pub source_channel: u32,
+    pub destination_channel: u32,
+    pub poll_for_feedback: bool,
+}
+
+impl RealearnTarget for RouteMatrixCellTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Switch,
+        )
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let existing_send = self.find_send();
+        if value.to_unit_value()?.is_zero() {
+            let Some(send) = existing_send else {
+                return Ok(HitResponse::ignored());
+            };
+            unsafe {
+                Reaper::get().medium_reaper().remove_track_send(
+                    self.source_track.raw(),
+                    TrackSendCategory::Send,
+                    send.index(),
+                )
+            }
+            .map_err(|_| "couldn't remove send")?;
+        } else {
+            let send_index = match existing_send {
+                None => unsafe {
+                    Reaper::get().medium_reaper().create_track_send(
+                        self.source_track.raw(),
+                        SendTarget::OtherTrack(self.destination_track.raw()),
+                    )
+                }
+                .map_err(|_| "couldn't create send")?,
+                Some(send) => send.index(),
+            };
+            self.apply_send_settings(send_index)?;
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.source_track.is_available() && self.destination_track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track()?.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        match self.route_type {
+            TrackRouteType::Receive => Some(&self.destination_track),
+            _ => Some(&self.source_track),
+        }
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        self.poll_for_feedback
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::RouteMatrixCell)
+    }
+}
+
+impl RouteMatrixCellTarget {
+    fn find_send(&self) -> Option<TrackRoute> {
+        self.source_track
+            .find_send_by_destination_track(&self.destination_track)
+    }
+
+    fn apply_send_settings(&self, send_index: u32) -> Result<(), &'static str> {
+        let settings = [
+            (
+                TrackSendAttributeKey::SendMode,
+                reaper_send_mode_value(self.send_mode),
+            ),
+            (TrackSendAttributeKey::SrcChan, self.source_channel as f64),
+            (
+                TrackSendAttributeKey::DstChan,
+                self.destination_channel as f64,
+            ),
+        ];
+        for (key, value) in settings {
+            unsafe {
+                Reaper::get().medium_reaper().set_track_send_info_value(
+                    self.source_track.raw(),
+                    TrackSendCategory::Send,
+                    send_index,
+                    key,
+                    value,
+                )
+            }
+            .map_err(|_| "couldn't apply send settings")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Target<'a> for RouteMatrixCellTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let exists = self.find_send().is_some();
+        Some(AbsoluteValue::Continuous(convert_bool_to_unit_value(
+            exists,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+fn reaper_send_mode_value(mode: SendMode) -> f64 {
+    match mode {
+        SendMode::PostFader => 0.0,
+        SendMode::PreFx => 1.0,
+        SendMode::PreFaderPostFx => 3.0,
+    }
+}
+
+pub const ROUTE_MATRIX_CELL_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Send,
+    name: "Create/remove",
+    short_name: "Create/remove send",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_poll_for_feedback: true,
+    supports_track: true,
+    supports_send: true,
+    ..DEFAULT_TARGET
+};
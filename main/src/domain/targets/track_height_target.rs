@@ -0,0 +1,148 @@
+use crate::domain::{
+    get_effective_tracks, CompartmentKind, ControlContext, ExtendedProcessorContext,
+    FeedbackResolution, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, TrackDescriptor,
+    UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, NumericValue, Target, UnitValue};
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::TrackAttributeKey;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackHeightTarget {
+    pub track_descriptor: TrackDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackHeightTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| ReaperTarget::TrackHeight(TrackHeightTarget { track }))
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about track height changes
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackHeightTarget {
+    pub track: Track,
+}
+
+impl RealearnTarget for TrackHeightTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let height = convert_unit_value_to_track_height(value.to_unit_value()?);
+        unsafe {
+            Reaper::get().medium_reaper().set_media_track_info_value(
+                self.track.raw(),
+                TrackAttributeKey::HeightOverride,
+                height as f64,
+            )
+        }
+        .map_err(|_| "couldn't set track height")?;
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .TrackList_AdjustWindows(false);
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format!("{} px", self.current_height()).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Discrete(self.current_height() as _))
+    }
+
+    fn numeric_value_unit(&self, _: ControlContext) -> &'static str {
+        "px"
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackHeight)
+    }
+}
+
+impl TrackHeightTarget {
+    /// Returns the current height of the track in the arrange view in pixels.
+    fn current_height(&self) -> u32 {
+        let height = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_media_track_info_value(self.track.raw(), TrackAttributeKey::TcpH)
+        };
+        height.max(0.0) as u32
+    }
+}
+
+impl<'a> Target<'a> for TrackHeightTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let value = convert_track_height_to_unit_value(self.current_height());
+        Some(AbsoluteValue::Continuous(value))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_HEIGHT_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Track,
+    name: "Set height",
+    short_name: "Track height",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
+
+/// Track heights in pixels. REAPER itself enforces a theme-dependent minimum height.
+const MIN_TRACK_HEIGHT: u32 = 20;
+const MAX_TRACK_HEIGHT: u32 = 1000;
+
+fn convert_track_height_to_unit_value(height: u32) -> UnitValue {
+    let height = height.clamp(MIN_TRACK_HEIGHT, MAX_TRACK_HEIGHT);
+    UnitValue::new_clamped(
+        (height - MIN_TRACK_HEIGHT) as f64 / (MAX_TRACK_HEIGHT - MIN_TRACK_HEIGHT) as f64,
+    )
+}
+
+fn convert_unit_value_to_track_height(value: UnitValue) -> u32 {
+    MIN_TRACK_HEIGHT + (value.get() * (MAX_TRACK_HEIGHT - MIN_TRACK_HEIGHT) as f64).round() as u32
+}
@@ -0,0 +1,196 @@
+use crate::domain::{
+    available_recording_inputs, convert_count_to_step_size, convert_discrete_to_unit_value,
+    convert_unit_to_discrete_value, format_recording_input, get_effective_tracks, CompartmentKind,
+    CompoundChangeEvent, ControlContext, ExtendedProcessorContext, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{ChangeEvent, Project, Track};
+use reaper_medium::RecordingInput;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackRecordInputTarget {
+    pub track_descriptor: TrackDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackRecordInputTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let tracks = get_effective_tracks(context, &self.track_descriptor.track, compartment)?;
+        let inputs = available_recording_inputs();
+        Ok(tracks
+            .into_iter()
+            .map(|track| {
+                ReaperTarget::TrackRecordInput(TrackRecordInputTarget {
+                    track,
+                    inputs: inputs.clone(),
+                })
+            })
+            .collect())
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackRecordInputTarget {
+    pub track: Track,
+    /// The inputs to browse, determined when resolving the target (querying them is expensive).
+    ///
+    /// The target is resolved again whenever MIDI devices are connected or disconnected.
+    pub inputs: Vec<RecordingInput>,
+}
+
+impl RealearnTarget for TrackRecordInputTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(self.input_count()),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        Ok(convert_unit_to_discrete_value(input, self.input_count()))
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        let index = convert_unit_to_discrete_value(value, self.input_count());
+        match self.inputs.get(index as usize) {
+            None => "<None>".to_string(),
+            Some(input) => format_recording_input(*input),
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => convert_unit_to_discrete_value(v, self.input_count()),
+            AbsoluteValue::Discrete(f) => f.actual(),
+        };
+        let input = *self
+            .inputs
+            .get(index as usize)
+            .ok_or("input not available")?;
+        self.track.set_recording_input(Some(input));
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Reaper(ChangeEvent::TrackInputChanged(e))
+                if e.track == self.track =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        Ok(convert_discrete_to_unit_value(value, self.input_count()))
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_recording_input(self.track.recording_input()?).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.current_input_index()?;
+        Some(NumericValue::Discrete(index as i32 + 1))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackRecordInput)
+    }
+}
+
+impl TrackRecordInputTarget {
+    fn input_count(&self) -> u32 {
+        self.inputs.len() as u32
+    }
+
+    fn current_input_index(&self) -> Option<u32> {
+        let current_input = self.track.recording_input()?;
+        self.inputs
+            .iter()
+            .position(|input| *input == current_input)
+            .map(|i| i as u32)
+    }
+}
+
+impl<'a> Target<'a> for TrackRecordInputTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let index = self.current_input_index()?;
+        let max_value = self.input_count().saturating_sub(1);
+        Some(AbsoluteValue::Discrete(Fraction::new(index, max_value)))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_RECORD_INPUT_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Track,
+    name: "Browse record inputs",
+    short_name: "Browse record inputs",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
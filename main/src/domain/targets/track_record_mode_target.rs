@@ -0,0 +1,148 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, get_effective_tracks, CompartmentKind, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    TrackDescriptor, UnresolvedReaperTargetDef, AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use realearn_api::persistence::TrackRecordMode;
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::TrackAttributeKey;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackRecordModeTarget {
+    pub track_descriptor: TrackDescriptor,
+    pub mode: TrackRecordMode,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackRecordModeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| {
+                    ReaperTarget::TrackRecordMode(TrackRecordModeTarget {
+                        track,
+                        mode: self.mode,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // REAPER doesn't notify us about record mode changes
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackRecordModeTarget {
+    pub track: Track,
+    pub mode: TrackRecordMode,
+}
+
+impl RealearnTarget for TrackRecordModeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        // A record mode can't be switched off, only another record mode can be selected
+        if value.to_unit_value()?.is_zero() || self.mode_is_active() {
+            return Ok(HitResponse::ignored());
+        }
+        unsafe {
+            Reaper::get().medium_reaper().set_media_track_info_value(
+                self.track.raw(),
+                TrackAttributeKey::RecMode,
+                reaper_record_mode_value(self.mode),
+            )
+        }
+        .map_err(|_| "couldn't set record mode")?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackRecordMode)
+    }
+}
+
+impl TrackRecordModeTarget {
+    #[allow(clippy::float_cmp)]
+    fn mode_is_active(&self) -> bool {
+        let actual_value = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_media_track_info_value(self.track.raw(), TrackAttributeKey::RecMode)
+        };
+        actual_value.round() == reaper_record_mode_value(self.mode)
+    }
+}
+
+impl<'a> Target<'a> for TrackRecordModeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_bool_to_unit_value(self.mode_is_active());
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_RECORD_MODE_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Track,
+    name: "Set record mode",
+    short_name: "Track record mode",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
+
+/// Returns the value of REAPER's track attribute "I_RECMODE" which corresponds to the given mode.
+fn reaper_record_mode_value(mode: TrackRecordMode) -> f64 {
+    match mode {
+        TrackRecordMode::Input => 0.0,
+        TrackRecordMode::Output => 1.0,
+        TrackRecordMode::MidiOverdub => 7.0,
+        TrackRecordMode::MidiReplace => 8.0,
+    }
+}
@@ -1,12 +1,12 @@
 use crate::domain::{
-    format_value_as_on_off, transport_is_enabled_unit_value, AdditionalFeedbackEvent,
-    CompartmentKind, CompoundChangeEvent, ControlContext, ExtendedProcessorContext,
-    FeedbackResolution, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
-    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, TransportAction,
-    UnresolvedReaperTargetDef, DEFAULT_TARGET,
+    format_value_as_on_off, project_config_var_ptr, transport_is_enabled_unit_value,
+    AdditionalFeedbackEvent, CompartmentKind, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, FeedbackResolution, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    TransportAction, UnresolvedReaperTargetDef, DEFAULT_TARGET,
 };
 use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
-use reaper_high::{ChangeEvent, Project, Reaper};
+use reaper_high::{Action, ChangeEvent, Project, Reaper};
 use std::borrow::Cow;
 
 #[derive(Debug)]
@@ -27,7 +27,12 @@ impl UnresolvedReaperTargetDef for UnresolvedTransportTarget {
     }
 
     fn feedback_resolution(&self) -> Option<FeedbackResolution> {
-        Some(FeedbackResolution::Beat)
+        if self.action.needs_polling() {
+            // REAPER doesn't notify us about changes of these settings
+            Some(FeedbackResolution::High)
+        } else {
+            Some(FeedbackResolution::Beat)
+        }
     }
 }
 
@@ -102,6 +107,41 @@ impl RealearnTarget for TransportTarget {
                 }
                 HitResponse::processed_with_effect()
             }
+            CountIn => {
+                let ptr = count_in_flags_ptr(self.project).ok_or("count-in setting not found")?;
+                let flags = unsafe { *ptr };
+                let new_flags = if on {
+                    flags | COUNT_IN_BEFORE_RECORDING_FLAG
+                } else {
+                    flags & !COUNT_IN_BEFORE_RECORDING_FLAG
+                };
+                if new_flags == flags {
+                    HitResponse::ignored()
+                } else {
+                    // Writing the config variable directly neither creates an undo point nor
+                    // marks the project as dirty, but the undo block does both.
+                    self.project.undoable(
+                        "ReaLearn: Toggle count-in before recording",
+                        || unsafe {
+                            *ptr = new_flags;
+                        },
+                    );
+                    HitResponse::processed_with_effect()
+                }
+            }
+            _ => {
+                let action = self
+                    .setting_action()
+                    .ok_or("no REAPER action for setting")?;
+                let is_on = action.is_on().ok().flatten().unwrap_or(false);
+                // Mode selectors can't be switched off, only another mode can be selected
+                if on == is_on || (!on && self.action.is_mode_selector()) {
+                    HitResponse::ignored()
+                } else {
+                    action.invoke_as_trigger(Some(self.project))?;
+                    HitResponse::processed_with_effect()
+                }
+            }
         };
         Ok(response)
     }
@@ -169,10 +209,13 @@ impl RealearnTarget for TransportTarget {
                         ),
                         _ => (false, None),
                     },
+                    // Polled
+                    _ => (false, None),
                 }
             }
             CompoundChangeEvent::Additional(AdditionalFeedbackEvent::BeatChanged(e))
                 if self.action != TransportAction::Repeat
+                    && !self.action.needs_polling()
                     && e.project == self.project
                     && e.project != Reaper::get().current_project() =>
             {
@@ -191,6 +234,17 @@ impl RealearnTarget for TransportTarget {
     }
 }
 
+impl TransportTarget {
+    fn setting_action(&self) -> Option<Action> {
+        let command_id = self.action.reaper_command_id()?;
+        Some(
+            Reaper::get()
+                .main_section()
+                .action_by_command_id(command_id),
+        )
+    }
+}
+
 impl<'a> Target<'a> for TransportTarget {
     type Context = ControlContext<'a>;
 
@@ -205,6 +259,11 @@ impl<'a> Target<'a> for TransportTarget {
             Pause => transport_is_enabled_unit_value(play_state.is_paused),
             RecordStop => transport_is_enabled_unit_value(play_state.is_recording),
             Repeat => transport_is_enabled_unit_value(self.project.repeat_is_enabled()),
+            CountIn => {
+                let flags = unsafe { *count_in_flags_ptr(self.project)? };
+                transport_is_enabled_unit_value(flags & COUNT_IN_BEFORE_RECORDING_FLAG != 0)
+            }
+            _ => transport_is_enabled_unit_value(self.setting_action()?.is_on().ok()??),
         };
         Some(AbsoluteValue::Continuous(value))
     }
@@ -220,3 +279,17 @@ pub const TRANSPORT_TARGET: TargetTypeDef = TargetTypeDef {
     short_name: "Transport",
     ..DEFAULT_TARGET
 };
+
+/// Bit within the project's metronome flags ("projmetroen") that corresponds to the checkbox
+/// "Count-in before recording" in REAPER's metronome settings.
+///
+/// The remaining bits hold other metronome settings (e.g. bit 0 enables the metronome), so we must
+/// only touch this one. REAPER doesn't offer a dedicated API function or an action with toggle state
+/// for this setting, that's why we access the config variable directly. It's stored as part of the
+/// project.
+const COUNT_IN_BEFORE_RECORDING_FLAG: i32 = 16;
+
+/// Returns a pointer to the project's metronome flags ("projmetroen").
+fn count_in_flags_ptr(project: Project) -> Option<*mut i32> {
+    project_config_var_ptr(project, b"projmetroen\0")
+}
@@ -4,22 +4,26 @@ use crate::domain::{
     scoped_track_index, Backbone, CompartmentKind, CompartmentParamIndex, CompartmentParams,
     ControlContext, ExtendedProcessorContext, FeedbackResolution, ReaperTarget,
     UnresolvedActionTarget, UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
-    UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
-    UnresolvedBrowseTracksTarget, UnresolvedCompartmentParameterValueTarget, UnresolvedDummyTarget,
-    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget, UnresolvedFxEnableTarget,
-    UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget, UnresolvedFxParameterTarget,
-    UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget, UnresolvedFxToolTarget,
-    UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
-    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget,
+    UnresolvedArrangeViewTarget, UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget,
+    UnresolvedBrowseGroupTarget, UnresolvedBrowsePotFilterItemsTarget,
+    UnresolvedBrowsePotPresetsTarget, UnresolvedBrowseTracksTarget,
+    UnresolvedCompartmentParameterValueTarget, UnresolvedDummyTarget,
+    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget, UnresolvedFxChainEditTarget,
+    UnresolvedFxEnableTarget, UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget,
+    UnresolvedFxParameterTarget, UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget,
+    UnresolvedFxToolTarget, UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget,
+    UnresolvedLoadFxSnapshotTarget, UnresolvedLoadMappingSnapshotTarget,
+    UnresolvedLoadPotPresetTarget, UnresolvedMetronomeVolumeTarget, UnresolvedMidiSendTarget,
     UnresolvedModifyMappingTarget, UnresolvedMouseTarget, UnresolvedOscSendTarget,
     UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget,
-    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
-    UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget,
-    UnresolvedRouteVolumeTarget, UnresolvedSeekTarget, UnresolvedTakeMappingSnapshotTarget,
-    UnresolvedTempoTarget, UnresolvedTrackArmTarget, UnresolvedTrackAutomationModeTarget,
+    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMatrixCellTarget,
+    UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget,
+    UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget, UnresolvedRouteVolumeTarget,
+    UnresolvedSeekTarget, UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget,
+    UnresolvedTrackArmTarget, UnresolvedTrackAutomationModeTarget, UnresolvedTrackHeightTarget,
     UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget,
     UnresolvedTrackParentSendTarget, UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget,
+    UnresolvedTrackRecordInputTarget, UnresolvedTrackRecordModeTarget,
     UnresolvedTrackSelectionTarget, UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget,
     UnresolvedTrackToolTarget, UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget,
     UnresolvedTrackWidthTarget, UnresolvedTransportTarget,
@@ -62,6 +66,7 @@ pub enum UnresolvedReaperTarget {
     TrackSendVolume(UnresolvedRouteVolumeTarget),
     TrackPan(UnresolvedTrackPanTarget),
     TrackWidth(UnresolvedTrackWidthTarget),
+    TrackHeight(UnresolvedTrackHeightTarget),
     TrackArm(UnresolvedTrackArmTarget),
     TrackParentSend(UnresolvedTrackParentSendTarget),
     TrackSelection(UnresolvedTrackSelectionTarget),
@@ -71,13 +76,18 @@ pub enum UnresolvedReaperTarget {
     TrackSolo(UnresolvedTrackSoloTarget),
     TrackAutomationMode(UnresolvedTrackAutomationModeTarget),
     TrackMonitoringMode(UnresolvedTrackMonitoringModeTarget),
+    TrackRecordInput(UnresolvedTrackRecordInputTarget),
+    TrackRecordMode(UnresolvedTrackRecordModeTarget),
     RoutePan(UnresolvedRoutePanTarget),
     RouteMute(UnresolvedRouteMuteTarget),
     RoutePhase(UnresolvedRoutePhaseTarget),
     RouteMono(UnresolvedRouteMonoTarget),
     RouteAutomationMode(UnresolvedRouteAutomationModeTarget),
     RouteTouchState(UnresolvedRouteTouchStateTarget),
+    RouteMatrixCell(UnresolvedRouteMatrixCellTarget),
     Tempo(UnresolvedTempoTarget),
+    ArrangeView(UnresolvedArrangeViewTarget),
+    MetronomeVolume(UnresolvedMetronomeVolumeTarget),
     Playrate(UnresolvedPlayrateTarget),
     AutomationModeOverride(UnresolvedAutomationModeOverrideTarget),
     FxTool(UnresolvedFxToolTarget),
@@ -87,6 +97,7 @@ pub enum UnresolvedReaperTarget {
     FxPreset(UnresolvedFxPresetTarget),
     SelectedTrack(UnresolvedBrowseTracksTarget),
     BrowseFxs(UnresolvedBrowseFxsTarget),
+    FxChainEdit(UnresolvedFxChainEditTarget),
     AllTrackFxEnable(UnresolvedAllTrackFxEnableTarget),
     Transport(UnresolvedTransportTarget),
     LoadFxPreset(UnresolvedLoadFxSnapshotTarget),
@@ -612,6 +623,23 @@ impl TrackRouteSelector {
         Ok(route)
     }
 
+    /// Resolves the track on the other side of the route without requiring the route to exist.
+    ///
+    /// Only selectors by ID and by name can do that. The others refer to existing routes.
+    pub fn resolve_related_track(&self, track: &Track) -> Result<Track, &'static str> {
+        use TrackRouteSelector::*;
+        match self {
+            ById(guid) => track
+                .project()
+                .track_by_guid(guid)
+                .map_err(|_| "related track not found"),
+            ByName(name) => {
+                find_track_by_name(track.project(), name).ok_or("related track not found")
+            }
+            Dynamic(_) | ByIndex(_) => Err("related track can only be resolved by ID or name"),
+        }
+    }
+
     pub fn calculated_route_index(
         &self,
         context: ExtendedProcessorContext,
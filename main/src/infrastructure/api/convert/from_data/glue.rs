@@ -58,6 +58,7 @@ pub fn convert_glue(
             };
             style.required_value(v)
         },
+        response_curve: data.response_curve,
         takeover_mode: {
             use persistence::TakeoverMode as T;
             use TakeoverMode::*;
@@ -75,7 +76,10 @@ pub fn convert_glue(
             data.round_target_value,
             defaults::GLUE_ROUND_TARGET_VALUE,
         ),
+        glide: data.glide,
+        scale_quantization: data.scale_quantization,
         control_transformation: style.required_value(data.eel_control_transformation),
+        control_transformation_kind: style.required_value(data.control_transformation_kind),
         button_filter: {
             use persistence::ButtonFilter as T;
             use ButtonUsage::*;
@@ -102,6 +106,7 @@ pub fn convert_glue(
             };
             style.required_value(v)
         },
+        encoder_acceleration: data.encoder_acceleration,
         interaction: {
             use persistence::Interaction as T;
             use GroupInteraction::*;
@@ -147,48 +152,59 @@ pub fn convert_glue(
         fire_mode: {
             use persistence::FireMode as T;
             use FireMode::*;
-            let v = match data.fire_mode {
-                Normal => T::Normal(persistence::NormalFireMode {
-                    press_duration_interval: {
-                        let interval = persistence::Interval(
-                            data.min_press_millis as _,
-                            data.max_press_millis as _,
-                        );
-                        style.required_value_with_default(
-                            interval,
-                            defaults::FIRE_MODE_PRESS_DURATION_INTERVAL,
-                        )
-                    },
-                }),
-                AfterTimeout => T::AfterTimeout(persistence::AfterTimeoutFireMode {
-                    timeout: style.required_value_with_default(
-                        data.min_press_millis as _,
-                        defaults::FIRE_MODE_TIMEOUT,
-                    ),
-                }),
-                AfterTimeoutKeepFiring => {
-                    T::AfterTimeoutKeepFiring(persistence::AfterTimeoutKeepFiringFireMode {
+            let v = if let Some(gesture) = data.fire_gesture {
+                use persistence::FireGesture as G;
+                match gesture {
+                    G::MultiPress(m) => T::OnMultiPress(m),
+                    G::LongPressRelease(m) => T::OnLongPressRelease(m),
+                    G::Chord(m) => T::OnChord(m),
+                    G::Sequence(m) => T::OnSequence(m),
+                }
+            } else {
+                match data.fire_mode {
+                    Normal => T::Normal(persistence::NormalFireMode {
+                        press_duration_interval: {
+                            let interval = persistence::Interval(
+                                data.min_press_millis as _,
+                                data.max_press_millis as _,
+                            );
+                            style.required_value_with_default(
+                                interval,
+                                defaults::FIRE_MODE_PRESS_DURATION_INTERVAL,
+                            )
+                        },
+                    }),
+                    AfterTimeout => T::AfterTimeout(persistence::AfterTimeoutFireMode {
                         timeout: style.required_value_with_default(
                             data.min_press_millis as _,
                             defaults::FIRE_MODE_TIMEOUT,
                         ),
-                        rate: style.required_value_with_default(
-                            data.turbo_rate as _,
-                            defaults::FIRE_MODE_RATE,
+                    }),
+                    AfterTimeoutKeepFiring => {
+                        T::AfterTimeoutKeepFiring(persistence::AfterTimeoutKeepFiringFireMode {
+                            timeout: style.required_value_with_default(
+                                data.min_press_millis as _,
+                                defaults::FIRE_MODE_TIMEOUT,
+                            ),
+                            rate: style.required_value_with_default(
+                                data.turbo_rate as _,
+                                defaults::FIRE_MODE_RATE,
+                            ),
+                        })
+                    }
+                    OnSinglePress => T::OnSinglePress(persistence::OnSinglePressFireMode {
+                        max_duration: style.required_value_with_default(
+                            data.max_press_millis as _,
+                            defaults::FIRE_MODE_SINGLE_PRESS_MAX_DURATION,
                         ),
-                    })
+                    }),
+                    OnDoublePress => T::OnDoublePress,
                 }
-                OnSinglePress => T::OnSinglePress(persistence::OnSinglePressFireMode {
-                    max_duration: style.required_value_with_default(
-                        data.max_press_millis as _,
-                        defaults::FIRE_MODE_SINGLE_PRESS_MAX_DURATION,
-                    ),
-                }),
-                OnDoublePress => T::OnDoublePress,
             };
             style.required_value(v)
         },
         feedback_value_table: data.feedback_value_table,
+        feedback_animations: style.required_value(data.feedback_animations),
     };
     Ok(glue)
 }
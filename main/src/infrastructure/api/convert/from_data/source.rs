@@ -1,4 +1,8 @@
 use crate::application::{MidiSourceType, ReaperSourceType, SourceCategory};
+use crate::domain::{
+    AdsrSettings, DEFAULT_AUDIO_SIGNAL_ATTACK_MS, DEFAULT_AUDIO_SIGNAL_RELEASE_MS,
+    DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB, DEFAULT_MODULATOR_FREQUENCY,
+};
 use crate::infrastructure::api::convert::from_data::{
     convert_control_element_id, convert_control_element_kind, convert_keystroke,
     convert_osc_argument, ConversionStyle,
@@ -135,6 +139,12 @@ pub fn convert_source(
                     };
                     persistence::Source::MidiScript(s)
                 }
+                InputScript => {
+                    let s = persistence::MidiInputScriptSource {
+                        script: style.required_value(data.midi_script),
+                    };
+                    persistence::Source::MidiInputScript(s)
+                }
                 Display => {
                     use DisplayType::*;
                     match data.display_type {
@@ -245,6 +255,86 @@ pub fn convert_source(
                     })
                 }
                 Speech => persistence::Source::Speech,
+                AudioSignal => persistence::Source::AudioSignal(persistence::AudioSignalSource {
+                    signal_kind: style.required_value(data.audio_signal_kind),
+                    attack: style.required_value_with_default(
+                        data.audio_signal_attack_millis,
+                        DEFAULT_AUDIO_SIGNAL_ATTACK_MS,
+                    ),
+                    release: style.required_value_with_default(
+                        data.audio_signal_release_millis,
+                        DEFAULT_AUDIO_SIGNAL_RELEASE_MS,
+                    ),
+                    threshold: style.required_value_with_default(
+                        data.audio_signal_threshold,
+                        DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB,
+                    ),
+                }),
+                Modulator => {
+                    let default_adsr = AdsrSettings::default();
+                    let is_adsr = data.modulator_shape == persistence::ModulatorShape::Adsr;
+                    persistence::Source::Modulator(persistence::ModulatorSource {
+                        shape: style.required_value(data.modulator_shape),
+                        frequency: if is_adsr || data.modulator_tempo_sync.is_some() {
+                            None
+                        } else {
+                            style.required_value_with_default(
+                                data.modulator_frequency,
+                                DEFAULT_MODULATOR_FREQUENCY,
+                            )
+                        },
+                        tempo_sync: if is_adsr {
+                            None
+                        } else {
+                            data.modulator_tempo_sync
+                        },
+                        reset_on_transport_start: if is_adsr {
+                            None
+                        } else {
+                            style.required_value_with_default(
+                                data.modulator_reset_on_transport_start,
+                                true,
+                            )
+                        },
+                        attack: if is_adsr {
+                            style.required_value_with_default(
+                                data.modulator_attack_millis,
+                                default_adsr.attack.as_millis() as _,
+                            )
+                        } else {
+                            None
+                        },
+                        decay: if is_adsr {
+                            style.required_value_with_default(
+                                data.modulator_decay_millis,
+                                default_adsr.decay.as_millis() as _,
+                            )
+                        } else {
+                            None
+                        },
+                        sustain: if is_adsr {
+                            style.required_value_with_default(
+                                data.modulator_sustain,
+                                default_adsr.sustain,
+                            )
+                        } else {
+                            None
+                        },
+                        release: if is_adsr {
+                            style.required_value_with_default(
+                                data.modulator_release_millis,
+                                default_adsr.release.as_millis() as _,
+                            )
+                        } else {
+                            None
+                        },
+                        gate_parameter_index: if is_adsr {
+                            Some(data.parameter_index.get())
+                        } else {
+                            None
+                        },
+                    })
+                }
             }
         }
         Virtual => {
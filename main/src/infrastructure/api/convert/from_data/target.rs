@@ -20,24 +20,26 @@ use crate::infrastructure::data::{
 use base::hash_util::convert_into_other_hash_set;
 use realearn_api::persistence;
 use realearn_api::persistence::{
-    AllTrackFxOnOffStateTarget, AnyOnTarget, AutomationModeOverrideTarget,
+    AllTrackFxOnOffStateTarget, AnyOnTarget, ArrangeViewTarget, AutomationModeOverrideTarget,
     BackwardCompatibleMappingSnapshotDescForTake, BookmarkDescriptor, BookmarkRef,
     BrowseFxChainTarget, BrowseFxPresetsTarget, BrowseGroupMappingsTarget,
     BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget,
     CompartmentParameterDescriptor, CompartmentParameterValueTarget, DummyTarget,
-    EnableInstancesTarget, EnableMappingsTarget, FxOnOffStateTarget, FxOnlineOfflineStateTarget,
-    FxParameterAutomationTouchStateTarget, FxParameterValueTarget, FxToolTarget,
-    FxVisibilityTarget, GoToBookmarkTarget, LastTouchedTarget, LearnTargetMappingModification,
-    LoadFxSnapshotTarget, LoadMappingSnapshotTarget, LoadPotPresetTarget, MappingModification,
-    ModifyMappingTarget, MouseTarget, PlayRateTarget, PreviewPotPresetTarget, ReaperActionTarget,
-    RouteAutomationModeTarget, RouteMonoStateTarget, RouteMuteStateTarget, RoutePanTarget,
-    RoutePhaseTarget, RouteTouchStateTarget, RouteVolumeTarget, SeekTarget, SendMidiTarget,
-    SendOscTarget, SetTargetToLastTouchedMappingModification, TakeMappingSnapshotTarget,
-    TempoTarget, TrackArmStateTarget, TrackAutomationModeTarget, TrackAutomationTouchStateTarget,
-    TrackMonitoringModeTarget, TrackMuteStateTarget, TrackPanTarget, TrackParentSendStateTarget,
-    TrackPeakTarget, TrackPhaseTarget, TrackSelectionStateTarget, TrackSoloStateTarget,
-    TrackToolTarget, TrackVisibilityTarget, TrackVolumeTarget, TrackWidthTarget,
-    TransportActionTarget,
+    EnableInstancesTarget, EnableMappingsTarget, FxChainEditTarget, FxOnOffStateTarget,
+    FxOnlineOfflineStateTarget, FxParameterAutomationTouchStateTarget, FxParameterValueTarget,
+    FxToolTarget, FxVisibilityTarget, GoToBookmarkTarget, LastTouchedTarget,
+    LearnTargetMappingModification, LoadFxSnapshotTarget, LoadMappingSnapshotTarget,
+    LoadPotPresetTarget, MappingModification, MetronomeVolumeTarget, ModifyMappingTarget,
+    MouseTarget, PlayRateTarget, PreviewPotPresetTarget, ReaperActionTarget,
+    RouteAutomationModeTarget, RouteMatrixCellTarget, RouteMonoStateTarget, RouteMuteStateTarget,
+    RoutePanTarget, RoutePhaseTarget, RouteTouchStateTarget, RouteVolumeTarget, SeekTarget,
+    SendMidiTarget, SendOscTarget, SetTargetToLastTouchedMappingModification,
+    TakeMappingSnapshotTarget, TempoTarget, TrackArmStateTarget, TrackAutomationModeTarget,
+    TrackAutomationTouchStateTarget, TrackHeightTarget, TrackMonitoringModeTarget,
+    TrackMuteStateTarget, TrackPanTarget, TrackParentSendStateTarget, TrackPeakTarget,
+    TrackPhaseTarget, TrackRecordInputTarget, TrackRecordModeTarget, TrackSelectionStateTarget,
+    TrackSoloStateTarget, TrackToolTarget, TrackVisibilityTarget, TrackVolumeTarget,
+    TrackWidthTarget, TransportActionTarget,
 };
 
 pub fn convert_target(
@@ -174,6 +176,25 @@ fn convert_real_target(
                 defaults::TARGET_USE_SELECTION_GANGING,
             ),
         }),
+        TrackRecordInput => T::TrackRecordInput(TrackRecordInputTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        }),
+        TrackRecordMode => T::TrackRecordMode(TrackRecordModeTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+            mode: data.track_record_mode,
+        }),
         TrackTouchState => T::TrackAutomationTouchState(TrackAutomationTouchStateTarget {
             commons,
             track: convert_track_descriptor(
@@ -289,6 +310,17 @@ fn convert_real_target(
             },
             route: convert_route_descriptor(data, style),
         }),
+        RouteMatrixCell => T::RouteMatrixCell(RouteMatrixCellTarget {
+            commons,
+            send_mode: style.required_value(data.route_send_mode),
+            source_channel: style.required_value(data.route_source_channel),
+            destination_channel: style.required_value(data.route_destination_channel),
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            route: convert_route_descriptor(data, style),
+        }),
         PlaytimeSlotTransportAction => T::PlaytimeSlotTransportAction(
             realearn_api::persistence::PlaytimeSlotTransportActionTarget {
                 commons,
@@ -415,6 +447,11 @@ fn convert_real_target(
         }),
         PlayRate => T::PlayRate(PlayRateTarget { commons }),
         Tempo => T::Tempo(TempoTarget { commons }),
+        MetronomeVolume => T::MetronomeVolume(MetronomeVolumeTarget { commons }),
+        ArrangeView => T::ArrangeView(ArrangeViewTarget {
+            commons,
+            property: style.required_value(data.arrange_view_property),
+        }),
         TrackArm => T::TrackArmState(TrackArmStateTarget {
             commons,
             track: convert_track_descriptor(
@@ -541,6 +578,15 @@ fn convert_real_target(
                 defaults::TARGET_USE_SELECTION_GANGING,
             ),
         }),
+        TrackHeight => T::TrackHeight(TrackHeightTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        }),
         TrackWidth => T::TrackWidth(TrackWidthTarget {
             commons,
             track: convert_track_descriptor(
@@ -620,6 +666,12 @@ fn convert_real_target(
             instance_tags: convert_tags(&data.tags, style),
             fx: convert_fx_descriptor(data, style),
         }),
+        FxChainEdit => T::FxChainEdit(FxChainEditTarget {
+            commons,
+            action: style.required_value(data.fx_chain_edit_action),
+            plugin_id: style.required_value(data.plugin_id.clone()),
+            fx: convert_fx_descriptor(data, style),
+        }),
         FxEnable => T::FxOnOffState(FxOnOffStateTarget {
             commons,
             fx: convert_fx_descriptor(data, style),
@@ -804,6 +856,17 @@ fn convert_transport_action(transport_action: TransportAction) -> persistence::T
         Pause => T::Pause,
         RecordStop => T::Record,
         Repeat => T::Repeat,
+        Metronome => T::Metronome,
+        CountIn => T::CountIn,
+        PreRoll => T::PreRoll,
+        AutoCrossfade => T::AutoCrossfade,
+        Snap => T::Snap,
+        RecordModeNormal => T::RecordModeNormal,
+        RecordModeTimeSelectionAutoPunch => T::RecordModeTimeSelectionAutoPunch,
+        RecordModeItemAutoPunch => T::RecordModeItemAutoPunch,
+        RippleOff => T::RippleOff,
+        RipplePerTrack => T::RipplePerTrack,
+        RippleAllTracks => T::RippleAllTracks,
     }
 }
 
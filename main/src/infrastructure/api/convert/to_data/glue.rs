@@ -86,7 +86,11 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
                 let min = m.timeout.unwrap_or(defaults::FIRE_MODE_TIMEOUT) as u64;
                 (min, min)
             }
-            OnDoublePress => (0, 0),
+            OnDoublePress
+            | OnMultiPress(_)
+            | OnLongPressRelease(_)
+            | OnChord(_)
+            | OnSequence(_) => (0, 0),
         }
     };
     let data = ModeModelData {
@@ -121,6 +125,7 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
             }
         },
         eel_control_transformation: g.control_transformation.unwrap_or_default(),
+        control_transformation_kind: g.control_transformation_kind.unwrap_or_default(),
         eel_feedback_transformation: fb_data.transformation,
         reverse_is_enabled: g.reverse.unwrap_or(defaults::GLUE_REVERSE),
         feedback_color: fb_data.commons.color,
@@ -144,6 +149,19 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
                 AfterTimeoutKeepFiring(_) => T::AfterTimeoutKeepFiring,
                 OnSinglePress(_) => T::OnSinglePress,
                 OnDoublePress => T::OnDoublePress,
+                // Gestures are detected before the mode
+                OnMultiPress(_) | OnLongPressRelease(_) | OnChord(_) | OnSequence(_) => T::Normal,
+            }
+        },
+        fire_gesture: {
+            use FireGesture as G;
+            use FireMode::*;
+            match &fire_mode {
+                OnMultiPress(m) => Some(G::MultiPress(m.clone())),
+                OnLongPressRelease(m) => Some(G::LongPressRelease(m.clone())),
+                OnChord(m) => Some(G::Chord(m.clone())),
+                OnSequence(m) => Some(G::Sequence(m.clone())),
+                _ => None,
             }
         },
         round_target_value: g
@@ -188,6 +206,10 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         },
         rotate_is_enabled: g.wrap.unwrap_or(defaults::GLUE_WRAP),
         make_absolute_enabled: g.relative_mode.unwrap_or_default() == RelativeMode::MakeAbsolute,
+        encoder_acceleration: g.encoder_acceleration,
+        glide: g.glide,
+        scale_quantization: g.scale_quantization,
+        response_curve: g.response_curve,
         group_interaction: {
             use helgoboss_learn::GroupInteraction as T;
             if let Some(i) = g.interaction {
@@ -211,6 +233,7 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         },
         feedback_type: fb_data.feedback_type,
         feedback_value_table: g.feedback_value_table,
+        feedback_animations: g.feedback_animations.unwrap_or_default(),
     };
     Ok(data)
 }
@@ -1,4 +1,8 @@
 use crate::application::{MidiSourceType, ReaperSourceType, SourceCategory};
+use crate::domain::{
+    AdsrSettings, DEFAULT_AUDIO_SIGNAL_ATTACK_MS, DEFAULT_AUDIO_SIGNAL_RELEASE_MS,
+    DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB, DEFAULT_MODULATOR_FREQUENCY,
+};
 use crate::infrastructure::api::convert::to_data::{
     convert_control_element_id, convert_control_element_type, convert_keystroke,
     convert_osc_arg_type, convert_osc_value_range,
@@ -12,6 +16,7 @@ use realearn_api::persistence::*;
 use std::convert::TryInto;
 
 pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
+    let default_adsr = AdsrSettings::default();
     let data = SourceModelData {
         category: convert_category(&s),
         r#type: convert_midi_source_type(&s),
@@ -46,6 +51,7 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
         },
         midi_script: match &s {
             Source::MidiScript(s) => s.script.as_ref().cloned().unwrap_or_default(),
+            Source::MidiInputScript(s) => s.script.as_ref().cloned().unwrap_or_default(),
             _ => Default::default(),
         },
         display_type: match &s {
@@ -139,16 +145,70 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
             Source::RealearnInstanceStart => ReaperSourceType::RealearnUnitStart,
             Source::Timer(_) => ReaperSourceType::Timer,
             Source::RealearnParameter(_) => ReaperSourceType::RealearnParameter,
+            Source::AudioSignal(_) => ReaperSourceType::AudioSignal,
+            Source::Modulator(_) => ReaperSourceType::Modulator,
             _ => Default::default(),
         },
         timer_millis: match &s {
             Source::Timer(t) => t.duration,
             _ => Default::default(),
         },
+        audio_signal_kind: match &s {
+            Source::AudioSignal(s) => s.signal_kind.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        audio_signal_attack_millis: match &s {
+            Source::AudioSignal(s) => s.attack.unwrap_or(DEFAULT_AUDIO_SIGNAL_ATTACK_MS),
+            _ => Default::default(),
+        },
+        audio_signal_release_millis: match &s {
+            Source::AudioSignal(s) => s.release.unwrap_or(DEFAULT_AUDIO_SIGNAL_RELEASE_MS),
+            _ => Default::default(),
+        },
+        audio_signal_threshold: match &s {
+            Source::AudioSignal(s) => s.threshold.unwrap_or(DEFAULT_AUDIO_SIGNAL_THRESHOLD_DB),
+            _ => Default::default(),
+        },
+        modulator_shape: match &s {
+            Source::Modulator(s) => s.shape.unwrap_or_default(),
+            _ => Default::default(),
+        },
+        modulator_frequency: match &s {
+            Source::Modulator(s) => s.frequency.unwrap_or(DEFAULT_MODULATOR_FREQUENCY),
+            _ => Default::default(),
+        },
+        modulator_tempo_sync: match &s {
+            Source::Modulator(s) => s.tempo_sync,
+            _ => Default::default(),
+        },
+        modulator_reset_on_transport_start: match &s {
+            Source::Modulator(s) => s.reset_on_transport_start.unwrap_or(true),
+            _ => Default::default(),
+        },
+        modulator_attack_millis: match &s {
+            Source::Modulator(s) => s.attack.unwrap_or(default_adsr.attack.as_millis() as _),
+            _ => Default::default(),
+        },
+        modulator_decay_millis: match &s {
+            Source::Modulator(s) => s.decay.unwrap_or(default_adsr.decay.as_millis() as _),
+            _ => Default::default(),
+        },
+        modulator_sustain: match &s {
+            Source::Modulator(s) => s.sustain.unwrap_or(default_adsr.sustain),
+            _ => Default::default(),
+        },
+        modulator_release_millis: match &s {
+            Source::Modulator(s) => s.release.unwrap_or(default_adsr.release.as_millis() as _),
+            _ => Default::default(),
+        },
         parameter_index: match &s {
             Source::RealearnParameter(s) => {
                 s.parameter_index.try_into().map_err(anyhow::Error::msg)?
             }
+            Source::Modulator(ModulatorSource {
+                gate_parameter_index: Some(i),
+                ..
+            }) => (*i).try_into().map_err(anyhow::Error::msg)?,
             _ => Default::default(),
         },
     };
@@ -159,9 +219,13 @@ fn convert_category(s: &Source) -> SourceCategory {
     use Source::*;
     match s {
         None => SourceCategory::Never,
-        MidiDeviceChanges | RealearnInstanceStart | Timer(_) | RealearnParameter(_) | Speech => {
-            SourceCategory::Reaper
-        }
+        MidiDeviceChanges
+        | RealearnInstanceStart
+        | Timer(_)
+        | RealearnParameter(_)
+        | Speech
+        | AudioSignal(_)
+        | Modulator(_) => SourceCategory::Reaper,
         MidiNoteVelocity(_)
         | MidiNoteKeyNumber(_)
         | MidiPolyphonicKeyPressureAmount(_)
@@ -175,6 +239,7 @@ fn convert_category(s: &Source) -> SourceCategory {
         | MidiClockTransport(_)
         | MidiRaw(_)
         | MidiScript(_)
+        | MidiInputScript(_)
         | MackieLcd(_)
         | XTouchMackieLcd(_)
         | MackieSevenSegmentDisplay(_)
@@ -203,6 +268,7 @@ fn convert_midi_source_type(s: &Source) -> MidiSourceType {
         MidiClockTransport(_) => MidiSourceType::ClockTransport,
         MidiRaw(_) => MidiSourceType::Raw,
         MidiScript(_) => MidiSourceType::Script,
+        MidiInputScript(_) => MidiSourceType::InputScript,
         MackieLcd(_) | XTouchMackieLcd(_) | MackieSevenSegmentDisplay(_) | SiniConE24Display(_) => {
             MidiSourceType::Display
         }
@@ -151,6 +151,17 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
             r#type: ReaperTargetType::Tempo,
             ..init(d.commons)
         },
+        Target::MetronomeVolume(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::MetronomeVolume,
+            ..init(d.commons)
+        },
+        Target::ArrangeView(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::ArrangeView,
+            arrange_view_property: d.property.unwrap_or_default(),
+            ..init(d.commons)
+        },
         Target::GoToBookmark(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::GoToBookmark,
@@ -336,6 +347,29 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::TrackRecordInput(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackRecordInput,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
+        Target::TrackRecordMode(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackRecordMode,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_record_mode: d.mode,
+                ..init(d.commons)
+            }
+        }
         Target::TrackAutomationTouchState(d) => {
             let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
             TargetModelData {
@@ -376,6 +410,17 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::TrackHeight(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackHeight,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
         Target::TrackWidth(d) => {
             let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
             TargetModelData {
@@ -508,6 +553,22 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::FxChainEdit(d) => {
+            let fx_desc = convert_fx_desc(d.fx.unwrap_or_default())?;
+            let track_desc = fx_desc.chain_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::FxChainEdit,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                fx_data: fx_desc.fx_data,
+                enable_only_if_fx_has_focus: fx_desc.fx_must_have_focus,
+                fx_chain_edit_action: d.action.unwrap_or_default(),
+                plugin_id: d.plugin_id.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
         Target::FxOnOffState(d) => {
             let fx_desc = convert_fx_desc(d.fx.unwrap_or_default())?;
             let track_desc = fx_desc.chain_desc.track_desc;
@@ -744,6 +805,25 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::RouteMatrixCell(d) => {
+            let route_desc = convert_route_desc(d.route)?;
+            let track_desc = route_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::RouteMatrixCell,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_route_data: route_desc.track_route_data,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                route_send_mode: d.send_mode.unwrap_or_default(),
+                route_source_channel: d.source_channel.unwrap_or_default(),
+                route_destination_channel: d.destination_channel.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
         Target::PlaytimeSlotTransportAction(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::PlaytimeSlotTransportAction,
@@ -1417,6 +1497,17 @@ fn convert_transport_action(transport_action: TransportAction) -> domain::Transp
         Pause => T::Pause,
         Record => T::RecordStop,
         Repeat => T::Repeat,
+        Metronome => T::Metronome,
+        CountIn => T::CountIn,
+        PreRoll => T::PreRoll,
+        AutoCrossfade => T::AutoCrossfade,
+        Snap => T::Snap,
+        RecordModeNormal => T::RecordModeNormal,
+        RecordModeTimeSelectionAutoPunch => T::RecordModeTimeSelectionAutoPunch,
+        RecordModeItemAutoPunch => T::RecordModeItemAutoPunch,
+        RippleOff => T::RippleOff,
+        RipplePerTrack => T::RipplePerTrack,
+        RippleAllTracks => T::RippleAllTracks,
     }
 }
 
@@ -44,4 +44,12 @@ impl MigrationDescriptor {
             },
         }
     }
+
+    /// Returns `true` if at least one migration needs to be applied.
+    pub fn is_required(&self) -> bool {
+        self.target_interval_transformation_117
+            || self.fx_selector_transformation_188
+            || self.jump_overhaul_485
+            || self.action_invocation_swap_761
+    }
 }
@@ -37,6 +37,15 @@ pub use enabled_data::*;
 mod preset;
 pub use preset::*;
 
+mod preset_bundle;
+pub use preset_bundle::*;
+
+mod preset_query;
+pub use preset_query::*;
+
+mod preset_build_environment;
+pub use preset_build_environment::*;
+
 mod compartment_preset_data;
 pub use compartment_preset_data::*;
 
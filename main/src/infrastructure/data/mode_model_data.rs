@@ -7,7 +7,10 @@ use helgoboss_learn::{
     GroupInteraction, Interval, OutOfRangeBehavior, SoftSymmetricUnitValue, TakeoverMode,
     UnitValue, ValueSequence, VirtualColor,
 };
-use realearn_api::persistence::FeedbackValueTable;
+use realearn_api::persistence::{
+    ControlTransformationKind, EncoderAcceleration, FeedbackAnimation, FeedbackValueTable,
+    FireGesture, Glide, ResponseCurve, ScaleQuantization,
+};
 use serde::{Deserialize, Serialize};
 use slog::debug;
 use std::time::Duration;
@@ -96,6 +99,13 @@ pub struct ModeModelData {
         skip_serializing_if = "is_default"
     )]
     pub eel_control_transformation: String,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub control_transformation_kind: ControlTransformationKind,
     /// Also used as text expression for text feedback
     #[serde(
         default,
@@ -135,18 +145,46 @@ pub struct ModeModelData {
         skip_serializing_if = "is_default"
     )]
     pub out_of_range_behavior: OutOfRangeBehavior,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub response_curve: Option<ResponseCurve>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
     pub fire_mode: FireMode,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub fire_gesture: Option<FireGesture>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
     pub round_target_value: bool,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub glide: Option<Glide>,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub scale_quantization: Option<ScaleQuantization>,
     // Serialization skipped because this is deprecated in favor of takeover_mode
     // since ReaLearn v2.8.0-pre3.
     #[serde(
@@ -185,6 +223,13 @@ pub struct ModeModelData {
         skip_serializing_if = "is_default"
     )]
     pub make_absolute_enabled: bool,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub encoder_acceleration: Option<EncoderAcceleration>,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
@@ -209,6 +254,13 @@ pub struct ModeModelData {
         skip_serializing_if = "is_default"
     )]
     pub feedback_value_table: Option<FeedbackValueTable>,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub feedback_animations: Vec<FeedbackAnimation>,
 }
 
 fn default_step_size() -> SoftSymmetricUnitValue {
@@ -243,6 +295,7 @@ impl ModeModelData {
             max_press_millis: model.press_duration_interval().max_val().as_millis() as _,
             turbo_rate: model.turbo_rate().as_millis() as _,
             eel_control_transformation: model.eel_control_transformation().to_owned(),
+            control_transformation_kind: model.control_transformation_kind(),
             eel_feedback_transformation: if model.feedback_type().is_textual() {
                 model.textual_feedback_expression().to_owned()
             } else {
@@ -254,8 +307,12 @@ impl ModeModelData {
             // Not used anymore since ReaLearn v1.11.0
             ignore_out_of_range_source_values_is_enabled: false,
             out_of_range_behavior: model.out_of_range_behavior(),
+            response_curve: model.response_curve().cloned(),
             fire_mode: model.fire_mode(),
+            fire_gesture: model.fire_gesture().cloned(),
             round_target_value: model.round_target_value(),
+            glide: model.glide().cloned(),
+            scale_quantization: model.scale_quantization().cloned(),
             // Not used anymore since ReaLearn v2.8.0-pre3
             scale_mode_enabled: false,
             takeover_mode: model.takeover_mode(),
@@ -263,10 +320,12 @@ impl ModeModelData {
             encoder_usage: model.encoder_usage(),
             rotate_is_enabled: model.rotate(),
             make_absolute_enabled: model.make_absolute(),
+            encoder_acceleration: model.encoder_acceleration().cloned(),
             group_interaction: model.group_interaction(),
             target_value_sequence: model.target_value_sequence().clone(),
             feedback_type: model.feedback_type(),
             feedback_value_table: model.feedback_value_table().cloned(),
+            feedback_animations: model.feedback_animations().to_vec(),
         }
     }
 
@@ -366,6 +425,9 @@ impl ModeModelData {
         model.change(P::SetEelControlTransformation(
             self.eel_control_transformation.clone(),
         ));
+        model.change(P::SetControlTransformationKind(
+            self.control_transformation_kind,
+        ));
         let (eel_fb_transformation, textual_fb_expression) = if self.feedback_type.is_textual() {
             (String::new(), self.eel_feedback_transformation.clone())
         } else {
@@ -385,18 +447,24 @@ impl ModeModelData {
             self.out_of_range_behavior
         };
         model.change(P::SetFireMode(self.fire_mode));
+        model.change(P::SetFireGesture(self.fire_gesture.clone()));
         model.change(P::SetOutOfRangeBehavior(actual_out_of_range_behavior));
+        model.change(P::SetResponseCurve(self.response_curve.clone()));
         model.change(P::SetRoundTargetValue(self.round_target_value));
+        model.change(P::SetGlide(self.glide.clone()));
+        model.change(P::SetScaleQuantization(self.scale_quantization.clone()));
         model.change(P::SetButtonUsage(self.button_usage));
         model.change(P::SetEncoderUsage(self.encoder_usage));
         model.change(P::SetRotate(self.rotate_is_enabled));
         model.change(P::SetMakeAbsolute(self.make_absolute_enabled));
+        model.change(P::SetEncoderAcceleration(self.encoder_acceleration.clone()));
         model.change(P::SetGroupInteraction(self.group_interaction));
         model.change(P::SetTargetValueSequence(
             self.target_value_sequence.clone(),
         ));
         model.change(P::SetFeedbackType(self.feedback_type));
         model.change(P::SetFeedbackValueTable(self.feedback_value_table.clone()));
+        model.change(P::SetFeedbackAnimations(self.feedback_animations.clone()));
     }
 }
 
@@ -3,23 +3,25 @@ use crate::application::{CompartmentPresetManager, CompartmentPresetModel};
 use crate::base::notification;
 use crate::base::notification::{warn_user_about_anyhow_error, warn_user_on_anyhow_error};
 use crate::domain::{
-    CompartmentKind, FsDirLuaModuleFinder, IncludedDirLuaModuleFinder, LuaModuleContainer,
-    LuaModuleFinder, SafeLua,
+    parse_lua_frontmatter, CompartmentKind, FsDirLuaModuleFinder, LuaModuleContainer,
+    LuaModuleFinder, PresetBuildEnvironment, PresetBuildRecording, SafeLua, Unit,
 };
 use crate::infrastructure::api::convert::to_data::convert_compartment;
-use crate::infrastructure::data::CompartmentPresetData;
+use crate::infrastructure::data::{
+    CompartmentPresetData, PresetBundleFile, PresetQuery, ReaperPresetBuildEnvironment,
+};
 use crate::infrastructure::plugin::{midi_output_port_patterns_match, BackboneShell};
 use anyhow::{anyhow, bail, Context};
 use base::byte_pattern::BytePattern;
 use base::file_util;
 use base::file_util::is_hidden;
+use base::hash_util::NonCryptoHashMap;
 use include_dir::{include_dir, Dir};
-use itertools::Itertools;
 use mlua::LuaSerdeExt;
 use realearn_api::persistence::{
     CommonPresetMetaData, ControllerPresetMetaData, MainPresetMetaData, VirtualControlSchemeId,
 };
-use reaper_high::Reaper;
+use reaper_high::{Project, Reaper};
 use rxrust::prelude::*;
 use serde::Deserialize;
 use std::borrow::Cow;
@@ -50,6 +52,23 @@ pub struct FileBasedCompartmentPresetManager<M> {
     preset_infos: Vec<PresetInfo<M>>,
     changed_subject: LocalSubject<'static, (), ()>,
     event_handler: Box<dyn CompartmentPresetManagerEventHandler<Source = Self>>,
+    lua_preset_cache: RefCell<LuaPresetCache<CompartmentPresetModel>>,
+}
+
+/// Lua presets which have been built already, by preset ID.
+///
+/// Building a Lua preset again leads to the same result as long as its code, the modules and the
+/// environment information that it has read didn't change.
+#[derive(Debug)]
+struct LuaPresetCache<T> {
+    entries: NonCryptoHashMap<String, CachedLuaPreset<T>>,
+}
+
+#[derive(Debug)]
+struct CachedLuaPreset<T> {
+    file_content: String,
+    recording: PresetBuildRecording,
+    preset: T,
 }
 
 impl FileBasedCompartmentPresetManager<ControllerPresetMetaData> {
@@ -135,10 +154,27 @@ pub trait CommonCompartmentPresetManager {
     fn remove_preset(&mut self, id: &str) -> anyhow::Result<()>;
     fn common_preset_infos(&self) -> Box<dyn Iterator<Item = &CommonPresetInfo> + '_>;
     fn common_preset_info_by_id(&self, id: &str) -> Option<&CommonPresetInfo>;
+    /// Returns all presets matching the given query.
+    fn query_common_preset_infos<'a>(
+        &'a self,
+        query: &'a PresetQuery,
+    ) -> Box<dyn Iterator<Item = &'a CommonPresetInfo> + 'a>;
+    /// Returns the virtual control schemes provided (controller presets) or used (main presets)
+    /// by the given preset.
+    fn preset_virtual_control_schemes(&self, id: &str) -> Option<&HashSet<VirtualControlSchemeId>>;
     fn export_preset_workspace(
         &mut self,
         include_factory_presets: bool,
     ) -> anyhow::Result<PresetWorkspaceDescriptor>;
+    /// Collects all files in the given namespace (a sub folder of the preset folder), including
+    /// Lua modules and other files which are not presets.
+    fn collect_preset_bundle_files(&self, namespace: &str)
+        -> anyhow::Result<Vec<PresetBundleFile>>;
+    /// Returns a description of each installed preset or file which would be overwritten when
+    /// installing the given files.
+    fn find_preset_bundle_collisions(&self, files: &[PresetBundleFile]) -> Vec<String>;
+    /// Writes the given files into the preset folder and reloads all presets.
+    fn install_preset_bundle_files(&mut self, files: &[PresetBundleFile]) -> anyhow::Result<()>;
 }
 
 pub struct PresetWorkspaceDescriptor {
@@ -258,11 +294,37 @@ struct CombinedPresetMetaData<S> {
     specific: S,
 }
 
-pub trait SpecificPresetMetaData: fmt::Debug + for<'a> Deserialize<'a> {}
+pub trait SpecificPresetMetaData: fmt::Debug + for<'a> Deserialize<'a> {
+    /// Virtual control schemes provided (controller presets) or used (main presets).
+    fn virtual_control_schemes(&self) -> &HashSet<VirtualControlSchemeId>;
+
+    /// Device manufacturer and name (only available for controller presets).
+    fn device_names(&self) -> Vec<&str>;
+}
+
+impl SpecificPresetMetaData for ControllerPresetMetaData {
+    fn virtual_control_schemes(&self) -> &HashSet<VirtualControlSchemeId> {
+        &self.provided_schemes
+    }
 
-impl SpecificPresetMetaData for ControllerPresetMetaData {}
+    fn device_names(&self) -> Vec<&str> {
+        [&self.device_manufacturer, &self.device_name]
+            .into_iter()
+            .flatten()
+            .map(|n| n.as_str())
+            .collect()
+    }
+}
 
-impl SpecificPresetMetaData for MainPresetMetaData {}
+impl SpecificPresetMetaData for MainPresetMetaData {
+    fn virtual_control_schemes(&self) -> &HashSet<VirtualControlSchemeId> {
+        &self.used_schemes
+    }
+
+    fn device_names(&self) -> Vec<&str> {
+        vec![]
+    }
+}
 
 impl<S: SpecificPresetMetaData> CombinedPresetMetaData<S> {
     pub fn from_json(json: &str) -> anyhow::Result<Self> {
@@ -286,6 +348,7 @@ impl<S: SpecificPresetMetaData> FileBasedCompartmentPresetManager<S> {
             preset_infos: vec![],
             changed_subject: Default::default(),
             event_handler,
+            lua_preset_cache: Default::default(),
         }
     }
 
@@ -300,6 +363,7 @@ impl<S: SpecificPresetMetaData> FileBasedCompartmentPresetManager<S> {
         let compartment = self.compartment;
         let factory_preset_dir = get_factory_preset_dir(compartment);
         let mut all_preset_infos = vec![];
+        self.lua_preset_cache.get_mut().clear();
         walk_included_dir(factory_preset_dir, false, &mut |file| {
             let relative_file_path = file.path();
             let Some(basics) = PresetBasics::from_relative_path(relative_file_path, "factory/")
@@ -413,14 +477,39 @@ impl<S: SpecificPresetMetaData> FileBasedCompartmentPresetManager<S> {
         &self.preset_infos
     }
 
+    pub fn query_presets<'a>(
+        &'a self,
+        query: &'a PresetQuery,
+    ) -> impl Iterator<Item = &'a PresetInfo<S>> + 'a {
+        self.preset_infos
+            .iter()
+            .filter(move |info| query.matches(info))
+    }
+
     fn notify_presets_changed(&mut self) {
         self.event_handler.presets_changed(self);
         self.changed_subject.next(());
     }
 
+    fn find_by_id_internal(
+        &self,
+        id: &str,
+        environment: ReaperPresetBuildEnvironment,
+    ) -> Option<CompartmentPresetModel> {
+        let preset_info = self.preset_infos.iter().find(|info| info.common.id == id)?;
+        match self.load_full_preset(preset_info, Rc::new(environment)) {
+            Ok(p) => Some(p),
+            Err(e) => {
+                warn_user_about_anyhow_error(e);
+                None
+            }
+        }
+    }
+
     fn load_full_preset(
         &self,
         preset_info: &PresetInfo<S>,
+        environment: Rc<dyn PresetBuildEnvironment>,
     ) -> anyhow::Result<CompartmentPresetModel> {
         let file_content: Cow<str> = match &preset_info.common.origin {
             PresetOrigin::User { absolute_file_path } => fs::read_to_string(absolute_file_path)
@@ -480,8 +569,21 @@ impl<S: SpecificPresetMetaData> FileBasedCompartmentPresetManager<S> {
                         Ok(Rc::new(IncludedDirLuaModuleFinder::new(module_root)))
                     }
                 };
+                let id = &preset_info.common.id;
+                if let Ok(finder) = &module_finder {
+                    let cached_preset = self.lua_preset_cache.borrow().get(
+                        id,
+                        &file_content,
+                        finder.as_ref(),
+                        environment.as_ref(),
+                    );
+                    if let Some(preset) = cached_preset {
+                        return Ok(preset);
+                    }
+                }
                 let lua = lua.start_execution_time_limit_countdown()?;
-                let module_container = LuaModuleContainer::new(module_finder);
+                let module_container =
+                    LuaModuleContainer::new(module_finder).with_build_environment(environment);
                 let value = module_container.execute_as_module(
                     lua.as_ref(),
                     Some(preset_info.common.normalized_relative_path.clone()),
@@ -502,12 +604,61 @@ impl<S: SpecificPresetMetaData> FileBasedCompartmentPresetManager<S> {
                     self.compartment,
                     compartment_model,
                 );
+                self.lua_preset_cache.borrow_mut().insert(
+                    id.clone(),
+                    file_content.into_owned(),
+                    module_container.recording(),
+                    preset_model.clone(),
+                );
                 Ok(preset_model)
             }
         }
     }
 }
 
+impl<T> Default for LuaPresetCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+impl<T: Clone> LuaPresetCache<T> {
+    /// Returns the cached preset if it would still be built the same way.
+    fn get(
+        &self,
+        id: &str,
+        file_content: &str,
+        finder: &dyn LuaModuleFinder,
+        environment: &dyn PresetBuildEnvironment,
+    ) -> Option<T> {
+        let cached = self.entries.get(id)?;
+        let up_to_date = cached.file_content == file_content
+            && cached.recording.is_up_to_date(finder, environment);
+        up_to_date.then(|| cached.preset.clone())
+    }
+
+    fn insert(
+        &mut self,
+        id: String,
+        file_content: String,
+        recording: PresetBuildRecording,
+        preset: T,
+    ) {
+        let cached = CachedLuaPreset {
+            file_content,
+            recording,
+            preset,
+        };
+        self.entries.insert(id, cached);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 fn get_factory_preset_content(
     compartment: CompartmentKind,
     relative_file_path: &Path,
@@ -551,6 +702,18 @@ impl<M: SpecificPresetMetaData> CommonCompartmentPresetManager
             .map(|info| &info.common)
     }
 
+    fn query_common_preset_infos<'a>(
+        &'a self,
+        query: &'a PresetQuery,
+    ) -> Box<dyn Iterator<Item = &'a CommonPresetInfo> + 'a> {
+        Box::new(self.query_presets(query).map(|info| &info.common))
+    }
+
+    fn preset_virtual_control_schemes(&self, id: &str) -> Option<&HashSet<VirtualControlSchemeId>> {
+        let info = self.find_preset_info_by_id(id)?;
+        Some(info.specific_meta_data.virtual_control_schemes())
+    }
+
     fn export_preset_workspace(
         &mut self,
         include_factory_presets: bool,
@@ -581,18 +744,81 @@ impl<M: SpecificPresetMetaData> CommonCompartmentPresetManager
         };
         Ok(desc)
     }
+
+    fn collect_preset_bundle_files(
+        &self,
+        namespace: &str,
+    ) -> anyhow::Result<Vec<PresetBundleFile>> {
+        let namespace_dir = self.preset_dir_path.join(namespace);
+        if !namespace_dir.is_dir() {
+            bail!(
+                "Preset folder \"{}\" doesn't exist.",
+                namespace_dir.display()
+            );
+        }
+        WalkDir::new(&namespace_dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| !file_util::is_hidden(e.file_name()))
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| {
+                let relative_path = entry.path().strip_prefix(&self.preset_dir_path)?;
+                let file = PresetBundleFile {
+                    relative_path: relative_path.to_path_buf(),
+                    content: fs::read(entry.path())?,
+                };
+                Ok(file)
+            })
+            .collect()
+    }
+
+    fn find_preset_bundle_collisions(&self, files: &[PresetBundleFile]) -> Vec<String> {
+        files
+            .iter()
+            .filter_map(|file| {
+                let existing_preset = PresetBasics::from_relative_path(&file.relative_path, "")
+                    .and_then(|basics| self.find_preset_info_by_id(&basics.id));
+                if let Some(info) = existing_preset {
+                    // Looking up by ID because the existing preset might have another file type
+                    return Some(format!(
+                        "Preset \"{}\" ({})",
+                        info.common.meta_data.name, info.common.id
+                    ));
+                }
+                if self.preset_dir_path.join(&file.relative_path).exists() {
+                    return Some(format!("File \"{}\"", file.relative_path.display()));
+                }
+                None
+            })
+            .collect()
+    }
+
+    fn install_preset_bundle_files(&mut self, files: &[PresetBundleFile]) -> anyhow::Result<()> {
+        for file in files {
+            let path = self.preset_dir_path.join(&file.relative_path);
+            fs::create_dir_all(path.parent().context("impossible")?)
+                .context("couldn't create preset directory")?;
+            fs::write(&path, &file.content)
+                .with_context(|| format!("couldn't write file {}", path.display()))?;
+        }
+        let _ = self.load_presets_from_disk();
+        Ok(())
+    }
 }
 
 impl<M: SpecificPresetMetaData> CompartmentPresetManager for FileBasedCompartmentPresetManager<M> {
     fn find_by_id(&self, id: &str) -> Option<CompartmentPresetModel> {
-        let preset_info = self.preset_infos.iter().find(|info| info.common.id == id)?;
-        match self.load_full_preset(preset_info) {
-            Ok(p) => Some(p),
-            Err(e) => {
-                warn_user_about_anyhow_error(e);
-                None
-            }
-        }
+        self.find_by_id_internal(id, Default::default())
+    }
+
+    fn find_by_id_for_unit(
+        &self,
+        id: &str,
+        unit: &Unit,
+        project: Project,
+    ) -> Option<CompartmentPresetModel> {
+        self.find_by_id_internal(id, ReaperPresetBuildEnvironment::for_unit(unit, project))
     }
 }
 
@@ -600,6 +826,15 @@ impl<T: CompartmentPresetManager> CompartmentPresetManager for Rc<RefCell<T>> {
     fn find_by_id(&self, id: &str) -> Option<CompartmentPresetModel> {
         self.borrow().find_by_id(id)
     }
+
+    fn find_by_id_for_unit(
+        &self,
+        id: &str,
+        unit: &Unit,
+        project: Project,
+    ) -> Option<CompartmentPresetModel> {
+        self.borrow().find_by_id_for_unit(id, unit, project)
+    }
 }
 
 pub static FACTORY_CONTROLLER_PRESETS_DIR: Dir<'_> =
@@ -674,28 +909,205 @@ pub fn get_factory_preset_dir(compartment: CompartmentKind) -> &'static Dir<'sta
     }
 }
 
-pub fn parse_lua_frontmatter<T: for<'a> Deserialize<'a>>(lua_code: &str) -> anyhow::Result<T> {
-    const PREFIX: &str = "--- ";
-    let frontmatter = lua_code
-        .lines()
-        .map_while(|line| {
-            if let Some(yaml_line) = line.strip_prefix(PREFIX) {
-                // Valid non-empty YAML frontmatter line
-                Some(yaml_line)
-            } else if line.starts_with("---") {
-                // Valid empty SYAML frontmatter line
-                Some("")
-            } else {
-                // End of frontmatter
-                None
+/// Finds Lua modules within a specified binary-included directory.
+#[derive(Clone)]
+pub struct IncludedDirLuaModuleFinder {
+    dir: Dir<'static>,
+}
+
+impl IncludedDirLuaModuleFinder {
+    pub fn new(dir: Dir<'static>) -> Self {
+        Self { dir }
+    }
+}
+
+impl LuaModuleFinder for IncludedDirLuaModuleFinder {
+    fn module_root_path(&self) -> String {
+        "factory:/".to_string()
+    }
+
+    fn find_source_by_path(&self, path: &str) -> Option<Cow<'static, str>> {
+        let contents = self.dir.get_file(path)?.contents_utf8()?;
+        Some(contents.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PresetBuildQuery;
+    use serde_json::json;
+
+    const PRESET_CODE: &str = r#"
+        local runtime = require("preset_runtime")
+        local lib = require("lib")
+        return { tracks = runtime.get_tracks(), lib = lib }
+    "#;
+
+    #[test]
+    fn lua_preset_cache_hit_if_nothing_changed() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        // When
+        let preset = build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        let cached_preset = cache.get("my/preset", PRESET_CODE, &finder, &environment);
+        // Then
+        assert_eq!(cached_preset, Some(preset));
+    }
+
+    #[test]
+    fn lua_preset_cache_miss_if_answer_changed() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        // When
+        environment.set_tracks(json!(["Track 1", "Track 2"]));
+        let cached_preset = cache.get("my/preset", PRESET_CODE, &finder, &environment);
+        // Then
+        assert_eq!(cached_preset, None);
+    }
+
+    #[test]
+    fn lua_preset_cache_miss_if_module_source_changed() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        // When
+        finder.set_lib_source("return 2");
+        let cached_preset = cache.get("my/preset", PRESET_CODE, &finder, &environment);
+        // Then
+        assert_eq!(cached_preset, None);
+    }
+
+    #[test]
+    fn lua_preset_cache_miss_if_file_content_changed() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        // When
+        let changed_code = format!("{PRESET_CODE}\n-- Comment");
+        let cached_preset = cache.get("my/preset", &changed_code, &finder, &environment);
+        // Then
+        assert_eq!(cached_preset, None);
+    }
+
+    #[test]
+    fn lua_preset_cache_hit_after_rebuild() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        environment.set_tracks(json!(["Track 1", "Track 2"]));
+        // When
+        let preset = build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        let cached_preset = cache.get("my/preset", PRESET_CODE, &finder, &environment);
+        // Then
+        assert_eq!(preset["tracks"], json!(["Track 1", "Track 2"]));
+        assert_eq!(cached_preset, Some(preset));
+    }
+
+    #[test]
+    fn lua_preset_cache_miss_after_clear() {
+        // Given
+        let finder = FakeModuleFinder::new("return 1");
+        let environment = FakeEnvironment::new(json!(["Track 1"]));
+        let mut cache = LuaPresetCache::default();
+        build_and_cache_preset(&mut cache, PRESET_CODE, &finder, &environment);
+        // When
+        cache.clear();
+        let cached_preset = cache.get("my/preset", PRESET_CODE, &finder, &environment);
+        // Then
+        assert_eq!(cached_preset, None);
+    }
+
+    /// Builds the given preset code in the same way as presets are built in practice and caches
+    /// its result.
+    fn build_and_cache_preset(
+        cache: &mut LuaPresetCache<serde_json::Value>,
+        code: &str,
+        finder: &FakeModuleFinder,
+        environment: &FakeEnvironment,
+    ) -> serde_json::Value {
+        let lua = SafeLua::new().unwrap();
+        let module_container = LuaModuleContainer::new(Ok(finder.clone()))
+            .with_build_environment(Rc::new(environment.clone()));
+        let value = module_container
+            .execute_as_module(lua.as_ref(), None, "Test".to_string(), code)
+            .unwrap();
+        let preset: serde_json::Value = lua.as_ref().from_value(value).unwrap();
+        cache.insert(
+            "my/preset".to_string(),
+            code.to_string(),
+            module_container.recording(),
+            preset.clone(),
+        );
+        preset
+    }
+
+    /// Only knows the module `lib.lua`.
+    #[derive(Clone)]
+    struct FakeModuleFinder {
+        lib_source: Rc<RefCell<String>>,
+    }
+
+    impl FakeModuleFinder {
+        fn new(lib_source: &str) -> Self {
+            Self {
+                lib_source: Rc::new(RefCell::new(lib_source.to_string())),
             }
-        })
-        .join("\n");
-    if frontmatter.is_empty() {
-        bail!("Lua presets need at least a \"{PREFIX} name: ...\" line at the very top!");
+        }
+
+        fn set_lib_source(&self, lib_source: &str) {
+            *self.lib_source.borrow_mut() = lib_source.to_string();
+        }
+    }
+
+    impl LuaModuleFinder for FakeModuleFinder {
+        fn module_root_path(&self) -> String {
+            "fake".to_string()
+        }
+
+        fn find_source_by_path(&self, path: &str) -> Option<Cow<'static, str>> {
+            if path != "lib.lua" {
+                return None;
+            }
+            Some(self.lib_source.borrow().clone().into())
+        }
+    }
+
+    /// Only knows tracks.
+    #[derive(Clone)]
+    struct FakeEnvironment {
+        tracks: Rc<RefCell<serde_json::Value>>,
+    }
+
+    impl FakeEnvironment {
+        fn new(tracks: serde_json::Value) -> Self {
+            Self {
+                tracks: Rc::new(RefCell::new(tracks)),
+            }
+        }
+
+        fn set_tracks(&self, tracks: serde_json::Value) {
+            *self.tracks.borrow_mut() = tracks;
+        }
+    }
+
+    impl PresetBuildEnvironment for FakeEnvironment {
+        fn query(&self, query: &PresetBuildQuery) -> anyhow::Result<serde_json::Value> {
+            match query {
+                PresetBuildQuery::Tracks => Ok(self.tracks.borrow().clone()),
+                _ => Ok(serde_json::Value::Null),
+            }
+        }
     }
-    let value: T = serde_yaml::from_str(&frontmatter).map_err(|e| {
-        anyhow!("Error while parsing Lua preset frontmatter:\n\n{e}\n\nFrontmatter was:\n===\n{frontmatter}\n===\n")
-    })?;
-    Ok(value)
 }
@@ -0,0 +1,210 @@
+use crate::domain::{
+    available_midi_input_devices, get_fx_name, CompartmentKind, PresetBuildCompartment,
+    PresetBuildEnvironment, PresetBuildQuery, Unit,
+};
+use crate::infrastructure::plugin::BackboneShell;
+use anyhow::{anyhow, Context};
+use base::hash_util::NonCryptoHashMap;
+use enum_map::EnumMap;
+use reaper_high::{Guid, Project, Reaper, Track};
+use serde::Serialize;
+
+/// Answers queries of Lua presets by looking at the unit's REAPER project and the configured
+/// devices.
+#[derive(Debug, Default)]
+pub struct ReaperPresetBuildEnvironment {
+    custom_data: EnumMap<CompartmentKind, NonCryptoHashMap<String, serde_json::Value>>,
+    /// `None` means the current project is used.
+    project: Option<Project>,
+}
+
+#[derive(Serialize)]
+struct TrackInfo {
+    guid: String,
+    index: u32,
+    name: String,
+    /// GUID of the folder track which contains this track.
+    parent: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FxInfo {
+    index: u32,
+    guid: Option<String>,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct FxParameterInfo {
+    index: u32,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct FocusedFxInfo {
+    track: String,
+    fx_index: u32,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct DeviceInfo {
+    id: String,
+    name: String,
+}
+
+impl ReaperPresetBuildEnvironment {
+    /// Creates an environment which gives access to the custom compartment data of the given unit
+    /// and to the given project (which should be the project in which the unit lives).
+    pub fn for_unit(unit: &Unit, project: Project) -> Self {
+        let mut custom_data: EnumMap<CompartmentKind, _> = Default::default();
+        for compartment in CompartmentKind::enum_iter() {
+            custom_data[compartment] = unit.custom_compartment_data(compartment).clone();
+        }
+        Self {
+            custom_data,
+            project: Some(project),
+        }
+    }
+}
+
+impl PresetBuildEnvironment for ReaperPresetBuildEnvironment {
+    fn query(&self, query: &PresetBuildQuery) -> anyhow::Result<serde_json::Value> {
+        let project = self
+            .project
+            .unwrap_or_else(|| Reaper::get().current_project());
+        let answer = match query {
+            PresetBuildQuery::Tracks => serde_json::to_value(get_tracks(project))?,
+            PresetBuildQuery::Fxs { track_guid } => {
+                let track = find_track(project, track_guid)?;
+                let fxs: Vec<_> = track
+                    .normal_fx_chain()
+                    .fxs()
+                    .map(|fx| FxInfo {
+                        index: fx.index(),
+                        guid: fx.guid().map(|g| g.to_string_without_braces()),
+                        name: get_fx_name(&fx),
+                    })
+                    .collect();
+                serde_json::to_value(fxs)?
+            }
+            PresetBuildQuery::FxParameters {
+                track_guid,
+                fx_index,
+            } => {
+                let track = find_track(project, track_guid)?;
+                let fx = track
+                    .normal_fx_chain()
+                    .fx_by_index(*fx_index)
+                    .with_context(|| format!("track has no FX at index {fx_index}"))?;
+                let params: Vec<_> = fx
+                    .parameters()
+                    .map(|p| FxParameterInfo {
+                        index: p.index(),
+                        // Parameter names are not reliably UTF-8-encoded
+                        name: p
+                            .name()
+                            .map(|n| n.into_inner().to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+                serde_json::to_value(params)?
+            }
+            PresetBuildQuery::FocusedFx => {
+                let focused_fx = Reaper::get().focused_fx().and_then(|res| {
+                    let fx = res.fx;
+                    if fx.is_input_fx() {
+                        return None;
+                    }
+                    let track = fx.track()?;
+                    if track.project() != project {
+                        // Focused FX lives in another project tab
+                        return None;
+                    }
+                    let info = FocusedFxInfo {
+                        track: track.guid().to_string_without_braces(),
+                        fx_index: fx.index(),
+                        name: get_fx_name(&fx),
+                    };
+                    Some(info)
+                });
+                serde_json::to_value(focused_fx)?
+            }
+            PresetBuildQuery::MidiInputDevices => {
+                let devs: Vec<_> = available_midi_input_devices()
+                    .map(|d| DeviceInfo {
+                        id: d.id().get().to_string(),
+                        name: d.name().unwrap_or_default().into_string(),
+                    })
+                    .collect();
+                serde_json::to_value(devs)?
+            }
+            PresetBuildQuery::MidiOutputDevices => {
+                let devs: Vec<_> = Reaper::get()
+                    .midi_output_devices()
+                    .filter(|d| d.is_available())
+                    .map(|d| DeviceInfo {
+                        id: d.id().get().to_string(),
+                        name: d.name().unwrap_or_default().into_string(),
+                    })
+                    .collect();
+                serde_json::to_value(devs)?
+            }
+            PresetBuildQuery::OscDevices => {
+                let dev_manager = BackboneShell::get().osc_device_manager();
+                let dev_manager = dev_manager.borrow();
+                let devs: Vec<_> = dev_manager
+                    .devices()
+                    .map(|d| DeviceInfo {
+                        id: d.id().to_string(),
+                        name: d.name().to_string(),
+                    })
+                    .collect();
+                serde_json::to_value(devs)?
+            }
+            PresetBuildQuery::CustomData { compartment, key } => {
+                let compartment = match compartment {
+                    PresetBuildCompartment::Controller => CompartmentKind::Controller,
+                    PresetBuildCompartment::Main => CompartmentKind::Main,
+                };
+                self.custom_data[compartment]
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_default()
+            }
+        };
+        Ok(answer)
+    }
+}
+
+fn get_tracks(project: Project) -> Vec<TrackInfo> {
+    let mut folder_stack: Vec<String> = vec![];
+    project
+        .tracks()
+        .map(|t| {
+            let guid = t.guid().to_string_without_braces();
+            let info = TrackInfo {
+                guid: guid.clone(),
+                index: t.index().unwrap_or_default(),
+                name: t.name().map(|n| n.into_string()).unwrap_or_default(),
+                parent: folder_stack.last().cloned(),
+            };
+            let folder_depth_change = t.folder_depth_change();
+            if folder_depth_change > 0 {
+                folder_stack.push(guid);
+            } else {
+                for _ in 0..folder_depth_change.unsigned_abs() {
+                    folder_stack.pop();
+                }
+            }
+            info
+        })
+        .collect()
+}
+
+fn find_track(project: Project, guid: &str) -> anyhow::Result<Track> {
+    let guid = Guid::from_string_without_braces(guid).map_err(anyhow::Error::msg)?;
+    project
+        .track_by_guid(&guid)
+        .map_err(|_| anyhow!("track {} not found", guid.to_string_without_braces()))
+}
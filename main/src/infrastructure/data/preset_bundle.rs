@@ -0,0 +1,227 @@
+use crate::domain::CompartmentKind;
+use crate::infrastructure::data::MigrationDescriptor;
+use crate::infrastructure::plugin::BackboneShell;
+use anyhow::{bail, Context};
+use enum_map::EnumMap;
+use realearn_api::persistence::PresetBundleManifest;
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Maximum total size of the unpacked files of a preset bundle. Protects against zip bombs.
+const MAX_UNCOMPRESSED_SIZE: u64 = 100 * 1024 * 1024;
+
+/// A set of controller and main presets (including shared Lua modules) which can be shared as one
+/// zip file.
+#[derive(Clone, Debug)]
+pub struct PresetBundle {
+    pub manifest: PresetBundleManifest,
+    pub files: EnumMap<CompartmentKind, Vec<PresetBundleFile>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PresetBundleFile {
+    /// Path relative to the controller or main preset folder, e.g. `my-namespace/util.luau`.
+    pub relative_path: PathBuf,
+    pub content: Vec<u8>,
+}
+
+/// Result of checking whether a preset bundle can be installed.
+#[derive(Clone, Debug, Default)]
+pub struct PresetBundleImportCheck {
+    /// Whether the bundle has been built for an older ReaLearn version.
+    ///
+    /// Its presets will be migrated when loading them.
+    pub needs_migration: bool,
+    /// Installed presets and files which would be overwritten.
+    pub collisions: Vec<String>,
+    /// IDs of presets which the bundle depends on but which are not installed.
+    pub missing_dependencies: Vec<String>,
+}
+
+impl PresetBundle {
+    /// Collects the files of the given preset namespaces (sub folders of the preset folders).
+    pub fn collect(
+        manifest: PresetBundleManifest,
+        namespaces: &[(CompartmentKind, String)],
+    ) -> anyhow::Result<Self> {
+        let mut files: EnumMap<CompartmentKind, Vec<PresetBundleFile>> = Default::default();
+        for (compartment, namespace) in namespaces {
+            let namespace_files = BackboneShell::get()
+                .compartment_preset_manager(*compartment)
+                .borrow()
+                .collect_preset_bundle_files(namespace)?;
+            files[*compartment].extend(namespace_files);
+        }
+        let bundle = Self { manifest, files };
+        Ok(bundle)
+    }
+
+    pub fn read_zip(reader: impl Read + Seek) -> anyhow::Result<Self> {
+        let mut archive =
+            ZipArchive::new(reader).context("preset bundle is not a valid zip file")?;
+        let mut manifest = None;
+        let mut files: EnumMap<CompartmentKind, Vec<PresetBundleFile>> = Default::default();
+        let mut remaining_size = MAX_UNCOMPRESSED_SIZE;
+        for i in 0..archive.len() {
+            let mut zip_file = archive.by_index(i)?;
+            if zip_file.is_dir() {
+                continue;
+            }
+            let path = zip_file
+                .enclosed_name()
+                .with_context(|| format!("invalid file path \"{}\"", zip_file.name()))?
+                .to_path_buf();
+            // The sizes declared in the zip file can't be trusted, so we limit the actual reads
+            let mut content = vec![];
+            let read_size = (&mut zip_file)
+                .take(remaining_size + 1)
+                .read_to_end(&mut content)? as u64;
+            if read_size > remaining_size {
+                bail!(
+                    "preset bundle is too large when unpacked (more than {} MB)",
+                    MAX_UNCOMPRESSED_SIZE / (1024 * 1024)
+                );
+            }
+            remaining_size -= read_size;
+            if path == Path::new(MANIFEST_FILE_NAME) {
+                manifest = Some(serde_json::from_slice(&content).context("invalid manifest")?);
+                continue;
+            }
+            let (compartment, relative_path) = split_bundle_path(&path)?;
+            files[compartment].push(PresetBundleFile {
+                relative_path,
+                content,
+            });
+        }
+        let bundle = Self {
+            manifest: manifest.context("preset bundle doesn't contain a manifest")?,
+            files,
+        };
+        Ok(bundle)
+    }
+
+    pub fn write_zip(&self, writer: impl Write + Seek) -> anyhow::Result<()> {
+        let mut zip = ZipWriter::new(writer);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(MANIFEST_FILE_NAME, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&self.manifest)?)?;
+        for (compartment, files) in &self.files {
+            for file in files {
+                let path = Path::new(compartment_dir_name(compartment)).join(&file.relative_path);
+                // Zip files always use forward slashes
+                let name = path.to_string_lossy().replace('\\', "/");
+                zip.start_file(name, options)?;
+                zip.write_all(&file.content)?;
+            }
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Checks whether this bundle can be installed.
+    ///
+    /// Returns an error if the bundle has been built for a newer ReaLearn version.
+    pub fn check_import(&self) -> anyhow::Result<PresetBundleImportCheck> {
+        let required_version = &self.manifest.realearn_version;
+        if BackboneShell::version() < required_version {
+            bail!(
+                "Preset bundle \"{}\" has been built for ReaLearn {required_version}, which is newer than the installed version {}. Please update your ReaLearn version.",
+                self.manifest.name,
+                BackboneShell::version()
+            );
+        }
+        let mut check = PresetBundleImportCheck {
+            needs_migration: MigrationDescriptor::new(Some(required_version)).is_required(),
+            ..Default::default()
+        };
+        let dependencies = &self.manifest.dependencies;
+        for compartment in CompartmentKind::enum_iter() {
+            let preset_manager = BackboneShell::get().compartment_preset_manager(compartment);
+            let preset_manager = preset_manager.borrow();
+            check
+                .collisions
+                .extend(preset_manager.find_preset_bundle_collisions(&self.files[compartment]));
+            let dependency_ids = match compartment {
+                CompartmentKind::Controller => &dependencies.controller_presets,
+                CompartmentKind::Main => &dependencies.main_presets,
+            };
+            let missing_ids = dependency_ids
+                .iter()
+                .filter(|id| preset_manager.common_preset_info_by_id(id).is_none())
+                .cloned();
+            check.missing_dependencies.extend(missing_ids);
+        }
+        Ok(check)
+    }
+
+    /// Writes all files into the preset folders, overwriting existing ones.
+    pub fn install(&self) -> anyhow::Result<()> {
+        for (compartment, files) in &self.files {
+            BackboneShell::get()
+                .compartment_preset_manager(compartment)
+                .borrow_mut()
+                .install_preset_bundle_files(files)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a path within the bundle into compartment and path relative to the compartment preset
+/// folder.
+fn split_bundle_path(path: &Path) -> anyhow::Result<(CompartmentKind, PathBuf)> {
+    let mut components = path.components();
+    let compartment = match components.next() {
+        Some(Component::Normal(c)) if c == compartment_dir_name(CompartmentKind::Controller) => {
+            CompartmentKind::Controller
+        }
+        Some(Component::Normal(c)) if c == compartment_dir_name(CompartmentKind::Main) => {
+            CompartmentKind::Main
+        }
+        _ => bail!(
+            "unexpected file \"{}\" (files must be located in the controller or main folder)",
+            path.display()
+        ),
+    };
+    let relative_path: PathBuf = components.collect();
+    let namespace = relative_path.components().next();
+    match namespace {
+        Some(Component::Normal(ns)) if relative_path.components().count() > 1 => {
+            if ns.to_string_lossy().eq_ignore_ascii_case("factory") {
+                bail!("preset bundles must not contain factory presets");
+            }
+        }
+        _ => bail!(
+            "unexpected file \"{}\" (files must be located in a namespace folder)",
+            path.display()
+        ),
+    }
+    Ok((compartment, relative_path))
+}
+
+/// Same as the sub folder names in ReaLearn's preset folder.
+fn compartment_dir_name(compartment: CompartmentKind) -> &'static str {
+    match compartment {
+        CompartmentKind::Controller => "controller",
+        CompartmentKind::Main => "main",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_paths() {
+        let (compartment, relative_path) =
+            split_bundle_path(Path::new("main/helgoboss/util.luau")).unwrap();
+        assert_eq!(compartment, CompartmentKind::Main);
+        assert_eq!(relative_path, Path::new("helgoboss/util.luau"));
+        assert!(split_bundle_path(Path::new("main/util.luau")).is_err());
+        assert!(split_bundle_path(Path::new("controller/factory/x.json")).is_err());
+        assert!(split_bundle_path(Path::new("other/helgoboss/x.json")).is_err());
+    }
+}
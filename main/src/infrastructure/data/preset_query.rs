@@ -0,0 +1,143 @@
+use crate::infrastructure::data::{PresetInfo, SpecificPresetMetaData};
+use realearn_api::persistence::VirtualControlSchemeId;
+use std::collections::HashSet;
+
+/// Criteria for searching installed presets.
+///
+/// A preset matches if it satisfies all given criteria. Text criteria are matched
+/// case-insensitively as substrings.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PresetQuery {
+    /// Each word must be contained in the preset name or ID.
+    pub text: Option<String>,
+    pub author: Option<String>,
+    /// Matched against device manufacturer and device name (controller presets only).
+    pub device: Option<String>,
+    /// The preset must provide (controller presets) or use (main presets) at least one of
+    /// these virtual control schemes.
+    pub schemes: Option<Vec<String>>,
+    pub origin: Option<PresetOriginKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PresetOriginKind {
+    Factory,
+    User,
+}
+
+impl PresetQuery {
+    /// Parses a search expression such as `mixer author:helgoboss scheme:grid origin:factory`.
+    ///
+    /// Supported keys are `author`, `device`, `scheme` (can be given multiple times) and `origin`
+    /// (`factory` or `user`). Everything else is treated as text.
+    pub fn parse(expression: &str) -> Self {
+        let mut query = Self::default();
+        let mut text_words = vec![];
+        for word in expression.split_whitespace() {
+            let Some((key, value)) = word.split_once(':') else {
+                text_words.push(word);
+                continue;
+            };
+            match key.to_lowercase().as_str() {
+                "author" => query.author = Some(value.to_string()),
+                "device" => query.device = Some(value.to_string()),
+                "scheme" => query
+                    .schemes
+                    .get_or_insert_with(Default::default)
+                    .push(value.to_string()),
+                "origin" => match value.to_lowercase().as_str() {
+                    "factory" => query.origin = Some(PresetOriginKind::Factory),
+                    "user" => query.origin = Some(PresetOriginKind::User),
+                    _ => text_words.push(word),
+                },
+                _ => text_words.push(word),
+            }
+        }
+        if !text_words.is_empty() {
+            query.text = Some(text_words.join(" "));
+        }
+        query
+    }
+
+    pub fn matches<S: SpecificPresetMetaData>(&self, info: &PresetInfo<S>) -> bool {
+        let common = &info.common;
+        if let Some(text) = &self.text {
+            let text_matches = text.split_whitespace().all(|word| {
+                contains_ignoring_case(&common.meta_data.name, word)
+                    || contains_ignoring_case(&common.id, word)
+            });
+            if !text_matches {
+                return false;
+            }
+        }
+        if let Some(author) = &self.author {
+            let author_matches = common
+                .meta_data
+                .author
+                .as_ref()
+                .is_some_and(|a| contains_ignoring_case(a, author));
+            if !author_matches {
+                return false;
+            }
+        }
+        if let Some(origin) = self.origin {
+            let actual_origin = if common.origin.is_factory() {
+                PresetOriginKind::Factory
+            } else {
+                PresetOriginKind::User
+            };
+            if actual_origin != origin {
+                return false;
+            }
+        }
+        if let Some(device) = &self.device {
+            let device_matches = info
+                .specific_meta_data
+                .device_names()
+                .into_iter()
+                .any(|name| contains_ignoring_case(name, device));
+            if !device_matches {
+                return false;
+            }
+        }
+        if let Some(schemes) = &self.schemes {
+            if !schemes_intersect(info.specific_meta_data.virtual_control_schemes(), schemes) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn schemes_intersect(actual: &HashSet<VirtualControlSchemeId>, wanted: &[String]) -> bool {
+    actual
+        .iter()
+        .any(|scheme| wanted.iter().any(|w| w == scheme.get()))
+}
+
+fn contains_ignoring_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let query = PresetQuery::parse(
+            "Mixer  Deluxe author:helgoboss scheme:grid scheme:daw origin:Factory",
+        );
+        assert_eq!(
+            query,
+            PresetQuery {
+                text: Some("Mixer Deluxe".to_string()),
+                author: Some("helgoboss".to_string()),
+                device: None,
+                schemes: Some(vec!["grid".to_string(), "daw".to_string()]),
+                origin: Some(PresetOriginKind::Factory),
+            }
+        );
+        assert_eq!(PresetQuery::parse(""), PresetQuery::default());
+    }
+}
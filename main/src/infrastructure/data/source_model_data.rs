@@ -4,16 +4,19 @@ use crate::application::{
     VirtualControlElementType,
 };
 use crate::base::notification;
-use crate::domain::{CompartmentKind, CompartmentParamIndex, Keystroke};
+use crate::domain::{AdsrSettings, CompartmentKind, CompartmentParamIndex, Keystroke};
 use crate::infrastructure::data::common::OscValueRange;
 use crate::infrastructure::data::VirtualControlElementIdData;
 use base::default_util::{deserialize_null_default, is_default};
 use helgoboss_learn::{DisplayType, MidiClockTransportMessage, OscTypeTag, SourceCharacter};
 use helgoboss_midi::{Channel, U14, U7};
-use realearn_api::persistence::MidiScriptKind;
+use realearn_api::persistence::{
+    AudioSignalKind, MidiScriptKind, ModulatorNoteValue, ModulatorShape,
+};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
+use std::time::Duration;
 
 /// This is the structure in which source settings are loaded and saved. It's optimized for being
 /// represented as JSON. The JSON representation must be 100% backward-compatible.
@@ -176,6 +179,90 @@ pub struct SourceModelData {
         skip_serializing_if = "is_default"
     )]
     pub timer_millis: u64,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_signal_kind: AudioSignalKind,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_signal_attack_millis: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_signal_release_millis: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub audio_signal_threshold: f64,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_shape: ModulatorShape,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_frequency: f64,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_tempo_sync: Option<ModulatorNoteValue>,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_reset_on_transport_start: bool,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_attack_millis: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_decay_millis: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_sustain: f64,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator_release_millis: u32,
     #[serde(
         default,
         deserialize_with = "deserialize_null_default",
@@ -186,6 +273,12 @@ pub struct SourceModelData {
 
 impl SourceModelData {
     pub fn from_model(model: &SourceModel) -> Self {
+        // Audio signal settings have non-zero defaults, so only save them where they matter
+        let is_audio_signal = model.category() == SourceCategory::Reaper
+            && model.reaper_source_type() == ReaperSourceType::AudioSignal;
+        let is_modulator = model.category() == SourceCategory::Reaper
+            && model.reaper_source_type() == ReaperSourceType::Modulator;
+        let adsr = model.modulator_adsr();
         Self {
             category: model.category(),
             r#type: model.midi_source_type(),
@@ -218,6 +311,66 @@ impl SourceModelData {
             ),
             reaper_source_type: model.reaper_source_type(),
             timer_millis: model.timer_millis(),
+            audio_signal_kind: if is_audio_signal {
+                model.audio_signal_kind()
+            } else {
+                Default::default()
+            },
+            audio_signal_attack_millis: if is_audio_signal {
+                model.audio_signal_attack_millis()
+            } else {
+                Default::default()
+            },
+            audio_signal_release_millis: if is_audio_signal {
+                model.audio_signal_release_millis()
+            } else {
+                Default::default()
+            },
+            audio_signal_threshold: if is_audio_signal {
+                model.audio_signal_threshold()
+            } else {
+                Default::default()
+            },
+            modulator_shape: if is_modulator {
+                model.modulator_shape()
+            } else {
+                Default::default()
+            },
+            modulator_frequency: if is_modulator {
+                model.modulator_frequency()
+            } else {
+                Default::default()
+            },
+            modulator_tempo_sync: if is_modulator {
+                model.modulator_tempo_sync()
+            } else {
+                Default::default()
+            },
+            modulator_reset_on_transport_start: if is_modulator {
+                model.modulator_reset_on_transport_start()
+            } else {
+                Default::default()
+            },
+            modulator_attack_millis: if is_modulator {
+                adsr.attack.as_millis() as _
+            } else {
+                Default::default()
+            },
+            modulator_decay_millis: if is_modulator {
+                adsr.decay.as_millis() as _
+            } else {
+                Default::default()
+            },
+            modulator_sustain: if is_modulator {
+                adsr.sustain
+            } else {
+                Default::default()
+            },
+            modulator_release_millis: if is_modulator {
+                adsr.release.as_millis() as _
+            } else {
+                Default::default()
+            },
             parameter_index: model.parameter_index(),
         }
     }
@@ -298,6 +451,30 @@ impl SourceModelData {
         ));
         model.change(P::SetReaperSourceType(self.reaper_source_type));
         model.change(P::SetTimerMillis(self.timer_millis));
+        if self.reaper_source_type == ReaperSourceType::AudioSignal {
+            model.change(P::SetAudioSignalKind(self.audio_signal_kind));
+            model.change(P::SetAudioSignalAttackMillis(
+                self.audio_signal_attack_millis,
+            ));
+            model.change(P::SetAudioSignalReleaseMillis(
+                self.audio_signal_release_millis,
+            ));
+            model.change(P::SetAudioSignalThreshold(self.audio_signal_threshold));
+        }
+        if self.reaper_source_type == ReaperSourceType::Modulator {
+            model.change(P::SetModulatorShape(self.modulator_shape));
+            model.change(P::SetModulatorFrequency(self.modulator_frequency));
+            model.change(P::SetModulatorTempoSync(self.modulator_tempo_sync));
+            model.change(P::SetModulatorResetOnTransportStart(
+                self.modulator_reset_on_transport_start,
+            ));
+            model.change(P::SetModulatorAdsr(AdsrSettings {
+                attack: Duration::from_millis(self.modulator_attack_millis as _),
+                decay: Duration::from_millis(self.modulator_decay_millis as _),
+                sustain: self.modulator_sustain,
+                release: Duration::from_millis(self.modulator_release_millis as _),
+            }));
+        }
         model.change(P::SetParameterIndex(self.parameter_index));
         model.change(P::SetKeystroke(self.keystroke));
     }
@@ -30,9 +30,10 @@ use base::default_util::{
 };
 use helgoboss_learn::{AbsoluteValue, Fraction, OscTypeTag, UnitValue};
 use realearn_api::persistence::{
-    Axis, BrowseTracksMode, FxToolAction, LearnableTargetKind, MappingSnapshotDescForLoad,
-    MappingSnapshotDescForTake, MonitoringMode, MouseAction, PotFilterKind, SeekBehavior,
-    TargetTouchCause, TargetValue, TrackScope, TrackToolAction,
+    ArrangeViewProperty, Axis, BrowseTracksMode, FxChainEditAction, FxToolAction,
+    LearnableTargetKind, MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MonitoringMode,
+    MouseAction, PotFilterKind, SeekBehavior, SendMode, TargetTouchCause, TargetValue,
+    TrackRecordMode, TrackScope, TrackToolAction,
 };
 
 use base::hash_util::NonCryptoHashSet;
@@ -513,6 +514,55 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub touch_cause: TargetTouchCause,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub fx_chain_edit_action: FxChainEditAction,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub plugin_id: String,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub route_send_mode: SendMode,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub route_source_channel: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub route_destination_channel: u32,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub track_record_mode: TrackRecordMode,
+    /// New since ReaLearn v2.16.0-pre.15
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub arrange_view_property: ArrangeViewProperty,
 }
 
 impl TargetModelData {
@@ -641,6 +691,13 @@ impl TargetModelData {
                 None
             },
             touch_cause: model.touch_cause(),
+            fx_chain_edit_action: model.fx_chain_edit_action(),
+            plugin_id: model.plugin_id().to_owned(),
+            route_send_mode: model.route_send_mode(),
+            route_source_channel: model.route_source_channel(),
+            route_destination_channel: model.route_destination_channel(),
+            track_record_mode: model.track_record_mode(),
+            arrange_view_property: model.arrange_view_property(),
         }
     }
 
@@ -892,6 +949,8 @@ impl TargetModelData {
                 Pause => T::Pause,
                 RecordStop => T::RecordStop,
                 Repeat => T::Looped,
+                // Didn't exist when clip targets still used transport actions
+                _ => T::Trigger,
             }
         });
         model.change(C::SetPlaytimeSlotTransportAction(clip_transport_action));
@@ -1006,6 +1065,15 @@ impl TargetModelData {
         });
         model.change(C::SetLearnableTargetKinds(target_kinds));
         model.change(C::SetTouchCause(self.touch_cause));
+        model.change(C::SetFxChainEditAction(self.fx_chain_edit_action));
+        model.change(C::SetPluginId(self.plugin_id.clone()));
+        model.change(C::SetRouteSendMode(self.route_send_mode));
+        model.change(C::SetRouteSourceChannel(self.route_source_channel));
+        model.change(C::SetRouteDestinationChannel(
+            self.route_destination_channel,
+        ));
+        model.change(C::SetTrackRecordMode(self.track_record_mode));
+        model.change(C::SetArrangeViewProperty(self.arrange_view_property));
         Ok(())
     }
 }
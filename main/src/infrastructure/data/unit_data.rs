@@ -346,6 +346,7 @@ impl CompartmentState {
 #[serde(untagged)]
 enum ControlDeviceId {
     Keyboard(KeyboardDevice),
+    WebClients(WebClientsDevice),
     Osc(OscDeviceId),
     Midi(String),
 }
@@ -356,6 +357,12 @@ enum KeyboardDevice {
     TheKeyboard,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum WebClientsDevice {
+    #[serde(rename = "web-clients")]
+    AllWebClients,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 enum FeedbackDeviceId {
@@ -473,6 +480,9 @@ impl UnitData {
                     ControlInput::Keyboard => {
                         Some(ControlDeviceId::Keyboard(KeyboardDevice::TheKeyboard))
                     }
+                    ControlInput::WebClients => {
+                        Some(ControlDeviceId::WebClients(WebClientsDevice::AllWebClients))
+                    }
                 }
             },
             feedback_device_id: {
@@ -605,6 +615,7 @@ impl UnitData {
                 use ControlDeviceId::*;
                 match dev_id {
                     Keyboard(_) => ControlInput::Keyboard,
+                    WebClients(_) => ControlInput::WebClients,
                     Midi(midi_dev_id_string) => {
                         let raw_midi_dev_id = midi_dev_id_string
                             .parse::<u8>()
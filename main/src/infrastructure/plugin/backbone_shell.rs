@@ -50,6 +50,7 @@ use crate::infrastructure::plugin::{
     ini_util, update_auto_units_async, SharedInstanceShell, WeakInstanceShell,
     ACTION_SHOW_HIDE_PLAYTIME_COMMAND_NAME,
 };
+use crate::infrastructure::server::auth::{generate_token, ServerAuth, TokenScope};
 use crate::infrastructure::server::services::Services;
 use crate::infrastructure::ui::instance_panel::InstancePanel;
 use crate::infrastructure::ui::util::open_child_panel;
@@ -388,6 +389,10 @@ impl BackboneShell {
             config.main.server_grpc_port,
             BackboneShell::server_resource_dir_path().join("certificates"),
             MetricsReporter::new(),
+            ServerAuth::new(
+                config.server_auth_is_enabled(),
+                config.server_client_tokens_with_scope(),
+            ),
         );
         // OSC devices are reconnected only if device list changes (= while instance active)
         let osc_feedback_processor = OscFeedbackProcessor::new(osc_feedback_task_receiver);
@@ -1016,6 +1021,46 @@ impl BackboneShell {
         self.server.borrow_mut().stop();
     }
 
+    pub fn toggle_server_auth(&self) {
+        self.change_config(|config| {
+            config.main.server_auth_enabled = (!config.server_auth_is_enabled()).into();
+        });
+        self.sync_server_auth();
+    }
+
+    /// Returns a one-time code which a client can exchange for a token with the given scope.
+    pub fn start_server_client_pairing(&self, scope: TokenScope) -> String {
+        self.server
+            .borrow()
+            .auth()
+            .write()
+            .unwrap()
+            .start_pairing(scope)
+    }
+
+    /// Generates and persists a new client token.
+    pub fn add_server_client_token(&self, scope: TokenScope, client_name: String) -> String {
+        let token = generate_token();
+        self.change_config(|config| {
+            config.add_server_client_token(token.clone(), scope, client_name);
+        });
+        self.sync_server_auth();
+        token
+    }
+
+    pub fn revoke_server_client_token(&self, token: &str) {
+        self.change_config(|config| config.revoke_server_client_token(token));
+        self.sync_server_auth();
+    }
+
+    fn sync_server_auth(&self) {
+        let config = self.config.borrow();
+        let server = self.server.borrow();
+        let mut auth = server.auth().write().unwrap();
+        auth.set_enabled(config.server_auth_is_enabled());
+        auth.set_tokens(config.server_client_tokens_with_scope());
+    }
+
     /// Requires REAPER version >= 711+dev0305.
     pub fn toggle_toolbar_button_dynamically(&self, command_name: &str) -> anyhow::Result<()> {
         self.change_config(|config| {
@@ -1154,6 +1199,10 @@ impl BackboneShell {
         Self::realearn_preset_dir_path().join(sub_dir)
     }
 
+    pub fn realearn_preset_bundle_dir_path() -> PathBuf {
+        Self::realearn_preset_dir_path().join("bundles")
+    }
+
     pub fn realearn_auto_load_configs_dir_path() -> PathBuf {
         Self::realearn_data_dir_path().join("auto-load-configs")
     }
@@ -2108,6 +2157,16 @@ impl Drop for BackboneShell {
 pub struct BackboneConfig {
     main: MainConfig,
     toolbar: HashMap<String, u8>,
+    /// Maps tokens of paired server clients with control scope to client names.
+    server_control_tokens: HashMap<String, String>,
+    /// Maps tokens of paired server clients with read-only scope to client names.
+    server_read_only_tokens: HashMap<String, String>,
+}
+
+pub struct ServerClientToken<'a> {
+    pub token: &'a str,
+    pub scope: TokenScope,
+    pub client_name: &'a str,
 }
 
 impl BackboneConfig {
@@ -2139,6 +2198,47 @@ impl BackboneConfig {
         self.main.server_enabled > 0
     }
 
+    pub fn server_auth_is_enabled(&self) -> bool {
+        self.main.server_auth_enabled > 0
+    }
+
+    pub fn server_client_tokens(&self) -> impl Iterator<Item = ServerClientToken<'_>> {
+        let control_tokens = self
+            .server_control_tokens
+            .iter()
+            .map(|(token, name)| (token, name, TokenScope::Control));
+        let read_only_tokens = self
+            .server_read_only_tokens
+            .iter()
+            .map(|(token, name)| (token, name, TokenScope::ReadOnly));
+        control_tokens
+            .chain(read_only_tokens)
+            .map(|(token, client_name, scope)| ServerClientToken {
+                token,
+                scope,
+                client_name,
+            })
+    }
+
+    fn server_client_tokens_with_scope(&self) -> Vec<(String, TokenScope)> {
+        self.server_client_tokens()
+            .map(|t| (t.token.to_string(), t.scope))
+            .collect()
+    }
+
+    fn add_server_client_token(&mut self, token: String, scope: TokenScope, client_name: String) {
+        let tokens = match scope {
+            TokenScope::ReadOnly => &mut self.server_read_only_tokens,
+            TokenScope::Control => &mut self.server_control_tokens,
+        };
+        tokens.insert(token, client_name);
+    }
+
+    fn revoke_server_client_token(&mut self, token: &str) {
+        self.server_control_tokens.remove(token);
+        self.server_read_only_tokens.remove(token);
+    }
+
     pub fn companion_web_app_url(&self) -> url::Url {
         Url::parse(&self.main.companion_web_app_url).expect("invalid companion web app URL")
     }
@@ -2156,6 +2256,9 @@ impl BackboneConfig {
 struct MainConfig {
     #[serde(default, skip_serializing_if = "is_default")]
     server_enabled: u8,
+    /// If enabled, server clients need to pair first and send their token with each request.
+    #[serde(default, skip_serializing_if = "is_default")]
+    server_auth_enabled: u8,
     #[serde(
         default = "default_server_http_port",
         skip_serializing_if = "is_default_server_http_port"
@@ -2219,6 +2322,7 @@ impl Default for MainConfig {
     fn default() -> Self {
         MainConfig {
             server_enabled: 0,
+            server_auth_enabled: 0,
             server_http_port: default_server_http_port(),
             server_https_port: default_server_https_port(),
             server_grpc_port: default_server_grpc_port(),
@@ -2926,7 +3030,7 @@ mod playtime_impl {
                 &self,
                 item: &dyn playtime_clip_engine::PlaytimeItem,
             ) -> anyhow::Result<()> {
-                let text = crate::infrastructure::ui::lua_serializer::to_string(item)?;
+                let text = realearn_api::lua_serializer::to_string(item)?;
                 crate::infrastructure::ui::copy_text_to_clipboard(text);
                 Ok(())
             }
@@ -255,6 +255,15 @@ impl Plugin for HelgoboxPlugin {
                 // (TimeInfoFlags::TRANSPORT_CHANGED doesn't work the way we want it).
                 self.was_playing_in_last_cycle = self.is_now_playing();
                 if let Some(lazy_data) = self.lazy_data.get() {
+                    // Analyze input audio before Playtime gets the chance to touch the buffer
+                    {
+                        let (inputs, _) = buffer.split();
+                        lazy_data.instance_shell.process_incoming_audio_from_plugin(
+                            &inputs,
+                            ControlEventTimestamp::now(),
+                            self.host,
+                        );
+                    }
                     #[cfg(feature = "playtime")]
                     lazy_data.instance_shell.run_playtime_from_plugin(
                         buffer,
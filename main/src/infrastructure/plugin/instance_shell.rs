@@ -1,7 +1,8 @@
 use crate::application::{AutoUnitData, SharedUnitModel};
 use crate::domain::{
-    ControlEvent, IncomingMidiMessage, Instance, InstanceHandler, InstanceId, MidiEvent,
-    ProcessorContext, SharedInstance, SharedRealTimeInstance, UnitId,
+    CompartmentKind, ControlEvent, ControlEventTimestamp, ControlLogEntry, IncomingMidiMessage,
+    Instance, InstanceHandler, InstanceId, MappingKey, MidiEvent, ProcessorContext, SharedInstance,
+    SharedRealTimeInstance, UnitId,
 };
 use crate::infrastructure::data::{InstanceData, InstanceOrUnitData, UnitData};
 use crate::infrastructure::plugin::unit_shell::UnitShell;
@@ -12,9 +13,11 @@ use anyhow::{bail, Context};
 use base::hash_util::NonCryptoHashMap;
 use base::{blocking_read_lock, blocking_write_lock, non_blocking_try_read_lock};
 use fragile::Fragile;
+use helgoboss_learn::AbsoluteValue;
 use playtime_api::persistence::FlexibleMatrix;
 use realearn_api::persistence::{instance_features, InstanceSettings};
 use reaper_high::Project;
+use rosc::OscMessage;
 use std::cell::RefCell;
 use std::iter::once;
 use std::rc::Rc;
@@ -67,6 +70,73 @@ struct CustomInstanceHandler {
 }
 
 impl InstanceHandler for CustomInstanceHandler {
+    fn wants_mapping_events(&self) -> bool {
+        BackboneShell::get()
+            .proto_hub()
+            .wants_continuous_unit_updates()
+    }
+
+    fn mapping_target_value_changed(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        new_value: AbsoluteValue,
+    ) {
+        BackboneShell::get()
+            .proto_hub()
+            .notify_mapping_target_value_changed(
+                instance_id,
+                unit_id,
+                compartment,
+                mapping_key,
+                new_value,
+            );
+    }
+
+    fn mapping_target_controlled(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        log_entry: ControlLogEntry,
+    ) {
+        BackboneShell::get()
+            .proto_hub()
+            .notify_mapping_target_controlled(
+                instance_id,
+                unit_id,
+                compartment,
+                mapping_key,
+                log_entry,
+            );
+    }
+
+    fn wants_osc_source_values(&self) -> bool {
+        let server = BackboneShell::get().server().borrow();
+        server.is_running()
+            && server
+                .oscquery()
+                .read()
+                .is_ok_and(|oscquery| oscquery.has_listening_clients())
+    }
+
+    fn osc_source_value_changed(&self, unit_id: UnitId, msg: &OscMessage) {
+        let server = BackboneShell::get().server().borrow();
+        if let Ok(mut oscquery) = server.oscquery().write() {
+            oscquery.update_value(unit_id, msg);
+        }
+    }
+
+    fn mappings_in_unit_changed(&self, _unit_id: UnitId) {
+        let server = BackboneShell::get().server().borrow();
+        if let Ok(mut oscquery) = server.oscquery().write() {
+            oscquery.invalidate_namespace();
+        }
+    }
+
     #[cfg(feature = "playtime")]
     fn clip_matrix_changed(
         &self,
@@ -580,6 +650,24 @@ impl InstanceShell {
         }
     }
 
+    /// Forwards the audio arriving at the plug-in input pins to all units.
+    ///
+    /// To be called from real-time thread (in the plug-in's processing function).
+    pub fn process_incoming_audio_from_plugin(
+        &self,
+        inputs: &vst::buffer::Inputs<f64>,
+        timestamp: ControlEventTimestamp,
+        host: HostCallback,
+    ) {
+        let Some(unit_shells) = non_blocking_try_read_lock(&self.additional_unit_shells) else {
+            // Better miss one block than blocking the entire audio thread
+            return;
+        };
+        for unit_shell in once(&self.main_unit_shell).chain(&*unit_shells) {
+            unit_shell.process_incoming_audio_from_vst(inputs, timestamp, host);
+        }
+    }
+
     /// Invokes the processing function for each unit.
     ///
     /// To be called from real-time thread (in the plug-in's processing function).
@@ -1,9 +1,9 @@
 use vst::plugin::HostCallback;
 
 use crate::domain::{
-    ControlEvent, IncomingMidiMessage, InstanceId, MainProcessor, MidiEvent, ParameterManager,
-    ProcessorContext, RealTimeProcessorLocker, SharedInstance, SharedRealTimeProcessor, Unit,
-    UnitId, WeakRealTimeInstance,
+    ControlEvent, ControlEventTimestamp, IncomingMidiMessage, InstanceId, MainProcessor, MidiEvent,
+    ParameterManager, ProcessorContext, RealTimeProcessorLocker, SharedInstance,
+    SharedRealTimeProcessor, Unit, UnitId, WeakRealTimeInstance,
 };
 use crate::domain::{NormalRealTimeTask, RealTimeProcessor};
 use crate::infrastructure::plugin::UnitInfo;
@@ -251,6 +251,17 @@ impl UnitShell {
         self.real_time_processor.lock_recover().run_from_vst(&host);
     }
 
+    pub fn process_incoming_audio_from_vst(
+        &self,
+        inputs: &vst::buffer::Inputs<f64>,
+        timestamp: ControlEventTimestamp,
+        host: HostCallback,
+    ) {
+        self.real_time_processor
+            .lock_recover()
+            .process_incoming_audio_from_vst(inputs, timestamp, &host);
+    }
+
     pub fn set_sample_rate(&self, rate: f32) {
         // This is called in main thread, so we need to send it to the real-time processor via
         // channel. Real-time processor needs sample rate to do some MIDI clock calculations.
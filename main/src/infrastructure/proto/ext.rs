@@ -1,30 +1,35 @@
 use enumflags2::BitFlags;
+use helgoboss_learn::AbsoluteValue;
 use reaper_high::Reaper;
 use reaper_medium::{PlayState, ReaperString};
 
 use realearn_api::runtime::{ControllerPreset, LicenseInfo, MainPreset, ValidatedLicense};
 
 use crate::application::UnitModel;
-use crate::domain::CompartmentKind;
+use crate::domain::{
+    audio_input_channel_names, available_midi_input_devices, CompartmentKind, ControlLogEntry,
+};
 use crate::infrastructure::data::{
     ControllerManager, FileBasedControllerPresetManager, FileBasedMainPresetManager, LicenseManager,
 };
 use crate::infrastructure::plugin::InstanceShell;
 use crate::infrastructure::proto::{
     event_reply, occasional_global_update, occasional_instance_update,
-    qualified_occasional_unit_update, ArrangementPlayState, AudioInputChannel, AudioInputChannels,
-    CellAddress, Compartment, ContinuousColumnUpdate, ContinuousMatrixUpdate,
-    GetContinuousColumnUpdatesReply, GetContinuousMatrixUpdatesReply,
-    GetContinuousSlotUpdatesReply, GetOccasionalClipUpdatesReply, GetOccasionalColumnUpdatesReply,
-    GetOccasionalGlobalUpdatesReply, GetOccasionalInstanceUpdatesReply,
-    GetOccasionalMatrixUpdatesReply, GetOccasionalPlaytimeEngineUpdatesReply,
-    GetOccasionalRowUpdatesReply, GetOccasionalSlotUpdatesReply, GetOccasionalTrackUpdatesReply,
-    GetOccasionalUnitUpdatesReply, HostColorScheme, MidiDeviceStatus, MidiInputDevice,
+    qualified_continuous_unit_update, qualified_occasional_unit_update, ArrangementPlayState,
+    AudioInputChannel, AudioInputChannels, CellAddress, Compartment, ContinuousColumnUpdate,
+    ContinuousMatrixUpdate, GetContinuousColumnUpdatesReply, GetContinuousMatrixUpdatesReply,
+    GetContinuousSlotUpdatesReply, GetContinuousUnitUpdatesReply, GetOccasionalClipUpdatesReply,
+    GetOccasionalColumnUpdatesReply, GetOccasionalGlobalUpdatesReply,
+    GetOccasionalInstanceUpdatesReply, GetOccasionalMatrixUpdatesReply,
+    GetOccasionalPlaytimeEngineUpdatesReply, GetOccasionalRowUpdatesReply,
+    GetOccasionalSlotUpdatesReply, GetOccasionalTrackUpdatesReply, GetOccasionalUnitUpdatesReply,
+    HostColorScheme, MappingControlLogEntry, MappingTargetValue, MidiDeviceStatus, MidiInputDevice,
     MidiInputDevices, MidiOutputDevice, MidiOutputDevices, OccasionalGlobalUpdate,
     OccasionalInstanceUpdate, OccasionalMatrixUpdate, OccasionalPlaytimeEngineUpdate,
-    QualifiedContinuousSlotUpdate, QualifiedOccasionalClipUpdate, QualifiedOccasionalColumnUpdate,
-    QualifiedOccasionalRowUpdate, QualifiedOccasionalSlotUpdate, QualifiedOccasionalTrackUpdate,
-    QualifiedOccasionalUnitUpdate, RgbColor, SlotAddress, Unit, Units,
+    QualifiedContinuousSlotUpdate, QualifiedContinuousUnitUpdate, QualifiedOccasionalClipUpdate,
+    QualifiedOccasionalColumnUpdate, QualifiedOccasionalRowUpdate, QualifiedOccasionalSlotUpdate,
+    QualifiedOccasionalTrackUpdate, QualifiedOccasionalUnitUpdate, RgbColor, SlotAddress, Unit,
+    Units,
 };
 use crate::infrastructure::server::data::get_controller_routing;
 
@@ -43,6 +48,12 @@ impl occasional_instance_update::Update {
     }
 
     pub fn units(instance_shell: &InstanceShell) -> Self {
+        Self::Units(Units::from_engine(instance_shell))
+    }
+}
+
+impl Units {
+    pub fn from_engine(instance_shell: &InstanceShell) -> Self {
         let units = instance_shell.all_unit_models().map(|unit_model| {
             let unit_model = unit_model.borrow();
             Unit {
@@ -51,9 +62,9 @@ impl occasional_instance_update::Update {
                 name: unit_model.name().map(|n| n.to_string()),
             }
         });
-        Self::Units(Units {
+        Self {
             units: units.collect(),
-        })
+        }
     }
 }
 
@@ -66,6 +77,40 @@ impl qualified_occasional_unit_update::Update {
     }
 }
 
+impl qualified_continuous_unit_update::Update {
+    pub fn target_value(
+        compartment: CompartmentKind,
+        mapping_key: String,
+        value: AbsoluteValue,
+    ) -> Self {
+        Self::TargetValue(MappingTargetValue {
+            compartment: Compartment::from_engine(compartment).into(),
+            mapping_key,
+            value: value.to_unit_value().get(),
+        })
+    }
+
+    pub fn control_log_entry(
+        compartment: CompartmentKind,
+        mapping_key: String,
+        entry: ControlLogEntry,
+    ) -> Self {
+        Self::ControlLogEntry(MappingControlLogEntry {
+            compartment: Compartment::from_engine(compartment).into(),
+            mapping_key,
+            kind: entry.kind.to_string(),
+            control_value: entry.control_value.map(|v| v.to_string()),
+            target_index: entry.target_index as _,
+            invocation_count: entry.invocation_count,
+            error: if entry.error.is_empty() {
+                None
+            } else {
+                Some(entry.error.to_string())
+            },
+        })
+    }
+}
+
 impl RgbColor {
     pub fn from_engine(color: Option<reaper_medium::RgbColor>) -> Self {
         Self {
@@ -110,11 +155,7 @@ impl occasional_global_update::Update {
     }
 
     pub fn midi_input_devices() -> Self {
-        Self::MidiInputDevices(MidiInputDevices::from_engine(
-            Reaper::get()
-                .midi_input_devices()
-                .filter(|d| d.is_available()),
-        ))
+        Self::MidiInputDevices(MidiInputDevices::from_engine(available_midi_input_devices()))
     }
 
     pub fn midi_output_devices() -> Self {
@@ -126,9 +167,7 @@ impl occasional_global_update::Update {
     }
 
     pub fn audio_input_channels() -> Self {
-        Self::AudioInputChannels(AudioInputChannels::from_engine(
-            Reaper::get().input_channels(),
-        ))
+        Self::AudioInputChannels(AudioInputChannels::from_engine(audio_input_channel_names()))
     }
 
     pub fn controller_presets(manager: &FileBasedControllerPresetManager) -> Self {
@@ -205,6 +244,13 @@ impl MidiInputDevices {
 }
 
 impl Compartment {
+    pub fn from_engine(compartment: CompartmentKind) -> Self {
+        match compartment {
+            CompartmentKind::Controller => Compartment::Controller,
+            CompartmentKind::Main => Compartment::Main,
+        }
+    }
+
     pub fn to_engine(self) -> CompartmentKind {
         match self {
             Compartment::Controller => CompartmentKind::Controller,
@@ -321,6 +367,14 @@ impl From<Vec<QualifiedOccasionalUnitUpdate>> for event_reply::Value {
     }
 }
 
+impl From<Vec<QualifiedContinuousUnitUpdate>> for event_reply::Value {
+    fn from(value: Vec<QualifiedContinuousUnitUpdate>) -> Self {
+        event_reply::Value::ContinuousUnitUpdatesReply(GetContinuousUnitUpdatesReply {
+            unit_updates: value,
+        })
+    }
+}
+
 impl From<Vec<OccasionalMatrixUpdate>> for event_reply::Value {
     fn from(value: Vec<OccasionalMatrixUpdate>) -> Self {
         event_reply::Value::OccasionalMatrixUpdatesReply(GetOccasionalMatrixUpdatesReply {
@@ -47,7 +47,7 @@ pub mod reply {
 pub struct CommandRequest {
     #[prost(
         oneof = "command_request::Value",
-        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 37, 50, 40, 25, 26, 27, 34, 28, 29, 31, 32, 33, 35, 36, 38, 39, 41, 42, 43, 44, 51, 45, 46, 47, 48, 49"
+        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 37, 50, 40, 25, 26, 27, 34, 28, 29, 31, 32, 33, 35, 36, 38, 39, 41, 42, 43, 44, 52, 53, 54, 55, 51, 45, 46, 47, 48, 49"
     )]
     pub value: ::core::option::Option<command_request::Value>,
 }
@@ -147,6 +147,14 @@ pub mod command_request {
         SetAppSettings(super::SetAppSettingsRequest),
         #[prost(message, tag = "44")]
         SaveCustomCompartmentData(super::SaveCustomCompartmentDataRequest),
+        #[prost(message, tag = "52")]
+        SaveMapping(super::SaveMappingRequest),
+        #[prost(message, tag = "53")]
+        DeleteMapping(super::DeleteMappingRequest),
+        #[prost(message, tag = "54")]
+        SetGroupEnabled(super::SetGroupEnabledRequest),
+        #[prost(message, tag = "55")]
+        SetCompartmentParameter(super::SetCompartmentParameterRequest),
         #[prost(message, tag = "51")]
         SetCustomInstanceData(super::SetCustomInstanceDataRequest),
         #[prost(message, tag = "45")]
@@ -181,7 +189,7 @@ pub struct QueryRequest {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Query {
-    #[prost(oneof = "query::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    #[prost(oneof = "query::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
     pub value: ::core::option::Option<query::Value>,
 }
 /// Nested message and enum types in `Query`.
@@ -205,6 +213,8 @@ pub mod query {
         GetCompartmentData(super::GetCompartmentDataRequest),
         #[prost(message, tag = "8")]
         GetCustomInstanceData(super::GetCustomInstanceDataRequest),
+        #[prost(message, tag = "9")]
+        GetUnits(super::GetUnitsRequest),
     }
 }
 /// Envelope for query results.
@@ -225,7 +235,7 @@ pub struct QueryReply {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryResult {
-    #[prost(oneof = "query_result::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
+    #[prost(oneof = "query_result::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10")]
     pub value: ::core::option::Option<query_result::Value>,
 }
 /// Nested message and enum types in `QueryResult`.
@@ -251,6 +261,8 @@ pub mod query_result {
         GetCompartmentDataReply(super::GetCompartmentDataReply),
         #[prost(message, tag = "9")]
         GetCustomInstanceDataReply(super::GetCustomInstanceDataReply),
+        #[prost(message, tag = "10")]
+        GetUnitsReply(super::GetUnitsReply),
     }
 }
 /// Should contain all possible *event* replies from above service.
@@ -261,7 +273,7 @@ pub mod query_result {
 pub struct EventReply {
     #[prost(
         oneof = "event_reply::Value",
-        tags = "1, 15, 13, 14, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12"
+        tags = "1, 15, 13, 14, 16, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12"
     )]
     pub value: ::core::option::Option<event_reply::Value>,
 }
@@ -281,6 +293,8 @@ pub mod event_reply {
         OccasionalInstanceUpdatesReply(super::GetOccasionalInstanceUpdatesReply),
         #[prost(message, tag = "14")]
         OccasionalUnitUpdatesReply(super::GetOccasionalUnitUpdatesReply),
+        #[prost(message, tag = "16")]
+        ContinuousUnitUpdatesReply(super::GetContinuousUnitUpdatesReply),
         #[prost(message, tag = "2")]
         OccasionalMatrixUpdatesReply(super::GetOccasionalMatrixUpdatesReply),
         #[prost(message, tag = "3")]
@@ -695,6 +709,50 @@ pub struct SaveCustomCompartmentDataRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SaveMappingRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_id: ::core::option::Option<FullCompartmentId>,
+    /// Mapping as JSON (in the format of the ReaLearn API).
+    ///
+    /// If the mapping has a key and the compartment already contains a mapping with that key,
+    /// the existing mapping is updated. Otherwise, the mapping is added at the end.
+    #[prost(string, tag = "2")]
+    pub data: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteMappingRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_id: ::core::option::Option<FullCompartmentId>,
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetGroupEnabledRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_id: ::core::option::Option<FullCompartmentId>,
+    /// Empty for the default group.
+    #[prost(string, tag = "2")]
+    pub group_key: ::prost::alloc::string::String,
+    /// Enables/disables both control and feedback.
+    #[prost(bool, tag = "3")]
+    pub enabled: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetCompartmentParameterRequest {
+    #[prost(message, optional, tag = "1")]
+    pub compartment_id: ::core::option::Option<FullCompartmentId>,
+    /// Zero-based compartment parameter index.
+    #[prost(uint32, tag = "2")]
+    pub index: u32,
+    /// Normalized value between 0.0 and 1.0.
+    #[prost(double, tag = "3")]
+    pub value: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FullCompartmentId {
     #[prost(uint32, tag = "1")]
     pub instance_id: u32,
@@ -834,6 +892,12 @@ pub struct GetCompartmentDataRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUnitsRequest {
+    #[prost(uint32, tag = "1")]
+    pub instance_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetClipDetailRequest {
     #[prost(message, optional, tag = "1")]
     pub clip_address: ::core::option::Option<FullClipAddress>,
@@ -847,6 +911,12 @@ pub struct GetCompartmentDataReply {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUnitsReply {
+    #[prost(message, repeated, tag = "1")]
+    pub units: ::prost::alloc::vec::Vec<Unit>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetClipDetailReply {
     #[prost(bytes = "vec", optional, tag = "1")]
     pub rea_peaks: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
@@ -871,6 +941,12 @@ pub struct GetOccasionalUnitUpdatesRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContinuousUnitUpdatesRequest {
+    #[prost(uint32, tag = "1")]
+    pub instance_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOccasionalMatrixUpdatesRequest {
     #[prost(uint32, tag = "1")]
     pub matrix_id: u32,
@@ -1014,6 +1090,65 @@ pub struct Unit {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContinuousUnitUpdatesReply {
+    #[prost(message, repeated, tag = "1")]
+    pub unit_updates: ::prost::alloc::vec::Vec<QualifiedContinuousUnitUpdate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QualifiedContinuousUnitUpdate {
+    #[prost(uint32, tag = "1")]
+    pub unit_id: u32,
+    #[prost(oneof = "qualified_continuous_unit_update::Update", tags = "2, 3")]
+    pub update: ::core::option::Option<qualified_continuous_unit_update::Update>,
+}
+/// Nested message and enum types in `QualifiedContinuousUnitUpdate`.
+pub mod qualified_continuous_unit_update {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Update {
+        /// The target value of a mapping has changed.
+        #[prost(message, tag = "2")]
+        TargetValue(super::MappingTargetValue),
+        /// A mapping has been controlled (same as what ends up in the control log).
+        #[prost(message, tag = "3")]
+        ControlLogEntry(super::MappingControlLogEntry),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MappingTargetValue {
+    #[prost(enumeration = "Compartment", tag = "1")]
+    pub compartment: i32,
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+    /// Normalized value between 0.0 and 1.0.
+    #[prost(double, tag = "3")]
+    pub value: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MappingControlLogEntry {
+    #[prost(enumeration = "Compartment", tag = "1")]
+    pub compartment: i32,
+    #[prost(string, tag = "2")]
+    pub mapping_key: ::prost::alloc::string::String,
+    /// Human-readable description of what happened.
+    #[prost(string, tag = "3")]
+    pub kind: ::prost::alloc::string::String,
+    /// The control value that arrived at the target, if any.
+    #[prost(string, optional, tag = "4")]
+    pub control_value: ::core::option::Option<::prost::alloc::string::String>,
+    /// For multi-targets, this can be greater than 0.
+    #[prost(uint32, tag = "5")]
+    pub target_index: u32,
+    #[prost(uint32, tag = "6")]
+    pub invocation_count: u32,
+    #[prost(string, optional, tag = "7")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetOccasionalMatrixUpdatesReply {
     /// For each updated matrix property
     #[prost(message, repeated, tag = "1")]
@@ -2646,6 +2781,11 @@ pub mod helgobox_service_server {
             tonic::Response<super::GetCompartmentDataReply>,
             tonic::Status,
         >;
+        /// ReaLearn unit queries
+        async fn get_units(
+            &self,
+            request: tonic::Request<super::GetUnitsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetUnitsReply>, tonic::Status>;
         /// General global commands
         async fn trigger_global(
             &self,
@@ -2817,6 +2957,23 @@ pub mod helgobox_service_server {
             &self,
             request: tonic::Request<super::SaveCustomCompartmentDataRequest>,
         ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// ReaLearn mapping, group and parameter commands
+        async fn save_mapping(
+            &self,
+            request: tonic::Request<super::SaveMappingRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn delete_mapping(
+            &self,
+            request: tonic::Request<super::DeleteMappingRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn set_group_enabled(
+            &self,
+            request: tonic::Request<super::SetGroupEnabledRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        async fn set_compartment_parameter(
+            &self,
+            request: tonic::Request<super::SetCompartmentParameterRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
         /// Server streaming response type for the GetOccasionalGlobalUpdates method.
         type GetOccasionalGlobalUpdatesStream: tonic::codegen::tokio_stream::Stream<
                 Item = std::result::Result<
@@ -2884,6 +3041,23 @@ pub mod helgobox_service_server {
             tonic::Response<Self::GetOccasionalUnitUpdatesStream>,
             tonic::Status,
         >;
+        /// Server streaming response type for the GetContinuousUnitUpdates method.
+        type GetContinuousUnitUpdatesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::GetContinuousUnitUpdatesReply,
+                    tonic::Status,
+                >,
+            >
+            + Send
+            + 'static;
+        /// ReaLearn unit events
+        async fn get_continuous_unit_updates(
+            &self,
+            request: tonic::Request<super::GetContinuousUnitUpdatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::GetContinuousUnitUpdatesStream>,
+            tonic::Status,
+        >;
         /// Server streaming response type for the GetOccasionalMatrixUpdates method.
         type GetOccasionalMatrixUpdatesStream: tonic::codegen::tokio_stream::Stream<
                 Item = std::result::Result<
@@ -3498,6 +3672,52 @@ pub mod helgobox_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/generated.HelgoboxService/GetUnits" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUnitsSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::UnaryService<super::GetUnitsRequest>
+                    for GetUnitsSvc<T> {
+                        type Response = super::GetUnitsReply;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetUnitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::get_units(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetUnitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/generated.HelgoboxService/TriggerGlobal" => {
                     #[allow(non_camel_case_types)]
                     struct TriggerGlobalSvc<T: HelgoboxService>(pub Arc<T>);
@@ -5390,6 +5610,198 @@ pub mod helgobox_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/generated.HelgoboxService/SaveMapping" => {
+                    #[allow(non_camel_case_types)]
+                    struct SaveMappingSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::UnaryService<super::SaveMappingRequest>
+                    for SaveMappingSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SaveMappingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::save_mapping(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SaveMappingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/generated.HelgoboxService/DeleteMapping" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteMappingSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::UnaryService<super::DeleteMappingRequest>
+                    for DeleteMappingSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteMappingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::delete_mapping(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteMappingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/generated.HelgoboxService/SetGroupEnabled" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetGroupEnabledSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::UnaryService<super::SetGroupEnabledRequest>
+                    for SetGroupEnabledSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetGroupEnabledRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::set_group_enabled(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetGroupEnabledSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/generated.HelgoboxService/SetCompartmentParameter" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetCompartmentParameterSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::UnaryService<super::SetCompartmentParameterRequest>
+                    for SetCompartmentParameterSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::SetCompartmentParameterRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::set_compartment_parameter(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetCompartmentParameterSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/generated.HelgoboxService/GetOccasionalGlobalUpdates" => {
                     #[allow(non_camel_case_types)]
                     struct GetOccasionalGlobalUpdatesSvc<T: HelgoboxService>(pub Arc<T>);
@@ -5610,6 +6022,60 @@ pub mod helgobox_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/generated.HelgoboxService/GetContinuousUnitUpdates" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContinuousUnitUpdatesSvc<T: HelgoboxService>(pub Arc<T>);
+                    impl<
+                        T: HelgoboxService,
+                    > tonic::server::ServerStreamingService<
+                        super::GetContinuousUnitUpdatesRequest,
+                    > for GetContinuousUnitUpdatesSvc<T> {
+                        type Response = super::GetContinuousUnitUpdatesReply;
+                        type ResponseStream = T::GetContinuousUnitUpdatesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetContinuousUnitUpdatesRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as HelgoboxService>::get_continuous_unit_updates(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContinuousUnitUpdatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/generated.HelgoboxService/GetOccasionalMatrixUpdates" => {
                     #[allow(non_camel_case_types)]
                     struct GetOccasionalMatrixUpdatesSvc<T: HelgoboxService>(pub Arc<T>);
@@ -1,18 +1,20 @@
+use helgoboss_learn::AbsoluteValue;
 use reaper_high::ChangeEvent;
 
 use realearn_api::runtime::{GlobalInfoEvent, InstanceInfoEvent};
 
 use crate::application::UnitModel;
-use crate::domain::{InstanceId, UnitId};
+use crate::domain::{CompartmentKind, ControlLogEntry, InstanceId, MappingKey, UnitId};
 use crate::infrastructure::data::{
     ControllerManager, FileBasedControllerPresetManager, FileBasedMainPresetManager, LicenseManager,
 };
 use crate::infrastructure::plugin::InstanceShell;
 use crate::infrastructure::proto::helgobox_service_server::HelgoboxServiceServer;
 use crate::infrastructure::proto::{
-    occasional_global_update, occasional_instance_update, qualified_occasional_unit_update,
-    HelgoboxServiceImpl, OccasionalGlobalUpdate, OccasionalInstanceUpdate,
-    OccasionalInstanceUpdateBatch, OccasionalUnitUpdateBatch, ProtoRequestHandler, ProtoSenders,
+    occasional_global_update, occasional_instance_update, qualified_continuous_unit_update,
+    qualified_occasional_unit_update, ContinuousUnitUpdateBatch, HelgoboxServiceImpl,
+    OccasionalGlobalUpdate, OccasionalInstanceUpdate, OccasionalInstanceUpdateBatch,
+    OccasionalUnitUpdateBatch, ProtoRequestHandler, ProtoSenders, QualifiedContinuousUnitUpdate,
     QualifiedOccasionalUnitUpdate,
 };
 
@@ -116,6 +118,44 @@ impl ProtoHub {
         })
     }
 
+    pub fn wants_continuous_unit_updates(&self) -> bool {
+        self.senders.continuous_unit_update_sender.receiver_count() > 0
+    }
+
+    pub fn notify_mapping_target_value_changed(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        new_value: AbsoluteValue,
+    ) {
+        self.send_continuous_unit_updates(instance_id, unit_id, || {
+            [qualified_continuous_unit_update::Update::target_value(
+                compartment,
+                mapping_key.to_string(),
+                new_value,
+            )]
+        });
+    }
+
+    pub fn notify_mapping_target_controlled(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        compartment: CompartmentKind,
+        mapping_key: &MappingKey,
+        log_entry: ControlLogEntry,
+    ) {
+        self.send_continuous_unit_updates(instance_id, unit_id, || {
+            [qualified_continuous_unit_update::Update::control_log_entry(
+                compartment,
+                mapping_key.to_string(),
+                log_entry,
+            )]
+        });
+    }
+
     pub fn notify_controller_config_changed(&self, controller_manager: &ControllerManager) {
         self.send_occasional_global_updates(|| {
             [occasional_global_update::Update::controller_config(
@@ -185,6 +225,32 @@ impl ProtoHub {
         let _ = sender.send(batch_event);
     }
 
+    fn send_continuous_unit_updates<F, I>(
+        &self,
+        instance_id: InstanceId,
+        unit_id: UnitId,
+        create_updates: F,
+    ) where
+        F: FnOnce() -> I,
+        I: IntoIterator<Item = qualified_continuous_unit_update::Update>,
+    {
+        let sender = &self.senders.continuous_unit_update_sender;
+        if sender.receiver_count() == 0 {
+            return;
+        }
+        let wrapped_updates = create_updates()
+            .into_iter()
+            .map(|u| QualifiedContinuousUnitUpdate {
+                unit_id: unit_id.into(),
+                update: Some(u),
+            });
+        let batch_event = ContinuousUnitUpdateBatch {
+            instance_id,
+            value: wrapped_updates.collect(),
+        };
+        let _ = sender.send(batch_event);
+    }
+
     pub fn notify_instance_units_changed(&self, instance_shell: &InstanceShell) {
         self.send_occasional_instance_updates(instance_shell.instance_id(), || {
             [occasional_instance_update::Update::units(instance_shell)]
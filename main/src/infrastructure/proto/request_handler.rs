@@ -1,35 +1,43 @@
 use anyhow::Context;
 use helgoboss_license_api::persistence::LicenseKey;
+use realearn_api::persistence;
 use reaper_high::{OrCurrentProject, Reaper};
+use reaper_medium::ReaperNormalizedFxParamValue;
+use std::iter;
+use std::rc::Rc;
 use tonic::{Response, Status};
 
 use base::spawn_in_main_thread;
 
-use crate::domain::{CompartmentKind, UnitId};
-use crate::infrastructure::api::convert::from_data;
+use crate::application::{GroupCommand, UnitModel};
+use crate::domain::{
+    CompartmentKind, CompartmentParamIndex, GroupKey, MappingKey, QualifiedMappingId, UnitId,
+};
 use crate::infrastructure::api::convert::from_data::ConversionStyle;
+use crate::infrastructure::api::convert::{from_data, to_data};
 use crate::infrastructure::data::CompartmentModelData;
 use crate::infrastructure::plugin::{BackboneShell, InstanceShell, UnitShell};
 #[cfg(feature = "playtime")]
 use crate::infrastructure::proto::PlaytimeProtoRequestHandler;
 use crate::infrastructure::proto::{
-    AddLicenseRequest, Compartment, DeleteControllerRequest, DragClipRequest, DragColumnRequest,
-    DragRowRequest, DragSlotRequest, Empty, FullCompartmentId, GetAppSettingsReply,
-    GetAppSettingsRequest, GetArrangementInfoReply, GetArrangementInfoRequest, GetClipDetailReply,
-    GetClipDetailRequest, GetCompartmentDataReply, GetCompartmentDataRequest,
+    AddLicenseRequest, Compartment, DeleteControllerRequest, DeleteMappingRequest, DragClipRequest,
+    DragColumnRequest, DragRowRequest, DragSlotRequest, Empty, FullCompartmentId,
+    GetAppSettingsReply, GetAppSettingsRequest, GetArrangementInfoReply, GetArrangementInfoRequest,
+    GetClipDetailReply, GetClipDetailRequest, GetCompartmentDataReply, GetCompartmentDataRequest,
     GetCustomInstanceDataReply, GetCustomInstanceDataRequest, GetHostInfoReply, GetHostInfoRequest,
-    GetProjectDirReply, GetProjectDirRequest, ImportFilesRequest, InsertColumnsRequest,
-    ProveAuthenticityReply, ProveAuthenticityRequest, SaveControllerRequest,
-    SaveCustomCompartmentDataRequest, SetAppSettingsRequest, SetClipDataRequest,
-    SetClipNameRequest, SetColumnSettingsRequest, SetColumnTrackRequest,
-    SetCustomInstanceDataRequest, SetInstanceSettingsRequest, SetMatrixPanRequest,
-    SetMatrixSettingsRequest, SetMatrixTempoRequest, SetMatrixTimeSignatureRequest,
-    SetMatrixVolumeRequest, SetPlaytimeEngineSettingsRequest, SetRowDataRequest,
-    SetSequenceInfoRequest, SetTrackColorRequest, SetTrackInputMonitoringRequest,
-    SetTrackInputRequest, SetTrackNameRequest, SetTrackPanRequest, SetTrackVolumeRequest,
-    TriggerClipRequest, TriggerColumnRequest, TriggerGlobalAction, TriggerGlobalRequest,
-    TriggerInstanceAction, TriggerInstanceRequest, TriggerMatrixRequest, TriggerRowRequest,
-    TriggerSequenceRequest, TriggerSlotRequest, TriggerTrackRequest, HOST_API_VERSION,
+    GetProjectDirReply, GetProjectDirRequest, GetUnitsReply, GetUnitsRequest, ImportFilesRequest,
+    InsertColumnsRequest, ProveAuthenticityReply, ProveAuthenticityRequest, SaveControllerRequest,
+    SaveCustomCompartmentDataRequest, SaveMappingRequest, SetAppSettingsRequest,
+    SetClipDataRequest, SetClipNameRequest, SetColumnSettingsRequest, SetColumnTrackRequest,
+    SetCompartmentParameterRequest, SetCustomInstanceDataRequest, SetGroupEnabledRequest,
+    SetInstanceSettingsRequest, SetMatrixPanRequest, SetMatrixSettingsRequest,
+    SetMatrixTempoRequest, SetMatrixTimeSignatureRequest, SetMatrixVolumeRequest,
+    SetPlaytimeEngineSettingsRequest, SetRowDataRequest, SetSequenceInfoRequest,
+    SetTrackColorRequest, SetTrackInputMonitoringRequest, SetTrackInputRequest,
+    SetTrackNameRequest, SetTrackPanRequest, SetTrackVolumeRequest, TriggerClipRequest,
+    TriggerColumnRequest, TriggerGlobalAction, TriggerGlobalRequest, TriggerInstanceAction,
+    TriggerInstanceRequest, TriggerMatrixRequest, TriggerRowRequest, TriggerSequenceRequest,
+    TriggerSlotRequest, TriggerTrackRequest, Units, HOST_API_VERSION,
 };
 
 #[derive(Debug)]
@@ -614,6 +622,124 @@ impl ProtoRequestHandler {
         )
     }
 
+    pub fn get_units(&self, request: GetUnitsRequest) -> Result<Response<GetUnitsReply>, Status> {
+        self.handle_instance_command_internal(request.instance_id, |instance_shell| {
+            let reply = GetUnitsReply {
+                units: Units::from_engine(instance_shell).units,
+            };
+            Ok(Response::new(reply))
+        })
+    }
+
+    pub fn save_mapping(&self, request: SaveMappingRequest) -> Result<Response<Empty>, Status> {
+        let api_mapping = parse_api_mapping(&request.data)?;
+        self.handle_compartment_command_internal(
+            &request.compartment_id,
+            |unit_shell, compartment| {
+                let shared_unit_model = unit_shell.model();
+                let mut unit_model = shared_unit_model.borrow_mut();
+                let conversion_context = unit_model.compartment_in_session(compartment);
+                let mapping_data = to_data::convert_mapping(api_mapping, &conversion_context)?;
+                let existing_mapping = mapping_data
+                    .id
+                    .as_ref()
+                    .and_then(|key| unit_model.find_mapping_by_key(compartment, key));
+                if let Some(mapping) = existing_mapping {
+                    let mut mapping = mapping.borrow_mut();
+                    mapping_data
+                        .apply_to_model(
+                            &mut mapping,
+                            &conversion_context,
+                            Some(unit_model.extended_context()),
+                            None,
+                        )
+                        .map_err(anyhow::Error::msg)?;
+                    unit_model.notify_mapping_has_changed(
+                        mapping.qualified_id(),
+                        Rc::downgrade(shared_unit_model),
+                    );
+                } else {
+                    let mapping = mapping_data
+                        .to_model(
+                            compartment,
+                            &conversion_context,
+                            Some(unit_model.extended_context()),
+                            None,
+                        )
+                        .map_err(anyhow::Error::msg)?;
+                    let index = unit_model.mapping_count(compartment);
+                    unit_model.insert_mappings_at(compartment, index, iter::once(mapping));
+                }
+                Ok(Response::new(Empty {}))
+            },
+        )
+    }
+
+    pub fn delete_mapping(&self, request: DeleteMappingRequest) -> Result<Response<Empty>, Status> {
+        self.handle_compartment_command_internal(
+            &request.compartment_id,
+            |unit_shell, compartment| {
+                let mut unit_model = unit_shell.model().borrow_mut();
+                let mapping_key = MappingKey::from(request.mapping_key);
+                let mapping_id = unit_model
+                    .find_mapping_id_by_key(compartment, &mapping_key)
+                    .context("Mapping not found")?;
+                unit_model.remove_mapping(QualifiedMappingId::new(compartment, mapping_id));
+                Ok(Response::new(Empty {}))
+            },
+        )
+    }
+
+    pub fn set_group_enabled(
+        &self,
+        request: SetGroupEnabledRequest,
+    ) -> Result<Response<Empty>, Status> {
+        self.handle_compartment_command_internal(
+            &request.compartment_id,
+            |unit_shell, compartment| {
+                let shared_unit_model = unit_shell.model();
+                let mut unit_model = shared_unit_model.borrow_mut();
+                let group = if request.group_key.is_empty() {
+                    unit_model.default_group(compartment).clone()
+                } else {
+                    let group_key = GroupKey::from(request.group_key);
+                    unit_model
+                        .find_group_by_key(compartment, &group_key)
+                        .context("Group not found")?
+                        .clone()
+                };
+                let mut group = group.borrow_mut();
+                for cmd in [
+                    GroupCommand::SetControlIsEnabled(request.enabled),
+                    GroupCommand::SetFeedbackIsEnabled(request.enabled),
+                ] {
+                    unit_model.change_group_from_ui_expert(
+                        &mut group,
+                        cmd,
+                        None,
+                        Rc::downgrade(shared_unit_model),
+                    );
+                }
+                Ok(Response::new(Empty {}))
+            },
+        )
+    }
+
+    pub fn set_compartment_parameter(
+        &self,
+        request: SetCompartmentParameterRequest,
+    ) -> Result<Response<Empty>, Status> {
+        let index = validate_compartment_parameter(request.index, request.value)?;
+        self.handle_compartment_command_internal(
+            &request.compartment_id,
+            |unit_shell, compartment| {
+                let unit_model = unit_shell.model().borrow();
+                set_compartment_parameter_value(&unit_model, compartment, index, request.value)?;
+                Ok(Response::new(Empty {}))
+            },
+        )
+    }
+
     pub fn get_custom_instance_data(
         &self,
         request: GetCustomInstanceDataRequest,
@@ -756,6 +882,44 @@ impl ProtoRequestHandler {
     }
 }
 
+fn parse_api_mapping(data: &str) -> Result<persistence::Mapping, Status> {
+    serde_json::from_str(data).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+fn validate_compartment_parameter(index: u32, value: f64) -> Result<CompartmentParamIndex, Status> {
+    let index = CompartmentParamIndex::try_from(index)
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(Status::invalid_argument(
+            "parameter value must be between 0.0 and 1.0",
+        ));
+    }
+    Ok(index)
+}
+
+fn set_compartment_parameter_value(
+    unit_model: &UnitModel,
+    compartment: CompartmentKind,
+    index: CompartmentParamIndex,
+    value: f64,
+) -> anyhow::Result<()> {
+    let plugin_param_index = compartment.to_plugin_param_index(index);
+    let unit = unit_model.unit().borrow();
+    if unit.is_main_unit() {
+        // Same as in the "Compartment parameter value" target: The compartment parameters of the
+        // main unit are connected to the plug-in parameters, so we go through the FX parameter.
+        unit_model
+            .processor_context()
+            .containing_fx()
+            .parameter_by_index(plugin_param_index.get())
+            .set_reaper_normalized_value(ReaperNormalizedFxParamValue::new(value))?;
+    } else {
+        unit.parameter_manager()
+            .set_single_parameter(plugin_param_index, value as _);
+    }
+    Ok(())
+}
+
 #[cfg(not(feature = "playtime"))]
 pub fn playtime_not_available() -> Result<Response<Empty>, Status> {
     Err(playtime_not_available_status())
@@ -765,3 +929,49 @@ pub fn playtime_not_available() -> Result<Response<Empty>, Status> {
 pub fn playtime_not_available_status() -> Status {
     Status::not_found("Playtime not available")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn parse_valid_api_mapping() {
+        // Given
+        let data = r#"{"id": "volume", "name": "Volume"}"#;
+        // When
+        let mapping = parse_api_mapping(data).unwrap();
+        // Then
+        assert_eq!(mapping.id.as_deref(), Some("volume"));
+        assert_eq!(mapping.name.as_deref(), Some("Volume"));
+    }
+
+    #[test]
+    fn reject_invalid_api_mapping() {
+        // When
+        let result = parse_api_mapping(r#"{"id": 5}"#);
+        // Then
+        assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn validate_compartment_parameters() {
+        assert_eq!(validate_compartment_parameter(3, 0.5).unwrap().get(), 3);
+        assert_eq!(
+            validate_compartment_parameter(100_000, 0.5)
+                .unwrap_err()
+                .code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            validate_compartment_parameter(3, 1.5).unwrap_err().code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            validate_compartment_parameter(3, f64::NAN)
+                .unwrap_err()
+                .code(),
+            Code::InvalidArgument
+        );
+    }
+}
@@ -2,9 +2,9 @@ use crate::domain::InstanceId;
 use crate::infrastructure::proto::{
     event_reply, ContinuousColumnUpdate, ContinuousMatrixUpdate, EventReply,
     OccasionalGlobalUpdate, OccasionalInstanceUpdate, OccasionalMatrixUpdate,
-    OccasionalPlaytimeEngineUpdate, QualifiedContinuousSlotUpdate, QualifiedOccasionalClipUpdate,
-    QualifiedOccasionalColumnUpdate, QualifiedOccasionalRowUpdate, QualifiedOccasionalSlotUpdate,
-    QualifiedOccasionalTrackUpdate, QualifiedOccasionalUnitUpdate,
+    OccasionalPlaytimeEngineUpdate, QualifiedContinuousSlotUpdate, QualifiedContinuousUnitUpdate,
+    QualifiedOccasionalClipUpdate, QualifiedOccasionalColumnUpdate, QualifiedOccasionalRowUpdate,
+    QualifiedOccasionalSlotUpdate, QualifiedOccasionalTrackUpdate, QualifiedOccasionalUnitUpdate,
 };
 use futures::future;
 use tokio::sync::broadcast::{Receiver, Sender};
@@ -26,6 +26,7 @@ pub struct ProtoSenders {
     pub continuous_matrix_update_sender: Sender<ContinuousMatrixUpdateBatch>,
     pub continuous_column_update_sender: Sender<ContinuousColumnUpdateBatch>,
     pub continuous_slot_update_sender: Sender<ContinuousSlotUpdateBatch>,
+    pub continuous_unit_update_sender: Sender<ContinuousUnitUpdateBatch>,
 }
 
 #[derive(Debug)]
@@ -43,6 +44,7 @@ pub struct ProtoReceivers {
     pub continuous_matrix_update_receiver: Receiver<ContinuousMatrixUpdateBatch>,
     pub continuous_column_update_receiver: Receiver<ContinuousColumnUpdateBatch>,
     pub continuous_slot_update_receiver: Receiver<ContinuousSlotUpdateBatch>,
+    pub continuous_unit_update_receiver: Receiver<ContinuousUnitUpdateBatch>,
 }
 
 impl ProtoReceivers {
@@ -112,9 +114,16 @@ impl ProtoReceivers {
                 process,
                 &mut self.occasional_unit_update_receiver,
             ),
-            keep_processing_updates(
-                process,
-                &mut self.occasional_playtime_engine_update_receiver,
+            future::join(
+                keep_processing_updates(
+                    process,
+                    &mut self.occasional_playtime_engine_update_receiver,
+                ),
+                keep_processing_session_filtered_updates(
+                    instance_id,
+                    process,
+                    &mut self.continuous_unit_update_receiver,
+                ),
             ),
         )
         .await;
@@ -177,6 +186,7 @@ impl ProtoSenders {
             continuous_slot_update_sender: tokio::sync::broadcast::channel(1000).0,
             continuous_column_update_sender: tokio::sync::broadcast::channel(500).0,
             continuous_matrix_update_sender: tokio::sync::broadcast::channel(500).0,
+            continuous_unit_update_sender: tokio::sync::broadcast::channel(1000).0,
         }
     }
 
@@ -197,6 +207,7 @@ impl ProtoSenders {
             continuous_matrix_update_receiver: self.continuous_matrix_update_sender.subscribe(),
             continuous_column_update_receiver: self.continuous_column_update_sender.subscribe(),
             continuous_slot_update_receiver: self.continuous_slot_update_sender.subscribe(),
+            continuous_unit_update_receiver: self.continuous_unit_update_sender.subscribe(),
         }
     }
 }
@@ -220,3 +231,4 @@ pub type OccasionalClipUpdateBatch = WithInstanceId<Vec<QualifiedOccasionalClipU
 pub type ContinuousMatrixUpdateBatch = WithInstanceId<ContinuousMatrixUpdate>;
 pub type ContinuousColumnUpdateBatch = WithInstanceId<Vec<ContinuousColumnUpdate>>;
 pub type ContinuousSlotUpdateBatch = WithInstanceId<Vec<QualifiedContinuousSlotUpdate>>;
+pub type ContinuousUnitUpdateBatch = WithInstanceId<Vec<QualifiedContinuousUnitUpdate>>;
@@ -4,14 +4,15 @@ use crate::infrastructure::proto::playtime_not_available_status;
 use crate::infrastructure::proto::senders::{ProtoSenders, WithInstanceId};
 use crate::infrastructure::proto::{
     create_initial_global_updates, create_initial_instance_updates, create_initial_unit_updates,
-    helgobox_service_server, AddLicenseRequest, DeleteControllerRequest, DragClipRequest,
-    DragColumnRequest, DragRowRequest, DragSlotRequest, Empty, GetAppSettingsReply,
-    GetAppSettingsRequest, GetArrangementInfoReply, GetArrangementInfoRequest, GetClipDetailReply,
-    GetClipDetailRequest, GetCompartmentDataReply, GetCompartmentDataRequest,
+    helgobox_service_server, AddLicenseRequest, DeleteControllerRequest, DeleteMappingRequest,
+    DragClipRequest, DragColumnRequest, DragRowRequest, DragSlotRequest, Empty,
+    GetAppSettingsReply, GetAppSettingsRequest, GetArrangementInfoReply, GetArrangementInfoRequest,
+    GetClipDetailReply, GetClipDetailRequest, GetCompartmentDataReply, GetCompartmentDataRequest,
     GetContinuousColumnUpdatesReply, GetContinuousColumnUpdatesRequest,
     GetContinuousMatrixUpdatesReply, GetContinuousMatrixUpdatesRequest,
-    GetContinuousSlotUpdatesReply, GetContinuousSlotUpdatesRequest, GetCustomInstanceDataReply,
-    GetCustomInstanceDataRequest, GetOccasionalClipUpdatesReply, GetOccasionalClipUpdatesRequest,
+    GetContinuousSlotUpdatesReply, GetContinuousSlotUpdatesRequest, GetContinuousUnitUpdatesReply,
+    GetContinuousUnitUpdatesRequest, GetCustomInstanceDataReply, GetCustomInstanceDataRequest,
+    GetOccasionalClipUpdatesReply, GetOccasionalClipUpdatesRequest,
     GetOccasionalColumnUpdatesReply, GetOccasionalColumnUpdatesRequest,
     GetOccasionalGlobalUpdatesReply, GetOccasionalGlobalUpdatesRequest,
     GetOccasionalInstanceUpdatesReply, GetOccasionalInstanceUpdatesRequest,
@@ -20,17 +21,19 @@ use crate::infrastructure::proto::{
     GetOccasionalRowUpdatesReply, GetOccasionalRowUpdatesRequest, GetOccasionalSlotUpdatesReply,
     GetOccasionalSlotUpdatesRequest, GetOccasionalTrackUpdatesReply,
     GetOccasionalTrackUpdatesRequest, GetOccasionalUnitUpdatesReply,
-    GetOccasionalUnitUpdatesRequest, GetProjectDirReply, GetProjectDirRequest, ImportFilesRequest,
-    InsertColumnsRequest, ProtoRequestHandler, ProveAuthenticityReply, ProveAuthenticityRequest,
-    SaveControllerRequest, SaveCustomCompartmentDataRequest, SetAppSettingsRequest,
+    GetOccasionalUnitUpdatesRequest, GetProjectDirReply, GetProjectDirRequest, GetUnitsReply,
+    GetUnitsRequest, ImportFilesRequest, InsertColumnsRequest, ProtoRequestHandler,
+    ProveAuthenticityReply, ProveAuthenticityRequest, SaveControllerRequest,
+    SaveCustomCompartmentDataRequest, SaveMappingRequest, SetAppSettingsRequest,
     SetClipDataRequest, SetClipNameRequest, SetColumnSettingsRequest, SetColumnTrackRequest,
-    SetCustomInstanceDataRequest, SetInstanceSettingsRequest, SetMatrixPanRequest,
-    SetMatrixSettingsRequest, SetMatrixTempoRequest, SetMatrixTimeSignatureRequest,
-    SetMatrixVolumeRequest, SetPlaytimeEngineSettingsRequest, SetRowDataRequest,
-    SetTrackColorRequest, SetTrackInputMonitoringRequest, SetTrackInputRequest,
-    SetTrackNameRequest, SetTrackPanRequest, SetTrackVolumeRequest, TriggerClipRequest,
-    TriggerColumnRequest, TriggerGlobalRequest, TriggerInstanceRequest, TriggerMatrixRequest,
-    TriggerRowRequest, TriggerSlotRequest, TriggerTrackRequest,
+    SetCompartmentParameterRequest, SetCustomInstanceDataRequest, SetGroupEnabledRequest,
+    SetInstanceSettingsRequest, SetMatrixPanRequest, SetMatrixSettingsRequest,
+    SetMatrixTempoRequest, SetMatrixTimeSignatureRequest, SetMatrixVolumeRequest,
+    SetPlaytimeEngineSettingsRequest, SetRowDataRequest, SetTrackColorRequest,
+    SetTrackInputMonitoringRequest, SetTrackInputRequest, SetTrackNameRequest, SetTrackPanRequest,
+    SetTrackVolumeRequest, TriggerClipRequest, TriggerColumnRequest, TriggerGlobalRequest,
+    TriggerInstanceRequest, TriggerMatrixRequest, TriggerRowRequest, TriggerSlotRequest,
+    TriggerTrackRequest,
 };
 use base::future_util;
 use futures::{FutureExt, Stream, StreamExt};
@@ -376,6 +379,22 @@ impl helgobox_service_server::HelgoboxService for HelgoboxServiceImpl {
         )
     }
 
+    type GetContinuousUnitUpdatesStream =
+        SyncBoxStream<'static, Result<GetContinuousUnitUpdatesReply, Status>>;
+
+    async fn get_continuous_unit_updates(
+        &self,
+        request: Request<GetContinuousUnitUpdatesRequest>,
+    ) -> Result<Response<Self::GetContinuousUnitUpdatesStream>, Status> {
+        let receiver = self.senders.continuous_unit_update_sender.subscribe();
+        stream_by_session_id(
+            request.into_inner().instance_id,
+            receiver,
+            |unit_updates| GetContinuousUnitUpdatesReply { unit_updates },
+            iter::empty(),
+        )
+    }
+
     type GetOccasionalTrackUpdatesStream =
         SyncBoxStream<'static, Result<GetOccasionalTrackUpdatesReply, Status>>;
 
@@ -722,6 +741,42 @@ impl helgobox_service_server::HelgoboxService for HelgoboxServiceImpl {
             .save_custom_compartment_data(request.into_inner())
     }
 
+    async fn get_units(
+        &self,
+        request: Request<GetUnitsRequest>,
+    ) -> Result<Response<GetUnitsReply>, Status> {
+        self.command_handler.get_units(request.into_inner())
+    }
+
+    async fn save_mapping(
+        &self,
+        request: Request<SaveMappingRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.command_handler.save_mapping(request.into_inner())
+    }
+
+    async fn delete_mapping(
+        &self,
+        request: Request<DeleteMappingRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.command_handler.delete_mapping(request.into_inner())
+    }
+
+    async fn set_group_enabled(
+        &self,
+        request: Request<SetGroupEnabledRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.command_handler.set_group_enabled(request.into_inner())
+    }
+
+    async fn set_compartment_parameter(
+        &self,
+        request: Request<SetCompartmentParameterRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        self.command_handler
+            .set_compartment_parameter(request.into_inner())
+    }
+
     async fn get_custom_instance_data(
         &self,
         request: Request<GetCustomInstanceDataRequest>,
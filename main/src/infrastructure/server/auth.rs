@@ -0,0 +1,229 @@
+//! Contains the client authentication state shared between the HTTP/WebSocket and gRPC servers.
+
+use base::hash_util::NonCryptoHashMap;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+// We don't take the async RwLock by Tokio because we need to access this in sync code, too!
+pub type SharedServerAuth = Arc<RwLock<ServerAuth>>;
+
+/// How long a pairing code can be redeemed.
+const PAIRING_CODE_VALIDITY: Duration = Duration::from_secs(5 * 60);
+
+/// After that many wrong guesses, a client can't redeem the current pairing code anymore (prevents
+/// brute-forcing).
+///
+/// This is counted per client address. Otherwise, anyone in the network could prevent legitimate
+/// clients from pairing just by sending a few wrong codes.
+const MAX_FAILED_PAIRING_ATTEMPTS: u32 = 5;
+
+const PAIRING_CODE_ALPHABET: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+/// What a client is allowed to do.
+///
+/// Ordered from least to most powerful.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenScope {
+    /// Can read data and subscribe to updates.
+    ReadOnly,
+    /// Can additionally control targets, trigger Playtime actions and change data.
+    Control,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AuthError {
+    MissingToken,
+    InvalidToken,
+    InsufficientScope,
+    /// The authentication state couldn't be accessed (poisoned lock).
+    Internal,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PairingError {
+    /// No pairing in progress, pairing code expired or wrong pairing code.
+    InvalidCode,
+    /// The client made too many failed attempts to redeem the current pairing code.
+    TooManyAttempts,
+}
+
+#[derive(Debug, Default)]
+pub struct ServerAuth {
+    /// If disabled, every client is treated as if it had a token with control scope.
+    enabled: bool,
+    tokens: NonCryptoHashMap<String, TokenScope>,
+    pending_pairing: Option<PendingPairing>,
+}
+
+#[derive(Debug)]
+struct PendingPairing {
+    code: String,
+    scope: TokenScope,
+    expires_at: Instant,
+    failed_attempts_by_client: NonCryptoHashMap<IpAddr, u32>,
+}
+
+impl ServerAuth {
+    pub fn new(
+        enabled: bool,
+        tokens: impl IntoIterator<Item = (String, TokenScope)>,
+    ) -> SharedServerAuth {
+        let auth = Self {
+            enabled,
+            tokens: tokens.into_iter().collect(),
+            pending_pairing: None,
+        };
+        Arc::new(RwLock::new(auth))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Replaces all tokens, e.g. after a token has been added or revoked in the config.
+    pub fn set_tokens(&mut self, tokens: impl IntoIterator<Item = (String, TokenScope)>) {
+        self.tokens = tokens.into_iter().collect();
+    }
+
+    /// Checks if the given token grants at least the required scope and returns the granted scope.
+    pub fn authorize(
+        &self,
+        token: Option<&str>,
+        required_scope: TokenScope,
+    ) -> Result<TokenScope, AuthError> {
+        if !self.enabled {
+            return Ok(TokenScope::Control);
+        }
+        let token = token.ok_or(AuthError::MissingToken)?;
+        let granted_scope = *self.tokens.get(token).ok_or(AuthError::InvalidToken)?;
+        if granted_scope < required_scope {
+            return Err(AuthError::InsufficientScope);
+        }
+        Ok(granted_scope)
+    }
+
+    /// Generates a new one-time pairing code, replacing any previous one.
+    pub fn start_pairing(&mut self, scope: TokenScope) -> String {
+        let code = nanoid::nanoid!(6, &PAIRING_CODE_ALPHABET);
+        self.pending_pairing = Some(PendingPairing {
+            code: code.clone(),
+            scope,
+            expires_at: Instant::now() + PAIRING_CODE_VALIDITY,
+            failed_attempts_by_client: Default::default(),
+        });
+        code
+    }
+
+    /// Consumes the pending pairing code if it matches and returns the scope to be granted.
+    pub fn redeem_pairing_code(
+        &mut self,
+        client: IpAddr,
+        code: &str,
+    ) -> Result<TokenScope, PairingError> {
+        let pairing = self
+            .pending_pairing
+            .as_mut()
+            .ok_or(PairingError::InvalidCode)?;
+        if Instant::now() > pairing.expires_at {
+            self.pending_pairing = None;
+            return Err(PairingError::InvalidCode);
+        }
+        let failed_attempts = pairing.failed_attempts_by_client.entry(client).or_default();
+        if *failed_attempts >= MAX_FAILED_PAIRING_ATTEMPTS {
+            return Err(PairingError::TooManyAttempts);
+        }
+        if pairing.code != code {
+            *failed_attempts += 1;
+            return Err(PairingError::InvalidCode);
+        }
+        let pairing = self.pending_pairing.take().expect("pairing checked above");
+        Ok(pairing.scope)
+    }
+}
+
+pub fn generate_token() -> String {
+    nanoid::nanoid!(32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorize() {
+        let auth = ServerAuth::new(true, [("abc".to_string(), TokenScope::ReadOnly)]);
+        let auth = auth.read().unwrap();
+        assert_eq!(
+            auth.authorize(Some("abc"), TokenScope::ReadOnly),
+            Ok(TokenScope::ReadOnly)
+        );
+        assert_eq!(
+            auth.authorize(Some("abc"), TokenScope::Control),
+            Err(AuthError::InsufficientScope)
+        );
+        assert_eq!(
+            auth.authorize(Some("xyz"), TokenScope::ReadOnly),
+            Err(AuthError::InvalidToken)
+        );
+        assert_eq!(
+            auth.authorize(None, TokenScope::ReadOnly),
+            Err(AuthError::MissingToken)
+        );
+    }
+
+    #[test]
+    fn pairing_attempts_are_limited_per_client() {
+        // Given
+        let auth = ServerAuth::new(true, []);
+        let mut auth = auth.write().unwrap();
+        let attacker = IpAddr::from([192, 168, 0, 66]);
+        let client = IpAddr::from([192, 168, 0, 10]);
+        let code = auth.start_pairing(TokenScope::Control);
+        // When
+        for _ in 0..MAX_FAILED_PAIRING_ATTEMPTS {
+            assert_eq!(
+                auth.redeem_pairing_code(attacker, "wrong"),
+                Err(PairingError::InvalidCode)
+            );
+        }
+        // Then
+        assert_eq!(
+            auth.redeem_pairing_code(attacker, &code),
+            Err(PairingError::TooManyAttempts)
+        );
+        assert_eq!(
+            auth.redeem_pairing_code(client, &code),
+            Ok(TokenScope::Control)
+        );
+        assert_eq!(
+            auth.redeem_pairing_code(client, &code),
+            Err(PairingError::InvalidCode)
+        );
+    }
+
+    #[test]
+    fn new_pairing_code_resets_failed_attempts() {
+        // Given
+        let auth = ServerAuth::new(true, []);
+        let mut auth = auth.write().unwrap();
+        let client = IpAddr::from([127, 0, 0, 1]);
+        auth.start_pairing(TokenScope::ReadOnly);
+        for _ in 0..MAX_FAILED_PAIRING_ATTEMPTS {
+            let _ = auth.redeem_pairing_code(client, "wrong");
+        }
+        // When
+        let code = auth.start_pairing(TokenScope::ReadOnly);
+        // Then
+        assert_eq!(
+            auth.redeem_pairing_code(client, &code),
+            Ok(TokenScope::ReadOnly)
+        );
+    }
+}
@@ -1,16 +1,30 @@
 //! Contains the actual application interface and implementation without any HTTP-specific stuff.
 
 use crate::application::{
-    CompartmentPresetManager, CompartmentPresetModel, SourceCategory, TargetCategory, UnitModel,
+    CompartmentInSession, CompartmentPresetManager, CompartmentPresetModel, SourceCategory,
+    TargetCategory, UnitModel,
 };
-use crate::domain::{CompartmentKind, MappingKey, ProjectionFeedbackValue};
-use crate::infrastructure::data::CompartmentPresetData;
+use crate::domain::{
+    CompartmentKind, MappingKey, ProjectionFeedbackValue, VirtualControlElement,
+    VirtualControlElementId, VirtualFeedbackValue, VirtualSourceValue,
+};
+use crate::infrastructure::api::convert::from_data;
+use crate::infrastructure::api::convert::from_data::ConversionStyle;
+use crate::infrastructure::api::convert::to_data;
+use crate::infrastructure::api::convert::to_data::ApiToDataConversionContext;
+use crate::infrastructure::data::{CompartmentModelData, CompartmentPresetData};
 use crate::infrastructure::plugin::BackboneShell;
+use crate::infrastructure::server::auth::{PairingError, TokenScope};
+use crate::infrastructure::ui::{DataObject, UntaggedApiObject};
 use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
-use helgoboss_learn::UnitValue;
+use helgoboss_learn::{ControlValue, DiscreteIncrement, FeedbackValue, UnitValue};
+use realearn_api::persistence;
+use realearn_api::persistence::{Envelope, VirtualControlElementCharacter};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::net::IpAddr;
 use std::rc::Rc;
 
 #[derive(Serialize)]
@@ -18,6 +32,42 @@ use std::rc::Rc;
 // Right now just a placeholder
 pub struct SessionResponseData {}
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceResponseData {
+    id: String,
+    track_name: Option<String>,
+    units: Vec<UnitResponseData>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitResponseData {
+    /// This is what the other endpoints refer to as session ID.
+    key: String,
+    name: Option<String>,
+    instance_id: String,
+    is_main_unit: bool,
+}
+
+/// Returned if a request fails. Contains details for validation errors.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponseData {
+    message: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<String>,
+}
+
+impl From<DataError> for ErrorResponseData {
+    fn from(e: DataError) -> Self {
+        Self {
+            message: e.description(),
+            details: e.into_details(),
+        }
+    }
+}
+
 pub enum DataError {
     SessionNotFound,
     SessionHasNoActiveController,
@@ -25,12 +75,25 @@ pub enum DataError {
     OnlyPatchReplaceIsSupported,
     OnlyCustomDataKeyIsSupportedAsPatchPath,
     ControllerUpdateFailed,
+    InvalidCompartment,
+    /// Contains the chain of error messages, starting with the most general one.
+    InvalidCompartmentData(Vec<String>),
+    CompartmentMismatch,
+    NotACompartment,
+    CompartmentExportFailed,
+    InvalidVirtualControlElementName,
+    InvalidVirtualControlValue,
+    InvalidPairingCode,
+    TooManyPairingAttempts,
+    ServerAuthUnavailable,
 }
 
 pub enum DataErrorCategory {
     NotFound,
     BadRequest,
     MethodNotAllowed,
+    Unauthorized,
+    TooManyRequests,
     InternalServerError,
 }
 
@@ -46,9 +109,32 @@ impl DataError {
                 "only '/customData/{key}' is supported as path"
             }
             ControllerUpdateFailed => "couldn't update controller",
+            InvalidCompartment => "compartment must be 'main' or 'controller'",
+            InvalidCompartmentData(_) => "invalid compartment data",
+            CompartmentMismatch => "body contains a different compartment than the one in the path",
+            NotACompartment => "body contains an API object which is not a compartment",
+            CompartmentExportFailed => "couldn't export compartment",
+            InvalidVirtualControlElementName => "invalid virtual control element name",
+            InvalidVirtualControlValue => "relative virtual control value must not be 0",
+            InvalidPairingCode => "pairing code is invalid or expired",
+            TooManyPairingAttempts => {
+                "too many failed pairing attempts, please generate a new pairing code"
+            }
+            ServerAuthUnavailable => "server authentication state is unavailable",
         }
     }
 
+    pub fn into_details(self) -> Vec<String> {
+        match self {
+            DataError::InvalidCompartmentData(details) => details,
+            _ => vec![],
+        }
+    }
+
+    fn invalid_compartment_data(error: anyhow::Error) -> Self {
+        Self::InvalidCompartmentData(error.chain().map(|e| e.to_string()).collect())
+    }
+
     pub fn category(&self) -> DataErrorCategory {
         use DataError::*;
         match self {
@@ -56,8 +142,18 @@ impl DataError {
                 DataErrorCategory::NotFound
             }
             OnlyPatchReplaceIsSupported => DataErrorCategory::MethodNotAllowed,
-            OnlyCustomDataKeyIsSupportedAsPatchPath => DataErrorCategory::BadRequest,
-            ControllerUpdateFailed => DataErrorCategory::InternalServerError,
+            InvalidPairingCode => DataErrorCategory::Unauthorized,
+            TooManyPairingAttempts => DataErrorCategory::TooManyRequests,
+            OnlyCustomDataKeyIsSupportedAsPatchPath
+            | InvalidCompartment
+            | InvalidCompartmentData(_)
+            | CompartmentMismatch
+            | NotACompartment
+            | InvalidVirtualControlElementName
+            | InvalidVirtualControlValue => DataErrorCategory::BadRequest,
+            ControllerUpdateFailed | CompartmentExportFailed | ServerAuthUnavailable => {
+                DataErrorCategory::InternalServerError
+            }
         }
     }
 }
@@ -102,6 +198,147 @@ pub fn get_session_data(session_id: String) -> Result<SessionResponseData, DataE
     Ok(SessionResponseData {})
 }
 
+pub fn get_instances() -> Vec<InstanceResponseData> {
+    let units = get_units();
+    BackboneShell::get().with_instance_shell_infos(|infos| {
+        infos
+            .iter()
+            .map(|info| {
+                let id = info.instance_id.to_string();
+                InstanceResponseData {
+                    track_name: info
+                        .processor_context
+                        .track()
+                        .and_then(|t| t.name())
+                        .map(|n| n.into_string()),
+                    units: units
+                        .iter()
+                        .filter(|u| u.instance_id == id)
+                        .cloned()
+                        .collect(),
+                    id,
+                }
+            })
+            .collect()
+    })
+}
+
+pub fn get_units() -> Vec<UnitResponseData> {
+    BackboneShell::get().with_unit_infos(|infos| {
+        infos
+            .iter()
+            .filter_map(|info| {
+                let unit_model = info.unit_model.upgrade()?;
+                let unit_model = unit_model.try_borrow().ok()?;
+                let data = UnitResponseData {
+                    key: unit_model.unit_key().to_string(),
+                    name: unit_model.name().map(|n| n.to_string()),
+                    instance_id: info.instance_id.to_string(),
+                    is_main_unit: info.is_main_unit,
+                };
+                Some(data)
+            })
+            .collect()
+    })
+}
+
+pub fn get_unit_compartment(
+    session_id: String,
+    compartment: String,
+) -> Result<persistence::Compartment, DataError> {
+    let compartment = parse_compartment(&compartment)?;
+    let session = BackboneShell::get()
+        .find_unit_model_by_key(&session_id)
+        .ok_or(DataError::SessionNotFound)?;
+    let model = session.borrow().extract_compartment_model(compartment);
+    let data = CompartmentModelData::from_model(&model);
+    from_data::convert_compartment(data, ConversionStyle::Minimal)
+        .map_err(|_| DataError::CompartmentExportFailed)
+}
+
+/// Replaces the given compartment of the given unit.
+///
+/// The body can either be a plain compartment or a tagged API object (a compartment of the same
+/// kind). Invalid compartments are reported together with the location of the error.
+pub fn put_unit_compartment(
+    session_id: String,
+    compartment: String,
+    body: &str,
+) -> Result<(), DataError> {
+    let compartment = parse_compartment(&compartment)?;
+    let session = BackboneShell::get()
+        .find_unit_model_by_key(&session_id)
+        .ok_or(DataError::SessionNotFound)?;
+    let mut session = session.borrow_mut();
+    let conversion_context = CompartmentInSession::new(&*session, compartment);
+    let Envelope {
+        value: data,
+        version,
+    } = deserialize_compartment_body(body, &conversion_context)?;
+    let model = data
+        .to_model(version.as_ref(), compartment, Some(&*session))
+        .map_err(DataError::invalid_compartment_data)?;
+    session.import_compartment(compartment, Some(model));
+    Ok(())
+}
+
+/// Deserializes the body of a compartment upload into the compartment of the given context.
+fn deserialize_compartment_body(
+    body: &str,
+    conversion_context: &impl ApiToDataConversionContext,
+) -> Result<Envelope<Box<CompartmentModelData>>, DataError> {
+    let raw: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| DataError::InvalidCompartmentData(vec![e.to_string()]))?;
+    // We pick the variant ourselves instead of deserializing the untagged object directly. Otherwise
+    // we would lose the error location and an invalid tagged object would end up as empty plain
+    // compartment.
+    let api_object = if raw.get("kind").is_some() {
+        UntaggedApiObject::Tagged(deserialize_with_error_location(&raw)?)
+    } else {
+        UntaggedApiObject::LuaPresetLike(deserialize_with_error_location(&raw)?)
+    };
+    let compartment = conversion_context.compartment();
+    match api_object {
+        UntaggedApiObject::Tagged(api_object) => {
+            let data_object = DataObject::try_from_api_object(api_object, conversion_context)
+                .map_err(DataError::invalid_compartment_data)?;
+            match (compartment, data_object) {
+                (CompartmentKind::Controller, DataObject::ControllerCompartment(e))
+                | (CompartmentKind::Main, DataObject::MainCompartment(e)) => Ok(e),
+                (_, DataObject::ControllerCompartment(_) | DataObject::MainCompartment(_)) => {
+                    Err(DataError::CompartmentMismatch)
+                }
+                _ => Err(DataError::NotACompartment),
+            }
+        }
+        UntaggedApiObject::LuaPresetLike(api_compartment) => {
+            let data = to_data::convert_compartment(compartment, *api_compartment)
+                .map_err(DataError::invalid_compartment_data)?;
+            Ok(Envelope::new(None, Box::new(data)))
+        }
+    }
+}
+
+fn deserialize_with_error_location<T: DeserializeOwned>(
+    raw: &serde_json::Value,
+) -> Result<T, DataError> {
+    serde_path_to_error::deserialize(raw).map_err(|e| {
+        let path = e.path().to_string();
+        DataError::InvalidCompartmentData(vec![format!(
+            "invalid compartment at \"{path}\": {}",
+            e.into_inner()
+        )])
+    })
+}
+
+fn parse_compartment(compartment: &str) -> Result<CompartmentKind, DataError> {
+    match compartment {
+        "controller" => Ok(CompartmentKind::Controller),
+        "main" => Ok(CompartmentKind::Main),
+        _ => Err(DataError::InvalidCompartment),
+    }
+}
+
 pub fn get_controller_routing_by_session_id(
     session_id: String,
 ) -> Result<ControllerRouting, DataError> {
@@ -217,6 +454,44 @@ pub fn patch_controller(controller_id: String, req: PatchRequest) -> Result<(),
     Ok(())
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingRequest {
+    /// The one-time code displayed in ReaLearn.
+    code: String,
+    /// Shown in ReaLearn when listing paired clients.
+    client_name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingResponseData {
+    /// Needs to be sent with each subsequent request, either as bearer token in the
+    /// `Authorization` header or as `token` query parameter.
+    token: String,
+    scope: TokenScope,
+}
+
+/// Exchanges a one-time pairing code for a long-lived client token.
+///
+/// Failed attempts are counted per client address.
+pub fn pair_client(client: IpAddr, req: PairingRequest) -> Result<PairingResponseData, DataError> {
+    let shell = BackboneShell::get();
+    let scope = shell
+        .server()
+        .borrow()
+        .auth()
+        .write()
+        .map_err(|_| DataError::ServerAuthUnavailable)?
+        .redeem_pairing_code(client, &req.code)
+        .map_err(|e| match e {
+            PairingError::InvalidCode => DataError::InvalidPairingCode,
+            PairingError::TooManyAttempts => DataError::TooManyPairingAttempts,
+        })?;
+    let token = shell.add_server_client_token(scope, req.client_name);
+    Ok(PairingResponseData { token, scope })
+}
+
 #[derive(Deserialize)]
 pub struct WebSocketRequest {
     pub topics: String,
@@ -230,12 +505,140 @@ impl WebSocketRequest {
 
 pub type Topics = NonCryptoHashSet<Topic>;
 
+/// A message which a WebSocket client sends to ReaLearn.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WebSocketClientMessage {
+    /// Lets the client act as a controller by sending values for virtual control elements.
+    Control(VirtualControlRequest),
+}
+
+/// Only has an effect if the unit's control input is set to web clients.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualControlRequest {
+    session_id: String,
+    element: VirtualControlElementData,
+    value: VirtualControlValueData,
+}
+
+/// Either an absolute value such as `0.5` (e.g. for faders and buttons) or a relative increment
+/// such as `{ "increment": -1 }` (e.g. for endless encoders).
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum VirtualControlValueData {
+    /// Absolute value between 0.0 and 1.0.
+    Absolute(f64),
+    /// Number of steps, must not be 0.
+    Relative { increment: i32 },
+}
+
+impl VirtualControlValueData {
+    fn to_domain(&self) -> Result<ControlValue, DataError> {
+        let value = match *self {
+            Self::Absolute(v) => ControlValue::AbsoluteContinuous(UnitValue::new_clamped(v)),
+            Self::Relative { increment } => {
+                if increment == 0 {
+                    return Err(DataError::InvalidVirtualControlValue);
+                }
+                ControlValue::RelativeDiscrete(DiscreteIncrement::new(increment))
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualControlElementData {
+    #[serde(default)]
+    character: VirtualControlElementCharacter,
+    /// Either a zero-based index or a name.
+    id: persistence::VirtualControlElementId,
+}
+
+impl VirtualControlElementData {
+    fn from_domain(element: VirtualControlElement) -> Self {
+        let (character, id) = match element {
+            VirtualControlElement::Multi(id) => (VirtualControlElementCharacter::Multi, id),
+            VirtualControlElement::Button(id) => (VirtualControlElementCharacter::Button, id),
+        };
+        let id = match id {
+            VirtualControlElementId::Indexed(i) => persistence::VirtualControlElementId::Indexed(i),
+            VirtualControlElementId::Named(n) => {
+                persistence::VirtualControlElementId::Named(n.to_string())
+            }
+        };
+        Self { character, id }
+    }
+
+    fn to_domain(&self) -> Result<VirtualControlElement, DataError> {
+        let id = match &self.id {
+            persistence::VirtualControlElementId::Indexed(i) => {
+                VirtualControlElementId::Indexed(*i)
+            }
+            persistence::VirtualControlElementId::Named(n) => n
+                .parse()
+                .map_err(|_| DataError::InvalidVirtualControlElementName)?,
+        };
+        let element = match self.character {
+            VirtualControlElementCharacter::Multi => VirtualControlElement::Multi(id),
+            VirtualControlElementCharacter::Button => VirtualControlElement::Button(id),
+        };
+        Ok(element)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualFeedbackData {
+    element: VirtualControlElementData,
+    /// `null` if the feedback value is not numeric, e.g. if the control element should be
+    /// switched off.
+    value: Option<f64>,
+    text: Option<String>,
+}
+
+impl VirtualFeedbackData {
+    fn from_domain(feedback_value: &VirtualFeedbackValue) -> Self {
+        let value = feedback_value.feedback_value();
+        Self {
+            element: VirtualControlElementData::from_domain(feedback_value.control_element()),
+            value: value.to_numeric().map(|v| v.value.to_unit_value().get()),
+            text: match value {
+                FeedbackValue::Textual(v) => Some(v.text.to_string()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Needs to be executed in the main thread!
+pub fn process_web_socket_client_message(msg: WebSocketClientMessage) -> Result<(), DataError> {
+    match msg {
+        WebSocketClientMessage::Control(req) => control_virtual_element(req),
+    }
+}
+
+fn control_virtual_element(req: VirtualControlRequest) -> Result<(), DataError> {
+    let session = BackboneShell::get()
+        .find_unit_model_by_key(&req.session_id)
+        .ok_or(DataError::SessionNotFound)?;
+    let element = req.element.to_domain()?;
+    let value = req.value.to_domain()?;
+    session
+        .borrow()
+        .control_virtual_element(VirtualSourceValue::new(element, value));
+    Ok(())
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Topic {
     Session { session_id: String },
     ActiveController { session_id: String },
     ControllerRouting { session_id: String },
     Feedback { session_id: String },
+    VirtualFeedback { session_id: String },
 }
 
 impl TryFrom<&str> for Topic {
@@ -253,6 +656,9 @@ impl TryFrom<&str> for Topic {
             ["realearn", "session", id, "feedback"] => Topic::Feedback {
                 session_id: id.to_string(),
             },
+            ["realearn", "session", id, "virtual-feedback"] => Topic::VirtualFeedback {
+                session_id: id.to_string(),
+            },
             ["realearn", "session", id] => Topic::Session {
                 session_id: id.to_string(),
             },
@@ -287,6 +693,16 @@ pub fn get_projection_feedback_event(
     Event::patch(format!("/realearn/session/{session_id}/feedback"), map)
 }
 
+pub fn get_virtual_feedback_event(
+    session_id: &str,
+    feedback_value: &VirtualFeedbackValue,
+) -> Event<VirtualFeedbackData> {
+    Event::patch(
+        format!("/realearn/session/{session_id}/virtual-feedback"),
+        VirtualFeedbackData::from_domain(feedback_value),
+    )
+}
+
 pub fn get_session_updated_event(
     session_id: &str,
     session_data: Option<SessionResponseData>,
@@ -371,3 +787,96 @@ fn get_controller_preset_data_internal(
     );
     Ok(CompartmentPresetData::from_model(&preset))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CompartmentParamIndex;
+
+    #[test]
+    fn deserialize_plain_compartment_body() {
+        // Given
+        let body = r#"{ "notes": "Hello" }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Main));
+        // Then
+        let Ok(envelope) = result else {
+            panic!("plain compartment not accepted");
+        };
+        assert_eq!(envelope.version, None);
+        assert_eq!(envelope.value.notes, "Hello");
+    }
+
+    #[test]
+    fn deserialize_tagged_compartment_body() {
+        // Given
+        let body = r#"{
+            "kind": "ControllerCompartment",
+            "version": "2.16.0",
+            "value": { "notes": "Hello" }
+        }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Controller));
+        // Then
+        let Ok(envelope) = result else {
+            panic!("tagged compartment not accepted");
+        };
+        assert_eq!(envelope.version, Some("2.16.0".parse().unwrap()));
+        assert_eq!(envelope.value.notes, "Hello");
+    }
+
+    #[test]
+    fn reject_tagged_compartment_body_of_other_compartment() {
+        // Given
+        let body = r#"{ "kind": "MainCompartment", "value": {} }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Controller));
+        // Then
+        assert!(matches!(result, Err(DataError::CompartmentMismatch)));
+    }
+
+    #[test]
+    fn reject_tagged_non_compartment_body() {
+        // Given
+        let body = r#"{ "kind": "Mappings", "value": [] }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Main));
+        // Then
+        assert!(matches!(result, Err(DataError::NotACompartment)));
+    }
+
+    #[test]
+    fn reject_invalid_tagged_body() {
+        // Given
+        let body = r#"{ "kind": "MainCompartment", "value": { "notes": 5 } }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Main));
+        // Then
+        assert!(matches!(result, Err(DataError::InvalidCompartmentData(_))));
+    }
+
+    #[test]
+    fn report_error_location_in_plain_body() {
+        // Given
+        let body = r#"{ "notes": 5 }"#;
+        // When
+        let result = deserialize_compartment_body(body, &TestContext(CompartmentKind::Main));
+        // Then
+        let Err(DataError::InvalidCompartmentData(messages)) = result else {
+            panic!("invalid compartment accepted");
+        };
+        assert!(messages[0].contains("\"notes\""), "{messages:?}");
+    }
+
+    struct TestContext(CompartmentKind);
+
+    impl ApiToDataConversionContext for TestContext {
+        fn compartment(&self) -> CompartmentKind {
+            self.0
+        }
+
+        fn param_index_by_key(&self, _key: &str) -> Option<CompartmentParamIndex> {
+            None
+        }
+    }
+}
@@ -1,12 +1,16 @@
+use crate::infrastructure::server::auth::SharedServerAuth;
+use crate::infrastructure::server::layers::{AuthLayer, MainThreadLayer};
 use crate::infrastructure::server::services::Services;
 use std::net::SocketAddr;
 
 pub async fn start_grpc_server(
     address: SocketAddr,
     services: Services,
+    auth: SharedServerAuth,
 ) -> Result<(), tonic::transport::Error> {
     tonic::transport::Server::builder()
-        .layer(crate::infrastructure::server::layers::MainThreadLayer)
+        .layer(AuthLayer::new(auth))
+        .layer(MainThreadLayer)
         .add_service(services.helgobox_service)
         .serve(address)
         .await
@@ -1,27 +1,56 @@
 use crate::infrastructure::data::CompartmentPresetData;
+use crate::infrastructure::server::auth::TokenScope;
 use crate::infrastructure::server::data::{
-    get_controller_preset_data, get_controller_routing_by_session_id, patch_controller,
-    ControllerRouting, DataError, DataErrorCategory, PatchRequest, SessionResponseData, Topics,
+    get_controller_preset_data, get_controller_routing_by_session_id, get_instances,
+    get_unit_compartment, get_units, pair_client, patch_controller,
+    process_web_socket_client_message, put_unit_compartment, ControllerRouting, DataError,
+    DataErrorCategory, ErrorResponseData, InstanceResponseData, PairingRequest,
+    PairingResponseData, PatchRequest, SessionResponseData, Topics, UnitResponseData,
+    WebSocketClientMessage,
 };
 use crate::infrastructure::server::http::{send_initial_events, ServerClients, WebSocketClient};
+use crate::infrastructure::server::oscquery::{oscquery_handler, SharedOscQueryState};
 use crate::infrastructure::server::MetricsReporter;
 use axum::body::Body;
 use axum::extract::ws::{Message, WebSocket};
-use axum::extract::Path;
-use axum::http::{Response, StatusCode};
-use axum::response::Html;
-use axum::Json;
+use axum::extract::{ConnectInfo, Path, WebSocketUpgrade};
+use axum::http::header::ACCEPT;
+use axum::http::{HeaderMap, Method, Response, StatusCode, Uri};
+use axum::response::{Html, IntoResponse};
+use axum::{Extension, Json};
 use base::Global;
+use realearn_api::persistence;
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 type SimpleResponse = (StatusCode, &'static str);
 
+type JsonErrorResponse = (StatusCode, Json<ErrorResponseData>);
+
 pub async fn welcome_handler() -> Html<&'static str> {
     Html(include_str!("../http/welcome_page.html"))
 }
 
+/// Serves the welcome page to browsers and the root of the OSCQuery namespace to everyone else.
+pub async fn root_handler(
+    ws: Option<WebSocketUpgrade>,
+    method: Method,
+    headers: HeaderMap,
+    uri: Uri,
+    oscquery: Extension<SharedOscQueryState>,
+) -> axum::response::Response {
+    let accepts_html = headers
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"));
+    if ws.is_none() && uri.query().is_none() && accepts_html {
+        return welcome_handler().await.into_response();
+    }
+    oscquery_handler(ws, method, uri, oscquery).await
+}
+
 /// Needs to be executed in the main thread!
 pub async fn session_handler(
     Path(session_id): Path<String>,
@@ -57,6 +86,46 @@ pub async fn patch_controller_handler(
     Ok(StatusCode::OK)
 }
 
+/// Needs to be executed in the main thread!
+pub async fn instances_handler() -> Json<Vec<InstanceResponseData>> {
+    Json(get_instances())
+}
+
+/// Needs to be executed in the main thread!
+pub async fn units_handler() -> Json<Vec<UnitResponseData>> {
+    Json(get_units())
+}
+
+/// Needs to be executed in the main thread!
+pub async fn get_unit_compartment_handler(
+    Path((session_id, compartment)): Path<(String, String)>,
+) -> Result<Json<persistence::Compartment>, JsonErrorResponse> {
+    let compartment =
+        get_unit_compartment(session_id, compartment).map_err(translate_data_error_to_json)?;
+    Ok(Json(compartment))
+}
+
+/// Needs to be executed in the main thread!
+///
+/// Takes the body as plain text (and not as JSON) in order to report deserialization errors in
+/// the same structured way as validation errors.
+pub async fn put_unit_compartment_handler(
+    Path((session_id, compartment)): Path<(String, String)>,
+    body: String,
+) -> Result<StatusCode, JsonErrorResponse> {
+    put_unit_compartment(session_id, compartment, &body).map_err(translate_data_error_to_json)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Needs to be executed in the main thread!
+pub async fn pair_client_handler(
+    ConnectInfo(client_addr): ConnectInfo<SocketAddr>,
+    Json(req): Json<PairingRequest>,
+) -> Result<Json<PairingResponseData>, SimpleResponse> {
+    let response_data = pair_client(client_addr.ip(), req).map_err(translate_data_error)?;
+    Ok(Json(response_data))
+}
+
 pub fn create_cert_response(cert: String, cert_file_name: &str) -> Response<Body> {
     Response::builder()
         .status(StatusCode::OK)
@@ -80,7 +149,12 @@ pub async fn create_metrics_response(metrics_reporter: MetricsReporter) -> Respo
         .unwrap()
 }
 
-pub async fn handle_websocket_upgrade(socket: WebSocket, topics: Topics, clients: ServerClients) {
+pub async fn handle_websocket_upgrade(
+    socket: WebSocket,
+    topics: Topics,
+    scope: TokenScope,
+    clients: ServerClients,
+) {
     use futures::{FutureExt, StreamExt};
     let (ws_sender_sink, mut ws_receiver_stream) = socket.split();
     let (client_sender, client_receiver) = mpsc::unbounded_channel();
@@ -114,26 +188,55 @@ pub async fn handle_websocket_upgrade(socket: WebSocket, topics: Topics, clients
         .unwrap();
     // Keep receiving websocket receiver stream messages
     while let Some(result) = ws_receiver_stream.next().await {
-        // We will need this as soon as we are interested in what the client says
-        let _msg = match result {
+        let msg = match result {
             Ok(msg) => msg,
             Err(e) => {
                 eprintln!("websocket error: {e}");
                 break;
             }
         };
+        let Message::Text(text) = msg else {
+            continue;
+        };
+        let client_msg: WebSocketClientMessage = match serde_json::from_str(&text) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("invalid websocket msg: {e}");
+                continue;
+            }
+        };
+        if scope < TokenScope::Control {
+            eprintln!("ignoring websocket msg because client token has read-only scope");
+            continue;
+        }
+        Global::task_support()
+            .do_later_in_main_thread_asap(move || {
+                if let Err(e) = process_web_socket_client_message(client_msg) {
+                    eprintln!("couldn't process websocket msg: {}", e.description());
+                }
+            })
+            .unwrap();
     }
     // Stream closed up, so remove from the client list
     clients.write().unwrap().remove(&client_id);
 }
 
 fn translate_data_error(e: DataError) -> SimpleResponse {
+    (status_code(&e), e.description())
+}
+
+fn translate_data_error_to_json(e: DataError) -> JsonErrorResponse {
+    (status_code(&e), Json(e.into()))
+}
+
+fn status_code(e: &DataError) -> StatusCode {
     use DataErrorCategory::*;
-    let status_code = match e.category() {
+    match e.category() {
         NotFound => StatusCode::NOT_FOUND,
         BadRequest => StatusCode::BAD_REQUEST,
         MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+        Unauthorized => StatusCode::UNAUTHORIZED,
+        TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
         InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
-    };
-    (status_code, e.description())
+    }
 }
@@ -1,12 +1,12 @@
 //! Contains functions for sending data to WebSocket clients.
 use crate::application::{SharedUnitModel, UnitModel};
 use crate::base::when;
-use crate::domain::ProjectionFeedbackValue;
+use crate::domain::{ProjectionFeedbackValue, VirtualFeedbackValue};
 use crate::infrastructure::plugin::BackboneShell;
 use crate::infrastructure::server::data::{
     get_active_controller_updated_event, get_controller_routing_updated_event,
-    get_projection_feedback_event, get_session_updated_event, send_initial_feedback,
-    SessionResponseData, Topic,
+    get_projection_feedback_event, get_session_updated_event, get_virtual_feedback_event,
+    send_initial_feedback, SessionResponseData, Topic,
 };
 use crate::infrastructure::server::http::client::WebSocketClient;
 use base::Global;
@@ -29,7 +29,7 @@ fn send_initial_events_for_topic(
         Session { session_id } => send_initial_session(client, session_id),
         ControllerRouting { session_id } => send_initial_controller_routing(client, session_id),
         ActiveController { session_id } => send_initial_controller(client, session_id),
-        Feedback { session_id } => {
+        Feedback { session_id } | VirtualFeedback { session_id } => {
             send_initial_feedback(session_id);
             Ok(())
         }
@@ -115,6 +115,18 @@ pub fn send_projection_feedback_to_subscribed_clients(
     )
 }
 
+pub fn send_virtual_feedback_to_subscribed_clients(
+    session_id: &str,
+    value: &VirtualFeedbackValue,
+) -> Result<(), &'static str> {
+    send_to_clients_subscribed_to(
+        &Topic::VirtualFeedback {
+            session_id: session_id.to_string(),
+        },
+        || Some(get_virtual_feedback_event(session_id, value)),
+    )
+}
+
 fn send_to_clients_subscribed_to<T: Serialize>(
     topic: &Topic,
     create_message: impl FnOnce() -> Option<T>,
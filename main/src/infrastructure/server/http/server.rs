@@ -2,17 +2,19 @@ use crate::infrastructure::plugin::BackboneShell;
 use crate::infrastructure::server::http::ServerClients;
 use axum::extract::{Query, WebSocketUpgrade};
 use axum::handler::Handler;
-use axum::http::header::CONTENT_TYPE;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
 use axum::http::Method;
-use axum::routing::{get, patch};
-use axum::Router;
+use axum::routing::{get, patch, post};
+use axum::{Extension, Router};
 use std::io;
 use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::infrastructure::server::auth::{SharedServerAuth, TokenScope};
 use crate::infrastructure::server::data::WebSocketRequest;
 pub use crate::infrastructure::server::http::handlers::*;
-use crate::infrastructure::server::layers::MainThreadLayer;
+use crate::infrastructure::server::layers::{AuthLayer, MainThreadLayer};
+use crate::infrastructure::server::oscquery::{oscquery_handler, SharedOscQueryState};
 use crate::infrastructure::server::MetricsReporter;
 use base::Global;
 
@@ -21,15 +23,21 @@ pub async fn start_http_server(
     http_port: u16,
     https_port: u16,
     clients: ServerClients,
+    oscquery: SharedOscQueryState,
     (key, cert): (String, String),
     metrics_reporter: MetricsReporter,
+    auth: SharedServerAuth,
 ) -> Result<(), io::Error> {
     // Router
-    let router = create_router(cert.clone(), clients, metrics_reporter);
+    let router = create_router(cert.clone(), clients, oscquery, metrics_reporter, auth);
     // Binding
     let http_future = {
         let addr = SocketAddr::from(([0, 0, 0, 0], http_port));
-        axum_server::bind(addr).serve(router.clone().into_make_service())
+        axum_server::bind(addr).serve(
+            router
+                .clone()
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
     };
     let https_future = {
         let addr = SocketAddr::from(([0, 0, 0, 0], https_port));
@@ -37,7 +45,8 @@ pub async fn start_http_server(
             axum_server::tls_rustls::RustlsConfig::from_pem(cert.into(), key.into())
                 .await
                 .unwrap();
-        axum_server::bind_rustls(addr, rustls_config).serve(router.into_make_service())
+        axum_server::bind_rustls(addr, rustls_config)
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
     };
     // Notify UI
     Global::task_support()
@@ -55,14 +64,23 @@ pub async fn start_http_server(
 fn create_router(
     cert: String,
     clients: ServerClients,
+    oscquery: SharedOscQueryState,
     metrics_reporter: MetricsReporter,
+    auth: SharedServerAuth,
 ) -> Router {
-    let router = Router::new()
-        .route("/", get(welcome_handler))
+    // Routes which must be reachable without a client token
+    let public_router = Router::new()
+        // Also the root of the OSCQuery namespace
+        .route("/", get(root_handler))
         .route(
             "/realearn.cer",
             get(|| async move { create_cert_response(cert.clone(), "realearn.cer") }),
         )
+        .route(
+            "/realearn/pair",
+            post(pair_client_handler.layer(MainThreadLayer)),
+        );
+    let protected_router = Router::new()
         .route(
             "/realearn/session/:id",
             get(session_handler.layer(MainThreadLayer)),
@@ -79,11 +97,44 @@ fn create_router(
             "/realearn/controller/:id",
             patch(patch_controller_handler.layer(MainThreadLayer)),
         )
+        .route(
+            "/realearn/instances",
+            get(instances_handler.layer(MainThreadLayer)),
+        )
+        .route("/realearn/units", get(units_handler.layer(MainThreadLayer)))
+        .route(
+            "/realearn/session/:id/compartment/:compartment",
+            get(get_unit_compartment_handler.layer(MainThreadLayer))
+                .put(put_unit_compartment_handler.layer(MainThreadLayer)),
+        )
         .route(
             "/realearn/metrics",
             get(move || async move { create_metrics_response(metrics_reporter).await }),
-        );
-    router
+        )
+        .route(
+            "/ws",
+            get(
+                |ws: WebSocketUpgrade,
+                 Query(req): Query<WebSocketRequest>,
+                 Extension(scope): Extension<TokenScope>| async move {
+                    let topics = req.parse_topics();
+                    ws.on_upgrade(move |socket| {
+                        handle_websocket_upgrade(socket, topics, scope, clients)
+                    })
+                },
+            ),
+        )
+        .layer(AuthLayer::new(auth));
+    // Allowing any origin is okay even if authentication is enabled: Tokens are not sent
+    // automatically by browsers (we don't use cookies and don't allow credentials), so a foreign
+    // website can't act on behalf of a paired client. What it can do is try to guess the pairing
+    // code, which is why failed pairing attempts are limited per client address.
+    public_router
+        .merge(protected_router)
+        // In OSCQuery, the URL path is the OSC address. Must be added after the protected routes
+        // have been layered with authentication because OSCQuery clients don't support tokens.
+        .fallback(oscquery_handler)
+        .layer(Extension(oscquery))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -94,15 +145,6 @@ fn create_router(
                     Method::DELETE,
                     Method::PATCH,
                 ])
-                .allow_headers(vec![CONTENT_TYPE]),
-        )
-        .route(
-            "/ws",
-            get(
-                |ws: WebSocketUpgrade, Query(req): Query<WebSocketRequest>| async move {
-                    let topics = req.parse_topics();
-                    ws.on_upgrade(|socket| handle_websocket_upgrade(socket, topics, clients))
-                },
-            ),
+                .allow_headers(vec![CONTENT_TYPE, AUTHORIZATION]),
         )
 }
@@ -0,0 +1,200 @@
+use crate::infrastructure::server::auth::{AuthError, SharedServerAuth, TokenScope};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderValue, Method, Request, Response, StatusCode};
+use futures::future::BoxFuture;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Path prefix of all methods of our gRPC service.
+const GRPC_SERVICE_PATH_PREFIX: &str = "/generated.HelgoboxService/";
+
+/// gRPC methods which only read data or subscribe to updates.
+///
+/// This is an explicit allowlist on purpose: Methods which are not listed here (e.g. newly added
+/// ones) require the control scope.
+const READ_ONLY_GRPC_METHODS: &[&str] = &[
+    "GetHostInfo",
+    "ProveAuthenticity",
+    "GetAppSettings",
+    "GetCustomInstanceData",
+    "GetProjectDir",
+    "GetArrangementInfo",
+    "GetClipDetail",
+    "GetCompartmentData",
+    "GetUnits",
+    "GetOccasionalGlobalUpdates",
+    "GetOccasionalPlaytimeEngineUpdates",
+    "GetOccasionalInstanceUpdates",
+    "GetOccasionalUnitUpdates",
+    "GetContinuousUnitUpdates",
+    "GetOccasionalMatrixUpdates",
+    "GetContinuousMatrixUpdates",
+    "GetOccasionalColumnUpdates",
+    "GetContinuousColumnUpdates",
+    "GetOccasionalTrackUpdates",
+    "GetOccasionalRowUpdates",
+    "GetOccasionalSlotUpdates",
+    "GetContinuousSlotUpdates",
+    "GetOccasionalClipUpdates",
+];
+
+/// A Tower layer that rejects requests without a token granting the necessary scope.
+///
+/// The token is taken from the `Authorization: Bearer <token>` header or, because browsers can't
+/// set headers when opening WebSocket connections, from the `token` query parameter. On success,
+/// the granted [`TokenScope`] is added to the request extensions.
+#[derive(Clone)]
+pub struct AuthLayer {
+    auth: SharedServerAuth,
+}
+
+impl AuthLayer {
+    pub fn new(auth: SharedServerAuth) -> Self {
+        Self { auth }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    auth: SharedServerAuth,
+}
+
+impl<S, E, ReqBody, ResBody> Service<Request<ReqBody>> for AuthService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>, Error = E>,
+    S::Future: Send + 'static,
+    ResBody: Default + Send + 'static,
+    E: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<ResBody>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let is_grpc = is_grpc_request(&request);
+        let result = match self.auth.read() {
+            Ok(auth) => {
+                let token = extract_token(&request);
+                auth.authorize(token.as_deref(), required_scope(&request, is_grpc))
+            }
+            Err(_) => Err(AuthError::Internal),
+        };
+        match result {
+            Ok(scope) => {
+                request.extensions_mut().insert(scope);
+                Box::pin(self.inner.call(request))
+            }
+            Err(e) => {
+                let response = create_rejection_response(e, is_grpc);
+                Box::pin(async { Ok(response) })
+            }
+        }
+    }
+}
+
+fn is_grpc_request<B>(request: &Request<B>) -> bool {
+    request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/grpc"))
+}
+
+fn required_scope<B>(request: &Request<B>, is_grpc: bool) -> TokenScope {
+    let is_read_only = if is_grpc {
+        is_read_only_grpc_method(request.uri().path())
+    } else {
+        // Control messages sent via WebSocket are checked separately
+        matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        )
+    };
+    if is_read_only {
+        TokenScope::ReadOnly
+    } else {
+        TokenScope::Control
+    }
+}
+
+/// Expects a gRPC path such as "/generated.HelgoboxService/GetHostInfo".
+fn is_read_only_grpc_method(path: &str) -> bool {
+    path.strip_prefix(GRPC_SERVICE_PATH_PREFIX)
+        .is_some_and(|method_name| READ_ONLY_GRPC_METHODS.contains(&method_name))
+}
+
+fn extract_token<B>(request: &Request<B>) -> Option<String> {
+    let header_token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if let Some(token) = header_token {
+        return Some(token.trim().to_string());
+    }
+    let query = request.uri().query()?;
+    url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned())
+}
+
+fn create_rejection_response<B: Default>(error: AuthError, is_grpc: bool) -> Response<B> {
+    let builder = if is_grpc {
+        // gRPC clients expect HTTP 200 and the actual status in the trailers-only response
+        let grpc_status = match error {
+            AuthError::MissingToken | AuthError::InvalidToken => "16",
+            AuthError::InsufficientScope => "7",
+            AuthError::Internal => "13",
+        };
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/grpc"))
+            .header("grpc-status", grpc_status)
+    } else {
+        let status = match error {
+            AuthError::MissingToken | AuthError::InvalidToken => StatusCode::UNAUTHORIZED,
+            AuthError::InsufficientScope => StatusCode::FORBIDDEN,
+            AuthError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Response::builder().status(status)
+    };
+    builder.body(B::default()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_allowlisted_grpc_methods_are_read_only() {
+        assert!(is_read_only_grpc_method(
+            "/generated.HelgoboxService/GetHostInfo"
+        ));
+        assert!(is_read_only_grpc_method(
+            "/generated.HelgoboxService/GetOccasionalUnitUpdates"
+        ));
+        assert!(!is_read_only_grpc_method(
+            "/generated.HelgoboxService/SetAppSettings"
+        ));
+        assert!(!is_read_only_grpc_method(
+            "/generated.HelgoboxService/GetSomethingNew"
+        ));
+        assert!(!is_read_only_grpc_method("/other.Service/GetHostInfo"));
+    }
+}
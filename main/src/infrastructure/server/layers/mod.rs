@@ -1,2 +1,5 @@
+mod auth;
+pub use auth::*;
+
 mod main_thread;
 pub use main_thread::*;
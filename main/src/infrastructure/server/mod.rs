@@ -15,19 +15,23 @@ use std::rc::Rc;
 
 use url::Url;
 
+use crate::infrastructure::server::auth::SharedServerAuth;
 use crate::infrastructure::server::grpc::start_grpc_server;
 use crate::infrastructure::server::http::start_http_server;
 use crate::infrastructure::server::http::ServerClients;
+use crate::infrastructure::server::oscquery::{advertise_oscquery_service, SharedOscQueryState};
 use crate::infrastructure::server::services::Services;
 use derivative::Derivative;
 use tokio::runtime::Runtime;
 
 pub type SharedRealearnServer = Rc<RefCell<RealearnServer>>;
 
+pub mod auth;
 pub mod data;
 mod grpc;
 pub mod http;
 mod layers;
+pub mod oscquery;
 pub mod services;
 
 #[derive(Debug)]
@@ -40,6 +44,8 @@ pub struct RealearnServer {
     changed_subject: LocalSubject<'static, (), ()>,
     local_ip: Option<IpAddr>,
     metrics_reporter: MetricsReporter,
+    auth: SharedServerAuth,
+    oscquery: SharedOscQueryState,
 }
 
 /// Responsible for reporting application metrics.
@@ -93,10 +99,14 @@ enum ServerState {
     Running(ServerRuntimeData),
 }
 
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 struct ServerRuntimeData {
     clients: ServerClients,
     server_join_handle: tokio::task::JoinHandle<()>,
+    /// Advertises the OSCQuery service. `None` if mDNS is not available.
+    #[derivative(Debug = "ignore")]
+    mdns_daemon: Option<mdns_sd::ServiceDaemon>,
 }
 
 impl ServerState {
@@ -118,6 +128,7 @@ impl RealearnServer {
         grpc_port: u16,
         certs_dir_path: PathBuf,
         metrics_reporter: MetricsReporter,
+        auth: SharedServerAuth,
     ) -> RealearnServer {
         RealearnServer {
             http_port,
@@ -128,6 +139,8 @@ impl RealearnServer {
             changed_subject: Default::default(),
             local_ip: get_local_ip(),
             metrics_reporter,
+            auth,
+            oscquery: Default::default(),
         }
     }
 
@@ -146,18 +159,34 @@ impl RealearnServer {
         let grpc_port = self.grpc_port;
         let key_and_cert = self.key_and_cert();
         let metrics_reporter = self.metrics_reporter.clone();
+        let auth = self.auth.clone();
+        let oscquery = self.oscquery.clone();
         let server_join_handle = runtime.spawn(start_servers(
             http_port,
             https_port,
             grpc_port,
             clients_clone,
+            oscquery,
             key_and_cert,
             metrics_reporter,
             services,
+            auth,
         ));
+        let host_name = self
+            .local_hostname()
+            .unwrap_or_else(|| "realearn".to_string());
+        let mdns_daemon =
+            match advertise_oscquery_service(&host_name, self.effective_ip(), self.http_port) {
+                Ok(d) => Some(d),
+                Err(e) => {
+                    tracing::warn!(msg = "Couldn't advertise OSCQuery service via mDNS", %e);
+                    None
+                }
+            };
         let runtime_data = ServerRuntimeData {
             clients,
             server_join_handle,
+            mdns_daemon,
         };
         self.state = ServerState::Starting(runtime_data);
         self.notify_changed();
@@ -187,6 +216,10 @@ impl RealearnServer {
         match std::mem::replace(&mut self.state, ServerState::Stopped) {
             ServerState::Running(runtime_data) | ServerState::Starting(runtime_data) => {
                 runtime_data.server_join_handle.abort();
+                if let Some(mdns_daemon) = runtime_data.mdns_daemon {
+                    let _ = mdns_daemon.shutdown();
+                }
+                self.oscquery.write().unwrap().clear_clients();
             }
             ServerState::Stopped => {}
         }
@@ -204,10 +237,20 @@ impl RealearnServer {
         }
     }
 
+    /// Gives access to the OSCQuery namespace and clients.
+    pub fn oscquery(&self) -> &SharedOscQueryState {
+        &self.oscquery
+    }
+
     pub fn is_running(&self) -> bool {
         matches!(&self.state, ServerState::Running { .. })
     }
 
+    /// Gives access to the client tokens and pairing state, which can be changed while running.
+    pub fn auth(&self) -> &SharedServerAuth {
+        &self.auth
+    }
+
     pub fn generate_full_companion_app_url(&self, session_id: &str, localhost: bool) -> String {
         let host = if localhost {
             None
@@ -292,19 +335,23 @@ async fn start_servers(
     https_port: u16,
     grpc_port: u16,
     clients: ServerClients,
+    oscquery: SharedOscQueryState,
     (key, cert): (String, String),
     metrics_reporter: MetricsReporter,
     services: Services,
+    auth: SharedServerAuth,
 ) {
     let http_server_future = start_http_server(
         http_port,
         https_port,
         clients,
+        oscquery,
         (key, cert),
         metrics_reporter,
+        auth.clone(),
     );
     let grpc_server_future =
-        start_grpc_server(SocketAddr::from(([0, 0, 0, 0], grpc_port)), services);
+        start_grpc_server(SocketAddr::from(([0, 0, 0, 0], grpc_port)), services, auth);
     let (http_result, grpc_result) =
         futures::future::join(http_server_future, grpc_server_future).await;
     http_result.expect("HTTP server error");
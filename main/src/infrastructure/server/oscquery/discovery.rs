@@ -0,0 +1,36 @@
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// The service type which OSCQuery clients browse for.
+const OSCQUERY_SERVICE_TYPE: &str = "_oscjson._tcp.local.";
+
+/// Announces the OSCQuery endpoint via mDNS, so OSCQuery clients can find ReaLearn without having
+/// to enter IP address and port.
+///
+/// The service is advertised as long as the returned daemon is running.
+pub fn advertise_oscquery_service(
+    host_name: &str,
+    ip: IpAddr,
+    http_port: u16,
+) -> Result<ServiceDaemon, mdns_sd::Error> {
+    // mDNS host names must be simple labels within the "local" domain
+    let host_label: String = host_name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let service = ServiceInfo::new(
+        OSCQUERY_SERVICE_TYPE,
+        &format!("ReaLearn on {host_label}"),
+        &format!("{host_label}.local."),
+        ip,
+        http_port,
+        HashMap::<String, String>::new(),
+    )?;
+    let daemon = ServiceDaemon::new()?;
+    daemon.register(service)?;
+    Ok(daemon)
+}
@@ -0,0 +1,143 @@
+use crate::infrastructure::server::oscquery::{
+    build_oscquery_namespace, OscQueryClient, OscQueryClientCommand, OscQueryResult,
+    SharedOscQueryState,
+};
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::WebSocketUpgrade;
+use axum::http::{Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use base::Global;
+use futures::channel::oneshot;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Serves namespace queries via HTTP and value streaming via WebSocket (on the same URL).
+///
+/// In OSCQuery, the URL path is the OSC address, so this is used as fallback for all paths which
+/// are not taken by other routes.
+///
+/// OSCQuery clients don't support tokens, so this doesn't require authentication. That's okay
+/// because it only exposes information, controlling works via OSC anyway.
+pub async fn oscquery_handler(
+    ws: Option<WebSocketUpgrade>,
+    method: Method,
+    uri: Uri,
+    Extension(state): Extension<SharedOscQueryState>,
+) -> Response {
+    if method != Method::GET {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if let Some(ws) = ws {
+        return ws
+            .on_upgrade(move |socket| handle_oscquery_websocket_upgrade(socket, state))
+            .into_response();
+    }
+    let path = uri.path().to_string();
+    let attribute = uri.query().and_then(extract_attribute);
+    let result = match query_namespace(&state, &path, attribute.as_deref()) {
+        Some(r) => r,
+        None => {
+            // Namespace not built yet or outdated. Building it needs access to the units, so we
+            // must do it in the main thread.
+            let (tx, rx) = oneshot::channel();
+            let scheduled = Global::task_support().do_later_in_main_thread_asap(move || {
+                let namespace = build_oscquery_namespace();
+                let Ok(mut state) = state.write() else {
+                    return;
+                };
+                state.set_namespace(namespace);
+                let _ = tx.send(state.query(&path, attribute.as_deref()));
+            });
+            if scheduled.is_err() {
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+            match rx.await {
+                Ok(Some(r)) => r,
+                _ => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            }
+        }
+    };
+    match result {
+        OscQueryResult::Found(json) => Json(json).into_response(),
+        OscQueryResult::AttributeNotAvailable => StatusCode::NO_CONTENT.into_response(),
+        OscQueryResult::NotFound => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn query_namespace(
+    state: &SharedOscQueryState,
+    path: &str,
+    attribute: Option<&str>,
+) -> Option<OscQueryResult> {
+    state.read().ok()?.query(path, attribute)
+}
+
+/// Extracts the requested attribute (e.g. `VALUE`) from the query.
+fn extract_attribute(query: &str) -> Option<String> {
+    url::form_urlencoded::parse(query.as_bytes())
+        .map(|(key, _)| key.into_owned())
+        .next()
+}
+
+async fn handle_oscquery_websocket_upgrade(socket: WebSocket, state: SharedOscQueryState) {
+    use futures::{FutureExt, StreamExt};
+    let (ws_sender_sink, mut ws_receiver_stream) = socket.split();
+    let (client_sender, client_receiver) = mpsc::unbounded_channel();
+    let client_receiver_stream = UnboundedReceiverStream::new(client_receiver);
+    // Keep forwarding OSC packets in client channel to websocket sender sink
+    tokio::task::spawn(
+        client_receiver_stream
+            .map(|bytes| Ok(Message::Binary(bytes)))
+            .forward(ws_sender_sink)
+            .map(|result| {
+                if let Err(e) = result {
+                    eprintln!("error sending OSCQuery websocket msg: {e}");
+                }
+            }),
+    );
+    // Create and memorize client
+    static NEXT_CLIENT_ID: AtomicUsize = AtomicUsize::new(1);
+    let client_id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
+    let client = OscQueryClient {
+        id: client_id,
+        listened_addresses: Default::default(),
+        sender: client_sender,
+    };
+    state.write().unwrap().add_client(client);
+    // Keep receiving LISTEN and IGNORE commands
+    while let Some(result) = ws_receiver_stream.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(e) => {
+                eprintln!("OSCQuery websocket error: {e}");
+                break;
+            }
+        };
+        let Message::Text(text) = msg else {
+            continue;
+        };
+        let command: OscQueryClientCommand = match serde_json::from_str(&text) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("unsupported OSCQuery websocket msg: {e}");
+                continue;
+            }
+        };
+        let mut state = state.write().unwrap();
+        let Some(client) = state.client_mut(client_id) else {
+            break;
+        };
+        match command {
+            OscQueryClientCommand::Listen(address) => {
+                client.listened_addresses.insert(address);
+            }
+            OscQueryClientCommand::Ignore(address) => {
+                client.listened_addresses.remove(&address);
+            }
+        }
+    }
+    // Stream closed up, so remove from the client list
+    state.write().unwrap().remove_client(client_id);
+}
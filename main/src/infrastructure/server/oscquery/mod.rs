@@ -0,0 +1,11 @@
+//! Contains the OSCQuery endpoint of the HTTP server, which lets OSCQuery-capable tools discover
+//! mappings with OSC sources.
+mod discovery;
+mod handlers;
+mod namespace;
+mod state;
+
+pub use discovery::*;
+pub use handlers::*;
+pub use namespace::*;
+pub use state::*;
@@ -0,0 +1,748 @@
+//! Contains the OSCQuery namespace, which is derived from all mappings with OSC sources.
+
+use crate::application::{SourceCategory, SourceModel};
+use crate::domain::{CompartmentKind, ControlInput, OscDeviceId, UnitId};
+use crate::infrastructure::plugin::BackboneShell;
+use crate::infrastructure::server::oscquery::OscQueryValues;
+use base::hash_util::NonCryptoHashMap;
+use helgoboss_learn::{Interval, OscTypeTag};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The OSCQuery namespace, derived from all mappings with OSC sources.
+///
+/// It only contains the structure. Values are added when answering a query.
+#[derive(Clone, Debug)]
+pub struct OscQueryNamespace {
+    root: OscQueryNode,
+    /// Local port of the OSC device which receives the messages of the published mappings.
+    osc_port: Option<u16>,
+}
+
+/// A node in the OSCQuery namespace.
+///
+/// Container nodes only have contents, method nodes (the ones which correspond to a mapping)
+/// describe the OSC message that the mapping source expects.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct OscQueryNode {
+    full_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contents: Option<BTreeMap<String, OscQueryNode>>,
+    #[serde(rename = "TYPE", skip_serializing_if = "Option::is_none")]
+    type_tag: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range: Option<[OscQueryRange; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<[OscQueryValue; 1]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Only set for method nodes.
+    #[serde(skip)]
+    method: Option<OscQueryMethodNode>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct OscQueryMethodNode {
+    /// The unit whose mapping is published at this address.
+    unit_id: UnitId,
+    arg: Option<OscQueryArg>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct OscQueryRange {
+    min: f64,
+    max: f64,
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum OscQueryValue {
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct OscQueryHostInfo {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    osc_port: Option<u16>,
+    osc_transport: &'static str,
+    extensions: OscQueryExtensions,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct OscQueryExtensions {
+    full_path: bool,
+    contents: bool,
+    r#type: bool,
+    access: bool,
+    range: bool,
+    value: bool,
+    description: bool,
+    listen: bool,
+}
+
+/// Message which an OSCQuery client sends via WebSocket.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "COMMAND", content = "DATA", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OscQueryClientCommand {
+    /// Starts streaming value changes of the given OSC address to the client.
+    Listen(String),
+    /// Stops streaming value changes of the given OSC address to the client.
+    Ignore(String),
+}
+
+pub enum OscQueryResult {
+    Found(serde_json::Value),
+    AttributeNotAvailable,
+    NotFound,
+}
+
+/// Describes the OSC message expected by an OSC source, as far as it can be expressed in OSCQuery.
+#[derive(Clone, Debug)]
+struct OscQueryMethod {
+    address: String,
+    arg: Option<OscQueryArg>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct OscQueryArg {
+    type_tag: OscTypeTag,
+    value_range: Interval<f64>,
+    is_relative: bool,
+}
+
+impl OscQueryNamespace {
+    /// Answers an OSCQuery HTTP request.
+    ///
+    /// The attribute is the query part of the URL, e.g. `VALUE` or `HOST_INFO`.
+    pub fn query(
+        &self,
+        path: &str,
+        attribute: Option<&str>,
+        values: &OscQueryValues,
+    ) -> OscQueryResult {
+        if attribute == Some("HOST_INFO") {
+            let host_info = get_oscquery_host_info(self.osc_port);
+            return OscQueryResult::Found(serde_json::to_value(host_info).unwrap_or_default());
+        }
+        let Some(node) = self.root.find(path) else {
+            return OscQueryResult::NotFound;
+        };
+        let Ok(json) = serde_json::to_value(node.with_values(values)) else {
+            return OscQueryResult::NotFound;
+        };
+        let Some(attribute) = attribute else {
+            return OscQueryResult::Found(json);
+        };
+        match json.get(attribute) {
+            None => OscQueryResult::AttributeNotAvailable,
+            Some(v) => {
+                let mut map = serde_json::Map::new();
+                map.insert(attribute.to_string(), v.clone());
+                OscQueryResult::Found(serde_json::Value::Object(map))
+            }
+        }
+    }
+
+    /// Returns the unit whose mapping is published at the given OSC address.
+    pub fn method_owner(&self, address: &str) -> Option<UnitId> {
+        Some(self.root.find(address)?.method?.unit_id)
+    }
+}
+
+/// Builds the namespace from the mappings of all units.
+///
+/// OSCQuery lets us announce only one OSC port. That's why only the mappings of units which
+/// receive from the same OSC device are published. If units use different OSC devices, the one
+/// with the most mappings wins. If multiple mappings use the same OSC address, the first one wins.
+///
+/// Needs to be executed in the main thread!
+pub fn build_oscquery_namespace() -> OscQueryNamespace {
+    let mut candidates: Vec<(OscDeviceId, UnitId, OscQueryMethod, String)> = vec![];
+    BackboneShell::get().with_unit_infos(|infos| {
+        for info in infos {
+            let Some(unit_model) = info.unit_model.upgrade() else {
+                continue;
+            };
+            let Ok(unit_model) = unit_model.try_borrow() else {
+                continue;
+            };
+            // Mappings of units which don't receive OSC can't be controlled via OSC anyway
+            let ControlInput::Osc(device_id) = unit_model.control_input() else {
+                continue;
+            };
+            for compartment in CompartmentKind::enum_iter() {
+                for mapping in unit_model.mappings(compartment) {
+                    let Ok(mapping) = mapping.try_borrow() else {
+                        continue;
+                    };
+                    let Some(method) = OscQueryMethod::from_source(&mapping.source_model) else {
+                        continue;
+                    };
+                    let description = format!(
+                        "{}: {}",
+                        unit_model.name().unwrap_or(unit_model.unit_key()),
+                        mapping.effective_name()
+                    );
+                    candidates.push((device_id, unit_model.unit_id(), method, description));
+                }
+            }
+        }
+    });
+    let mut method_count_by_device: NonCryptoHashMap<OscDeviceId, usize> = Default::default();
+    for (device_id, ..) in &candidates {
+        *method_count_by_device.entry(*device_id).or_default() += 1;
+    }
+    let device_id = method_count_by_device
+        .into_iter()
+        .max_by_key(|(id, count)| (*count, *id))
+        .map(|(id, _)| id);
+    let mut root = OscQueryNode {
+        full_path: "/".to_string(),
+        contents: Some(Default::default()),
+        ..Default::default()
+    };
+    for (candidate_device_id, unit_id, method, description) in candidates {
+        if Some(candidate_device_id) == device_id {
+            root.insert_method(unit_id, &method, description);
+        }
+    }
+    let osc_port = device_id.and_then(|id| {
+        BackboneShell::get()
+            .osc_device_manager()
+            .borrow()
+            .find_device_by_id(&id)?
+            .local_port()
+    });
+    OscQueryNamespace { root, osc_port }
+}
+
+fn get_oscquery_host_info(osc_port: Option<u16>) -> OscQueryHostInfo {
+    OscQueryHostInfo {
+        name: "ReaLearn",
+        osc_port,
+        osc_transport: "UDP",
+        extensions: OscQueryExtensions {
+            full_path: true,
+            contents: true,
+            r#type: true,
+            access: true,
+            range: true,
+            value: true,
+            description: true,
+            listen: true,
+        },
+    }
+}
+
+impl OscQueryNode {
+    /// Looks up the node with the given URL path, e.g. `/track/1/volume`.
+    pub fn find(&self, path: &str) -> Option<&OscQueryNode> {
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .try_fold(self, |node, segment| node.contents.as_ref()?.get(segment))
+    }
+
+    /// Returns a copy of this node (including children) with the latest values filled in.
+    fn with_values(&self, values: &OscQueryValues) -> OscQueryNode {
+        let mut node = self.clone();
+        node.fill_in_values(values);
+        node
+    }
+
+    fn fill_in_values(&mut self, values: &OscQueryValues) {
+        if let Some(method) = self.method {
+            let value = method.arg.and_then(|arg| {
+                let args = values.get(&(method.unit_id, self.full_path.clone()))?;
+                arg.value(args)
+            });
+            if let Some(OscQueryValue::Bool(on)) = value {
+                // OSC transmits booleans as type tag only
+                self.type_tag = Some(if on { "T" } else { "F" });
+            }
+            self.value = value.map(|v| [v]);
+        }
+        if let Some(contents) = &mut self.contents {
+            for child in contents.values_mut() {
+                child.fill_in_values(values);
+            }
+        }
+    }
+
+    fn insert_method(&mut self, unit_id: UnitId, method: &OscQueryMethod, description: String) {
+        let mut node = self;
+        let mut full_path = String::new();
+        for segment in method.address.split('/').filter(|s| !s.is_empty()) {
+            full_path.push('/');
+            full_path.push_str(segment);
+            node = node
+                .contents
+                .get_or_insert_with(Default::default)
+                .entry(segment.to_string())
+                .or_insert_with(|| OscQueryNode {
+                    full_path: full_path.clone(),
+                    ..Default::default()
+                });
+        }
+        if node.access.is_some() {
+            // Address already taken by another mapping
+            return;
+        }
+        node.type_tag = Some(method.type_tag());
+        node.access = Some(3);
+        node.range = method.range().map(|r| [r]);
+        node.description = Some(description);
+        node.method = Some(OscQueryMethodNode {
+            unit_id,
+            arg: method.arg,
+        });
+    }
+}
+
+impl OscQueryMethod {
+    /// Returns `None` if the source is not an OSC source or can't be expressed in OSCQuery.
+    ///
+    /// That's the case for address patterns with wildcards, for sources which don't look at the
+    /// first argument and for exotic argument types.
+    fn from_source(source: &SourceModel) -> Option<Self> {
+        if source.category() != SourceCategory::Osc {
+            return None;
+        }
+        let address = source.osc_address_pattern();
+        if !address.starts_with('/') || address.contains(['*', '?', '[', ']', '{', '}']) {
+            return None;
+        }
+        let arg = match source.osc_arg_index() {
+            None => None,
+            Some(0) => {
+                let arg = OscQueryArg {
+                    type_tag: source.osc_arg_type_tag(),
+                    value_range: source.osc_arg_value_range(),
+                    is_relative: source.osc_arg_is_relative(),
+                };
+                // Reject unsupported types early
+                arg.type_tag_char()?;
+                Some(arg)
+            }
+            Some(_) => return None,
+        };
+        let method = Self {
+            address: address.to_string(),
+            arg,
+        };
+        Some(method)
+    }
+
+    fn type_tag(&self) -> &'static str {
+        match self.arg {
+            // A message without arguments
+            None => "",
+            Some(arg) => arg.type_tag_char().unwrap_or_default(),
+        }
+    }
+
+    fn range(&self) -> Option<OscQueryRange> {
+        let arg = self.arg.filter(|arg| !arg.is_relative)?;
+        use OscTypeTag::*;
+        match arg.type_tag {
+            Float | Double | Int | Long => Some(OscQueryRange {
+                min: arg.value_range.min_val(),
+                max: arg.value_range.max_val(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl OscQueryArg {
+    /// Extracts the value from the arguments which the OSC source would send.
+    fn value(&self, args: &[OscType]) -> Option<OscQueryValue> {
+        if self.is_relative {
+            return None;
+        }
+        let value = match args.first()? {
+            OscType::Float(v) => OscQueryValue::Float(*v as f64),
+            OscType::Double(v) => OscQueryValue::Float(*v),
+            OscType::Int(v) => OscQueryValue::Int(*v as i64),
+            OscType::Long(v) => OscQueryValue::Int(*v),
+            OscType::Bool(v) => OscQueryValue::Bool(*v),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn type_tag_char(&self) -> Option<&'static str> {
+        use OscTypeTag::*;
+        let c = match self.type_tag {
+            Float => "f",
+            Double => "d",
+            Int => "i",
+            Long => "h",
+            // Changes to "T" as soon as we know that the current value is true
+            Bool => "F",
+            Nil => "N",
+            Inf => "I",
+            _ => return None,
+        };
+        Some(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{Change, SourceCommand};
+    use serde_json::json;
+
+    #[test]
+    fn insert_method_creates_containers() {
+        // Given
+        let mut root = create_root();
+        // When
+        root.insert_method(
+            UnitId::from(1),
+            &float_method("/track/1/volume"),
+            "Vol".into(),
+        );
+        // Then
+        let track = root.find("/track").unwrap();
+        assert_eq!(track.full_path, "/track");
+        assert!(track.method.is_none());
+        let track_1 = root.find("/track/1").unwrap();
+        assert_eq!(track_1.full_path, "/track/1");
+        let volume = root.find("/track/1/volume").unwrap();
+        assert_eq!(volume.full_path, "/track/1/volume");
+        assert_eq!(volume.type_tag, Some("f"));
+        assert_eq!(volume.access, Some(3));
+        assert_eq!(volume.description.as_deref(), Some("Vol"));
+        assert!(volume.contents.is_none());
+        assert_eq!(volume.method.unwrap().unit_id, UnitId::from(1));
+    }
+
+    #[test]
+    fn insert_method_keeps_first_mapping_on_same_address() {
+        // Given
+        let mut root = create_root();
+        // When
+        root.insert_method(UnitId::from(1), &float_method("/volume"), "First".into());
+        root.insert_method(UnitId::from(2), &float_method("/volume"), "Second".into());
+        // Then
+        let volume = root.find("/volume").unwrap();
+        assert_eq!(volume.description.as_deref(), Some("First"));
+        assert_eq!(volume.method.unwrap().unit_id, UnitId::from(1));
+    }
+
+    #[test]
+    fn insert_method_next_to_existing_method() {
+        // Given
+        let mut root = create_root();
+        // When
+        root.insert_method(
+            UnitId::from(1),
+            &float_method("/track/volume"),
+            "Vol".into(),
+        );
+        root.insert_method(UnitId::from(1), &float_method("/track/pan"), "Pan".into());
+        // Then
+        let contents = root.find("/track").unwrap().contents.as_ref().unwrap();
+        assert_eq!(contents.keys().collect::<Vec<_>>(), vec!["pan", "volume"]);
+    }
+
+    #[test]
+    fn find() {
+        // Given
+        let mut root = create_root();
+        root.insert_method(
+            UnitId::from(1),
+            &float_method("/track/1/volume"),
+            "Vol".into(),
+        );
+        // When
+        // Then
+        assert_eq!(root.find("/").unwrap().full_path, "/");
+        assert_eq!(root.find("").unwrap().full_path, "/");
+        assert_eq!(root.find("/track/1/").unwrap().full_path, "/track/1");
+        assert_eq!(root.find("track//1").unwrap().full_path, "/track/1");
+        assert!(root.find("/track/2").is_none());
+        assert!(root.find("/track/1/volume/foo").is_none());
+    }
+
+    #[test]
+    fn query_host_info() {
+        // Given
+        let namespace = create_namespace();
+        // When
+        let result = namespace.query("/does/not/exist", Some("HOST_INFO"), &Default::default());
+        // Then
+        let OscQueryResult::Found(json) = result else {
+            panic!("host info not found");
+        };
+        assert_eq!(json["NAME"], json!("ReaLearn"));
+        assert_eq!(json["OSC_PORT"], json!(9000));
+        assert_eq!(json["OSC_TRANSPORT"], json!("UDP"));
+        assert_eq!(json["EXTENSIONS"]["LISTEN"], json!(true));
+    }
+
+    #[test]
+    fn query_node_with_value() {
+        // Given
+        let namespace = create_namespace();
+        let values = create_values("/track/1/volume", OscType::Float(0.5));
+        // When
+        let result = namespace.query("/track/1/volume", None, &values);
+        // Then
+        let OscQueryResult::Found(json) = result else {
+            panic!("node not found");
+        };
+        assert_eq!(
+            json,
+            json!({
+                "FULL_PATH": "/track/1/volume",
+                "TYPE": "f",
+                "ACCESS": 3,
+                "RANGE": [{"MIN": 0.0, "MAX": 1.0}],
+                "VALUE": [0.5],
+                "DESCRIPTION": "Vol",
+            })
+        );
+    }
+
+    #[test]
+    fn query_container_includes_children() {
+        // Given
+        let namespace = create_namespace();
+        let values = create_values("/track/1/volume", OscType::Float(0.5));
+        // When
+        let result = namespace.query("/track", None, &values);
+        // Then
+        let OscQueryResult::Found(json) = result else {
+            panic!("node not found");
+        };
+        assert_eq!(json["FULL_PATH"], json!("/track"));
+        assert_eq!(
+            json["CONTENTS"]["1"]["CONTENTS"]["volume"]["VALUE"],
+            json!([0.5])
+        );
+    }
+
+    #[test]
+    fn query_attribute() {
+        // Given
+        let namespace = create_namespace();
+        let values = create_values("/track/1/volume", OscType::Float(0.5));
+        // When
+        let result = namespace.query("/track/1/volume", Some("VALUE"), &values);
+        // Then
+        let OscQueryResult::Found(json) = result else {
+            panic!("attribute not found");
+        };
+        assert_eq!(json, json!({"VALUE": [0.5]}));
+    }
+
+    #[test]
+    fn query_unavailable_attribute() {
+        // Given
+        let namespace = create_namespace();
+        // When
+        let missing_value = namespace.query("/track/1/volume", Some("VALUE"), &Default::default());
+        let unknown_attribute =
+            namespace.query("/track/1/volume", Some("FOO"), &Default::default());
+        // Then
+        assert!(matches!(
+            missing_value,
+            OscQueryResult::AttributeNotAvailable
+        ));
+        assert!(matches!(
+            unknown_attribute,
+            OscQueryResult::AttributeNotAvailable
+        ));
+    }
+
+    #[test]
+    fn query_unknown_path() {
+        // Given
+        let namespace = create_namespace();
+        // When
+        let result = namespace.query("/track/2", None, &Default::default());
+        // Then
+        assert!(matches!(result, OscQueryResult::NotFound));
+    }
+
+    #[test]
+    fn query_bool_value() {
+        // Given
+        let mut namespace = create_namespace();
+        let method = create_method(|s| {
+            s.change(SourceCommand::SetOscAddressPattern("/mute".to_string()));
+            s.change(SourceCommand::SetOscArgTypeTag(OscTypeTag::Bool));
+        })
+        .unwrap();
+        namespace
+            .root
+            .insert_method(UnitId::from(1), &method, "Mute".into());
+        let values = create_values("/mute", OscType::Bool(true));
+        // When
+        let result = namespace.query("/mute", None, &values);
+        // Then
+        let OscQueryResult::Found(json) = result else {
+            panic!("node not found");
+        };
+        assert_eq!(json["TYPE"], json!("T"));
+        assert_eq!(json["VALUE"], json!([true]));
+        assert!(json.get("RANGE").is_none());
+    }
+
+    #[test]
+    fn method_from_source_with_float_arg() {
+        // Given
+        // When
+        let method = float_method("/volume");
+        // Then
+        assert_eq!(method.address, "/volume");
+        assert_eq!(method.type_tag(), "f");
+        let range = method.range().unwrap();
+        assert_eq!((range.min, range.max), (0.0, 1.0));
+    }
+
+    #[test]
+    fn method_from_source_without_arg() {
+        // Given
+        // When
+        let method = create_method(|s| {
+            s.change(SourceCommand::SetOscAddressPattern("/play".to_string()));
+            s.change(SourceCommand::SetOscArgIndex(None));
+        })
+        .unwrap();
+        // Then
+        assert!(method.arg.is_none());
+        assert_eq!(method.type_tag(), "");
+        assert!(method.range().is_none());
+    }
+
+    #[test]
+    fn method_from_source_with_relative_arg_has_no_range() {
+        // Given
+        // When
+        let method = create_method(|s| {
+            s.change(SourceCommand::SetOscAddressPattern("/encoder".to_string()));
+            s.change(SourceCommand::SetOscArgIsRelative(true));
+        })
+        .unwrap();
+        // Then
+        assert_eq!(method.type_tag(), "f");
+        assert!(method.range().is_none());
+    }
+
+    #[test]
+    fn method_from_source_rejects_non_osc_source() {
+        // Given
+        let mut source = SourceModel::new();
+        source.change(SourceCommand::SetCategory(SourceCategory::Midi));
+        source.change(SourceCommand::SetOscAddressPattern("/volume".to_string()));
+        // When
+        let method = OscQueryMethod::from_source(&source);
+        // Then
+        assert!(method.is_none());
+    }
+
+    #[test]
+    fn method_from_source_rejects_unusable_addresses() {
+        for address in [
+            "",
+            "volume",
+            "/track/*/volume",
+            "/track/?",
+            "/track/[12]",
+            "/{a,b}",
+        ] {
+            // Given
+            // When
+            let method = create_method(|s| {
+                s.change(SourceCommand::SetOscAddressPattern(address.to_string()));
+            });
+            // Then
+            assert!(method.is_none(), "{address}");
+        }
+    }
+
+    #[test]
+    fn method_from_source_rejects_other_than_first_arg() {
+        // Given
+        // When
+        let method = create_method(|s| {
+            s.change(SourceCommand::SetOscAddressPattern("/volume".to_string()));
+            s.change(SourceCommand::SetOscArgIndex(Some(1)));
+        });
+        // Then
+        assert!(method.is_none());
+    }
+
+    #[test]
+    fn method_from_source_rejects_exotic_arg_types() {
+        for type_tag in [OscTypeTag::String, OscTypeTag::Blob, OscTypeTag::Midi] {
+            // Given
+            // When
+            let method = create_method(|s| {
+                s.change(SourceCommand::SetOscAddressPattern("/volume".to_string()));
+                s.change(SourceCommand::SetOscArgTypeTag(type_tag));
+            });
+            // Then
+            assert!(method.is_none(), "{type_tag:?}");
+        }
+    }
+
+    fn create_root() -> OscQueryNode {
+        OscQueryNode {
+            full_path: "/".to_string(),
+            contents: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+
+    fn create_namespace() -> OscQueryNamespace {
+        let mut root = create_root();
+        root.insert_method(
+            UnitId::from(1),
+            &float_method("/track/1/volume"),
+            "Vol".into(),
+        );
+        OscQueryNamespace {
+            root,
+            osc_port: Some(9000),
+        }
+    }
+
+    fn create_values(address: &str, value: OscType) -> OscQueryValues {
+        let mut values = OscQueryValues::default();
+        values.insert((UnitId::from(1), address.to_string()), vec![value]);
+        values
+    }
+
+    fn float_method(address: &str) -> OscQueryMethod {
+        create_method(|s| {
+            s.change(SourceCommand::SetOscAddressPattern(address.to_string()));
+        })
+        .unwrap()
+    }
+
+    /// Creates a method from an OSC source with a float as first argument, customized by `f`.
+    fn create_method(f: impl FnOnce(&mut SourceModel)) -> Option<OscQueryMethod> {
+        let mut source = SourceModel::new();
+        source.change(SourceCommand::SetCategory(SourceCategory::Osc));
+        source.change(SourceCommand::SetOscArgIndex(Some(0)));
+        source.change(SourceCommand::SetOscArgTypeTag(OscTypeTag::Float));
+        source.change(SourceCommand::SetOscArgIsRelative(false));
+        source.change(SourceCommand::SetOscArgValueRange(Interval::new(0.0, 1.0)));
+        f(&mut source);
+        OscQueryMethod::from_source(&source)
+    }
+}
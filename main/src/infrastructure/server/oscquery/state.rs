@@ -0,0 +1,113 @@
+use crate::domain::UnitId;
+use crate::infrastructure::server::oscquery::{OscQueryNamespace, OscQueryResult};
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
+use rosc::{OscMessage, OscPacket, OscType};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+// We don't take the async RwLock by Tokio because we need to access this in sync code, too!
+pub type SharedOscQueryState = Arc<std::sync::RwLock<OscQueryState>>;
+
+/// Latest arguments of each OSC source, by unit and OSC address.
+pub type OscQueryValues = NonCryptoHashMap<(UnitId, String), Vec<OscType>>;
+
+/// Everything that's necessary to answer OSCQuery requests without accessing the units.
+#[derive(Debug, Default)]
+pub struct OscQueryState {
+    /// `None` if mappings have changed and the namespace needs to be built again.
+    namespace: Option<OscQueryNamespace>,
+    /// Reported by the main processors whenever a target value changes.
+    ///
+    /// These are the arguments which the OSC device would need to send in order to arrive at the
+    /// current target value, so the mapping's mode is already taken into account.
+    values: OscQueryValues,
+    clients: NonCryptoHashMap<usize, OscQueryClient>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OscQueryClient {
+    pub id: usize,
+    /// OSC addresses for which the client wants to receive value changes.
+    pub listened_addresses: NonCryptoHashSet<String>,
+    /// Sends encoded OSC packets.
+    pub sender: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+impl OscQueryState {
+    /// Returns `None` if the namespace needs to be built first.
+    pub fn query(&self, path: &str, attribute: Option<&str>) -> Option<OscQueryResult> {
+        let namespace = self.namespace.as_ref()?;
+        Some(namespace.query(path, attribute, &self.values))
+    }
+
+    pub fn set_namespace(&mut self, namespace: OscQueryNamespace) {
+        self.namespace = Some(namespace);
+    }
+
+    pub fn invalidate_namespace(&mut self) {
+        self.namespace = None;
+    }
+
+    /// Memorizes the latest value of an OSC source and streams it to all clients listening to its
+    /// address.
+    pub fn update_value(&mut self, unit_id: UnitId, msg: &OscMessage) {
+        let key = (unit_id, msg.addr.clone());
+        if self.values.get(&key) == Some(&msg.args) {
+            return;
+        }
+        self.values.insert(key, msg.args.clone());
+        if let Some(namespace) = &self.namespace {
+            if namespace.method_owner(&msg.addr) != Some(unit_id) {
+                // Another unit's mapping is published at that address
+                return;
+            }
+        }
+        let mut listening_clients = self
+            .clients
+            .values()
+            .filter(|c| c.is_listening_to(&msg.addr))
+            .peekable();
+        if listening_clients.peek().is_none() {
+            return;
+        }
+        let Ok(bytes) = rosc::encoder::encode(&OscPacket::Message(msg.clone())) else {
+            return;
+        };
+        for client in listening_clients {
+            let _ = client.send(bytes.clone());
+        }
+    }
+
+    pub fn add_client(&mut self, client: OscQueryClient) {
+        self.clients.insert(client.id, client);
+    }
+
+    pub fn client_mut(&mut self, id: usize) -> Option<&mut OscQueryClient> {
+        self.clients.get_mut(&id)
+    }
+
+    pub fn remove_client(&mut self, id: usize) {
+        self.clients.remove(&id);
+    }
+
+    pub fn clear_clients(&mut self) {
+        self.clients.clear();
+    }
+
+    /// Returns `true` if at least one client wants to receive value changes.
+    pub fn has_listening_clients(&self) -> bool {
+        self.clients
+            .values()
+            .any(|client| !client.listened_addresses.is_empty())
+    }
+}
+
+impl OscQueryClient {
+    pub fn send(&self, osc_packet: Vec<u8>) -> Result<(), &'static str> {
+        self.sender.send(osc_packet).map_err(|_| "couldn't send")
+    }
+
+    pub fn is_listening_to(&self, address: &str) -> bool {
+        self.listened_addresses.contains(address)
+    }
+}
@@ -1,12 +1,13 @@
 use crate::infrastructure::ui::bindings::root;
 use crate::infrastructure::ui::egui_views::advanced_script_editor;
 use crate::infrastructure::ui::egui_views::advanced_script_editor::{
-    SharedValue, State, Toolbox, Value,
+    ResponseCurveEditor, SharedValue, State, Toolbox, Value,
 };
 use crate::infrastructure::ui::{egui_views, ScriptEditorInput};
 use base::{blocking_lock, SenderToNormalThread};
 use crossbeam_channel::Receiver;
 use derivative::Derivative;
+use realearn_api::persistence::ResponseCurve;
 use reaper_low::raw;
 use semver::Version;
 use std::cell::RefCell;
@@ -55,16 +56,41 @@ pub struct AdvancedScriptEditorPanel {
     value_receiver: Receiver<SharedValue>,
     #[derivative(Debug = "ignore")]
     set_value: Box<dyn Fn(Value)>,
+    response_curve_receiver: Option<Receiver<Option<ResponseCurve>>>,
+    #[derivative(Debug = "ignore")]
+    set_response_curve: Option<Box<dyn Fn(Option<ResponseCurve>)>>,
+}
+
+/// Makes the advanced script editor offer editing of a response curve in addition to the script.
+pub struct ResponseCurveEditorInput<F: Fn(Option<ResponseCurve>)> {
+    pub initial_value: Option<ResponseCurve>,
+    pub set_value: F,
 }
+
 const TIMER_ID: usize = 321;
 
 impl AdvancedScriptEditorPanel {
     pub fn new(
         input: ScriptEditorInput<impl Fn(String) + 'static>,
         script_template_groups: &'static [ScriptTemplateGroup],
+        response_curve_input: Option<
+            ResponseCurveEditorInput<impl Fn(Option<ResponseCurve>) + 'static>,
+        >,
     ) -> Self {
         let (value_sender, value_receiver) =
             SenderToNormalThread::new_unbounded_channel("advanced script editor apply");
+        let (response_curve_editor, response_curve_receiver, set_response_curve) =
+            match response_curve_input {
+                None => (None, None, None),
+                Some(i) => {
+                    let (sender, receiver) = SenderToNormalThread::new_unbounded_channel(
+                        "advanced script editor response curve",
+                    );
+                    let editor = ResponseCurveEditor::new(i.initial_value, sender);
+                    let set_value: Box<dyn Fn(Option<ResponseCurve>)> = Box::new(i.set_value);
+                    (Some(editor), Some(receiver), Some(set_value))
+                }
+            };
         Self {
             view: Default::default(),
             state: {
@@ -74,10 +100,16 @@ impl AdvancedScriptEditorPanel {
                     script_template_groups,
                     value_sender,
                 };
-                RefCell::new(Some(State::new(input.initial_value, toolbox)))
+                RefCell::new(Some(State::new(
+                    input.initial_value,
+                    toolbox,
+                    response_curve_editor,
+                )))
             },
             value_receiver,
             set_value: Box::new(input.set_value),
+            response_curve_receiver,
+            set_response_curve,
         }
     }
 }
@@ -116,6 +148,13 @@ impl View for AdvancedScriptEditorPanel {
             let v = blocking_lock(&v, "AdvancedScriptEditor value receiver (timer)");
             (self.set_value)(v.clone());
         }
+        if let (Some(receiver), Some(set_response_curve)) =
+            (&self.response_curve_receiver, &self.set_response_curve)
+        {
+            if let Some(curve) = receiver.try_iter().last() {
+                set_response_curve(curve);
+            }
+        }
         true
     }
 
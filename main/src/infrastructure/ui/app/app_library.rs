@@ -450,6 +450,12 @@ fn process_query_request(
                 Ok(query_result::Value::GetCustomInstanceDataReply(value))
             });
         }
+        GetUnits(req) => {
+            send_query_reply_to_app(instance_id, req_id, async move {
+                let value = handler.get_units(req)?.into_inner();
+                Ok(query_result::Value::GetUnitsReply(value))
+            });
+        }
     }
     Ok(())
 }
@@ -690,6 +696,18 @@ fn process_command(
         SaveCustomCompartmentData(req) => {
             handler.save_custom_compartment_data(req)?;
         }
+        SaveMapping(req) => {
+            handler.save_mapping(req)?;
+        }
+        DeleteMapping(req) => {
+            handler.delete_mapping(req)?;
+        }
+        SetGroupEnabled(req) => {
+            handler.set_group_enabled(req)?;
+        }
+        SetCompartmentParameter(req) => {
+            handler.set_compartment_parameter(req)?;
+        }
         InsertColumns(req) => {
             handler.insert_columns(req)?;
         }
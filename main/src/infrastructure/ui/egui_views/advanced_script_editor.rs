@@ -1,13 +1,15 @@
+use crate::domain;
 use crate::domain::AdditionalTransformationInput;
 use crate::infrastructure::ui::{ScriptEngine, ScriptTemplate, ScriptTemplateGroup};
 use base::{blocking_lock, NamedChannelSender, SenderToNormalThread};
 use derivative::Derivative;
-use egui::plot::{Legend, MarkerShape, Plot, Points, VLine};
-use egui::{CentralPanel, Color32, RichText, ScrollArea, Ui};
+use egui::plot::{Legend, Line, MarkerShape, Plot, Points, VLine};
+use egui::{CentralPanel, Color32, PointerButton, RichText, ScrollArea, Slider, Ui};
 use egui::{Context, SidePanel, TextEdit};
 use helgoboss_learn::{
     TransformationInput, TransformationInputMetaData, TransformationOutput, UnitValue,
 };
+use realearn_api::persistence::{ResponseCurve, ResponseCurvePoint};
 use std::ptr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -21,64 +23,23 @@ pub fn run_ui(ctx: &Context, state: &mut State) {
         .default_width(ctx.available_rect().width() * 0.5)
         .show(ctx, |ui| {
             ui.horizontal(|ui| {
-                let response = ui.menu_button("Templates", |ui| {
-                    for group in state.toolbox.script_template_groups {
-                        ui.menu_button(group.name, |ui| {
-                            for template in group.templates {
-                                let response = ui.button(template.name);
-                                if response.hovered() {
-                                    // Preview template
-                                    let template_changed = state
-                                        .template_in_preview
-                                        .as_ref()
-                                        .map(|t| !ptr::eq(t.template, template))
-                                        .unwrap_or(true);
-                                    if template_changed {
-                                        let build_outcome = state.toolbox.build(template.content);
-                                        let template_in_preview = TemplateInPreview {
-                                            template,
-                                            build_outcome,
-                                        };
-                                        state.template_in_preview = Some(template_in_preview);
-                                    }
-                                }
-                                if response.clicked() {
-                                    // Apply template
-                                    let mut content = String::new();
-                                    content += "// ";
-                                    content += template.name;
-                                    if template.description.is_empty() {
-                                        content += "\n";
-                                    } else {
-                                        content += ": ";
-                                        for (i, comment_line) in
-                                            template.description.lines().enumerate()
-                                        {
-                                            if i > 0 {
-                                                content += "// ";
-                                            }
-                                            content += comment_line;
-                                            content += "\n";
-                                        }
-                                    };
-                                    content += "\n";
-                                    content += template.content;
-                                    *blocking_lock(
-                                        &state.shared_value,
-                                        "AdvancedScriptEditor run_ui 1",
-                                    ) = content;
-                                    state.invalidate_and_send();
-                                    ui.close_menu();
-                                }
-                            }
-                        });
-                    }
-                });
-                if response.response.clicked_elsewhere() {
-                    // Menu closed
-                    state.template_in_preview = None;
+                // Not every script language has templates
+                if !state.toolbox.script_template_groups.is_empty() {
+                    show_templates_menu(ui, state);
                 }
                 ui.hyperlink_to("Help", state.toolbox.help_url).clicked();
+                if let Some(editor) = &mut state.response_curve_editor {
+                    let mut enabled = editor.points.is_some();
+                    if ui.checkbox(&mut enabled, "Response curve").changed() {
+                        editor.points = if enabled {
+                            Some(default_response_curve_points())
+                        } else {
+                            None
+                        };
+                        editor.selected_point = None;
+                        editor.send();
+                    }
+                }
             });
             ScrollArea::vertical().show(ui, |ui| {
                 let response = {
@@ -114,12 +75,244 @@ pub fn run_ui(ctx: &Context, state: &mut State) {
             // Plot preview
             plot_build_outcome(ui, &template_in_preview.build_outcome);
         } else {
-            // Plot our script
+            // Edit response curve (if enabled) and plot our script
+            if let Some(editor) = &mut state.response_curve_editor {
+                if editor.points.is_some() {
+                    let height = ui.available_height() * 0.5;
+                    show_response_curve_editor(ui, editor, height);
+                    ui.separator();
+                }
+            }
             plot_build_outcome(ui, &state.last_build_outcome);
         }
     });
 }
 
+fn show_templates_menu(ui: &mut Ui, state: &mut State) {
+    let response = ui.menu_button("Templates", |ui| {
+        for group in state.toolbox.script_template_groups {
+            ui.menu_button(group.name, |ui| {
+                for template in group.templates {
+                    let response = ui.button(template.name);
+                    if response.hovered() {
+                        // Preview template
+                        let template_changed = state
+                            .template_in_preview
+                            .as_ref()
+                            .map(|t| !ptr::eq(t.template, template))
+                            .unwrap_or(true);
+                        if template_changed {
+                            let build_outcome = state.toolbox.build(template.content);
+                            let template_in_preview = TemplateInPreview {
+                                template,
+                                build_outcome,
+                            };
+                            state.template_in_preview = Some(template_in_preview);
+                        }
+                    }
+                    if response.clicked() {
+                        // Apply template
+                        let mut content = String::new();
+                        content += "// ";
+                        content += template.name;
+                        if template.description.is_empty() {
+                            content += "\n";
+                        } else {
+                            content += ": ";
+                            for (i, comment_line) in template.description.lines().enumerate() {
+                                if i > 0 {
+                                    content += "// ";
+                                }
+                                content += comment_line;
+                                content += "\n";
+                            }
+                        };
+                        content += "\n";
+                        content += template.content;
+                        *blocking_lock(&state.shared_value, "AdvancedScriptEditor run_ui 1") =
+                            content;
+                        state.invalidate_and_send();
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+    });
+    if response.response.clicked_elsewhere() {
+        // Menu closed
+        state.template_in_preview = None;
+    }
+}
+
+/// Maximum distance in plot coordinates at which a point counts as hit by the mouse.
+const POINT_HIT_DISTANCE: f64 = 0.03;
+
+fn show_response_curve_editor(ui: &mut Ui, editor: &mut ResponseCurveEditor, height: f32) {
+    let Some(points) = &mut editor.points else {
+        return;
+    };
+    let curve = create_domain_response_curve(points);
+    let curve_coordinates: Vec<_> = (0..=100)
+        .map(|i| {
+            let x = 0.01 * i as f64;
+            [x, curve.evaluate(x)]
+        })
+        .collect();
+    let point_coordinates: Vec<_> = points.iter().map(|p| [p.x, p.y]).collect();
+    let selected_coordinates: Vec<_> = editor
+        .selected_point
+        .and_then(|i| point_coordinates.get(i))
+        .copied()
+        .into_iter()
+        .collect();
+    let plot = Plot::new("response_curve_plot")
+        .allow_boxed_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .allow_zoom(false)
+        .allow_double_click_reset(false)
+        .width(ui.available_width())
+        .height(height)
+        .data_aspect(1.0)
+        .include_x(0.0)
+        .include_x(1.0)
+        .include_y(0.0)
+        .include_y(1.0)
+        .show_background(false);
+    let interaction = plot
+        .show(ui, |plot_ui| {
+            let visuals = &plot_ui.ctx().style().visuals;
+            let line_color = visuals.hyperlink_color;
+            let selection_color = visuals.warn_fg_color;
+            plot_ui.line(Line::new(curve_coordinates).color(line_color));
+            plot_ui.points(
+                Points::new(point_coordinates)
+                    .color(line_color)
+                    .filled(true)
+                    .radius(5.0),
+            );
+            plot_ui.points(
+                Points::new(selected_coordinates)
+                    .color(selection_color)
+                    .filled(true)
+                    .radius(7.0),
+            );
+            let (pressed, down, double_clicked, secondary_clicked) = plot_ui.ctx().input(|i| {
+                (
+                    i.pointer.primary_pressed(),
+                    i.pointer.primary_down(),
+                    i.pointer.button_double_clicked(PointerButton::Primary),
+                    i.pointer.secondary_clicked(),
+                )
+            });
+            CurveEditorInteraction {
+                pointer: if plot_ui.plot_hovered() {
+                    plot_ui.pointer_coordinate().map(|p| (p.x, p.y))
+                } else {
+                    None
+                },
+                pressed,
+                down,
+                double_clicked,
+                secondary_clicked,
+            }
+        })
+        .inner;
+    let mut changed = false;
+    if !interaction.down {
+        editor.dragged_point = None;
+    }
+    if let Some((x, y)) = interaction.pointer {
+        let (x, y) = (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0));
+        let hit_point = points
+            .iter()
+            .position(|p| (p.x - x).hypot(p.y - y) <= POINT_HIT_DISTANCE);
+        if interaction.double_clicked && hit_point.is_none() {
+            // Add point
+            let index = points.partition_point(|p| p.x < x);
+            points.insert(
+                index,
+                ResponseCurvePoint {
+                    x,
+                    y,
+                    curvature: None,
+                },
+            );
+            editor.selected_point = Some(index);
+            changed = true;
+        } else if interaction.secondary_clicked && points.len() > 2 {
+            // Remove point
+            if let Some(i) = hit_point {
+                points.remove(i);
+                editor.selected_point = None;
+                changed = true;
+            }
+        } else if interaction.pressed {
+            // Select point and start dragging
+            editor.selected_point = hit_point;
+            editor.dragged_point = hit_point;
+        } else if let Some(i) = editor.dragged_point.filter(|_| interaction.down) {
+            // Drag point without passing its neighbors
+            let min_x = if i == 0 { 0.0 } else { points[i - 1].x };
+            let max_x = points.get(i + 1).map(|p| p.x).unwrap_or(1.0);
+            points[i].x = x.clamp(min_x, max_x);
+            points[i].y = y;
+            changed = true;
+        }
+    }
+    // Curvature of the segment starting at the selected point
+    if let Some(i) = editor.selected_point.filter(|i| i + 1 < points.len()) {
+        ui.horizontal(|ui| {
+            ui.label("Curvature");
+            let mut curvature = points[i].curvature.unwrap_or(0.0);
+            if ui.add(Slider::new(&mut curvature, -1.0..=1.0)).changed() {
+                points[i].curvature = if curvature == 0.0 {
+                    None
+                } else {
+                    Some(curvature)
+                };
+                changed = true;
+            }
+        });
+    }
+    ui.label("Drag points to move them. Double-click to add a point, right-click to remove it.");
+    if changed {
+        editor.send();
+    }
+}
+
+struct CurveEditorInteraction {
+    /// Plot coordinates of the mouse pointer if it hovers the plot.
+    pointer: Option<(f64, f64)>,
+    pressed: bool,
+    down: bool,
+    double_clicked: bool,
+    secondary_clicked: bool,
+}
+
+fn create_domain_response_curve(points: &[ResponseCurvePoint]) -> domain::ResponseCurve {
+    domain::ResponseCurve::new(points.iter().map(|p| domain::ResponseCurvePoint {
+        x: p.x,
+        y: p.y,
+        curvature: p.curvature.unwrap_or(0.0),
+    }))
+}
+
+fn default_response_curve_points() -> Vec<ResponseCurvePoint> {
+    vec![
+        ResponseCurvePoint {
+            x: 0.0,
+            y: 0.0,
+            curvature: None,
+        },
+        ResponseCurvePoint {
+            x: 1.0,
+            y: 1.0,
+            curvature: None,
+        },
+    ]
+}
+
 fn plot_build_outcome(ui: &mut Ui, build_outcome: &BuildOutcome) {
     if !build_outcome.error.is_empty() {
         ui.colored_label(ui.visuals().error_fg_color, &build_outcome.error);
@@ -207,6 +400,36 @@ pub struct State {
     last_build_outcome: BuildOutcome,
     template_in_preview: Option<TemplateInPreview>,
     toolbox: Toolbox,
+    response_curve_editor: Option<ResponseCurveEditor>,
+}
+
+/// Edits the response curve which is applied before the control transformation.
+#[derive(Debug)]
+pub struct ResponseCurveEditor {
+    /// `None` if no response curve is used.
+    points: Option<Vec<ResponseCurvePoint>>,
+    selected_point: Option<usize>,
+    dragged_point: Option<usize>,
+    sender: SenderToNormalThread<Option<ResponseCurve>>,
+}
+
+impl ResponseCurveEditor {
+    pub fn new(
+        initial_value: Option<ResponseCurve>,
+        sender: SenderToNormalThread<Option<ResponseCurve>>,
+    ) -> Self {
+        Self {
+            points: initial_value.map(|c| c.points),
+            selected_point: None,
+            dragged_point: None,
+            sender,
+        }
+    }
+
+    fn send(&self) {
+        let curve = self.points.clone().map(|points| ResponseCurve { points });
+        self.sender.send_complaining(curve);
+    }
 }
 
 #[derive(Debug)]
@@ -269,7 +492,10 @@ impl Toolbox {
                             rel_time: Duration::from_millis(rel_time_millis as u64),
                         },
                     );
-                    let additional_input = AdditionalTransformationInput { y_last: 0.0 };
+                    let additional_input = AdditionalTransformationInput {
+                        y_last: 0.0,
+                        ..Default::default()
+                    };
                     let output = match script.evaluate(input, prev_y, additional_input).ok() {
                         None => continue,
                         Some(e) => e,
@@ -306,12 +532,17 @@ impl Toolbox {
 }
 
 impl State {
-    pub fn new(initial_value: Value, toolbox: Toolbox) -> Self {
+    pub fn new(
+        initial_value: Value,
+        toolbox: Toolbox,
+        response_curve_editor: Option<ResponseCurveEditor>,
+    ) -> Self {
         let mut state = State {
             shared_value: SharedValue::new(Mutex::new(initial_value)),
             last_build_outcome: Default::default(),
             template_in_preview: None,
             toolbox,
+            response_curve_editor,
         };
         state.invalidate();
         state
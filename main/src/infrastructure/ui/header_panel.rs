@@ -13,10 +13,10 @@ use swell_ui::{DeviceContext, Pixels, Point, SharedView, View, ViewContext, Weak
 
 use crate::application::{
     get_appropriate_send_feedback_only_if_armed_default, reaper_supports_global_midi_filter,
-    Affected, CompartmentCommand, CompartmentPresetManager, CompartmentPresetModel,
-    CompartmentProp, FxId, FxPresetLinkConfig, MainPresetAutoLoadMode, MappingCommand,
-    MappingModel, PresetLinkMutator, SessionCommand, SessionProp, SharedMapping, SharedUnitModel,
-    VirtualControlElementType, WeakUnitModel,
+    Affected, CompartmentCommand, CompartmentModel, CompartmentPresetManager,
+    CompartmentPresetModel, CompartmentProp, FxId, FxPresetLinkConfig, MainPresetAutoLoadMode,
+    MappingCommand, MappingModel, PresetLinkMutator, SessionCommand, SessionProp, SharedMapping,
+    SharedUnitModel, VirtualControlElementType, WeakUnitModel,
 };
 use crate::base::when;
 use crate::domain::{
@@ -27,26 +27,29 @@ use crate::domain::{
 use crate::domain::{MidiControlInput, MidiDestination};
 use crate::infrastructure::data::{
     CommonCompartmentPresetManager, CommonPresetInfo, CompartmentModelData,
-    FileBasedMainPresetManager, InstanceOrUnitData, MappingModelData, OscDevice, PresetFileType,
-    PresetOrigin, UnitData,
+    FileBasedMainPresetManager, InstanceOrUnitData, MappingModelData, OscDevice, PresetBundle,
+    PresetFileType, PresetOrigin, PresetQuery, UnitData,
 };
 use crate::infrastructure::plugin::{
     update_auto_units_async, warn_about_failed_server_start, BackboneShell,
 };
 
+use crate::infrastructure::server::auth::TokenScope;
 use crate::infrastructure::ui::bindings::root;
 
 use crate::base::notification::{notify_processing_result, notify_user_about_anyhow_error};
 use crate::infrastructure::api::convert::from_data::ConversionStyle;
+use crate::infrastructure::api::convert::{from_data, to_data};
 use crate::infrastructure::ui::color_panel::{ColorPanel, ColorPanelDesc};
 use crate::infrastructure::ui::dialog_util::add_group_via_dialog;
 use crate::infrastructure::ui::instance_panel::InstancePanel;
 use crate::infrastructure::ui::menus::{
     build_compartment_preset_menu_entries, get_midi_input_device_list_label,
     get_midi_output_device_list_label, get_osc_device_list_label,
-    menu_containing_compartment_presets, ControlInputMenuAction, FeedbackOutputMenuAction,
+    menu_containing_compartment_presets, menu_containing_queried_compartment_presets,
+    CompartmentPresetMenuAction, ControlInputMenuAction, FeedbackOutputMenuAction,
     OscDeviceManagementAction, CONTROL_INPUT_KEYBOARD_LABEL, CONTROL_INPUT_MIDI_FX_INPUT_LABEL,
-    FEEDBACK_OUTPUT_MIDI_FX_OUTPUT, FEEDBACK_OUTPUT_NONE_LABEL,
+    CONTROL_INPUT_WEB_CLIENTS_LABEL, FEEDBACK_OUTPUT_MIDI_FX_OUTPUT, FEEDBACK_OUTPUT_NONE_LABEL,
 };
 use crate::infrastructure::ui::util::{
     close_child_panel_if_open, colors, open_child_panel, open_child_panel_dyn, open_in_browser,
@@ -64,11 +67,15 @@ use crate::infrastructure::ui::{
 use crate::infrastructure::ui::{dialog_util, CompanionAppPresenter};
 use anyhow::{bail, Context};
 use itertools::Itertools;
-use realearn_api::persistence::Envelope;
+use realearn_api::persistence::{
+    diff_compartments, merge_compartments, CompartmentDiff, Envelope, PresetBundleDependencies,
+    PresetBundleManifest,
+};
 use reaper_medium::Hbrush;
 use semver::Version;
 use std::cell::{Cell, RefCell};
 use std::error::Error;
+use std::fs;
 use std::net::Ipv4Addr;
 use std::ops::{DerefMut, RangeInclusive};
 use strum::IntoEnumIterator;
@@ -274,22 +281,98 @@ impl HeaderPanel {
     }
 
     fn browse_presets(&self) {
+        let compartment = self.active_compartment();
+        let compatibility_query = self.build_preset_compatibility_query(compartment);
+        let menu = {
+            let session = self.session();
+            let session = session.borrow();
+            let active_preset_id = session.active_preset_id(compartment);
+            let compatibility_query = compatibility_query
+                .as_ref()
+                .map(|q| ("Compatible with controller preset", q));
+            menu_containing_compartment_presets(compartment, active_preset_id, compatibility_query)
+        };
+        let result = self
+            .view
+            .require_window()
+            .open_popup_menu(menu, Window::cursor_pos());
+        match result {
+            None | Some(CompartmentPresetMenuAction::None) => {}
+            Some(CompartmentPresetMenuAction::Activate(preset_id)) => self.update_preset(preset_id),
+            Some(CompartmentPresetMenuAction::Search) => {
+                self.search_presets(compartment, compatibility_query)
+            }
+        }
+    }
+
+    /// Lets the user search presets by name, author, device, scheme and origin.
+    ///
+    /// If a compatibility query is given and the search expression doesn't contain any schemes,
+    /// only presets compatible with the active controller preset are shown.
+    fn search_presets(
+        &self,
+        compartment: CompartmentKind,
+        compatibility_query: Option<PresetQuery>,
+    ) {
+        let Some(expression) = dialog_util::prompt_for(
+            "Search (e.g. mixer author:x device:x scheme:x origin:factory)",
+            "",
+        ) else {
+            return;
+        };
+        let mut query = PresetQuery::parse(&expression);
+        if query.schemes.is_none() {
+            query.schemes = compatibility_query.and_then(|q| q.schemes);
+        }
         let menu = {
-            let compartment = self.active_compartment();
             let session = self.session();
             let session = session.borrow();
             let active_preset_id = session.active_preset_id(compartment);
-            menu_containing_compartment_presets(compartment, active_preset_id)
+            menu_containing_queried_compartment_presets(compartment, active_preset_id, &query)
+        };
+        let Some(menu) = menu else {
+            self.view
+                .require_window()
+                .alert("ReaLearn", "No matching presets found.");
+            return;
         };
         let result = self
             .view
             .require_window()
             .open_popup_menu(menu, Window::cursor_pos());
-        if let Some(selected_preset_id) = result {
-            self.update_preset(selected_preset_id);
+        if let Some(preset_id) = result {
+            self.update_preset(Some(preset_id));
         }
     }
 
+    /// Returns a query for main presets which use at least one of the virtual control schemes
+    /// provided by the active controller preset.
+    fn build_preset_compatibility_query(
+        &self,
+        compartment: CompartmentKind,
+    ) -> Option<PresetQuery> {
+        if compartment != CompartmentKind::Main {
+            return None;
+        }
+        let session = self.session();
+        let session = session.borrow();
+        let controller_preset_id = session.active_preset_id(CompartmentKind::Controller)?;
+        let controller_preset_manager = BackboneShell::get().controller_preset_manager().borrow();
+        let provided_schemes = controller_preset_manager
+            .preset_virtual_control_schemes(controller_preset_id)
+            .filter(|schemes| !schemes.is_empty())?;
+        let query = PresetQuery {
+            schemes: Some(
+                provided_schemes
+                    .iter()
+                    .map(|s| s.get().to_string())
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        Some(query)
+    }
+
     fn update_preset(&self, preset_id: Option<String>) {
         self.main_state.borrow_mut().stop_filter_learning();
         let session = self.session();
@@ -680,6 +763,27 @@ impl HeaderPanel {
                         ),
                         item("Add firewall rule", MainMenuAction::AddFirewallRule),
                         item("Change unit ID...", MainMenuAction::ChangeSessionId),
+                        separator(),
+                        item_with_opts(
+                            "Require client authentication",
+                            ItemOpts {
+                                enabled: true,
+                                checked: BackboneShell::get().config().server_auth_is_enabled(),
+                            },
+                            MainMenuAction::ToggleServerAuth,
+                        ),
+                        item(
+                            "Pair new client (read-only)...",
+                            MainMenuAction::PairServerClient(TokenScope::ReadOnly),
+                        ),
+                        item(
+                            "Pair new client (control)...",
+                            MainMenuAction::PairServerClient(TokenScope::Control),
+                        ),
+                        menu(
+                            "Revoke client token",
+                            generate_server_client_token_menu_entries(),
+                        ),
                     ],
                 ),
                 menu(
@@ -694,6 +798,14 @@ impl HeaderPanel {
                             MainMenuAction::CreateCompartmentPresetWorkspaceIncludingFactoryPresets,
                         ),
                         item("Open preset folder", MainMenuAction::OpenPresetFolder),
+                        item(
+                            "Export active presets as bundle...",
+                            MainMenuAction::ExportPresetBundle,
+                        ),
+                        item(
+                            "Import preset bundle...",
+                            MainMenuAction::ImportPresetBundle,
+                        ),
                         item(
                             "Reload all presets from disk",
                             MainMenuAction::ReloadAllPresets,
@@ -807,6 +919,21 @@ impl HeaderPanel {
                 self.view.require_window().alert("Helgobox", msg);
             }
             MainMenuAction::ChangeSessionId => self.change_unit_id(),
+            MainMenuAction::ToggleServerAuth => app.toggle_server_auth(),
+            MainMenuAction::PairServerClient(scope) => {
+                let code = app.start_server_client_pairing(scope);
+                let mut msg = format!(
+                    "Enter the following pairing code in the client within the next 5 minutes:\n\n{code}\n\nThe client will get {} access.",
+                    format_token_scope(scope)
+                );
+                if !app.config().server_auth_is_enabled() {
+                    msg.push_str("\n\nClient authentication is currently not required, so unpaired clients have full access as well.");
+                }
+                self.view.require_window().alert("Helgobox", msg);
+            }
+            MainMenuAction::RevokeServerClientToken(token) => {
+                app.revoke_server_client_token(&token);
+            }
             MainMenuAction::CreateCompartmentPresetWorkspace => {
                 self.create_compartment_preset_workspace(false)
             }
@@ -824,6 +951,12 @@ impl HeaderPanel {
                 self.close_app();
             }
             MainMenuAction::OpenPresetFolder => self.open_preset_folder(),
+            MainMenuAction::ExportPresetBundle => {
+                self.notify_user_on_anyhow_error(self.export_preset_bundle())
+            }
+            MainMenuAction::ImportPresetBundle => {
+                self.notify_user_on_anyhow_error(self.import_preset_bundle())
+            }
             MainMenuAction::SendFeedbackNow => self.session().borrow().send_all_feedback(),
             MainMenuAction::LogDebugInfo => self.log_debug_info(),
             MainMenuAction::EditPresetLinkFxId(scope, fx_id) => {
@@ -1393,7 +1526,7 @@ impl HeaderPanel {
                 true,
                 input == MidiControlInput::FxInput || reaper_supports_global_midi_filter(),
             ),
-            ControlInput::Osc(_) => (false, false),
+            ControlInput::Osc(_) | ControlInput::WebClients => (false, false),
             ControlInput::Keyboard => (true, true),
         };
         for c in controls {
@@ -1609,6 +1742,7 @@ impl HeaderPanel {
             },
             ControlInput::Osc(osc_device_id) => get_osc_dev_list_label(&osc_device_id, false),
             ControlInput::Keyboard => CONTROL_INPUT_KEYBOARD_LABEL.to_string(),
+            ControlInput::WebClients => CONTROL_INPUT_WEB_CLIENTS_LABEL.to_string(),
         };
         self.view
             .require_control(root::ID_CONTROL_INPUT_BUTTON)
@@ -1981,23 +2115,88 @@ impl HeaderPanel {
         version: Option<&Version>,
         data: Box<CompartmentModelData>,
     ) {
-        if self.view.require_window().confirm(
-            "ReaLearn",
-            format!(
-                "Do you want to continue replacing the {compartment} with the data in the clipboard?",
-            ),
-        ) {
+        if let Err(e) = self.import_compartment_internal(compartment, version, &data) {
+            self.view.require_window().alert("ReaLearn", e.to_string());
+        }
+    }
+
+    /// Shows what's going to change and, if the compartment contains unsaved changes to a preset,
+    /// offers to merge these changes with the imported data.
+    fn import_compartment_internal(
+        &self,
+        compartment: CompartmentKind,
+        version: Option<&Version>,
+        data: &CompartmentModelData,
+    ) -> anyhow::Result<()> {
+        let (current_model, imported_model, preset) = {
             let session = self.session();
-            let mut session = session.borrow_mut();
-            match data.to_model(version, compartment, Some(&session)) {
-                Ok(model) => {
-                    session.import_compartment(compartment, Some(model));
+            let session = session.borrow();
+            let imported_model = data.to_model(version, compartment, Some(&session))?;
+            let preset = if session.compartment_or_preset_is_dirty(compartment) {
+                session.active_preset(compartment)
+            } else {
+                None
+            };
+            (
+                session.extract_compartment_model(compartment),
+                imported_model,
+                preset,
+            )
+        };
+        let to_api = |model: &CompartmentModel| {
+            from_data::convert_compartment(
+                CompartmentModelData::from_model(model),
+                ConversionStyle::Minimal,
+            )
+        };
+        let ours = to_api(&current_model)?;
+        let theirs = to_api(&imported_model)?;
+        let diff = diff_compartments(&ours, &theirs)?;
+        let summary = summarize_compartment_diff(&diff);
+        let window = self.view.require_window();
+        let model = match preset {
+            None => {
+                let msg = format!(
+                    "Do you want to continue replacing the {compartment} with the data in the clipboard?\n\n{summary}"
+                );
+                if !window.confirm("ReaLearn", msg) {
+                    return Ok(());
                 }
-                Err(e) => {
-                    self.view.require_window().alert("ReaLearn", e.to_string());
+                imported_model
+            }
+            Some(preset) => {
+                let msg = format!(
+                    "The {compartment} contains unsaved changes to preset \"{}\". Do you want to merge these changes with the data in the clipboard?\n\nYes = Merge\nNo = Replace\n\n{summary}",
+                    preset.name()
+                );
+                match window.ask_yes_no_or_cancel("ReaLearn", msg) {
+                    None => return Ok(()),
+                    Some(false) => imported_model,
+                    Some(true) => {
+                        let base = to_api(preset.model())?;
+                        let merge = merge_compartments(&base, &ours, &theirs)?;
+                        if !merge.conflicts.is_empty() {
+                            notify_processing_result(
+                                "Merge conflicts (your changes have been kept)",
+                                merge.conflicts.iter().map(|c| c.to_string()).collect(),
+                            );
+                        }
+                        let merged_data = to_data::convert_compartment(compartment, merge.merged)?;
+                        let session = self.session();
+                        let session = session.borrow();
+                        merged_data.to_model(
+                            Some(BackboneShell::version()),
+                            compartment,
+                            Some(&session),
+                        )?
+                    }
                 }
             }
-        }
+        };
+        self.session()
+            .borrow_mut()
+            .import_compartment(compartment, Some(model));
+        Ok(())
     }
 
     pub fn export_to_clipboard(&self) -> anyhow::Result<()> {
@@ -2274,6 +2473,118 @@ impl HeaderPanel {
         self.notify_user_on_error(result);
     }
 
+    /// Bundles the namespace folders of the active controller and main preset.
+    ///
+    /// Active factory presets are not bundled but added as dependencies.
+    fn export_preset_bundle(&self) -> anyhow::Result<()> {
+        let mut namespaces = vec![];
+        let mut dependencies = PresetBundleDependencies::default();
+        {
+            let session = self.session();
+            let session = session.borrow();
+            for compartment in CompartmentKind::enum_iter() {
+                let Some(id) = session.active_preset_id(compartment) else {
+                    continue;
+                };
+                if id.starts_with("factory/") {
+                    let dependency_ids = match compartment {
+                        CompartmentKind::Controller => &mut dependencies.controller_presets,
+                        CompartmentKind::Main => &mut dependencies.main_presets,
+                    };
+                    dependency_ids.push(id.to_string());
+                    continue;
+                }
+                let Some((namespace, _)) = id.split_once('/') else {
+                    bail!("The active {compartment} preset \"{id}\" is not located in a sub folder of the preset folder, so it can't be bundled.");
+                };
+                namespaces.push((compartment, namespace.to_string()));
+            }
+        }
+        if namespaces.is_empty() {
+            bail!("There are no active user presets which could be bundled.");
+        }
+        let initial_name = namespaces
+            .iter()
+            .map(|(_, ns)| ns.as_str())
+            .dedup()
+            .join(" + ");
+        let Some(name) = dialog_util::prompt_for("Bundle name", &initial_name) else {
+            return Ok(());
+        };
+        let Some(version) = dialog_util::prompt_for("Bundle version", "1.0.0") else {
+            return Ok(());
+        };
+        let manifest = PresetBundleManifest {
+            version: Version::parse(&version).context("invalid bundle version")?,
+            realearn_version: BackboneShell::version().clone(),
+            author: None,
+            description: None,
+            dependencies,
+            name,
+        };
+        let bundle_dir = BackboneShell::realearn_preset_bundle_dir_path();
+        let bundle_path = bundle_dir.join(format!(
+            "{}-{}.zip",
+            slug::slugify(&manifest.name),
+            manifest.version
+        ));
+        let bundle = PresetBundle::collect(manifest, &namespaces)?;
+        fs::create_dir_all(&bundle_dir).context("couldn't create bundle directory")?;
+        let file = fs::File::create(&bundle_path).context("couldn't create bundle file")?;
+        bundle.write_zip(file)?;
+        let file_name = bundle_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        self.view.require_window().alert(
+            "ReaLearn",
+            format!("Exported preset bundle as \"{file_name}\". ReaLearn will now open the folder containing the bundle."),
+        );
+        let _ = open_in_file_manager(&bundle_dir);
+        Ok(())
+    }
+
+    fn import_preset_bundle(&self) -> anyhow::Result<()> {
+        let Some(path) = dialog_util::prompt_for("Path of preset bundle (.zip)", "") else {
+            return Ok(());
+        };
+        // Paths copied from the file manager are often quoted
+        let path = path.trim_matches('"');
+        if path.is_empty() {
+            return Ok(());
+        }
+        let file = fs::File::open(path).with_context(|| format!("couldn't open {path}"))?;
+        let bundle = PresetBundle::read_zip(file)?;
+        let check = bundle.check_import()?;
+        let manifest = &bundle.manifest;
+        let mut msg = format!(
+            "Do you want to install preset bundle \"{}\" {}?",
+            manifest.name, manifest.version
+        );
+        if check.needs_migration {
+            msg += &format!(
+                "\n\nThe bundle has been built for the older ReaLearn version {}. Its presets will be migrated when loading them.",
+                manifest.realearn_version
+            );
+        }
+        if !check.missing_dependencies.is_empty() {
+            msg += &format!(
+                "\n\nThe bundle needs the following presets, which are not installed:\n- {}",
+                check.missing_dependencies.join("\n- ")
+            );
+        }
+        if !check.collisions.is_empty() {
+            msg += &format!(
+                "\n\nThe following presets and files are already installed and will be overwritten:\n- {}",
+                check.collisions.join("\n- ")
+            );
+        }
+        if self.view.require_window().confirm("ReaLearn", msg) {
+            bundle.install()?;
+        }
+        Ok(())
+    }
+
     fn make_mappings_project_independent_if_desired(&self) {
         let session = self.session();
         let compartment = self.active_compartment();
@@ -3007,6 +3318,9 @@ enum MainMenuAction {
     ToggleUseUnitPresetLinksOnly,
     AddFirewallRule,
     ChangeSessionId,
+    ToggleServerAuth,
+    PairServerClient(TokenScope),
+    RevokeServerClientToken(String),
     EditPresetLinkFxId(PresetLinkScope, FxId),
     RemovePresetLink(PresetLinkScope, FxId),
     LinkToPreset(PresetLinkScope, FxId, String),
@@ -3015,6 +3329,8 @@ enum MainMenuAction {
     ShowApp,
     CloseApp,
     OpenPresetFolder,
+    ExportPresetBundle,
+    ImportPresetBundle,
     EditCompartmentParameter(CompartmentKind, RangeInclusive<CompartmentParamIndex>),
     SendFeedbackNow,
     LogDebugInfo,
@@ -3038,6 +3354,32 @@ impl Default for MainMenuAction {
     }
 }
 
+fn generate_server_client_token_menu_entries() -> Vec<swell_ui::menu_tree::Entry<MainMenuAction>> {
+    use swell_ui::menu_tree::*;
+    let config = BackboneShell::get().config();
+    let mut tokens: Vec<_> = config.server_client_tokens().collect();
+    if tokens.is_empty() {
+        return vec![disabled_item("<No paired clients>")];
+    }
+    tokens.sort_by_key(|t| t.client_name);
+    tokens
+        .into_iter()
+        .map(|t| {
+            item(
+                format!("{} ({})", t.client_name, format_token_scope(t.scope)),
+                MainMenuAction::RevokeServerClientToken(t.token.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn format_token_scope(scope: TokenScope) -> &'static str {
+    match scope {
+        TokenScope::ReadOnly => "read-only",
+        TokenScope::Control => "control",
+    }
+}
+
 #[derive(Copy, Clone)]
 enum PresetLinkScope {
     Global,
@@ -3150,3 +3492,26 @@ fn build_show_color_panel_desc() -> ColorPanelDesc {
         scaling: HEADER_PANEL_SCALING,
     }
 }
+
+/// Describes the given diff in a way that fits into a message box.
+fn summarize_compartment_diff(diff: &CompartmentDiff) -> String {
+    const MAX_LINE_COUNT: usize = 15;
+    const MAX_LINE_LENGTH: usize = 100;
+    if diff.is_empty() {
+        return "The clipboard doesn't contain any changes.".to_string();
+    }
+    let text = diff.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut summary = format!(
+        "Changes:\n{}",
+        lines
+            .iter()
+            .take(MAX_LINE_COUNT)
+            .map(|line| line.chars().take(MAX_LINE_LENGTH).collect::<String>())
+            .join("\n")
+    );
+    if lines.len() > MAX_LINE_COUNT {
+        summary += &format!("\n... ({} more)", lines.len() - MAX_LINE_COUNT);
+    }
+    summary
+}
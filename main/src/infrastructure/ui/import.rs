@@ -4,18 +4,20 @@ use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
-use crate::domain::{CompartmentKind, FsDirLuaModuleFinder, LuaModuleContainer, SafeLua};
+use crate::domain::{
+    parse_lua_frontmatter, CompartmentKind, FsDirLuaModuleFinder, LuaModuleContainer, SafeLua,
+};
 use crate::infrastructure::api::convert::from_data::ConversionStyle;
 use crate::infrastructure::api::convert::to_data::ApiToDataConversionContext;
 use crate::infrastructure::api::convert::{from_data, to_data};
 use crate::infrastructure::data::{
-    parse_lua_frontmatter, ActivationConditionData, CompartmentModelData, InstanceData,
-    MappingModelData, ModeModelData, SourceModelData, TargetModelData, UnitData,
+    ActivationConditionData, CompartmentModelData, InstanceData, MappingModelData, ModeModelData,
+    SourceModelData, TargetModelData, UnitData,
 };
 use crate::infrastructure::plugin::BackboneShell;
-use crate::infrastructure::ui::lua_serializer;
 use crate::infrastructure::ui::util::open_in_browser;
 use mlua::{Lua, LuaSerdeExt, Value};
+use realearn_api::lua_serializer;
 use realearn_api::persistence;
 use realearn_api::persistence::{ApiObject, CommonPresetMetaData, Envelope};
 use realearn_csi::{deserialize_csi_object_from_csi, AnnotatedResult, CsiObject};
@@ -26,13 +26,16 @@ use helgoboss_learn::{
     SourceCharacter, TakeoverMode, Target, UnitValue, ValueSequence, VirtualColor,
     DEFAULT_OSC_ARG_VALUE_RANGE,
 };
+#[cfg(feature = "egui")]
+use realearn_api::persistence::ResponseCurve;
 use realearn_api::persistence::{
-    Axis, BrowseTracksMode, FxDescriptor, FxToolAction, LearnableTargetKind, MidiScriptKind,
-    MonitoringMode, MouseButton, PlaytimeColumnAction, PlaytimeColumnDescriptor,
-    PlaytimeColumnDescriptorKind, PlaytimeMatrixAction, PlaytimeRowAction, PlaytimeRowDescriptor,
-    PlaytimeRowDescriptorKind, PlaytimeSlotDescriptor, PlaytimeSlotDescriptorKind,
-    PlaytimeSlotManagementAction, PlaytimeSlotTransportAction, PotFilterKind, SeekBehavior,
-    TrackToolAction,
+    ArrangeViewProperty, AudioSignalKind, Axis, BrowseTracksMode, ControlTransformationKind,
+    FxChainEditAction, FxDescriptor, FxToolAction, LearnableTargetKind, MidiScriptKind,
+    ModulatorNoteValue, ModulatorShape, MonitoringMode, MouseButton, PlaytimeColumnAction,
+    PlaytimeColumnDescriptor, PlaytimeColumnDescriptorKind, PlaytimeMatrixAction,
+    PlaytimeRowAction, PlaytimeRowDescriptor, PlaytimeRowDescriptorKind, PlaytimeSlotDescriptor,
+    PlaytimeSlotDescriptorKind, PlaytimeSlotManagementAction, PlaytimeSlotTransportAction,
+    PotFilterKind, SeekBehavior, SendMode, TrackRecordMode, TrackToolAction,
 };
 use swell_ui::{
     DeviceContext, DialogUnits, Point, SharedView, SwellStringArg, View, ViewContext, WeakView,
@@ -57,7 +60,7 @@ use crate::domain::ui_util::{
     format_as_percentage_without_unit, format_tags_as_csv, parse_unit_value_from_percentage,
 };
 use crate::domain::{
-    control_element_domains, AnyOnParameter, Backbone, ControlContext, Exclusivity,
+    control_element_domains, AdsrSettings, AnyOnParameter, Backbone, ControlContext, Exclusivity,
     FeedbackSendBehavior, KeyStrokePortability, MouseActionType, PortabilityIssue, ReaperTarget,
     ReaperTargetType, SendMidiDestination, SimpleExclusivity, SourceFeedbackEvent,
     TargetControlEvent, TouchedRouteParameterType, TrackGangBehavior, WithControlContext,
@@ -80,9 +83,15 @@ use crate::infrastructure::ui::util::{
 };
 use crate::infrastructure::ui::{
     menus, EelControlTransformationEngine, EelFeedbackTransformationEngine, EelMidiScriptEngine,
-    ItemProp, LuaFeedbackScriptEngine, LuaMidiScriptEngine, MappingHeaderPanel, MappingRowsPanel,
-    OscFeedbackArgumentsEngine, RawMidiScriptEngine, ScriptEditorInput, ScriptEngine,
-    SimpleScriptEditorPanel, TextualFeedbackExpressionEngine, UnitPanel, YamlEditorPanel,
+    ItemProp, LuaControlTransformationEngine, LuaFeedbackScriptEngine, LuaMidiInputScriptEngine,
+    LuaMidiScriptEngine, MappingHeaderPanel, MappingRowsPanel, OscFeedbackArgumentsEngine,
+    RawMidiScriptEngine, ScriptEditorInput, ScriptEngine, SimpleScriptEditorPanel,
+    TextualFeedbackExpressionEngine, UnitPanel, YamlEditorPanel,
+};
+#[cfg(feature = "egui")]
+use crate::infrastructure::ui::{
+    AdvancedScriptEditorPanel, ResponseCurveEditorInput, ScriptTemplateGroup,
+    CONTROL_TRANSFORMATION_TEMPLATES,
 };
 use base::hash_util::NonCryptoHashMap;
 use base::Global;
@@ -322,7 +331,27 @@ impl MappingPanel {
                                                 view.invalidate_source_line_7_edit_control(initiator);
                                             }
                                             P::ParameterIndex => {
-                                                view.invalidate_source_line_3_combo_box_1()
+                                                view.invalidate_source_line_3_combo_box_1();
+                                                view.invalidate_source_line_5_combo_box();
+                                            }
+                                            P::ModulatorShape | P::ModulatorTempoSync => {
+                                                view.invalidate_source_controls();
+                                            }
+                                            P::ModulatorFrequency | P::ModulatorAdsr => {
+                                                view.invalidate_source_line_4_edit_control(initiator);
+                                            }
+                                            P::ModulatorResetOnTransportStart => {
+                                                view.invalidate_source_check_box_2();
+                                            }
+                                            P::AudioSignalKind => {
+                                                view.invalidate_source_controls();
+                                                view.invalidate_mode_controls();
+                                            }
+                                            P::AudioSignalAttackMillis | P::AudioSignalReleaseMillis => {
+                                                view.invalidate_source_line_4_edit_control(initiator);
+                                            }
+                                            P::AudioSignalThreshold => {
+                                                view.invalidate_source_line_5_edit_control(initiator);
                                             }
                                             P::MidiScriptKind => {
                                                 view.invalidate_source_line_3(initiator);
@@ -391,6 +420,9 @@ impl MappingPanel {
                                             P::EelControlTransformation => {
                                                 view.invalidate_mode_controls_internal(initiator);
                                             }
+                                            P::ControlTransformationKind => {
+                                                view.invalidate_mode_controls_internal(initiator);
+                                            }
                                             P::EelFeedbackTransformation | P::TextualFeedbackExpression => {
                                                 view.invalidate_mode_eel_feedback_transformation_edit_control(initiator);
                                             }
@@ -417,9 +449,18 @@ impl MappingPanel {
                                             P::FeedbackColor | P::FeedbackBackgroundColor => {
                                                 view.invalidate_mode_feedback_type_button();
                                             }
-                                            P::FeedbackValueTable => {
+                                            P::FeedbackValueTable
+                                            | P::EncoderAcceleration
+                                            | P::Glide
+                                            | P::ScaleQuantization
+                                            | P::FireGesture
+                                            | P::FeedbackAnimations => {
                                                 // No representation in GUI at the moment.
                                             }
+                                            P::ResponseCurve => {
+                                                // Only editable in the advanced script editor,
+                                                // which manages its own state.
+                                            }
                                             P::LegacyJumpInterval => {
                                                 // Not supported in UI anymore since 2.14.0-pre.10
                                             }
@@ -491,7 +532,7 @@ impl MappingPanel {
                                                 view.invalidate_target_value_controls();
                                                 view.invalidate_mode_controls();
                                             }
-                                            P::SeekBehavior | P::TouchedTrackParameterType | P::AutomationMode | P::MonitoringMode | P::TrackArea => {
+                                            P::SeekBehavior | P::TouchedTrackParameterType | P::AutomationMode | P::MonitoringMode | P::TrackRecordMode | P::TrackArea => {
                                                 view.invalidate_target_line_3(None);
                                             }
                                             P::SoloBehavior => {
@@ -503,6 +544,10 @@ impl MappingPanel {
                                                 view.invalidate_target_line_2_combo_box_2(initiator);
                                                 view.invalidate_target_line_3(None);
                                             }
+                                            P::ArrangeViewProperty => {
+                                                view.invalidate_window_title();
+                                                view.invalidate_target_line_2_combo_box_2(initiator);
+                                            }
                                             P::FxSnapshot | P::FxDisplayType  => {
                                                 view.invalidate_target_line_4(None);
                                                 view.invalidate_target_value_controls();
@@ -512,7 +557,7 @@ impl MappingPanel {
                                                 view.invalidate_target_value_controls();
                                                 view.invalidate_mode_controls();
                                             }
-                                            P::TrackToolAction | P::FxToolAction  => {
+                                            P::TrackToolAction | P::FxToolAction | P::FxChainEditAction | P::PluginId => {
                                                 view.invalidate_target_line_4(initiator);
                                                 view.invalidate_target_value_controls();
                                                 view.invalidate_mode_controls();
@@ -593,9 +638,12 @@ impl MappingPanel {
                                                 view.invalidate_target_controls(initiator);
                                                 view.invalidate_mode_controls();
                                             }
-                                            P::TouchedRouteParameterType => {
+                                            P::TouchedRouteParameterType | P::RouteSendMode => {
                                                 view.invalidate_target_line_3_combo_box_2();
                                             }
+                                            P::RouteSourceChannel | P::RouteDestinationChannel => {
+                                                view.invalidate_target_line_5(initiator);
+                                            }
                                             P::MappingModificationKind => {
                                                 view.invalidate_target_line_2(initiator);
                                             }
@@ -1043,6 +1091,19 @@ impl MappingPanel {
                     ));
                 }
             }
+            ReaperTargetType::FxChainEdit => {
+                let current_plugin_id = mapping.borrow().target_model.plugin_id().to_owned();
+                let menu = menus::menu_containing_plugins(&current_plugin_id);
+                let plugin_id = self
+                    .view
+                    .require_window()
+                    .open_popup_menu(menu, Window::cursor_pos());
+                if let Some(plugin_id) = plugin_id {
+                    self.change_mapping(MappingCommand::ChangeTarget(TargetCommand::SetPluginId(
+                        plugin_id,
+                    )));
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -1118,6 +1179,27 @@ impl MappingPanel {
                             },
                         );
                     }
+                    MidiSourceType::InputScript => {
+                        let session = self.session.clone();
+                        let engine = Box::new(LuaMidiInputScriptEngine::new());
+                        let help_url =
+                            "https://github.com/helgoboss/realearn/blob/master/doc/user-guide.adoc#midi-input-script-source";
+                        self.edit_script_in_simple_editor(
+                            engine,
+                            help_url,
+                            |m| m.source_model.midi_script().to_owned(),
+                            move |m, text| {
+                                UnitModel::change_mapping_from_ui_simple(
+                                    session.clone(),
+                                    m,
+                                    MappingCommand::ChangeSource(SourceCommand::SetMidiScript(
+                                        text,
+                                    )),
+                                    None,
+                                );
+                            },
+                        );
+                    }
                     _ => {}
                 }
             }
@@ -1142,8 +1224,12 @@ impl MappingPanel {
 
     fn edit_control_transformation(&self) {
         let session = self.session.clone();
-        let engine = Box::new(EelControlTransformationEngine);
         let help_url = "https://github.com/helgoboss/realearn/blob/master/doc/user-guide.adoc#control-transformation";
+        let kind = self
+            .mapping()
+            .borrow()
+            .mode_model
+            .control_transformation_kind();
         let get_value = |m: &MappingModel| m.mode_model.eel_control_transformation().to_owned();
         let set_value = move |m: &mut MappingModel, eel: String| {
             UnitModel::change_mapping_from_ui_simple(
@@ -1153,12 +1239,54 @@ impl MappingPanel {
                 None,
             );
         };
+        let engine: Box<dyn ScriptEngine> = match kind {
+            ControlTransformationKind::Eel => Box::new(EelControlTransformationEngine),
+            ControlTransformationKind::Lua => Box::new(LuaControlTransformationEngine::new()),
+        };
         #[cfg(feature = "egui")]
-        self.edit_script_in_advanced_editor(engine, help_url, get_value, set_value);
+        {
+            let script_template_groups: &[ScriptTemplateGroup] = match kind {
+                ControlTransformationKind::Eel => CONTROL_TRANSFORMATION_TEMPLATES,
+                // The templates are written in EEL
+                ControlTransformationKind::Lua => &[],
+            };
+            self.edit_script_in_advanced_editor(
+                engine,
+                help_url,
+                get_value,
+                set_value,
+                script_template_groups,
+                Some(self.response_curve_editor_input()),
+            );
+        }
         #[cfg(not(feature = "egui"))]
         self.edit_script_in_simple_editor(engine, help_url, get_value, set_value);
     }
 
+    #[cfg(feature = "egui")]
+    fn response_curve_editor_input(
+        &self,
+    ) -> ResponseCurveEditorInput<impl Fn(Option<ResponseCurve>) + 'static> {
+        let mapping = self.mapping();
+        let session = self.session.clone();
+        let weak_mapping = Rc::downgrade(&mapping);
+        let initial_value = mapping.borrow().mode_model.response_curve().cloned();
+        ResponseCurveEditorInput {
+            initial_value,
+            set_value: move |curve| {
+                let Some(m) = weak_mapping.upgrade() else {
+                    return;
+                };
+                UnitModel::change_mapping_from_ui_simple(
+                    session.clone(),
+                    &mut m.borrow_mut(),
+                    MappingCommand::ChangeMode(ModeCommand::SetResponseCurve(curve)),
+                    None,
+                );
+            },
+        }
+    }
+
     fn edit_feedback_descriptor(&self) {
         let mapping = self.mapping();
         let feedback_type = mapping.borrow().mode_model.feedback_type();
@@ -1279,6 +1407,10 @@ impl MappingPanel {
         help_url: &'static str,
         get_initial_content: impl Fn(&MappingModel) -> String,
         apply: impl Fn(&mut MappingModel, String) + 'static,
+        script_template_groups: &'static [ScriptTemplateGroup],
+        response_curve_input: Option<
+            ResponseCurveEditorInput<impl Fn(Option<ResponseCurve>) + 'static>,
+        >,
     ) {
         let mapping = self.mapping();
         let weak_mapping = Rc::downgrade(&mapping);
@@ -1295,10 +1427,8 @@ impl MappingPanel {
                 apply(&mut m.borrow_mut(), edited_script);
             },
         };
-        let editor = crate::infrastructure::ui::AdvancedScriptEditorPanel::new(
-            input,
-            crate::infrastructure::ui::CONTROL_TRANSFORMATION_TEMPLATES,
-        );
+        let editor =
+            AdvancedScriptEditorPanel::new(input, script_template_groups, response_curve_input);
         self.open_extra_panel(editor);
     }
 
@@ -1947,7 +2077,12 @@ impl<'a> MutableMappingPanel<'a> {
                     SourceCommand::SetOscArgIsRelative(checked),
                 ));
             }
-            Reaper | Virtual | Never | Keyboard => {}
+            Reaper => {
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetModulatorResetOnTransportStart(checked),
+                ));
+            }
+            Virtual | Never | Keyboard => {}
         };
     }
 
@@ -1983,6 +2118,22 @@ impl<'a> MutableMappingPanel<'a> {
                         ),
                     ));
                 }
+                ReaperSourceType::AudioSignal => {
+                    let i = b.selected_combo_box_item_index();
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetAudioSignalKind(
+                            i.try_into().expect("invalid audio signal kind"),
+                        ),
+                    ));
+                }
+                ReaperSourceType::Modulator => {
+                    let i = b.selected_combo_box_item_index();
+                    self.change_mapping(MappingCommand::ChangeSource(
+                        SourceCommand::SetModulatorShape(
+                            i.try_into().expect("invalid modulator shape"),
+                        ),
+                    ));
+                }
                 _ => b.hide(),
             },
             _ => {}
@@ -2081,6 +2232,14 @@ impl<'a> MutableMappingPanel<'a> {
                     _ => {}
                 }
             }
+            Reaper => {
+                let index = b.selected_combo_box_item_index() as u32;
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetParameterIndex(
+                        index.try_into().expect("invalid param index"),
+                    ),
+                ));
+            }
             _ => {}
         }
     }
@@ -2148,6 +2307,15 @@ impl<'a> MutableMappingPanel<'a> {
                 }
                 _ => {}
             },
+            Reaper => {
+                let value = match b.selected_combo_box_item_data() {
+                    -1 => None,
+                    i => Some((i as usize).try_into().expect("invalid note value")),
+                };
+                self.change_mapping(MappingCommand::ChangeSource(
+                    SourceCommand::SetModulatorTempoSync(value),
+                ));
+            }
             _ => {}
         }
     }
@@ -2174,7 +2342,44 @@ impl<'a> MutableMappingPanel<'a> {
                     Some(edit_control_id),
                 );
             }
-            Reaper | Never | Keyboard | Osc => {}
+            Reaper => {
+                if self.mapping.source_model.reaper_source_type() == ReaperSourceType::Modulator {
+                    let cmd = if self.mapping.source_model.modulator_shape() == ModulatorShape::Adsr
+                    {
+                        let Some(adsr) = parse_adsr(&text) else {
+                            return;
+                        };
+                        SourceCommand::SetModulatorAdsr(adsr)
+                    } else {
+                        let Ok(frequency) = text.trim().parse() else {
+                            return;
+                        };
+                        SourceCommand::SetModulatorFrequency(frequency)
+                    };
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeSource(cmd),
+                        Some(edit_control_id),
+                    );
+                    return;
+                }
+                if self.mapping.source_model.reaper_source_type() != ReaperSourceType::AudioSignal {
+                    return;
+                }
+                let Some((attack, release)) = parse_attack_and_release(&text) else {
+                    return;
+                };
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetAudioSignalAttackMillis(attack)),
+                    Some(edit_control_id),
+                );
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetAudioSignalReleaseMillis(
+                        release,
+                    )),
+                    Some(edit_control_id),
+                );
+            }
+            Never | Keyboard | Osc => {}
         };
     }
 
@@ -2191,6 +2396,18 @@ impl<'a> MutableMappingPanel<'a> {
                     Some(edit_control_id),
                 );
             }
+            Reaper
+                if self.mapping.source_model.reaper_source_type()
+                    == ReaperSourceType::AudioSignal =>
+            {
+                let Ok(v) = text.trim().parse() else {
+                    return;
+                };
+                self.change_mapping_with_initiator(
+                    MappingCommand::ChangeSource(SourceCommand::SetAudioSignalThreshold(v)),
+                    Some(edit_control_id),
+                );
+            }
             _ => {}
         };
     }
@@ -2235,7 +2452,7 @@ impl<'a> MutableMappingPanel<'a> {
                         Some(edit_control_id),
                     );
                 }
-                MidiSourceType::Script => {
+                MidiSourceType::Script | MidiSourceType::InputScript => {
                     self.change_mapping_with_initiator(
                         MappingCommand::ChangeSource(SourceCommand::SetMidiScript(value)),
                         Some(edit_control_id),
@@ -2334,6 +2551,19 @@ impl<'a> MutableMappingPanel<'a> {
         ));
     }
 
+    fn update_mode_control_transformation_kind(&mut self) {
+        self.update_mode_hint(ModeParameter::ControlTransformation);
+        let kind = self
+            .view
+            .require_control(root::ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX)
+            .selected_combo_box_item_index()
+            .try_into()
+            .expect("invalid control transformation kind");
+        self.change_mapping(MappingCommand::ChangeMode(
+            ModeCommand::SetControlTransformationKind(kind),
+        ));
+    }
+
     fn update_takeover_mode(&mut self) {
         self.update_mode_hint(ModeParameter::TakeoverMode);
         let mode = self
@@ -3108,6 +3338,15 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetFxToolAction(action),
                     ));
                 }
+                ReaperTargetType::FxChainEdit => {
+                    let action: FxChainEditAction = combo
+                        .selected_combo_box_item_index()
+                        .try_into()
+                        .unwrap_or_default();
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetFxChainEditAction(action),
+                    ));
+                }
                 t if t.supports_fx_parameter() => {
                     let param_type = combo
                         .selected_combo_box_item_index()
@@ -3159,6 +3398,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetAutomationModeOverrideType(v),
                     ));
                 }
+                ReaperTargetType::ArrangeView => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid arrange view property");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetArrangeViewProperty(v),
+                    ));
+                }
                 ReaperTargetType::Mouse => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid mouse action type");
@@ -3359,6 +3605,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetMonitoringMode(v),
                     ));
                 }
+                ReaperTargetType::TrackRecordMode => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid record mode");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetTrackRecordMode(v),
+                    ));
+                }
                 ReaperTargetType::TrackTouchState => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid touched track parameter type");
@@ -3373,6 +3626,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetTouchedRouteParameterType(v),
                     ));
                 }
+                ReaperTargetType::RouteMatrixCell => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid send mode");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetRouteSendMode(v),
+                    ));
+                }
                 _ if self.mapping.target_model.supports_axis() => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid axis type");
@@ -3704,6 +3964,13 @@ impl<'a> MutableMappingPanel<'a> {
         let control = self.view.require_control(edit_control_id);
         match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
+                ReaperTargetType::FxChainEdit => {
+                    let plugin_id = control.text().unwrap_or_default();
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetPluginId(plugin_id)),
+                        Some(edit_control_id),
+                    );
+                }
                 t if t.supports_fx_parameter() => match self.mapping.target_model.param_type() {
                     VirtualFxParameterType::Dynamic => {
                         let expression = control.text().unwrap_or_default();
@@ -3817,6 +4084,25 @@ impl<'a> MutableMappingPanel<'a> {
                         Some(edit_control_id),
                     );
                 }
+                ReaperTargetType::RouteMatrixCell => {
+                    let text = control.text().unwrap_or_default();
+                    let Some((source_channel, destination_channel)) = parse_send_channels(&text)
+                    else {
+                        return;
+                    };
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetRouteSourceChannel(
+                            source_channel,
+                        )),
+                        Some(edit_control_id),
+                    );
+                    self.change_mapping_with_initiator(
+                        MappingCommand::ChangeTarget(TargetCommand::SetRouteDestinationChannel(
+                            destination_channel,
+                        )),
+                        Some(edit_control_id),
+                    );
+                }
                 _ => {}
             },
             TargetCategory::Virtual => {}
@@ -3875,6 +4161,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         self.fill_mode_out_of_range_behavior_combo_box();
         self.fill_mode_group_interaction_combo_box();
         self.fill_mode_takeover_mode_combo_box();
+        self.fill_mode_control_transformation_kind_combo_box();
         self.fill_mode_button_usage_combo_box();
         self.fill_mode_encoder_usage_combo_box();
         self.fill_mode_fire_mode_combo_box();
@@ -4189,6 +4476,8 @@ impl<'a> ImmutableMappingPanel<'a> {
             Reaper => match self.source.reaper_source_type() {
                 ReaperSourceType::Timer => Some("Millis"),
                 ReaperSourceType::RealearnParameter => Some("Param"),
+                ReaperSourceType::AudioSignal => Some("Kind"),
+                ReaperSourceType::Modulator => Some("Shape"),
                 _ => None,
             },
             Keyboard => Some("Key"),
@@ -4207,6 +4496,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 MidiSourceType::Display => Some("Protocol"),
                 _ => None,
             },
+            Reaper if self.source_is_modulator_lfo() => Some("Rate"),
             _ => None,
         };
         self.view
@@ -4251,6 +4541,16 @@ impl<'a> ImmutableMappingPanel<'a> {
                     b.show();
                     b.select_combo_box_item_by_index(self.source.parameter_index().get() as usize);
                 }
+                ReaperSourceType::AudioSignal => {
+                    b.fill_combo_box_indexed(AudioSignalKind::iter());
+                    b.show();
+                    b.select_combo_box_item_by_index(self.source.audio_signal_kind().into());
+                }
+                ReaperSourceType::Modulator => {
+                    b.fill_combo_box_indexed(ModulatorShape::iter());
+                    b.show();
+                    b.select_combo_box_item_by_index(self.source.modulator_shape().into());
+                }
                 _ => b.hide(),
             },
             _ => {
@@ -4277,6 +4577,15 @@ impl<'a> ImmutableMappingPanel<'a> {
                 }
             }
             Osc => Some(("Is relative", self.source.osc_arg_is_relative())),
+            Reaper
+                if self.source_is_modulator_lfo()
+                    && self.source.modulator_tempo_sync().is_none() =>
+            {
+                Some((
+                    "Reset on play",
+                    self.source.modulator_reset_on_transport_start(),
+                ))
+            }
             _ => None,
         };
         self.invalidate_check_box(root::ID_SOURCE_14_BIT_CHECK_BOX, state);
@@ -4346,6 +4655,14 @@ impl<'a> ImmutableMappingPanel<'a> {
             }
             Virtual => Some("ID"),
             Osc => Some("Argument"),
+            Reaper if self.source_uses_audio_signal_envelope() => Some("Att/Rel ms"),
+            Reaper if self.source_is_modulator_adsr() => Some("A/D/S/R"),
+            Reaper
+                if self.source_is_modulator_lfo()
+                    && self.source.modulator_tempo_sync().is_none() =>
+            {
+                Some("Hz")
+            }
             _ => None,
         };
         self.view
@@ -4449,6 +4766,20 @@ impl<'a> ImmutableMappingPanel<'a> {
                 _ => None,
             },
             Virtual => Some(self.source.control_element_id().to_string()),
+            Reaper if self.source_uses_audio_signal_envelope() => Some(format!(
+                "{} / {}",
+                self.source.audio_signal_attack_millis(),
+                self.source.audio_signal_release_millis()
+            )),
+            Reaper if self.source_is_modulator_adsr() => {
+                Some(format_adsr(self.source.modulator_adsr()))
+            }
+            Reaper
+                if self.source_is_modulator_lfo()
+                    && self.source.modulator_tempo_sync().is_none() =>
+            {
+                Some(format!("{:.2}", self.source.modulator_frequency()))
+            }
             _ => None,
         };
         self.view
@@ -4467,7 +4798,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         let text = match self.source.category() {
             Midi if matches!(
                 self.source.midi_source_type(),
-                MidiSourceType::Raw | MidiSourceType::Script
+                MidiSourceType::Raw | MidiSourceType::Script | MidiSourceType::InputScript
             ) =>
             {
                 Some("...")
@@ -4485,7 +4816,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         let text = match self.source.category() {
             Midi => match self.source.midi_source_type() {
                 MidiSourceType::Raw => Some("Pattern"),
-                MidiSourceType::Script => Some("Script"),
+                MidiSourceType::Script | MidiSourceType::InputScript => Some("Script"),
                 _ => None,
             },
             Osc => Some("Feedback arguments"),
@@ -4557,7 +4888,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                     let text = self.source.raw_midi_pattern();
                     (Some(text.to_owned()), text.chars().count() > 30)
                 }
-                MidiSourceType::Script => {
+                MidiSourceType::Script | MidiSourceType::InputScript => {
                     let text = self.source.midi_script();
                     (
                         Some(extract_first_line(text).to_owned()),
@@ -4576,6 +4907,32 @@ impl<'a> ImmutableMappingPanel<'a> {
         c.set_enabled(!read_only);
     }
 
+    fn source_uses_audio_signal_envelope(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::AudioSignal
+            && matches!(
+                self.source.audio_signal_kind(),
+                AudioSignalKind::PeakEnvelope | AudioSignalKind::RmsEnvelope
+            )
+    }
+
+    fn source_is_modulator_lfo(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::Modulator
+            && self.source.modulator_shape() != ModulatorShape::Adsr
+    }
+
+    fn source_is_modulator_adsr(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::Modulator
+            && self.source.modulator_shape() == ModulatorShape::Adsr
+    }
+
+    fn source_uses_audio_signal_threshold(&self) -> bool {
+        self.source.reaper_source_type() == ReaperSourceType::AudioSignal
+            && matches!(
+                self.source.audio_signal_kind(),
+                AudioSignalKind::Transient | AudioSignalKind::Pitch
+            )
+    }
+
     fn invalidate_source_line_5(&self, initiator: Option<u32>) {
         self.invalidate_source_line_5_label();
         self.invalidate_source_line_5_combo_box();
@@ -4600,6 +4957,8 @@ impl<'a> ImmutableMappingPanel<'a> {
                 }
             }
             Osc if self.source.supports_osc_arg_value_range() => Some("Range"),
+            Reaper if self.source_uses_audio_signal_threshold() => Some("Threshold dB"),
+            Reaper if self.source_is_modulator_adsr() => Some("Gate"),
             _ => None,
         };
         self.view
@@ -4646,6 +5005,15 @@ impl<'a> ImmutableMappingPanel<'a> {
                     }
                 }
             }
+            Reaper if self.source_is_modulator_adsr() => {
+                let contents = compartment_parameter_dropdown_contents(
+                    self.session,
+                    self.mapping.compartment(),
+                );
+                b.fill_combo_box_with_data_small(contents);
+                b.show();
+                b.select_combo_box_item_by_index(self.source.parameter_index().get() as usize);
+            }
             _ => {
                 b.hide();
             }
@@ -4665,6 +5033,9 @@ impl<'a> ImmutableMappingPanel<'a> {
                 );
                 Some(text)
             }
+            Reaper if self.source_uses_audio_signal_threshold() => {
+                Some(format!("{:.1}", self.source.audio_signal_threshold()))
+            }
             _ => None,
         };
         self.view
@@ -4695,6 +5066,20 @@ impl<'a> ImmutableMappingPanel<'a> {
                     b.hide();
                 }
             },
+            Reaper if self.source_is_modulator_lfo() => {
+                b.show();
+                b.fill_combo_box_with_data_small(
+                    iter::once((-1isize, "Free (Hz)".to_string())).chain(
+                        ModulatorNoteValue::iter()
+                            .map(|v| (usize::from(v) as isize, v.to_string())),
+                    ),
+                );
+                let data = match self.source.modulator_tempo_sync() {
+                    None => -1,
+                    Some(v) => usize::from(v) as isize,
+                };
+                b.select_combo_box_item_by_data(data).unwrap();
+            }
             _ => {
                 b.hide();
             }
@@ -4765,6 +5150,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::Transport => Some("Action"),
                 ReaperTargetType::AnyOn => Some("Parameter"),
                 ReaperTargetType::AutomationModeOverride => Some("Behavior"),
+                ReaperTargetType::ArrangeView => Some("Property"),
                 ReaperTargetType::GoToBookmark => match self.target.bookmark_type() {
                     BookmarkType::Marker => Some("Marker"),
                     BookmarkType::Region => Some("Region"),
@@ -4917,6 +5303,13 @@ impl<'a> ImmutableMappingPanel<'a> {
                             .into(),
                     );
                 }
+                ReaperTargetType::ArrangeView => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(ArrangeViewProperty::iter());
+                    combo.select_combo_box_item_by_index(
+                        self.mapping.target_model.arrange_view_property().into(),
+                    );
+                }
                 ReaperTargetType::GoToBookmark
                     if self.target.bookmark_anchor_type() == BookmarkAnchorType::Id =>
                 {
@@ -5224,6 +5617,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::Action => Some("Pick!"),
                 ReaperTargetType::LoadFxSnapshot => Some("Take!"),
                 ReaperTargetType::ModifyMapping => Some("Pick!"),
+                ReaperTargetType::FxChainEdit => Some("Pick!"),
                 _ => None,
             },
             TargetCategory::Virtual => None,
@@ -5264,6 +5658,10 @@ impl<'a> ImmutableMappingPanel<'a> {
                     );
                     Some(text)
                 }
+                ReaperTargetType::RouteMatrixCell => Some(format_send_channels(
+                    self.target.route_source_channel(),
+                    self.target.route_destination_channel(),
+                )),
                 _ => None,
             },
             TargetCategory::Virtual => None,
@@ -5282,6 +5680,7 @@ impl<'a> ImmutableMappingPanel<'a> {
             .require_control(root::ID_TARGET_LINE_4_EDIT_CONTROL);
         let text = match self.target_category() {
             TargetCategory::Reaper => match self.reaper_target_type() {
+                ReaperTargetType::FxChainEdit => Some(self.target.plugin_id().to_owned()),
                 ReaperTargetType::PlaytimeSlotManagementAction
                 | ReaperTargetType::PlaytimeSlotTransportAction
                 | ReaperTargetType::PlaytimeSlotSeek
@@ -5418,6 +5817,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::SendMidi => Some("Pattern"),
                 ReaperTargetType::SendOsc => Some("Address"),
                 ReaperTargetType::TrackMonitoringMode => Some("Mode"),
+                ReaperTargetType::TrackRecordMode => Some("Mode"),
                 ReaperTargetType::LoadMappingSnapshot => Some("Default"),
                 ReaperTargetType::ModifyMapping => Some("Unit"),
                 ReaperTargetType::PlaytimeColumnAction => Some("Column"),
@@ -5446,6 +5846,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::SendOsc if self.target.supports_osc_arg_value_range() => {
                     Some("Range")
                 }
+                ReaperTargetType::RouteMatrixCell => Some("Channels"),
                 _ => None,
             },
             TargetCategory::Virtual => None,
@@ -5465,6 +5866,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::LoadFxSnapshot => Some("Snapshot"),
                 ReaperTargetType::SendOsc => Some("Argument"),
                 ReaperTargetType::TrackTool | ReaperTargetType::FxTool => Some("Act/Tags"),
+                ReaperTargetType::FxChainEdit => Some("Act/Plug-in"),
                 ReaperTargetType::ModifyMapping => Some("Mapping"),
                 t if t.supports_fx_parameter() => Some("Parameter"),
                 t if t.supports_track_exclusivity() => Some("Exclusive"),
@@ -5637,6 +6039,12 @@ impl<'a> ImmutableMappingPanel<'a> {
                     let action: FxToolAction = self.target.fx_tool_action();
                     combo.select_combo_box_item_by_index(action.into());
                 }
+                ReaperTargetType::FxChainEdit => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(FxChainEditAction::iter());
+                    let action: FxChainEditAction = self.target.fx_chain_edit_action();
+                    combo.select_combo_box_item_by_index(action.into());
+                }
                 t if t.supports_fx_parameter() => {
                     combo.show();
                     combo.fill_combo_box_indexed(VirtualFxParameterType::iter());
@@ -5745,11 +6153,21 @@ impl<'a> ImmutableMappingPanel<'a> {
                         self.target.touched_route_parameter_type().into(),
                     );
                 }
+                ReaperTargetType::RouteMatrixCell => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(SendMode::iter());
+                    combo.select_combo_box_item_by_index(self.target.route_send_mode().into());
+                }
                 ReaperTargetType::TrackMonitoringMode => {
                     combo.show();
                     combo.fill_combo_box_indexed(MonitoringMode::iter());
                     combo.select_combo_box_item_by_index(self.target.monitoring_mode().into());
                 }
+                ReaperTargetType::TrackRecordMode => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(TrackRecordMode::iter());
+                    combo.select_combo_box_item_by_index(self.target.track_record_mode().into());
+                }
                 _ if self.target.supports_automation_mode() => {
                     combo.show();
                     combo.fill_combo_box_indexed(RealearnAutomationMode::iter());
@@ -6246,6 +6664,7 @@ impl<'a> ImmutableMappingPanel<'a> {
         self.invalidate_mode_encoder_usage_combo_box();
         self.invalidate_mode_reverse_check_box();
         self.invalidate_mode_target_value_sequence_edit_control(initiator);
+        self.invalidate_mode_control_transformation_kind_combo_box();
         self.invalidate_mode_eel_control_transformation_edit_control(initiator);
         self.invalidate_mode_eel_feedback_transformation_edit_control(initiator);
     }
@@ -6404,6 +6823,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 show_control_transformation,
                 &[
                     root::ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL,
+                    root::ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX,
                     root::ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL,
                     root::ID_MODE_EEL_CONTROL_TRANSFORMATION_DETAIL_BUTTON,
                 ],
@@ -6842,6 +7262,13 @@ impl<'a> ImmutableMappingPanel<'a> {
             .select_combo_box_item_by_index(mode.into());
     }
 
+    fn invalidate_mode_control_transformation_kind_combo_box(&self) {
+        let kind = self.mode.control_transformation_kind();
+        self.view
+            .require_control(root::ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX)
+            .select_combo_box_item_by_index(kind.into());
+    }
+
     fn invalidate_mode_button_usage_combo_box(&self) {
         let usage = self.mode.button_usage();
         self.view
@@ -6998,6 +7425,12 @@ impl<'a> ImmutableMappingPanel<'a> {
             .fill_combo_box_indexed(TakeoverMode::iter());
     }
 
+    fn fill_mode_control_transformation_kind_combo_box(&self) {
+        self.view
+            .require_control(root::ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX)
+            .fill_combo_box_indexed(ControlTransformationKind::iter());
+    }
+
     fn fill_mode_button_usage_combo_box(&self) {
         self.view
             .require_control(root::ID_MODE_BUTTON_FILTER_COMBO_BOX)
@@ -7203,6 +7636,9 @@ impl View for MappingPanel {
                 self.write(|p| p.update_mode_group_interaction())
             }
             root::ID_MODE_TAKEOVER_MODE => self.write(|p| p.update_takeover_mode()),
+            root::ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX => {
+                self.write(|p| p.update_mode_control_transformation_kind())
+            }
             root::ID_MODE_BUTTON_FILTER_COMBO_BOX => self.write(|p| p.update_button_usage()),
             root::ID_MODE_RELATIVE_FILTER_COMBO_BOX => self.write(|p| p.update_encoder_usage()),
             root::ID_MODE_FIRE_COMBO_BOX => self.write(|p| p.update_mode_fire_mode()),
@@ -8161,6 +8597,52 @@ fn parse_osc_arg_value_range(text: &str) -> Interval<f64> {
         .unwrap_or(DEFAULT_OSC_ARG_VALUE_RANGE)
 }
 
+/// Formats the zero-based first channels of a send as one-based channel numbers.
+fn format_send_channels(source_channel: u32, destination_channel: u32) -> String {
+    format!("{} > {}", source_channel + 1, destination_channel + 1)
+}
+
+/// Parses one-based channel numbers (e.g. "1 > 3") into zero-based first channels.
+fn parse_send_channels(text: &str) -> Option<(u32, u32)> {
+    let (source, destination) = text.split_once('>')?;
+    let source: u32 = source.trim().parse().ok()?;
+    let destination: u32 = destination.trim().parse().ok()?;
+    Some((source.checked_sub(1)?, destination.checked_sub(1)?))
+}
+
+/// Formats ADSR settings as "attack / decay / sustain / release" with times in milliseconds and
+/// sustain in percent.
+fn format_adsr(adsr: AdsrSettings) -> String {
+    format!(
+        "{} / {} / {} / {}",
+        adsr.attack.as_millis(),
+        adsr.decay.as_millis(),
+        (adsr.sustain * 100.0).round(),
+        adsr.release.as_millis()
+    )
+}
+
+fn parse_adsr(text: &str) -> Option<AdsrSettings> {
+    let parts: Vec<_> = text.split('/').map(|p| p.trim()).collect();
+    let [attack, decay, sustain, release] = parts.as_slice() else {
+        return None;
+    };
+    let millis = |p: &str| p.parse().ok().map(Duration::from_millis);
+    let sustain: f64 = sustain.parse().ok()?;
+    Some(AdsrSettings {
+        attack: millis(attack)?,
+        decay: millis(decay)?,
+        sustain: (sustain / 100.0).clamp(0.0, 1.0),
+        release: millis(release)?,
+    })
+}
+
+/// Parses envelope follower times in milliseconds (e.g. "10 / 100").
+fn parse_attack_and_release(text: &str) -> Option<(u32, u32)> {
+    let (attack, release) = text.split_once('/')?;
+    Some((attack.trim().parse().ok()?, release.trim().parse().ok()?))
+}
+
 fn extract_first_line(text: &str) -> &str {
     text.lines().next().unwrap_or_default()
 }
@@ -8380,6 +8862,7 @@ impl Section {
             | ID_MODE_TAKEOVER_MODE
             | ID_SETTINGS_ROUND_TARGET_VALUE_CHECK_BOX
             | ID_MODE_EEL_CONTROL_TRANSFORMATION_LABEL
+            | ID_MODE_CONTROL_TRANSFORMATION_KIND_COMBO_BOX
             | ID_MODE_EEL_CONTROL_TRANSFORMATION_EDIT_CONTROL
             | ID_MODE_EEL_CONTROL_TRANSFORMATION_DETAIL_BUTTON
             | ID_MODE_RELATIVE_GROUP_BOX
@@ -4,11 +4,12 @@ use crate::domain::{
     ControlInput, FeedbackOutput, MappingId, MidiControlInput, MidiDestination, OscDeviceId,
     ReaperTargetType, TargetSection,
 };
-use crate::infrastructure::data::{CommonPresetInfo, OscDevice};
+use crate::infrastructure::data::{CommonPresetInfo, OscDevice, PresetQuery};
 use crate::infrastructure::plugin::{ActionSection, BackboneShell, ACTION_DEFS};
 use crate::infrastructure::ui::Item;
 use camino::Utf8Path;
 use indexmap::IndexMap;
+use pot::PluginIdInPipeFormat;
 use reaper_high::{FxChainContext, MidiInputDevice, MidiOutputDevice, Reaper};
 
 use base::hash_util::NonCryptoIndexMap;
@@ -17,7 +18,7 @@ use reaper_medium::ReaperString;
 use std::iter;
 use strum::IntoEnumIterator;
 use swell_ui::menu_tree::{
-    anonymous_menu, item, item_with_opts, menu, separator, Entry, ItemOpts, Menu,
+    anonymous_menu, disabled_item, item, item_with_opts, menu, separator, Entry, ItemOpts, Menu,
 };
 
 pub enum ControlInputMenuAction {
@@ -83,6 +84,14 @@ pub fn control_input_menu(current_value: ControlInput) -> Menu<ControlInputMenuA
             checked: current_value == ControlInput::Keyboard,
         },
         ControlInputMenuAction::SelectControlInput(ControlInput::Keyboard),
+    )))
+    .chain(iter::once(item_with_opts(
+        CONTROL_INPUT_WEB_CLIENTS_LABEL,
+        ItemOpts {
+            enabled: true,
+            checked: current_value == ControlInput::WebClients,
+        },
+        ControlInputMenuAction::SelectControlInput(ControlInput::WebClients),
     )));
     anonymous_menu(entries.collect())
 }
@@ -159,6 +168,7 @@ pub fn feedback_output_menu(
 
 pub const CONTROL_INPUT_MIDI_FX_INPUT_LABEL: &str = "MIDI: <FX input>";
 pub const CONTROL_INPUT_KEYBOARD_LABEL: &str = "Computer keyboard";
+pub const CONTROL_INPUT_WEB_CLIENTS_LABEL: &str = "Web clients (WebSocket)";
 pub const FEEDBACK_OUTPUT_MIDI_FX_OUTPUT: &str = "MIDI: <FX output>";
 pub const FEEDBACK_OUTPUT_NONE_LABEL: &str = "<None>";
 
@@ -411,6 +421,43 @@ pub fn menu_containing_mappings(
     anonymous_menu(iter::once(none_item).chain(group_items).collect())
 }
 
+/// Returns plug-in IDs in pipe format.
+pub fn menu_containing_plugins(current_plugin_id: &str) -> Menu<String> {
+    let mut plugins: Vec<(String, String, &'static str)> = pot::pot_db().with_plugin_db(|db| {
+        db.plugins()
+            .map(|p| {
+                let id = p.common.core.id;
+                (
+                    p.common.to_string(),
+                    PluginIdInPipeFormat(&id).to_string(),
+                    id.kind().name(),
+                )
+            })
+            .collect()
+    });
+    plugins.sort_by(|(a, ..), (b, ..)| a.to_lowercase().cmp(&b.to_lowercase()));
+    let kind_menus = ["VST", "VST3", "CLAP", "JS"].into_iter().map(|kind| {
+        menu(
+            kind,
+            plugins
+                .iter()
+                .filter(|(_, _, k)| *k == kind)
+                .map(|(name, id, _)| {
+                    item_with_opts(
+                        name.clone(),
+                        ItemOpts {
+                            enabled: true,
+                            checked: id == current_plugin_id,
+                        },
+                        id.clone(),
+                    )
+                })
+                .collect(),
+        )
+    });
+    anonymous_menu(kind_menus.collect())
+}
+
 pub fn menu_containing_sessions(
     this_session: &UnitModel,
     current_other_session_id: Option<&str>,
@@ -556,30 +603,85 @@ fn bank_item(text: String, bank_index: usize, current_bank_index: u32) -> Entry<
     )
 }
 
+#[derive(Default)]
+pub enum CompartmentPresetMenuAction {
+    #[default]
+    None,
+    /// Activates the preset with the given ID or no preset at all.
+    Activate(Option<String>),
+    Search,
+}
+
+/// If a compatibility query is given, the menu additionally contains a sub menu with the presets
+/// matching this query.
 pub fn menu_containing_compartment_presets(
     compartment: CompartmentKind,
     current_value: Option<&str>,
-) -> Menu<Option<String>> {
+    compatibility_query: Option<(&str, &PresetQuery)>,
+) -> Menu<CompartmentPresetMenuAction> {
     let preset_manager = BackboneShell::get().compartment_preset_manager(compartment);
     let preset_manager = preset_manager.borrow();
+    let is_current_value = |info: &CommonPresetInfo| current_value.is_some_and(|id| id == info.id);
+    let compatible_presets_entry = match compatibility_query {
+        None => Entry::Nothing,
+        Some((label, query)) => {
+            let entries: Vec<_> = build_compartment_preset_menu_entries(
+                preset_manager.query_common_preset_infos(query),
+                |info| CompartmentPresetMenuAction::Activate(Some(info.id.clone())),
+                is_current_value,
+            )
+            .collect();
+            if entries.is_empty() {
+                disabled_item(format!("{label}: <None>"))
+            } else {
+                menu(label, entries)
+            }
+        }
+    };
     anonymous_menu(
-        iter::once(item_with_opts(
-            NONE,
-            ItemOpts {
-                enabled: true,
-                checked: current_value.is_none(),
-            },
-            None,
-        ))
+        [
+            item_with_opts(
+                NONE,
+                ItemOpts {
+                    enabled: true,
+                    checked: current_value.is_none(),
+                },
+                CompartmentPresetMenuAction::Activate(None),
+            ),
+            item("Search...", CompartmentPresetMenuAction::Search),
+            compatible_presets_entry,
+            separator(),
+        ]
+        .into_iter()
         .chain(build_compartment_preset_menu_entries(
             preset_manager.common_preset_infos(),
-            |info| Some(info.id.clone()),
-            |info| current_value.is_some_and(|id| id == info.id),
+            |info| CompartmentPresetMenuAction::Activate(Some(info.id.clone())),
+            is_current_value,
         ))
         .collect(),
     )
 }
 
+/// Returns `None` if no preset matches the given query.
+pub fn menu_containing_queried_compartment_presets(
+    compartment: CompartmentKind,
+    current_value: Option<&str>,
+    query: &PresetQuery,
+) -> Option<Menu<String>> {
+    let preset_manager = BackboneShell::get().compartment_preset_manager(compartment);
+    let preset_manager = preset_manager.borrow();
+    let entries: Vec<_> = build_compartment_preset_menu_entries(
+        preset_manager.query_common_preset_infos(query),
+        |info| info.id.clone(),
+        |info| current_value.is_some_and(|id| id == info.id),
+    )
+    .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    Some(anonymous_menu(entries))
+}
+
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, derive_more::Display)]
 enum PresetCategory<'a> {
     #[display(fmt = "Factory")]
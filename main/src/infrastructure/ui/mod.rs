@@ -75,8 +75,6 @@ pub use clipboard::*;
 mod import;
 pub use import::*;
 
-pub mod lua_serializer;
-
 #[cfg(feature = "egui")]
 mod egui_views;
 
@@ -1,6 +1,6 @@
 use crate::domain::{
     AdditionalTransformationInput, EelMidiSourceScript, EelTransformation, LuaFeedbackScript,
-    LuaMidiSourceScript, SafeLua, Script,
+    LuaMidiInputScript, LuaMidiSourceScript, LuaTransformation, SafeLua, Script,
 };
 use crate::infrastructure::ui::bindings::root;
 use crate::infrastructure::ui::bindings::root::ID_YAML_HELP_BUTTON;
@@ -8,7 +8,8 @@ use crate::infrastructure::ui::util::{open_in_browser, open_in_text_editor};
 use derivative::Derivative;
 use helgoboss_learn::{
     AbsoluteValue, FeedbackScript, FeedbackScriptInput, FeedbackStyle, FeedbackValue,
-    MidiSourceScript, NumericFeedbackValue, RawMidiEvent, RawMidiPattern, UnitValue,
+    MidiSourceScript, NumericFeedbackValue, RawMidiEvent, RawMidiPattern, Transformation,
+    UnitValue,
 };
 use reaper_low::raw;
 use std::cell::RefCell;
@@ -46,6 +47,30 @@ impl ScriptEngine for LuaMidiScriptEngine {
     }
 }
 
+pub struct LuaMidiInputScriptEngine {
+    lua: SafeLua,
+}
+
+impl LuaMidiInputScriptEngine {
+    pub fn new() -> Self {
+        Self {
+            lua: SafeLua::new().unwrap(),
+        }
+    }
+}
+
+impl ScriptEngine for LuaMidiInputScriptEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let script = LuaMidiInputScript::compile(&self.lua, code)?;
+        script.execute(&[0xb0, 0x4b, 0x7f])?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
 pub struct LuaFeedbackScriptEngine {
     lua: SafeLua,
 }
@@ -150,6 +175,34 @@ impl ScriptEngine for EelControlTransformationEngine {
     }
 }
 
+pub struct LuaControlTransformationEngine {
+    lua: SafeLua,
+}
+
+impl LuaControlTransformationEngine {
+    pub fn new() -> Self {
+        Self {
+            lua: SafeLua::new().unwrap(),
+        }
+    }
+}
+
+impl ScriptEngine for LuaControlTransformationEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let transformation = LuaTransformation::compile_for_control(&self.lua, code)?;
+        transformation.transform_continuous(
+            Default::default(),
+            Default::default(),
+            AdditionalTransformationInput::default(),
+        )?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
 pub struct EelFeedbackTransformationEngine;
 
 impl ScriptEngine for EelFeedbackTransformationEngine {
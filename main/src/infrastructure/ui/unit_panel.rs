@@ -17,11 +17,12 @@ use crate::domain::ui_util::format_tags_as_csv;
 use crate::domain::{
     CompartmentKind, InstanceId, InternalInfoEvent, MappingId, MappingMatchedEvent,
     ProjectionFeedbackValue, QualifiedMappingId, SourceFeedbackEvent, TargetControlEvent,
-    TargetValueChangedEvent,
+    TargetValueChangedEvent, VirtualFeedbackValue,
 };
 use crate::infrastructure::plugin::{update_auto_units_async, BackboneShell};
 use crate::infrastructure::server::http::{
     send_projection_feedback_to_subscribed_clients, send_updated_controller_routing,
+    send_virtual_feedback_to_subscribed_clients,
 };
 use crate::infrastructure::ui::instance_panel::InstancePanel;
 use crate::infrastructure::ui::util::{header_panel_height, parse_tags_from_csv};
@@ -542,6 +543,10 @@ impl SessionUi for Weak<UnitPanel> {
         let _ = send_projection_feedback_to_subscribed_clients(session.unit_key(), value);
     }
 
+    fn send_virtual_feedback(&self, session: &UnitModel, value: &VirtualFeedbackValue) {
+        let _ = send_virtual_feedback_to_subscribed_clients(session.unit_key(), value);
+    }
+
     fn mapping_matched(&self, event: MappingMatchedEvent) {
         upgrade_panel(self).handle_matched_mapping(event);
     }
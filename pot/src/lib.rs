@@ -1726,7 +1726,9 @@ pub enum FxEnsureOp {
     Replaced,
 }
 
-fn ensure_fx_has_correct_type(
+/// Makes sure that the FX at the destination is of the given plug-in type, replacing it if
+/// necessary.
+pub fn ensure_fx_has_correct_type(
     plugin_id: PluginId,
     destination: &Destination,
     existing_fx: Option<Fx>,
@@ -1765,7 +1767,8 @@ fn ensure_fx_has_correct_type(
     Ok(output)
 }
 
-fn insert_fx_by_plugin_id(
+/// Inserts an FX of the given plug-in type at the destination position.
+pub fn insert_fx_by_plugin_id(
     plugin_id: PluginId,
     destination: &Destination,
 ) -> Result<Fx, Box<dyn Error>> {
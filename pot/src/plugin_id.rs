@@ -36,6 +36,21 @@ impl PluginId {
         Ok(id)
     }
 
+    /// Parses the format produced by [`PluginIdInPipeFormat`], e.g. `vst|1967946098`.
+    pub fn parse_from_pipe_format(text: &str) -> Result<PluginId, &'static str> {
+        let (kind, content) = text
+            .trim()
+            .split_once('|')
+            .ok_or("plug-in ID must have the format KIND|ID")?;
+        let kind: PluginKind = kind.parse().map_err(|_| "unknown plug-in kind")?;
+        match kind {
+            PluginKind::Vst2 => Ok(Self::vst2(parse_vst2_magic_number(content)?)),
+            PluginKind::Vst3 => Ok(Self::vst3(parse_vst3_uid(content)?)),
+            PluginKind::Clap => Self::clap(content),
+            PluginKind::Js => Self::js(content),
+        }
+    }
+
     pub fn parse_from_rxml_line(line: &str) -> Result<PluginId, &'static str> {
         let line = line.trim();
         let mut tokens = splitty::split_unquoted_whitespace(line).unwrap_quotes(true);
@@ -182,8 +197,9 @@ pub fn parse_vst3_uid(expression: &str) -> Result<[u32; 4], &'static str> {
     fn parse_component(text: &str, i: usize) -> Result<u32, &'static str> {
         let from = i * 8;
         let until = from + 8;
-        let parsed = u32::from_str_radix(&text[from..until], 16)
-            .map_err(|_| "couldn't parse VST3 uid component")?;
+        let component = text.get(from..until).ok_or("VST3 uid too short")?;
+        let parsed =
+            u32::from_str_radix(component, 16).map_err(|_| "couldn't parse VST3 uid component")?;
         Ok(parsed)
     }
     let uid = [
@@ -197,7 +213,7 @@ pub fn parse_vst3_uid(expression: &str) -> Result<[u32; 4], &'static str> {
 
 #[cfg(test)]
 mod tests {
-    use crate::PluginId;
+    use crate::{PluginId, PluginIdInPipeFormat};
 
     #[test]
     pub fn vst2() {
@@ -231,6 +247,27 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn pipe_format_roundtrip() {
+        let ids = [
+            PluginId::vst2(1397572658),
+            PluginId::vst3([0x56535450, 0x74387170, 0x69616E6F, 0x74657120]),
+            PluginId::clap("org.surge-synth-team.surge-xt").unwrap(),
+            PluginId::js("analysis/hund").unwrap(),
+        ];
+        for id in ids {
+            let text = PluginIdInPipeFormat(&id).to_string();
+            assert_eq!(PluginId::parse_from_pipe_format(&text), Ok(id));
+        }
+    }
+
+    #[test]
+    pub fn pipe_format_invalid() {
+        assert!(PluginId::parse_from_pipe_format("1397572658").is_err());
+        assert!(PluginId::parse_from_pipe_format("foo|bar").is_err());
+        assert!(PluginId::parse_from_pipe_format("vst3|5653").is_err());
+    }
+
     #[test]
     pub fn js() {
         assert_eq!(
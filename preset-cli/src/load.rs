@@ -0,0 +1,137 @@
+use crate::lua::execute_lua_preset;
+use anyhow::{anyhow, bail, Context};
+use realearn_api::lua::parse_lua_frontmatter;
+use realearn_api::persistence::{ApiObject, CommonPresetMetaData, Compartment, Envelope};
+use realearn_csi::deserialize_csi_object_from_csi;
+use std::fs;
+use std::path::Path;
+
+/// A preset loaded from disk, in the format of ReaLearn's preset API.
+pub struct LoadedPreset {
+    /// Meta data (only available for Lua presets, which carry it as frontmatter).
+    pub meta_data: Option<CommonPresetMetaData>,
+    pub compartment: Compartment,
+    /// The compartment exactly as it was written, used for detecting unknown properties.
+    pub raw: serde_json::Value,
+    /// Hints that came up while converting the preset from a foreign format (CSI).
+    pub conversion_hints: Vec<String>,
+}
+
+/// Returned when encountering a JSON preset that has been saved by ReaLearn itself.
+///
+/// Those presets don't use the API format but ReaLearn's internal data format, which can only be
+/// interpreted by ReaLearn itself.
+#[derive(Debug, thiserror::Error)]
+#[error("preset uses ReaLearn's internal data format (as saved by ReaLearn), which can't be checked without ReaLearn")]
+pub struct InternalDataFormatError;
+
+enum PresetFileType {
+    Json,
+    Yaml,
+    Lua,
+    Csi,
+}
+
+impl PresetFileType {
+    fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        let file_type = match ext {
+            "json" => Self::Json,
+            "yaml" | "yml" => Self::Yaml,
+            "lua" | "luau" => Self::Lua,
+            "mst" => Self::Csi,
+            _ => bail!("Unsupported preset file extension \"{ext}\""),
+        };
+        Ok(file_type)
+    }
+}
+
+/// Loads the given preset file.
+///
+/// The module root is used for resolving `require` in Lua presets. If `None`, the directory
+/// containing the preset is used.
+pub fn load_preset(path: &Path, module_root: Option<&Path>) -> anyhow::Result<LoadedPreset> {
+    let file_type = PresetFileType::from_path(path)?;
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Couldn't read {}", path.display()))?;
+    match file_type {
+        PresetFileType::Json => {
+            let raw: serde_json::Value = serde_json::from_str(&text)?;
+            load_from_raw(raw, None)
+        }
+        PresetFileType::Yaml => {
+            let raw: serde_json::Value = serde_yaml::from_str(&text)?;
+            load_from_raw(raw, None)
+        }
+        PresetFileType::Lua => {
+            let meta_data: CommonPresetMetaData = parse_lua_frontmatter(&text)?;
+            let module_root = match module_root {
+                None => path
+                    .parent()
+                    .context("preset file has no parent directory")?,
+                Some(r) => r,
+            };
+            let raw = execute_lua_preset(&text, &path.to_string_lossy(), module_root)?;
+            load_from_raw(raw, Some(meta_data))
+        }
+        PresetFileType::Csi => {
+            let csi_object = deserialize_csi_object_from_csi(&text)
+                .map_err(|e| anyhow!("Couldn't parse CSI file: {e}"))?;
+            let annotated_result = csi_object
+                .try_into_api_object()
+                .map_err(|e| anyhow!("Couldn't convert CSI file: {e}"))?;
+            let ApiObject::ControllerCompartment(Envelope { value, .. }) = annotated_result.value
+            else {
+                bail!("CSI file didn't result in a controller compartment");
+            };
+            let preset = LoadedPreset {
+                meta_data: None,
+                raw: serde_json::to_value(&value)?,
+                compartment: *value,
+                conversion_hints: annotated_result
+                    .annotations
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect(),
+            };
+            Ok(preset)
+        }
+    }
+}
+
+fn load_from_raw(
+    raw: serde_json::Value,
+    meta_data: Option<CommonPresetMetaData>,
+) -> anyhow::Result<LoadedPreset> {
+    if is_internal_data_format(&raw) {
+        return Err(InternalDataFormatError.into());
+    }
+    // Also accept tagged API objects, e.g. copied from ReaLearn via "Copy as Lua"
+    let raw = match raw.get("kind").and_then(|k| k.as_str()) {
+        Some("MainCompartment" | "ControllerCompartment") => raw
+            .get("value")
+            .cloned()
+            .context("compartment API object without value")?,
+        Some(kind) => bail!("API object of kind \"{kind}\" is not a compartment"),
+        None => raw,
+    };
+    let compartment: Compartment = serde_path_to_error::deserialize(&raw).map_err(|e| {
+        let path = e.path().to_string();
+        anyhow!("Invalid preset at \"{path}\": {}", e.into_inner())
+    })?;
+    let preset = LoadedPreset {
+        meta_data,
+        compartment,
+        raw,
+        conversion_hints: vec![],
+    };
+    Ok(preset)
+}
+
+/// Presets saved by ReaLearn have their name at the top level, API compartments don't.
+fn is_internal_data_format(raw: &serde_json::Value) -> bool {
+    raw.get("name").is_some() && raw.get("kind").is_none()
+}
@@ -0,0 +1,49 @@
+//! Executes Lua presets the same way as ReaLearn does it, but resolving modules from the file
+//! system only.
+
+use mlua::LuaSerdeExt;
+use realearn_api::lua::{
+    FsDirLuaModuleFinder, LuaModuleContainer, PresetBuildEnvironment, PresetBuildQuery, SafeLua,
+};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// More generous than in ReaLearn because CI machines can be slow.
+const MAX_EXECUTION_DURATION: Duration = Duration::from_secs(5);
+
+/// Executes the given Lua preset code and returns its result as JSON value.
+pub fn execute_lua_preset(
+    code: &str,
+    display_name: &str,
+    module_root: &Path,
+) -> anyhow::Result<serde_json::Value> {
+    let lua = SafeLua::new()?.start_execution_time_limit_countdown_with(MAX_EXECUTION_DURATION)?;
+    let module_finder = FsDirLuaModuleFinder::new(module_root.to_path_buf());
+    let module_container = LuaModuleContainer::new(Ok(module_finder))
+        .with_build_environment(Rc::new(EmptyPresetBuildEnvironment));
+    let value =
+        module_container.execute_as_module(lua.as_ref(), None, display_name.to_string(), code)?;
+    let json = lua.as_ref().from_value(value)?;
+    Ok(json)
+}
+
+/// Without REAPER, presets are built as if running in an empty project without devices.
+struct EmptyPresetBuildEnvironment;
+
+impl PresetBuildEnvironment for EmptyPresetBuildEnvironment {
+    fn query(&self, query: &PresetBuildQuery) -> anyhow::Result<serde_json::Value> {
+        let answer = match query {
+            PresetBuildQuery::Tracks
+            | PresetBuildQuery::Fxs { .. }
+            | PresetBuildQuery::FxParameters { .. }
+            | PresetBuildQuery::MidiInputDevices
+            | PresetBuildQuery::MidiOutputDevices
+            | PresetBuildQuery::OscDevices => serde_json::Value::Array(vec![]),
+            PresetBuildQuery::FocusedFx | PresetBuildQuery::CustomData { .. } => {
+                serde_json::Value::Null
+            }
+        };
+        Ok(answer)
+    }
+}
@@ -0,0 +1,191 @@
+//! Command-line tool for validating and converting ReaLearn presets without REAPER.
+//!
+//! ReaLearn itself validates presets only when loading them. This tool makes it possible to check
+//! a whole preset repository in a CI pipeline.
+
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use realearn_api::lua::format_lua_frontmatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod load;
+mod lua;
+mod validate;
+
+use load::{load_preset, InternalDataFormatError, LoadedPreset};
+use validate::{collect_virtual_control_elements, validate_preset, Severity};
+
+#[derive(Parser)]
+#[command(
+    name = "realearn-preset",
+    about = "Validates and converts ReaLearn presets"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks presets for schema errors, unknown properties and broken references.
+    Validate {
+        /// Preset files (`.json`, `.yaml`, `.lua`, `.luau` or CSI `.mst`).
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Controller preset which provides the virtual control elements used by main presets.
+        ///
+        /// If given, virtual sources in the validated presets must refer to control elements
+        /// that are defined in this controller preset.
+        #[arg(long)]
+        controller: Option<PathBuf>,
+        /// Fails on warnings, too (e.g. on unknown properties or on presets in ReaLearn's internal
+        /// data format, which can't be checked).
+        #[arg(long)]
+        strict: bool,
+        #[command(flatten)]
+        lua: LuaArgs,
+    },
+    /// Converts a preset into another format.
+    Convert {
+        /// Preset file (`.json`, `.yaml`, `.lua`, `.luau` or CSI `.mst`).
+        file: PathBuf,
+        /// Target format.
+        #[arg(long, value_enum)]
+        to: OutputFormat,
+        /// Output file. If omitted, the result is printed to stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        lua: LuaArgs,
+    },
+}
+
+#[derive(Args)]
+struct LuaArgs {
+    /// Directory against which `require` in Lua presets is resolved.
+    ///
+    /// This corresponds to the preset sub directory in ReaLearn (or to the factory preset
+    /// directory). Defaults to the directory which contains the preset.
+    #[arg(long)]
+    module_root: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Yaml,
+    Lua,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Validate {
+            files,
+            controller,
+            strict,
+            lua,
+        } => validate_files(&files, controller.as_deref(), strict, &lua),
+        Command::Convert {
+            file,
+            to,
+            output,
+            lua,
+        } => convert_file(&file, to, output.as_deref(), &lua).map(|_| true),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns `false` if at least one preset is invalid.
+fn validate_files(
+    files: &[PathBuf],
+    controller: Option<&Path>,
+    strict: bool,
+    lua_args: &LuaArgs,
+) -> anyhow::Result<bool> {
+    let controller_elements = match controller {
+        None => None,
+        Some(path) => {
+            let preset = load_preset(path, lua_args.module_root.as_deref())
+                .with_context(|| format!("Couldn't load controller preset {}", path.display()))?;
+            Some(collect_virtual_control_elements(&preset.compartment))
+        }
+    };
+    let mut all_valid = true;
+    for file in files {
+        let preset = match load_preset(file, lua_args.module_root.as_deref()) {
+            Ok(p) => p,
+            Err(e) if e.is::<InternalDataFormatError>() => {
+                // Presets saved by ReaLearn can't be checked here. Not checking a preset is not
+                // the same as it being valid, so this is a warning (and a failure if strict).
+                println!("{} {}: {e}", Severity::Warning, file.display());
+                if strict {
+                    all_valid = false;
+                }
+                continue;
+            }
+            Err(e) => {
+                println!("ERROR {}: {e:#}", file.display());
+                all_valid = false;
+                continue;
+            }
+        };
+        let issues = validate_preset(&preset, controller_elements.as_deref());
+        let fails = issues
+            .iter()
+            .any(|i| strict || i.severity == Severity::Error);
+        if issues.is_empty() {
+            println!("OK {}", file.display());
+        } else {
+            for issue in &issues {
+                println!("{} {}: {}", issue.severity, file.display(), issue);
+            }
+        }
+        if fails {
+            all_valid = false;
+        }
+    }
+    Ok(all_valid)
+}
+
+fn convert_file(
+    file: &Path,
+    format: OutputFormat,
+    output: Option<&Path>,
+    lua_args: &LuaArgs,
+) -> anyhow::Result<()> {
+    let preset = load_preset(file, lua_args.module_root.as_deref())?;
+    let text = serialize_preset(&preset, format)?;
+    match output {
+        None => print!("{text}"),
+        Some(path) => {
+            fs::write(path, text).with_context(|| format!("Couldn't write {}", path.display()))?
+        }
+    }
+    Ok(())
+}
+
+fn serialize_preset(preset: &LoadedPreset, format: OutputFormat) -> anyhow::Result<String> {
+    let text = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&preset.compartment)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&preset.compartment)?,
+        OutputFormat::Lua => {
+            let frontmatter = match &preset.meta_data {
+                None => String::new(),
+                Some(meta_data) => format_lua_frontmatter(meta_data)?,
+            };
+            let table = realearn_api::lua_serializer::to_string(&preset.compartment)?;
+            format!("{frontmatter}return {table}\n")
+        }
+    };
+    Ok(text)
+}
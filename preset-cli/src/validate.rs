@@ -0,0 +1,365 @@
+use crate::load::LoadedPreset;
+use realearn_api::persistence::{
+    ActivationCondition, Compartment, CompartmentParameterDescriptor, Mapping, ParamRef, Source,
+    Target, VirtualControlElementId,
+};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// Number of parameters per compartment in ReaLearn.
+const COMPARTMENT_PARAMETER_COUNT: u32 = 100;
+
+#[derive(Debug)]
+pub struct Issue {
+    pub severity: Severity,
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, derive_more::Display)]
+pub enum Severity {
+    #[display(fmt = "WARN")]
+    Warning,
+    #[display(fmt = "ERROR")]
+    Error,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Returns the IDs of all virtual control elements which are provided by the given controller
+/// compartment.
+pub fn collect_virtual_control_elements(compartment: &Compartment) -> Vec<VirtualControlElementId> {
+    compartment
+        .mappings
+        .iter()
+        .flatten()
+        .filter_map(|m| match m.target.as_ref()? {
+            Target::Virtual(t) => Some(t.id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks the given preset for unknown properties and broken references.
+///
+/// Schema errors are already reported when loading the preset.
+pub fn validate_preset(
+    preset: &LoadedPreset,
+    controller_elements: Option<&[VirtualControlElementId]>,
+) -> Vec<Issue> {
+    let mut validator = Validator {
+        compartment: &preset.compartment,
+        controller_elements,
+        issues: vec![],
+    };
+    for hint in &preset.conversion_hints {
+        validator.warn("conversion", hint.clone());
+    }
+    if let Ok(processed) = serde_json::to_value(&preset.compartment) {
+        validator.check_unknown_properties(&preset.raw, &processed, "$".to_string());
+    }
+    validator.check_compartment();
+    validator.issues
+}
+
+struct Validator<'a> {
+    compartment: &'a Compartment,
+    controller_elements: Option<&'a [VirtualControlElementId]>,
+    issues: Vec<Issue>,
+}
+
+impl<'a> Validator<'a> {
+    /// Reports everything that's in the original but got lost when deserializing.
+    fn check_unknown_properties(
+        &mut self,
+        original: &serde_json::Value,
+        processed: &serde_json::Value,
+        path: String,
+    ) {
+        use serde_json::Value;
+        match (original, processed) {
+            (Value::Object(original), Value::Object(processed)) => {
+                for (key, original_value) in original {
+                    let child_path = format!("{path}.{key}");
+                    match processed.get(key) {
+                        None if !original_value.is_null() => {
+                            self.warn(child_path, "unknown property (will be ignored)");
+                        }
+                        None => {}
+                        Some(processed_value) => {
+                            self.check_unknown_properties(
+                                original_value,
+                                processed_value,
+                                child_path,
+                            );
+                        }
+                    }
+                }
+            }
+            (Value::Array(original), Value::Array(processed)) => {
+                for (i, (o, p)) in original.iter().zip(processed).enumerate() {
+                    self.check_unknown_properties(o, p, format!("{path}[{i}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_compartment(&mut self) {
+        let compartment = self.compartment;
+        let group_ids = self.check_unique_ids(
+            "groups",
+            compartment.groups.iter().flatten().map(|g| g.id.as_deref()),
+        );
+        let mapping_ids = self.check_unique_ids(
+            "mappings",
+            compartment
+                .mappings
+                .iter()
+                .flatten()
+                .map(|m| m.id.as_deref()),
+        );
+        self.check_unique_ids(
+            "parameters",
+            compartment
+                .parameters
+                .iter()
+                .flatten()
+                .map(|p| p.id.as_deref()),
+        );
+        for (i, p) in compartment.parameters.iter().flatten().enumerate() {
+            if p.index >= COMPARTMENT_PARAMETER_COUNT {
+                self.error(
+                    format!("parameters[{i}]"),
+                    format!("parameter index {} is out of range", p.index),
+                );
+            }
+        }
+        for (i, g) in compartment.groups.iter().flatten().enumerate() {
+            if let Some(c) = &g.activation_condition {
+                self.check_activation_condition(format!("groups[{i}]"), c, &mapping_ids);
+            }
+        }
+        for (i, m) in compartment.mappings.iter().flatten().enumerate() {
+            self.check_mapping(i, m, &group_ids, &mapping_ids);
+        }
+    }
+
+    fn check_mapping(
+        &mut self,
+        index: usize,
+        m: &Mapping,
+        group_ids: &HashSet<&str>,
+        mapping_ids: &HashSet<&str>,
+    ) {
+        let location = match m.name.as_ref().or(m.id.as_ref()) {
+            None => format!("mappings[{index}]"),
+            Some(label) => format!("mappings[{index}] (\"{label}\")"),
+        };
+        if let Some(unprocessed) = m.unprocessed.as_ref().filter(|u| !u.is_empty()) {
+            let keys: Vec<_> = unprocessed.keys().map(|k| k.as_str()).collect();
+            self.warn(
+                &location,
+                format!("contains unprocessed properties: {}", keys.join(", ")),
+            );
+        }
+        if let Some(group) = &m.group {
+            if !group_ids.contains(group.as_str()) {
+                self.error(&location, format!("refers to unknown group \"{group}\""));
+            }
+        }
+        if let Some(c) = &m.activation_condition {
+            self.check_activation_condition(location.clone(), c, mapping_ids);
+        }
+        if let (Some(Source::Virtual(s)), Some(elements)) = (&m.source, self.controller_elements) {
+            if !elements.contains(&s.id) {
+                self.error(
+                    &location,
+                    format!(
+                        "uses virtual control element {} which is not provided by the controller preset",
+                        format_control_element_id(&s.id)
+                    ),
+                );
+            }
+        }
+        match &m.target {
+            Some(Target::ModifyMapping(t)) if t.session.is_none() => {
+                if let Some(key) = &t.mapping {
+                    if !mapping_ids.contains(key.as_str()) {
+                        self.error(&location, format!("modifies unknown mapping \"{key}\""));
+                    }
+                }
+            }
+            Some(Target::BrowseGroupMappings(t)) => {
+                if let Some(group) = &t.group {
+                    if !group_ids.contains(group.as_str()) {
+                        self.error(&location, format!("browses unknown group \"{group}\""));
+                    }
+                }
+            }
+            Some(Target::CompartmentParameterValue(t)) => {
+                let CompartmentParameterDescriptor::ById { index } = &t.parameter;
+                self.check_param_ref(&location, &ParamRef::Index(*index));
+            }
+            _ => {}
+        }
+    }
+
+    fn check_activation_condition(
+        &mut self,
+        location: String,
+        condition: &ActivationCondition,
+        mapping_ids: &HashSet<&str>,
+    ) {
+        match condition {
+            ActivationCondition::Modifier(c) => {
+                for modifier in c.modifiers.iter().flatten() {
+                    self.check_param_ref(&location, &modifier.parameter);
+                }
+            }
+            ActivationCondition::Bank(c) => {
+                self.check_param_ref(&location, &c.parameter);
+            }
+            ActivationCondition::TargetValue(c) => {
+                if let Some(key) = &c.mapping {
+                    if !mapping_ids.contains(key.as_str()) {
+                        self.error(
+                            &location,
+                            format!("activation condition refers to unknown mapping \"{key}\""),
+                        );
+                    }
+                }
+            }
+            ActivationCondition::Eel(_) | ActivationCondition::Expression(_) => {}
+        }
+    }
+
+    fn check_param_ref(&mut self, location: &str, param_ref: &ParamRef) {
+        match param_ref {
+            ParamRef::Index(i) => {
+                if *i >= COMPARTMENT_PARAMETER_COUNT {
+                    self.error(location, format!("parameter index {i} is out of range"));
+                }
+            }
+            ParamRef::Key(key) => {
+                let exists = self
+                    .compartment
+                    .parameters
+                    .iter()
+                    .flatten()
+                    .any(|p| p.id.as_deref() == Some(key.as_str()));
+                if !exists {
+                    self.error(location, format!("refers to unknown parameter \"{key}\""));
+                }
+            }
+        }
+    }
+
+    /// Reports duplicate IDs and returns the set of all IDs.
+    fn check_unique_ids(
+        &mut self,
+        location: &str,
+        ids: impl Iterator<Item = Option<&'a str>>,
+    ) -> HashSet<&'a str> {
+        let mut set = HashSet::new();
+        for id in ids.flatten() {
+            if !set.insert(id) {
+                self.error(location, format!("ID \"{id}\" is used more than once"));
+            }
+        }
+        set
+    }
+
+    fn warn(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.add_issue(Severity::Warning, location.into(), message.into());
+    }
+
+    fn error(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.add_issue(Severity::Error, location.into(), message.into());
+    }
+
+    fn add_issue(&mut self, severity: Severity, location: String, message: String) {
+        self.issues.push(Issue {
+            severity,
+            location,
+            message,
+        });
+    }
+}
+
+fn format_control_element_id(id: &VirtualControlElementId) -> String {
+    match id {
+        VirtualControlElementId::Indexed(i) => i.to_string(),
+        VirtualControlElementId::Named(n) => format!("\"{n}\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn load(raw: serde_json::Value) -> LoadedPreset {
+        LoadedPreset {
+            meta_data: None,
+            compartment: serde_json::from_value(raw.clone()).unwrap(),
+            raw,
+            conversion_hints: vec![],
+        }
+    }
+
+    #[test]
+    fn unknown_properties() {
+        let preset = load(json!({
+            "mappings": [{ "id": "a", "nme": "Typo" }]
+        }));
+        let issues = validate_preset(&preset, None);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].location, "$.mappings[0].nme");
+    }
+
+    #[test]
+    fn broken_references() {
+        let preset = load(json!({
+            "parameters": [{ "index": 0, "id": "shift" }],
+            "groups": [{ "id": "faders" }],
+            "mappings": [
+                {
+                    "id": "a",
+                    "group": "knobs",
+                    "activation_condition": {
+                        "kind": "Modifier",
+                        "modifiers": [{ "parameter": "alt", "on": true }]
+                    },
+                    "source": { "kind": "Virtual", "id": "fader/1" }
+                },
+                {
+                    "id": "b",
+                    "group": "faders",
+                    "activation_condition": {
+                        "kind": "Modifier",
+                        "modifiers": [{ "parameter": "shift", "on": true }]
+                    },
+                    "source": { "kind": "Virtual", "id": 0 }
+                }
+            ]
+        }));
+        let controller_elements = [VirtualControlElementId::Indexed(0)];
+        let issues = validate_preset(&preset, Some(&controller_elements));
+        let messages: Vec<_> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "refers to unknown group \"knobs\"",
+                "refers to unknown parameter \"alt\"",
+                "uses virtual control element \"fader/1\" which is not provided by the controller preset",
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+    }
+}